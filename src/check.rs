@@ -0,0 +1,216 @@
+use rayon::prelude::*;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use unreal_asset::exports::ExportBaseTrait;
+
+use crate::{
+    catch_panics, graph, is_valid_extension, pak, parse_file, DirWalk, Error, IndexNames,
+    IndexOptions,
+};
+
+/// What `check` found.
+#[derive(Debug, Default)]
+pub struct CheckReport {
+    pub passed: usize,
+    pub failed: usize,
+}
+
+impl CheckReport {
+    pub fn add(&mut self, other: CheckReport) {
+        self.passed += other.passed;
+        self.failed += other.failed;
+    }
+}
+
+/// `export 3` or `import 2` for the package index `index`.
+fn describe(index: i32) -> String {
+    if index < 0 {
+        format!("import {}", -index)
+    } else {
+        format!("export {}", index)
+    }
+}
+
+/// Every way the package indices of an asset don't hold together:
+/// references in `edges` outside the import and export tables, imports whose
+/// outer chain doesn't end in a `Package` import, and exports whose outer
+/// chain loops. `import_classes` and `export_outers` are in table order.
+fn violations(
+    names: &IndexNames,
+    edges: &[graph::Edge],
+    import_classes: &[String],
+    export_outers: &[i32],
+) -> Vec<String> {
+    let mut violations = Vec::new();
+    for edge in edges {
+        if names.get(edge.to).is_none() {
+            violations.push(format!(
+                "{}: {} reference {} is out of range",
+                describe(edge.from),
+                edge.label,
+                edge.to
+            ));
+        }
+    }
+    for i in 1..=names.imports.len() as i32 {
+        let chain = names.import_chain(-i);
+        let outermost = chain[chain.len() - 1];
+        let position = (-outermost - 1) as usize;
+        match names.import_outers[position] {
+            0 if import_classes[position] == "Package" => {}
+            0 => violations.push(format!(
+                "{}: outermost import {} ({}) is a {}, not a Package",
+                describe(-i),
+                outermost,
+                names.get(outermost).unwrap(),
+                import_classes[position]
+            )),
+            outer => violations.push(format!(
+                "{}: outer chain doesn't end in a package, it stops at {}",
+                describe(-i),
+                outer
+            )),
+        }
+    }
+    for index in 1..=export_outers.len() as i32 {
+        let mut seen = HashSet::from([index]);
+        let mut current = export_outers[index as usize - 1];
+        while current > 0 && (current as usize) <= export_outers.len() {
+            if !seen.insert(current) {
+                violations.push(format!(
+                    "{}: outer chain loops back to {}",
+                    describe(index),
+                    describe(current)
+                ));
+                break;
+            }
+            current = export_outers[current as usize - 1];
+        }
+    }
+    violations
+}
+
+#[test]
+fn test_violations() {
+    let names = crate::test_index_names();
+    let edge = |from, to, label: &str| graph::Edge {
+        from,
+        to,
+        label: label.to_string(),
+    };
+    let classes = vec!["Package".to_string(), "Class".to_string()];
+    assert!(violations(&names, &[edge(1, -2, "class")], &classes, &[0]).is_empty());
+    assert_eq!(
+        vec!["export 1: outer reference 4 is out of range"],
+        violations(&names, &[edge(1, 4, "outer")], &classes, &[0])
+    );
+
+    let classes = vec!["Class".to_string(), "Class".to_string()];
+    assert_eq!(
+        vec![
+            "import 1: outermost import -1 (CoreUObject) is a Class, not a Package",
+            "import 2: outermost import -1 (CoreUObject) is a Class, not a Package",
+        ],
+        violations(&names, &[], &classes, &[0])
+    );
+
+    let mut names = crate::test_index_names();
+    names.exports = vec!["A".to_string(), "B".to_string()];
+    names.import_outers = vec![2, -1];
+    assert_eq!(
+        vec![
+            "import 1: outer chain doesn't end in a package, it stops at 2",
+            "import 2: outer chain doesn't end in a package, it stops at 2",
+            "export 1: outer chain loops back to export 1",
+            "export 2: outer chain loops back to export 2",
+        ],
+        violations(&names, &[], &["Package".to_string(); 2], &[2, 1])
+    );
+}
+
+/// Parses the asset at `path` and lists its `violations`.
+fn check_asset(path: &Path, options: &IndexOptions) -> Result<Vec<String>, Error> {
+    let (asset, _) = parse_file(path, options)?;
+    let names = IndexNames::from_asset(&asset);
+    let import_classes: Vec<String> = asset
+        .imports
+        .iter()
+        .map(|import| import.class_name.get_owned_content())
+        .collect();
+    let export_outers: Vec<i32> = asset
+        .asset_data
+        .exports
+        .iter()
+        .map(|export| export.get_base_export().outer_index.index)
+        .collect();
+    Ok(violations(
+        &names,
+        &graph::collect_edges(&asset),
+        &import_classes,
+        &export_outers,
+    ))
+}
+
+/// Collects the assets under `dir`, skipping subdirectories that can't be
+/// read.
+pub(crate) fn find_assets(dir: &Path, walk: &mut DirWalk, assets: &mut Vec<PathBuf>) {
+    let Ok(entries) = walk.read_dir(dir) else {
+        eprintln!("warning: skipping {}: can't be read", dir.display());
+        return;
+    };
+    assets.extend(entries.assets);
+    for subdir in entries.subdirs {
+        find_assets(&subdir, walk, assets);
+    }
+}
+
+/// Checks that the package indices of the asset at `path`, or of every asset
+/// under the directory `path`, resolve, printing a line per asset with the
+/// problems found. Nothing is written.
+pub fn check(path: &Path, options: &IndexOptions) -> CheckReport {
+    let mut assets = Vec::new();
+    if pak::is_pak(path) {
+        eprintln!("{}: --check doesn't read .pak files", path.display());
+        return CheckReport {
+            passed: 0,
+            failed: 1,
+        };
+    } else if path.is_dir() {
+        let mut walk = DirWalk::new(path, options);
+        find_assets(path, &mut walk, &mut assets);
+    } else if !is_valid_extension(path.extension(), &options.extensions) {
+        eprintln!("{}", Error::InvalidExtension(path.to_path_buf()));
+        return CheckReport {
+            passed: 0,
+            failed: 1,
+        };
+    } else {
+        assets.push(path.to_path_buf());
+    }
+    let results: Vec<Result<Vec<String>, Error>> = assets
+        .par_iter()
+        .map(|asset| catch_panics(asset, || check_asset(asset, options)))
+        .collect();
+    let mut report = CheckReport::default();
+    for (asset, result) in assets.iter().zip(results) {
+        match result {
+            Ok(violations) if violations.is_empty() => {
+                report.passed += 1;
+                options.log(format_args!("ok {}", asset.display()));
+            }
+            Ok(violations) => {
+                report.failed += 1;
+                options.log(format_args!("FAIL {}", asset.display()));
+                for violation in violations {
+                    options.log(format_args!("  {}", violation));
+                }
+            }
+            Err(err) => {
+                report.failed += 1;
+                options.log(format_args!("FAIL {}", asset.display()));
+                options.log(format_args!("  {}", err));
+            }
+        }
+    }
+    report
+}
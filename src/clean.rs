@@ -0,0 +1,78 @@
+use std::io::Error as IOError;
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+use crate::MARKER_FILE;
+use crate::{contains_assets, is_output_dir};
+
+/// What `clean_dir` did, for the summary printed at the end.
+#[derive(Debug, Default)]
+pub struct CleanReport {
+    pub removed: Vec<PathBuf>,
+    pub refused: Vec<PathBuf>,
+}
+
+/// Whether `dir` was generated by `index_file`: it holds our marker, or the
+/// `index.html`, `exports/` and `imports/` layout older runs left without one.
+fn is_generated_dir(dir: &Path) -> bool {
+    is_output_dir(dir)
+        || (dir.join("index.html").is_file()
+            && dir.join("exports").is_dir()
+            && dir.join("imports").is_dir())
+}
+
+/// Deletes every generated directory under `dir`. Directories that look
+/// generated but contain assets are left alone and reported as refused.
+pub fn clean_dir(dir: &Path, report: &mut CleanReport) -> Result<(), IOError> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let path = entry.path();
+        if is_generated_dir(&path) {
+            if contains_assets(&path, &[])? {
+                report.refused.push(path);
+            } else {
+                std::fs::remove_dir_all(&path)?;
+                report.removed.push(path);
+            }
+        } else {
+            clean_dir(&path, report)?;
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_clean_dir() {
+    use std::fs::{create_dir_all, File};
+
+    let dir = tempfile::tempdir().unwrap();
+    let marked = dir.path().join("Hero");
+    create_dir_all(&marked).unwrap();
+    File::create(marked.join(MARKER_FILE)).unwrap();
+
+    let legacy = dir.path().join("Maps").join("Level");
+    create_dir_all(legacy.join("exports")).unwrap();
+    create_dir_all(legacy.join("imports")).unwrap();
+    File::create(legacy.join("index.html")).unwrap();
+
+    let with_assets = dir.path().join("Weapon");
+    create_dir_all(&with_assets).unwrap();
+    File::create(with_assets.join(MARKER_FILE)).unwrap();
+    File::create(with_assets.join("Rifle.uasset")).unwrap();
+
+    File::create(dir.path().join("Hero.uasset")).unwrap();
+
+    let mut report = CleanReport::default();
+    clean_dir(dir.path(), &mut report).unwrap();
+    report.removed.sort();
+    assert_eq!(vec![legacy.clone(), marked.clone()], report.removed);
+    assert_eq!(vec![with_assets.clone()], report.refused);
+    assert!(!marked.exists());
+    assert!(!legacy.exists());
+    assert!(dir.path().join("Maps").exists());
+    assert!(with_assets.join("Rifle.uasset").exists());
+    assert!(dir.path().join("Hero.uasset").exists());
+}
@@ -0,0 +1,696 @@
+use clap::{parser::ValueSource, ArgMatches, Args, Parser, Subcommand};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use unreal_asset::engine_version::EngineVersion;
+
+use uindex::{
+    compress::Compression,
+    config::Config,
+    hexdump,
+    list::{ListFormat, ListTable},
+    pak, parse_engine_version, serve, OutputFormat, Theme, DEFAULT_COLLAPSE_DEPTH,
+    DEFAULT_ENGINE_VERSION, DEFAULT_GRAPH_MAX_NODES, DEFAULT_LISTING_PAGE_SIZE,
+    DEFAULT_MAX_ARRAY_ELEMENTS, DEFAULT_MAX_DUMP_SIZE, DEFAULT_MAX_STRING_BYTES, ENGINE_VERSIONS,
+    SUPPORTED_EXTENSIONS,
+};
+
+/// Generates browsable HTML pages for the imports and exports of Unreal
+/// Engine assets.
+#[derive(Parser, Debug)]
+#[command(
+    name = "uasset-index",
+    version,
+    after_help = after_help(),
+    args_conflicts_with_subcommands = true
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    #[command(flatten)]
+    pub index: IndexArgs,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Index assets and directories of assets (the default when no subcommand is given)
+    Index(IndexArgs),
+    /// Delete the directories generated by earlier runs. Directories that
+    /// contain assets are never deleted.
+    Clean {
+        #[arg(required = true)]
+        dirs: Vec<PathBuf>,
+    },
+    /// Compare two versions of an asset and write an HTML report of what
+    /// changed. Exits with 1 when they differ and 2 when either can't be read.
+    Diff(DiffArgs),
+    /// Print the imports of assets that reference a package or object, as
+    /// path:import: object path (class), reading only their import tables.
+    /// Exits with 1 when nothing references it and 2 when an asset can't be
+    /// read.
+    FindReferences(FindReferencesArgs),
+    /// Search the string, name and text properties of assets and print each
+    /// match as path:export:property: value, writing nothing. Exits with 1
+    /// when nothing matches and 2 when an asset can't be read.
+    Grep(GrepArgs),
+    /// Print a line per export of an asset, as index, class, name and serial
+    /// size separated by tabs, reading only its tables and writing nothing.
+    /// Exits with 1 when the asset can't be read.
+    List(ListArgs),
+    /// Browse the exports and imports of an asset in the terminal, writing
+    /// nothing. Enter follows a package index in the dump, Backspace goes back.
+    Tui(TuiArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct TuiArgs {
+    /// The asset to browse
+    pub path: PathBuf,
+    /// Engine version for assets that don't record their own, e.g. 4.27 or VER_UE4_27
+    #[arg(long, value_parser = engine_version_arg, default_value = default_engine_version())]
+    pub engine_version: EngineVersion,
+    /// Mappings for cooked assets saved with unversioned properties
+    #[arg(long)]
+    pub usmap: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct ListArgs {
+    /// The asset to list
+    pub path: PathBuf,
+    /// List the imports instead, as index, class and object path
+    #[arg(long, conflicts_with = "names")]
+    pub imports: bool,
+    /// List the name map instead, as index and name
+    #[arg(long)]
+    pub names: bool,
+    /// How to write each line: tab-separated, CSV with a header row, or a JSON object per line
+    #[arg(long, value_enum, default_value = "tsv")]
+    pub format: ListFormat,
+    /// Engine version for assets that don't record their own, e.g. 4.27 or VER_UE4_27
+    #[arg(long, value_parser = engine_version_arg, default_value = default_engine_version())]
+    pub engine_version: EngineVersion,
+}
+
+impl ListArgs {
+    /// The table `--imports` or `--names` asks for, the exports otherwise.
+    pub fn table(&self) -> ListTable {
+        if self.imports {
+            ListTable::Imports
+        } else if self.names {
+            ListTable::Names
+        } else {
+            ListTable::Exports
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct FindReferencesArgs {
+    /// Package or object path to look for, e.g. /Game/Hero/Hero_BP or /Game/Hero/Hero_BP.Hero_BP_C (case-insensitive)
+    pub object_path: String,
+    /// Assets or directories to search
+    #[arg(required = true)]
+    pub paths: Vec<PathBuf>,
+    /// Match every import from the object's package, not just the object
+    #[arg(long)]
+    pub package: bool,
+    /// Print the references as a JSON array
+    #[arg(long)]
+    pub json: bool,
+    /// Engine version for assets that don't record their own, e.g. 4.27 or VER_UE4_27
+    #[arg(long, value_parser = engine_version_arg, default_value = default_engine_version())]
+    pub engine_version: EngineVersion,
+}
+
+#[derive(Args, Debug)]
+pub struct GrepArgs {
+    /// Text to look for, or a regular expression with --regex
+    pub pattern: String,
+    /// Assets or directories to search
+    #[arg(required = true)]
+    pub paths: Vec<PathBuf>,
+    /// Treat the pattern as a regular expression
+    #[arg(long, short = 'E')]
+    pub regex: bool,
+    /// Ignore case when matching
+    #[arg(long, short)]
+    pub ignore_case: bool,
+    /// Only search exports of these classes, e.g. DataTable,Quest* (case-insensitive)
+    #[arg(long, value_delimiter = ',')]
+    pub class: Vec<String>,
+    /// Also search the paths of soft object and class references
+    #[arg(long)]
+    pub object_paths: bool,
+    /// Engine version for assets that don't record their own, e.g. 4.27 or VER_UE4_27
+    #[arg(long, value_parser = engine_version_arg, default_value = default_engine_version())]
+    pub engine_version: EngineVersion,
+    /// Mappings for cooked assets saved with unversioned properties
+    #[arg(long)]
+    pub usmap: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct DiffArgs {
+    /// The earlier version of the asset
+    pub old: PathBuf,
+    /// The later version of the asset
+    pub new: PathBuf,
+    /// Where to write the report
+    #[arg(long, short, default_value = "diff.html")]
+    pub output: PathBuf,
+    /// Engine version for assets that don't record their own, e.g. 4.27 or VER_UE4_27
+    #[arg(long, value_parser = engine_version_arg, default_value = default_engine_version())]
+    pub engine_version: EngineVersion,
+    /// Mappings for cooked assets saved with unversioned properties
+    #[arg(long)]
+    pub usmap: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct IndexArgs {
+    /// Assets or directories to index
+    pub paths: Vec<PathBuf>,
+    /// Also index the paths listed in this file, one per line, or from stdin with -. Blank lines and lines starting with # are skipped
+    #[arg(long, value_name = "FILE")]
+    pub files_from: Option<PathBuf>,
+    /// Paths in the --files-from list are separated by NUL bytes, as find -print0 writes them
+    #[arg(short = '0', long = "null", requires = "files_from")]
+    pub null: bool,
+    /// Engine version for assets that don't record their own, e.g. 4.27 or VER_UE4_27
+    #[arg(long, value_parser = engine_version_arg, default_value = default_engine_version())]
+    pub engine_version: EngineVersion,
+    /// JSON file mapping directories, relative to it, to the engine version of the assets under them, e.g. {"Game422": "4.22"}
+    #[arg(long, value_name = "FILE")]
+    pub version_map: Option<PathBuf>,
+    /// Write the generated pages under this directory instead of next to each asset
+    #[arg(long)]
+    pub output_dir: Option<PathBuf>,
+    /// Mappings for cooked assets saved with unversioned properties
+    #[arg(long)]
+    pub usmap: Option<PathBuf>,
+    /// Outputs to generate
+    #[arg(
+        long = "format",
+        value_enum,
+        value_delimiter = ',',
+        default_value = "html"
+    )]
+    pub formats: Vec<OutputFormat>,
+    /// Where --format ndjson writes its lines: a file, or - for stdout, which moves the log to stderr
+    #[arg(long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+    /// Assets indexed in parallel [default: one per core]
+    #[arg(long, value_parser = positive_number)]
+    pub jobs: Option<usize>,
+    /// Write a Graphviz graph.dot per asset
+    #[arg(long)]
+    pub graph: bool,
+    /// Nodes kept in each graph.dot, exports first
+    #[arg(long, default_value_t = DEFAULT_GRAPH_MAX_NODES, value_parser = positive_number)]
+    pub graph_max_nodes: usize,
+    /// Elements of each array, set or map listed on export pages. asset.json keeps them all
+    #[arg(long, default_value_t = DEFAULT_MAX_ARRAY_ELEMENTS, value_parser = positive_number)]
+    pub max_array_elements: usize,
+    /// Bytes of a string shown on export pages. Longer strings are cut, their full values written to strings/; asset.json keeps them whole
+    #[arg(long, default_value_t = DEFAULT_MAX_STRING_BYTES, value_parser = positive_number)]
+    pub max_string_bytes: usize,
+    /// Bytes of an export's dump formatted before it's cut short with a note, so one huge export doesn't stall the run
+    #[arg(long, value_name = "BYTES", default_value_t = DEFAULT_MAX_DUMP_SIZE, value_parser = positive_number)]
+    pub max_dump_size: usize,
+    /// Exports or imports per page of their listing. Longer listings are split into pages; search.json still covers everything
+    #[arg(long, default_value_t = DEFAULT_LISTING_PAGE_SIZE, value_parser = positive_number)]
+    pub listing_page_size: usize,
+    /// Give each export page a raw.html with a hex dump of the export's serialized bytes, the unparsed ones marked
+    #[arg(long)]
+    pub raw_dumps: bool,
+    /// Exports larger than this many bytes get no raw.html, unless --raw-full is given
+    #[arg(long, default_value_t = hexdump::DEFAULT_MAX_BYTES, value_parser = positive_number, requires = "raw_dumps")]
+    pub raw_max_bytes: usize,
+    /// Dump exports larger than --raw-max-bytes too, truncated to it
+    #[arg(long, requires = "raw_dumps")]
+    pub raw_full: bool,
+    /// Write layout.html and layout.json per asset, with the byte offset and length of each name, import and export entry and of the summary fields that locate them
+    #[arg(long)]
+    pub layout: bool,
+    /// Nesting depth from which the blocks of export and import dumps start collapsed
+    #[arg(long, default_value_t = DEFAULT_COLLAPSE_DEPTH, value_parser = positive_number)]
+    pub collapse_depth: usize,
+    /// Show export and import dumps as plain text, with nothing collapsible
+    #[arg(long, conflicts_with = "collapse_depth")]
+    pub no_collapse: bool,
+    /// Only check that every package index resolves, writing nothing. Exits with 1 if any asset fails
+    #[arg(long)]
+    pub check: bool,
+    /// Write each asset's pages into one self-contained <asset>.html
+    #[arg(long)]
+    pub single_file: bool,
+    /// Write import pages identical to an earlier import's of the same asset as redirects to it
+    #[arg(long, conflicts_with = "single_file")]
+    pub dedupe_pages: bool,
+    /// Write a JSON listing of every generated file and every failed asset
+    #[arg(long)]
+    pub manifest: Option<PathBuf>,
+    /// Store every generated file but thumbnails compressed, e.g. gzip for index.html.gz, for servers that serve precompressed files. Links keep the plain names
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    pub compress: Option<Compression>,
+    /// Print how long parsing and each kind of page took, per asset and in total, at the end. Also goes into --stats-json
+    #[arg(long)]
+    pub timings: bool,
+    /// Also write the run statistics printed at the end as JSON
+    #[arg(long, value_name = "PATH")]
+    pub stats_json: Option<PathBuf>,
+    /// With --format csv, also write exports.csv and imports.csv across every asset of a directory or .pak, with a package column, at the top of the output
+    #[arg(long)]
+    pub csv_combined: bool,
+    /// Write every text property of the assets indexed, with its namespace, key and source string, to this CSV file
+    #[arg(long, value_name = "PATH")]
+    pub texts_csv: Option<PathBuf>,
+    /// Write the string, name and text values of each asset's properties to strings.txt, and of a directory or .pak to strings.csv at the top of the output
+    #[arg(long)]
+    pub extract_strings: bool,
+    /// With --extract-strings, list each distinct string of an asset once, with how often it occurs
+    #[arg(long, requires = "extract_strings")]
+    pub strings_dedupe: bool,
+    /// With --extract-strings, also keep strings shorter than three characters and ones with control characters
+    #[arg(long, requires = "extract_strings")]
+    pub strings_unfiltered: bool,
+    /// Skip assets whose .uasset and .uexp haven't changed since the last run
+    #[arg(long)]
+    pub incremental: bool,
+    /// Descend into symlinked directories. Directories reached twice are skipped
+    #[arg(long)]
+    pub follow_symlinks: bool,
+    /// Levels of subdirectories walked below each directory given. 0 only indexes the files directly in it [default: no limit]
+    #[arg(long, value_name = "N")]
+    pub max_depth: Option<usize>,
+    /// Also index files with these extensions as assets, e.g. uasset2,bin for games that ship renamed assets (case-insensitive)
+    #[arg(long, value_delimiter = ',', value_parser = extension_arg)]
+    pub extensions: Vec<String>,
+    /// Skip files and directories whose path below the input directory matches this glob, e.g. "Movies/**". Repeatable
+    #[arg(long, value_name = "GLOB")]
+    pub exclude: Vec<String>,
+    /// Only index files whose path below the input directory matches one of these globs, e.g. "Maps/**". Repeatable
+    #[arg(long, value_name = "GLOB")]
+    pub include: Vec<String>,
+    /// When several files map to one package path, resolve links to the copy under this directory, given as walked or relative to the input directory. Repeatable, earliest first
+    #[arg(long, value_name = "ROOT")]
+    pub prefer: Vec<PathBuf>,
+    /// Only give exports of these classes their own page, e.g. DataTable,Sound* (case-insensitive)
+    #[arg(long, value_delimiter = ',')]
+    pub filter_class: Vec<String>,
+    /// After indexing, serve the generated pages over HTTP, e.g. --serve=0.0.0.0:8080
+    #[arg(
+        long,
+        value_name = "ADDR:PORT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = serve::DEFAULT_ADDR
+    )]
+    pub serve: Option<String>,
+    /// Copy this CSS file to style.css instead of the default stylesheet
+    #[arg(long, value_name = "FILE")]
+    pub style: Option<PathBuf>,
+    /// Built-in stylesheet to use: light, dark, or auto to follow the browser's preference
+    #[arg(long, value_enum, default_value = "auto", conflicts_with = "style")]
+    pub theme: Theme,
+    /// Key for encrypted .pak files, as 64 hex digits
+    #[arg(long, value_name = "HEX", value_parser = pak::parse_aes_key)]
+    pub aes_key: Option<pak::AesKey>,
+    /// Only index the assets of a .pak whose path inside it matches one of these patterns, e.g. */Maps/* (case-insensitive)
+    #[arg(long, value_delimiter = ',')]
+    pub pak_filter: Vec<String>,
+    /// Regenerate every asset, even with --incremental
+    #[arg(long)]
+    pub force: bool,
+    /// Ignore uasset-index.toml files, using only the options on the command line
+    #[arg(long)]
+    pub no_config: bool,
+}
+
+/// The options a `uasset-index.toml` can also set, by argument id.
+const CONFIG_ARGS: &[&str] = &[
+    "engine_version",
+    "usmap",
+    "output_dir",
+    "exclude",
+    "formats",
+];
+
+/// Which of `CONFIG_ARGS` were given on the command line, which overrides
+/// the config files. `matches` are those of the index arguments.
+pub fn given_on_command_line(matches: &ArgMatches) -> HashSet<&'static str> {
+    CONFIG_ARGS
+        .iter()
+        .copied()
+        .filter(|id| matches.value_source(id) == Some(ValueSource::CommandLine))
+        .collect()
+}
+
+impl IndexArgs {
+    /// Takes the run-wide options `config` sets unless they're in `given`,
+    /// adding those it sets to `given` so the first config to set one wins.
+    /// Its excludes join those of the other configs instead. Engine
+    /// versions and mappings apply below each config's directory, so
+    /// they're left to `DirectoryConfigs`.
+    pub fn apply_config(&mut self, config: &Config, given: &mut HashSet<&'static str>) {
+        if let Some(output_dir) = &config.output_dir {
+            if given.insert("output_dir") {
+                self.output_dir = Some(output_dir.clone());
+            }
+        }
+        if let Some(formats) = &config.formats {
+            if given.insert("formats") {
+                self.formats = formats.clone();
+            }
+        }
+        if !given.contains("exclude") {
+            self.exclude.extend(config.excludes.iter().cloned());
+        }
+    }
+}
+
+fn default_engine_version() -> &'static str {
+    ENGINE_VERSIONS
+        .iter()
+        .find(|(_, version)| *version == DEFAULT_ENGINE_VERSION)
+        .map(|(short, _)| *short)
+        .unwrap()
+}
+
+fn positive_number(s: &str) -> Result<usize, String> {
+    match s.parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err("expected a positive number".to_string()),
+    }
+}
+
+fn engine_version_arg(s: &str) -> Result<EngineVersion, String> {
+    parse_engine_version(s).ok_or_else(|| "see --help for the supported versions".to_string())
+}
+
+/// An extension for `--extensions`, with or without its leading dot.
+fn extension_arg(s: &str) -> Result<String, String> {
+    let ext = s.strip_prefix('.').unwrap_or(s);
+    if ext.is_empty() || ext.contains(['.', '/', '\\']) {
+        return Err("expected an extension such as uasset2".to_string());
+    }
+    Ok(ext.to_string())
+}
+
+fn after_help() -> String {
+    let extensions: Vec<String> = SUPPORTED_EXTENSIONS
+        .iter()
+        .map(|ext| format!(".{}", ext))
+        .collect();
+    let versions: Vec<&str> = ENGINE_VERSIONS.iter().map(|(short, _)| *short).collect();
+    format!(
+        "Supported extensions: {} (and those given with --extensions)\nSupported engine versions: {}\n\n\
+         Indexing the same inputs with the same options writes byte-identical files, \
+         without timestamps or absolute paths, so the output can be committed and diffed. \
+         The .uasset-index markers are the only exception: they record the modification \
+         times of the inputs for --incremental.",
+        extensions.join(", "),
+        versions.join(", ")
+    )
+}
+
+#[test]
+fn test_cli_keeps_plain_invocations() {
+    let cli = Cli::try_parse_from(["uasset-index", "a.uasset", "Content"]).unwrap();
+    assert!(cli.command.is_none());
+    assert_eq!(
+        vec![PathBuf::from("a.uasset"), PathBuf::from("Content")],
+        cli.index.paths
+    );
+    assert_eq!(DEFAULT_ENGINE_VERSION, cli.index.engine_version);
+    assert_eq!(vec![OutputFormat::Html], cli.index.formats);
+}
+
+#[test]
+fn test_cli_parses_options() {
+    let cli = Cli::try_parse_from([
+        "uasset-index",
+        "--engine-version",
+        "VER_UE4_27",
+        "--format",
+        "html,json",
+        "a.uasset",
+    ])
+    .unwrap();
+    assert_eq!(EngineVersion::VER_UE4_27, cli.index.engine_version);
+    assert_eq!(
+        vec![OutputFormat::Html, OutputFormat::Json],
+        cli.index.formats
+    );
+    assert!(Cli::try_parse_from(["uasset-index", "--format", "html,pdf", "a.uasset"]).is_err());
+    assert!(Cli::try_parse_from(["uasset-index", "--engine-version", "3.0", "a.uasset"]).is_err());
+    assert!(Cli::try_parse_from(["uasset-index", "--bogus", "a.uasset"]).is_err());
+
+    let cli = Cli::try_parse_from(["uasset-index", "--serve", "Content"]).unwrap();
+    assert_eq!(Some(serve::DEFAULT_ADDR), cli.index.serve.as_deref());
+    assert_eq!(vec![PathBuf::from("Content")], cli.index.paths);
+    let cli = Cli::try_parse_from(["uasset-index", "--serve=0.0.0.0:8080", "Content"]).unwrap();
+    assert_eq!(Some("0.0.0.0:8080"), cli.index.serve.as_deref());
+
+    let cli = Cli::try_parse_from(["uasset-index", "--files-from", "-", "-0"]).unwrap();
+    assert_eq!(Some(PathBuf::from("-")), cli.index.files_from);
+    assert!(cli.index.null && cli.index.paths.is_empty());
+    assert!(Cli::try_parse_from(["uasset-index", "-0", "Content"]).is_err());
+
+    let cli =
+        Cli::try_parse_from(["uasset-index", "--extensions", ".uasset2,bin", "Content"]).unwrap();
+    assert_eq!(vec!["uasset2", "bin"], cli.index.extensions);
+    assert!(Cli::try_parse_from(["uasset-index", "--extensions", "a/b", "Content"]).is_err());
+
+    let cli = Cli::try_parse_from([
+        "uasset-index",
+        "--extract-strings",
+        "--strings-dedupe",
+        "Content",
+    ])
+    .unwrap();
+    assert!(cli.index.extract_strings && cli.index.strings_dedupe && !cli.index.strings_unfiltered);
+    assert!(Cli::try_parse_from(["uasset-index", "--strings-dedupe", "Content"]).is_err());
+
+    let cli = Cli::try_parse_from([
+        "uasset-index",
+        "--prefer",
+        "Override",
+        "--prefer",
+        "Patch",
+        "Mods",
+    ])
+    .unwrap();
+    assert_eq!(
+        vec![PathBuf::from("Override"), PathBuf::from("Patch")],
+        cli.index.prefer
+    );
+
+    let cli = Cli::try_parse_from([
+        "uasset-index",
+        "--format",
+        "ndjson",
+        "--output",
+        "-",
+        "Content",
+    ])
+    .unwrap();
+    assert_eq!(vec![OutputFormat::Ndjson], cli.index.formats);
+    assert_eq!(Some(PathBuf::from("-")), cli.index.output);
+
+    let cli = Cli::try_parse_from(["uasset-index", "Content"]).unwrap();
+    assert_eq!(DEFAULT_MAX_STRING_BYTES, cli.index.max_string_bytes);
+    assert!(!cli.index.layout);
+    assert!(
+        Cli::try_parse_from(["uasset-index", "--layout", "Content"])
+            .unwrap()
+            .index
+            .layout
+    );
+    let cli =
+        Cli::try_parse_from(["uasset-index", "--max-string-bytes", "256", "Content"]).unwrap();
+    assert_eq!(256, cli.index.max_string_bytes);
+    assert!(Cli::try_parse_from(["uasset-index", "--max-string-bytes", "0", "Content"]).is_err());
+    assert_eq!(DEFAULT_MAX_DUMP_SIZE, cli.index.max_dump_size);
+    let cli =
+        Cli::try_parse_from(["uasset-index", "--max-dump-size", "1000000", "Content"]).unwrap();
+    assert_eq!(1_000_000, cli.index.max_dump_size);
+
+    let cli = Cli::try_parse_from(["uasset-index", "--theme", "dark", "Content"]).unwrap();
+    assert_eq!(Theme::Dark, cli.index.theme);
+    assert!(Cli::try_parse_from([
+        "uasset-index",
+        "--theme",
+        "dark",
+        "--style",
+        "my.css",
+        "Content"
+    ])
+    .is_err());
+
+    let cli = Cli::try_parse_from(["uasset-index", "--collapse-depth", "4", "Content"]).unwrap();
+    assert_eq!(4, cli.index.collapse_depth);
+    assert!(!cli.index.no_collapse);
+    assert!(Cli::try_parse_from([
+        "uasset-index",
+        "--no-collapse",
+        "--collapse-depth",
+        "4",
+        "Content"
+    ])
+    .is_err());
+
+    let cli = Cli::try_parse_from(["uasset-index", "--compress", "gzip", "Content"]).unwrap();
+    assert_eq!(Some(Compression::Gzip), cli.index.compress);
+    assert!(Cli::try_parse_from(["uasset-index", "--compress", "zip", "Content"]).is_err());
+}
+
+#[test]
+fn test_cli_overrides_config() {
+    use clap::{CommandFactory, FromArgMatches};
+    let parse = |args: &[&str]| {
+        let matches = Cli::command().try_get_matches_from(args).unwrap();
+        let given = given_on_command_line(&matches);
+        (Cli::from_arg_matches(&matches).unwrap().index, given)
+    };
+    let config = Config {
+        output_dir: Some(PathBuf::from("Content/../out")),
+        excludes: vec!["Movies/**".to_string()],
+        formats: Some(vec![OutputFormat::Json]),
+        ..Default::default()
+    };
+    let nested = Config {
+        output_dir: Some(PathBuf::from("elsewhere")),
+        excludes: vec!["Cooked/*.umap".to_string()],
+        ..Default::default()
+    };
+
+    let (mut args, mut given) = parse(&["uasset-index", "Content"]);
+    assert!(given.is_empty());
+    args.apply_config(&config, &mut given);
+    args.apply_config(&nested, &mut given);
+    assert_eq!(Some(PathBuf::from("Content/../out")), args.output_dir);
+    assert_eq!(vec![OutputFormat::Json], args.formats);
+    assert_eq!(vec!["Movies/**", "Cooked/*.umap"], args.exclude);
+
+    let (mut args, mut given) = parse(&[
+        "uasset-index",
+        "--format",
+        "text",
+        "--exclude",
+        "Maps/**",
+        "--engine-version",
+        "4.22",
+        "Content",
+    ]);
+    assert_eq!(
+        HashSet::from(["formats", "exclude", "engine_version"]),
+        given
+    );
+    args.apply_config(&config, &mut given);
+    assert_eq!(Some(PathBuf::from("Content/../out")), args.output_dir);
+    assert_eq!(vec![OutputFormat::Text], args.formats);
+    assert_eq!(vec!["Maps/**"], args.exclude);
+    assert_eq!(EngineVersion::VER_UE4_22, args.engine_version);
+
+    let (args, _) = parse(&["uasset-index", "--no-config", "Content"]);
+    assert!(args.no_config);
+}
+
+#[test]
+fn test_cli_diff_subcommand() {
+    let cli = Cli::try_parse_from(["uasset-index", "diff", "old/Hero.uasset", "new/Hero.uasset"])
+        .unwrap();
+    let Some(Command::Diff(args)) = cli.command else {
+        panic!("expected the diff subcommand");
+    };
+    assert_eq!(PathBuf::from("old/Hero.uasset"), args.old);
+    assert_eq!(PathBuf::from("new/Hero.uasset"), args.new);
+    assert_eq!(PathBuf::from("diff.html"), args.output);
+    assert!(Cli::try_parse_from(["uasset-index", "diff", "old/Hero.uasset"]).is_err());
+}
+
+#[test]
+fn test_cli_grep_subcommand() {
+    let cli = Cli::try_parse_from([
+        "uasset-index",
+        "grep",
+        "-i",
+        "--class",
+        "DataTable,Quest*",
+        "QuestID_017",
+        "Content",
+    ])
+    .unwrap();
+    let Some(Command::Grep(args)) = cli.command else {
+        panic!("expected the grep subcommand");
+    };
+    assert_eq!("QuestID_017", args.pattern);
+    assert_eq!(vec![PathBuf::from("Content")], args.paths);
+    assert!(args.ignore_case && !args.regex);
+    assert_eq!(vec!["DataTable", "Quest*"], args.class);
+    assert!(Cli::try_parse_from(["uasset-index", "grep", "QuestID_017"]).is_err());
+}
+
+#[test]
+fn test_cli_find_references_subcommand() {
+    let cli = Cli::try_parse_from([
+        "uasset-index",
+        "find-references",
+        "--package",
+        "--json",
+        "/Game/Hero/Hero_BP.Hero_BP_C",
+        "Content",
+    ])
+    .unwrap();
+    let Some(Command::FindReferences(args)) = cli.command else {
+        panic!("expected the find-references subcommand");
+    };
+    assert_eq!("/Game/Hero/Hero_BP.Hero_BP_C", args.object_path);
+    assert_eq!(vec![PathBuf::from("Content")], args.paths);
+    assert!(args.package && args.json);
+    assert!(Cli::try_parse_from(["uasset-index", "find-references", "/Game/Hero"]).is_err());
+}
+
+#[test]
+fn test_cli_list_subcommand() {
+    let cli = Cli::try_parse_from(["uasset-index", "list", "Hero.uasset"]).unwrap();
+    let Some(Command::List(args)) = cli.command else {
+        panic!("expected the list subcommand");
+    };
+    assert_eq!(PathBuf::from("Hero.uasset"), args.path);
+    assert_eq!(ListTable::Exports, args.table());
+    assert_eq!(ListFormat::Tsv, args.format);
+
+    let cli = Cli::try_parse_from([
+        "uasset-index",
+        "list",
+        "--imports",
+        "--format",
+        "json",
+        "Hero.uasset",
+    ])
+    .unwrap();
+    let Some(Command::List(args)) = cli.command else {
+        panic!("expected the list subcommand");
+    };
+    assert_eq!(ListTable::Imports, args.table());
+    assert_eq!(ListFormat::Json, args.format);
+    assert!(Cli::try_parse_from([
+        "uasset-index",
+        "list",
+        "--imports",
+        "--names",
+        "Hero.uasset"
+    ])
+    .is_err());
+}
+
+#[test]
+fn test_cli_clean_subcommand() {
+    let cli = Cli::try_parse_from(["uasset-index", "clean", "Content"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::Clean { dirs }) if dirs == vec![PathBuf::from("Content")]
+    ));
+}
@@ -0,0 +1,135 @@
+use std::io::{Result, Write};
+
+/// A block of the dump whose closing line hasn't been seen yet.
+struct Block {
+    /// The opening line, e.g. `properties: [`, as HTML.
+    summary: String,
+    body: String,
+    /// How many fields or elements it has directly.
+    children: usize,
+}
+
+/// Wraps each block nested inside a pretty-printed Debug dump in a
+/// `<details>` whose summary is the block's opening line and how many
+/// children it has, as the dump goes by line by line. Blocks nested
+/// `open_depth` deep or deeper start collapsed. The outermost value isn't
+/// wrapped, and a block's lines are held back until it closes, since the
+/// summary needs its count.
+pub struct DumpBlocks {
+    open_depth: usize,
+    /// How many blocks the current line is inside, the outermost included.
+    level: usize,
+    /// The blocks being wrapped, outermost first.
+    stack: Vec<Block>,
+}
+
+impl DumpBlocks {
+    pub fn new(open_depth: usize) -> Self {
+        DumpBlocks {
+            open_depth,
+            level: 0,
+            stack: Vec::new(),
+        }
+    }
+
+    fn emit<W: Write>(&mut self, sink: &mut W, html: &str) -> Result<()> {
+        match self.stack.last_mut() {
+            Some(block) => {
+                block.body += html;
+                Ok(())
+            }
+            None => sink.write_all(html.as_bytes()),
+        }
+    }
+
+    /// Takes the next line of the dump: `raw` as Debug wrote it, to tell
+    /// where blocks start and end, and `html` the same line escaped and
+    /// linked, line break included. Index linking only ever touches the
+    /// middle of a line, so the two always agree on the brackets.
+    pub fn push_line<W: Write>(&mut self, sink: &mut W, raw: &str, html: &str) -> Result<()> {
+        let trimmed = raw.trim();
+        if trimmed.starts_with(['}', ']', ')']) {
+            self.level = self.level.saturating_sub(1);
+            if self.level > 0 {
+                if let Some(block) = self.stack.pop() {
+                    let open = if self.level < self.open_depth {
+                        " open"
+                    } else {
+                        ""
+                    };
+                    let details = format!(
+                        "<details{}><summary>{} ({})</summary>{}{}</details>",
+                        open,
+                        block.summary,
+                        block.children,
+                        block.body,
+                        html.trim_end_matches('\n')
+                    );
+                    return self.emit(sink, &details);
+                }
+            }
+            return self.emit(sink, html);
+        }
+        if let Some(parent) = self.stack.last_mut() {
+            parent.children += 1;
+        }
+        if trimmed.trim_end_matches(',').ends_with(['{', '[', '(']) {
+            self.level += 1;
+            if self.level > 1 {
+                self.stack.push(Block {
+                    summary: html.trim_end_matches('\n').to_string(),
+                    body: String::new(),
+                    children: 0,
+                });
+                return Ok(());
+            }
+        }
+        self.emit(sink, html)
+    }
+
+    /// Writes out whatever is still held back, unwrapped, had the dump ended
+    /// inside a block.
+    pub fn finish<W: Write>(&mut self, sink: &mut W) -> Result<()> {
+        for block in std::mem::take(&mut self.stack) {
+            sink.write_all(block.summary.as_bytes())?;
+            sink.write_all(b"\n")?;
+            sink.write_all(block.body.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+fn collapse(dump: &str, open_depth: usize) -> String {
+    let mut blocks = DumpBlocks::new(open_depth);
+    let mut out = Vec::new();
+    for line in dump.split_inclusive('\n') {
+        blocks.push_line(&mut out, line, line).unwrap();
+    }
+    blocks.finish(&mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn test_dump_blocks() {
+    let dump = "Export {\n    name: \"Door {\",\n    base: Base {\n        serial: [\n            1,\n            2,\n        ],\n        flags: 3,\n    },\n    empty: [],\n}";
+    assert_eq!(
+        concat!(
+            "Export {\n    name: \"Door {\",\n",
+            "<details open><summary>    base: Base { (2)</summary>",
+            "<details><summary>        serial: [ (2)</summary>",
+            "            1,\n            2,\n        ],</details>",
+            "        flags: 3,\n    },</details>",
+            "    empty: [],\n}",
+        ),
+        collapse(dump, 2)
+    );
+    // Everything below the outermost value starts open.
+    assert!(!collapse(dump, 10).contains("<details>"));
+}
+
+#[test]
+fn test_dump_blocks_flattens_unclosed_blocks() {
+    let dump = "Export {\n    base: Base {\n        flags: 3,\n";
+    assert_eq!(dump, collapse(dump, 2));
+}
@@ -0,0 +1,99 @@
+use flate2::{read::GzDecoder, write::GzEncoder};
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Result, Write};
+use std::path::{Path, PathBuf};
+
+use crate::manifest::{temp_path, write_atomically};
+
+/// How `--compress` stores the generated files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Compression {
+    /// Each file as `<name>.gz`, for servers that serve precompressed
+    /// files. Links keep the plain names.
+    Gzip,
+}
+
+/// Where the gzipped copy of `path` goes: its name with `.gz` added.
+pub fn gz_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".gz");
+    path.with_file_name(name)
+}
+
+/// Whether `path` is stored compressed. Thumbnails already are.
+pub fn is_compressed(compression: Option<Compression>, path: &Path) -> bool {
+    let image = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("png") || ext.eq_ignore_ascii_case("jpg"));
+    compression.is_some() && !image
+}
+
+#[test]
+fn test_is_compressed() {
+    let gzip = Some(Compression::Gzip);
+    assert!(is_compressed(gzip, Path::new("Hero/exports/1/index.html")));
+    assert!(is_compressed(gzip, Path::new("Hero/asset.json")));
+    assert!(!is_compressed(gzip, Path::new("Hero/thumbnail.png")));
+    assert!(!is_compressed(None, Path::new("Hero/index.html")));
+}
+
+/// Gzips the file at `from` into a new file at `to`.
+pub fn gzip_file(from: &Path, to: &Path) -> Result<()> {
+    let mut reader = BufReader::new(File::open(from)?);
+    let mut encoder = GzEncoder::new(
+        BufWriter::new(File::create(to)?),
+        flate2::Compression::default(),
+    );
+    std::io::copy(&mut reader, &mut encoder)?;
+    encoder.finish()?.flush()
+}
+
+/// Whether the page at `path` was generated, compressed or not.
+pub fn page_exists(path: &Path) -> bool {
+    path.exists() || gz_path(path).exists()
+}
+
+/// Reads a generated page, from its `.gz` when it was only written
+/// compressed.
+pub fn read_page(path: &Path) -> Result<String> {
+    let gz = gz_path(path);
+    if path.exists() || !gz.exists() {
+        return fs::read_to_string(path);
+    }
+    let mut contents = String::new();
+    GzDecoder::new(BufReader::new(File::open(gz)?)).read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+/// Replaces a page read with `read_page`, keeping it compressed if it was.
+pub fn rewrite_page(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    let gz = gz_path(path);
+    if path.exists() || !gz.exists() {
+        return write_atomically(path, contents);
+    }
+    let temp = temp_path(&gz);
+    let mut encoder = GzEncoder::new(
+        BufWriter::new(File::create(&temp)?),
+        flate2::Compression::default(),
+    );
+    encoder.write_all(contents.as_ref())?;
+    encoder.finish()?.flush()?;
+    fs::rename(&temp, &gz).map_err(|err| {
+        let _ = fs::remove_file(&temp);
+        err
+    })
+}
+
+#[test]
+fn test_rewrite_compressed_page() {
+    let dir = tempfile::tempdir().unwrap();
+    let page = dir.path().join("index.html");
+    fs::write(dir.path().join("plain.html"), "<h1>Hero</h1>").unwrap();
+    gzip_file(&dir.path().join("plain.html"), &gz_path(&page)).unwrap();
+    assert!(page_exists(&page));
+    assert_eq!("<h1>Hero</h1>", read_page(&page).unwrap());
+
+    rewrite_page(&page, "<h1>Hero</h1><p>Resolves</p>").unwrap();
+    assert!(!page.exists());
+    assert_eq!("<h1>Hero</h1><p>Resolves</p>", read_page(&page).unwrap());
+}
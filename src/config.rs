@@ -0,0 +1,383 @@
+use clap::ValueEnum;
+use serde::{de::Error as _, Deserialize, Deserializer};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use unreal_asset::engine_version::EngineVersion;
+
+use crate::{parse_engine_version, version_map::canonical_parent, Mappings, OutputFormat};
+
+/// The name of the config file looked for in the directories being indexed.
+pub const CONFIG_FILE: &str = "uasset-index.toml";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("failed to read {}: {}", .0.display(), .1)]
+    Read(PathBuf, #[source] std::io::Error),
+    #[error("{}: {}", .0.display(), .1)]
+    Toml(PathBuf, #[source] toml::de::Error),
+    #[error("{}: {} can only be set in the config of a directory given on the command line", .0.display(), .1)]
+    RootOnly(PathBuf, &'static str),
+    #[error("{}: failed to load mappings {}: {}", .0.display(), .1.display(), .2)]
+    Mappings(PathBuf, PathBuf, #[source] unreal_asset::Error),
+}
+
+/// What a `uasset-index.toml` holds, with the same meaning as the command
+/// line options of the same name.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    #[serde(default, deserialize_with = "engine_version")]
+    engine_version: Option<EngineVersion>,
+    usmap: Option<PathBuf>,
+    output_dir: Option<PathBuf>,
+    #[serde(default)]
+    excludes: Vec<String>,
+    #[serde(default, deserialize_with = "formats")]
+    formats: Option<Vec<OutputFormat>>,
+}
+
+fn engine_version<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<EngineVersion>, D::Error> {
+    let version = String::deserialize(deserializer)?;
+    match parse_engine_version(&version) {
+        Some(engine_version) => Ok(Some(engine_version)),
+        None => Err(D::Error::custom(format!(
+            "unknown engine version {:?}, see --help for the supported versions",
+            version
+        ))),
+    }
+}
+
+fn formats<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Vec<OutputFormat>>, D::Error> {
+    let names = Vec::<String>::deserialize(deserializer)?;
+    let mut formats = Vec::with_capacity(names.len());
+    for name in names {
+        match OutputFormat::from_str(&name, true) {
+            Ok(format) => formats.push(format),
+            Err(_) => {
+                return Err(D::Error::custom(format!(
+                    "unknown format {:?}, expected html, json, text or csv",
+                    name
+                )))
+            }
+        }
+    }
+    Ok(Some(formats))
+}
+
+/// A `uasset-index.toml` found in one of the directories being indexed.
+/// `usmap` and `output_dir` are relative to the directory the file is in,
+/// and `excludes` to the directory given on the command line, so they can
+/// join the `--exclude` patterns.
+#[derive(Debug, Default, PartialEq)]
+pub struct Config {
+    pub path: PathBuf,
+    pub engine_version: Option<EngineVersion>,
+    pub usmap: Option<PathBuf>,
+    pub output_dir: Option<PathBuf>,
+    pub excludes: Vec<String>,
+    pub formats: Option<Vec<OutputFormat>>,
+}
+
+impl Config {
+    /// Parses the config file at `path`. Unknown keys and bad values are
+    /// errors, with the line they're on.
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| ConfigError::Read(path.to_path_buf(), err))?;
+        let file: ConfigFile =
+            toml::from_str(&text).map_err(|err| ConfigError::Toml(path.to_path_buf(), err))?;
+        let dir = path.parent().unwrap_or(Path::new(""));
+        Ok(Config {
+            path: path.to_path_buf(),
+            engine_version: file.engine_version,
+            usmap: file.usmap.map(|usmap| dir.join(usmap)),
+            output_dir: file.output_dir.map(|output_dir| dir.join(output_dir)),
+            excludes: file.excludes,
+            formats: file.formats,
+        })
+    }
+
+    /// The directory the file is in, whose assets it applies to.
+    pub fn dir(&self) -> &Path {
+        self.path.parent().unwrap_or(Path::new(""))
+    }
+}
+
+#[test]
+fn test_config_load() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(CONFIG_FILE);
+    std::fs::write(
+        &path,
+        "engine_version = \"4.25\"\nusmap = \"Mappings.usmap\"\n\
+         excludes = [\"Movies/**\"]\nformats = [\"html\", \"JSON\"]\n",
+    )
+    .unwrap();
+    assert_eq!(
+        Config {
+            path: path.clone(),
+            engine_version: Some(EngineVersion::VER_UE4_25),
+            usmap: Some(dir.path().join("Mappings.usmap")),
+            output_dir: None,
+            excludes: vec!["Movies/**".to_string()],
+            formats: Some(vec![OutputFormat::Html, OutputFormat::Json]),
+        },
+        Config::load(&path).unwrap()
+    );
+
+    std::fs::write(
+        &path,
+        "usmap = \"Mappings.usmap\"\nexclude = [\"Movies/**\"]\n",
+    )
+    .unwrap();
+    let err = Config::load(&path).unwrap_err().to_string();
+    assert!(err.contains("line 2"), "{}", err);
+    assert!(err.contains("unknown field `exclude`"), "{}", err);
+    std::fs::write(&path, "\n\nengine_version = \"4.99\"\n").unwrap();
+    let err = Config::load(&path).unwrap_err().to_string();
+    assert!(err.contains("line 3"), "{}", err);
+    assert!(err.contains("unknown engine version \"4.99\""), "{}", err);
+    std::fs::write(&path, "formats = [\"pdf\"]\n").unwrap();
+    assert!(Config::load(&path)
+        .unwrap_err()
+        .to_string()
+        .contains("unknown format \"pdf\""));
+}
+
+/// Loads the config file in `dir`, if there is one. `prefix` is the path of
+/// `dir` below the directory given on the command line, `None` for that
+/// directory itself.
+fn load_dir(dir: &Path, prefix: Option<&str>) -> Result<Option<Config>, ConfigError> {
+    let path = dir.join(CONFIG_FILE);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let mut config = Config::load(&path)?;
+    if let Some(prefix) = prefix {
+        if config.output_dir.is_some() {
+            return Err(ConfigError::RootOnly(path, "output_dir"));
+        }
+        if config.formats.is_some() {
+            return Err(ConfigError::RootOnly(path, "formats"));
+        }
+        for pattern in &mut config.excludes {
+            *pattern = format!("{}/{}", prefix, pattern);
+        }
+    }
+    Ok(Some(config))
+}
+
+/// Collects the config files below `dir`, in file name order, `prefix`
+/// being the path of `dir` below the directory given on the command line.
+/// Directories that can't be listed are left for the walk that indexes them
+/// to report.
+fn find_below(dir: &Path, prefix: &str, configs: &mut Vec<Config>) -> Result<(), ConfigError> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Ok(());
+    };
+    let mut subdirs: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .map(|entry| entry.path())
+        .collect();
+    subdirs.sort();
+    for subdir in subdirs {
+        let name = subdir.file_name().unwrap_or_default().to_string_lossy();
+        let prefix = if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        configs.extend(load_dir(&subdir, Some(&prefix))?);
+        find_below(&subdir, &prefix, configs)?;
+    }
+    Ok(())
+}
+
+/// The config files for indexing `paths`: each directory's own, then those
+/// of its subdirectories, and for a file or `.pak` the one next to it. Files
+/// reached from more than one path are loaded once.
+pub fn discover(paths: &[PathBuf]) -> Result<Vec<Config>, ConfigError> {
+    let mut configs: Vec<Config> = Vec::new();
+    let mut roots = HashSet::new();
+    for path in paths {
+        let root = if path.is_dir() {
+            path.as_path()
+        } else {
+            match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent,
+                _ => Path::new("."),
+            }
+        };
+        if !roots.insert(root.to_path_buf()) {
+            continue;
+        }
+        let mut found = Vec::new();
+        found.extend(load_dir(root, None)?);
+        if path.is_dir() {
+            find_below(root, "", &mut found)?;
+        }
+        for config in found {
+            if !configs.iter().any(|seen| seen.path == config.path) {
+                configs.push(config);
+            }
+        }
+    }
+    Ok(configs)
+}
+
+#[test]
+fn test_discover() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().join("Content");
+    std::fs::create_dir_all(root.join("Cooked/Maps")).unwrap();
+    std::fs::create_dir_all(root.join("Raw")).unwrap();
+    std::fs::write(root.join(CONFIG_FILE), "output_dir = \"../out\"\n").unwrap();
+    std::fs::write(
+        root.join("Cooked/Maps").join(CONFIG_FILE),
+        "usmap = \"Game.usmap\"\nexcludes = [\"*.umap\"]\n",
+    )
+    .unwrap();
+
+    let configs = discover(&[root.clone(), root.join("Raw")]).unwrap();
+    assert_eq!(2, configs.len());
+    assert_eq!(Some(root.join("../out")), configs[0].output_dir);
+    assert_eq!(root.join("Cooked/Maps"), configs[1].dir());
+    assert_eq!(vec!["Cooked/Maps/*.umap".to_string()], configs[1].excludes);
+    // A file picks up the config next to it, as the top of its tree.
+    let configs = discover(&[root.join("Cooked/Maps/Arena.umap")]).unwrap();
+    assert_eq!(vec!["*.umap".to_string()], configs[0].excludes);
+    assert!(discover(&[root.join("Raw")]).unwrap().is_empty());
+
+    std::fs::write(root.join("Raw").join(CONFIG_FILE), "formats = [\"json\"]\n").unwrap();
+    assert!(matches!(
+        discover(&[root.clone()]),
+        Err(ConfigError::RootOnly(_, "formats"))
+    ));
+}
+
+/// A config file's engine version and mappings, for the assets below the
+/// directory it's in.
+struct DirectoryConfig {
+    dir: PathBuf,
+    engine_version: Option<EngineVersion>,
+    mappings: Option<Mappings>,
+}
+
+/// The engine versions and mappings the config files of a run set, applied
+/// like `--version-map`: the config nearest to an asset that sets one wins.
+#[derive(Default)]
+pub struct DirectoryConfigs {
+    /// Canonical directory paths, longest first.
+    dirs: Vec<DirectoryConfig>,
+}
+
+impl DirectoryConfigs {
+    /// Loads the mappings the `configs` name. Engine versions or mappings
+    /// are left out when the command line gives its own, which overrides
+    /// every config file.
+    pub fn new(
+        configs: &[Config],
+        engine_versions: bool,
+        usmaps: bool,
+    ) -> Result<Self, ConfigError> {
+        let mut dirs = Vec::new();
+        for config in configs {
+            let engine_version = config.engine_version.filter(|_| engine_versions);
+            let mappings = match config.usmap.as_ref().filter(|_| usmaps) {
+                Some(usmap) => Some(Mappings::load(usmap).map_err(|err| {
+                    ConfigError::Mappings(config.path.clone(), usmap.clone(), err)
+                })?),
+                None => None,
+            };
+            if engine_version.is_some() || mappings.is_some() {
+                dirs.push(DirectoryConfig {
+                    dir: crate::canonical_path(config.dir()),
+                    engine_version,
+                    mappings,
+                });
+            }
+        }
+        dirs.sort_by_key(|config| std::cmp::Reverse(config.dir.components().count()));
+        Ok(DirectoryConfigs { dirs })
+    }
+
+    fn nearest(&self, path: &Path) -> impl Iterator<Item = &DirectoryConfig> {
+        // Canonicalizing goes to the disk, so skip it when nothing can match.
+        let dir = (!self.dirs.is_empty()).then(|| canonical_parent(path));
+        self.dirs
+            .iter()
+            .filter(move |config| dir.as_ref().is_some_and(|dir| dir.starts_with(&config.dir)))
+    }
+
+    /// The engine version for the asset at `path`, if a config above it
+    /// sets one.
+    pub fn engine_version(&self, path: &Path) -> Option<EngineVersion> {
+        self.nearest(path).find_map(|config| config.engine_version)
+    }
+
+    /// The mappings for the asset at `path`, if a config above it names
+    /// some.
+    pub fn mappings(&self, path: &Path) -> Option<&Mappings> {
+        self.nearest(path)
+            .find_map(|config| config.mappings.as_ref())
+    }
+}
+
+impl std::fmt::Debug for DirectoryConfigs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.dirs.iter().map(|config| {
+                (
+                    &config.dir,
+                    config.engine_version,
+                    config.mappings.as_ref().map(|mappings| &mappings.name),
+                )
+            }))
+            .finish()
+    }
+}
+
+#[test]
+fn test_directory_configs() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().join("Content");
+    std::fs::create_dir_all(root.join("Old/Maps")).unwrap();
+    let configs = [
+        Config {
+            path: root.join(CONFIG_FILE),
+            engine_version: Some(EngineVersion::VER_UE4_27),
+            ..Default::default()
+        },
+        Config {
+            path: root.join("Old").join(CONFIG_FILE),
+            engine_version: Some(EngineVersion::VER_UE4_22),
+            ..Default::default()
+        },
+    ];
+    let directory_configs = DirectoryConfigs::new(&configs, true, true).unwrap();
+    let version = |path: &str| directory_configs.engine_version(&root.join(path));
+    assert_eq!(Some(EngineVersion::VER_UE4_27), version("Hero.uasset"));
+    assert_eq!(
+        Some(EngineVersion::VER_UE4_22),
+        version("Old/Maps/Arena.umap")
+    );
+    assert_eq!(
+        None,
+        directory_configs.engine_version(&dir.path().join("Hero.uasset"))
+    );
+    assert!(directory_configs
+        .mappings(&root.join("Hero.uasset"))
+        .is_none());
+
+    // --engine-version on the command line beats every config.
+    let directory_configs = DirectoryConfigs::new(&configs, false, true).unwrap();
+    assert_eq!(
+        None,
+        directory_configs.engine_version(&root.join("Old/Hero.uasset"))
+    );
+}
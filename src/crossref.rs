@@ -0,0 +1,731 @@
+use regex::{NoExpand, Regex};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Result;
+use std::path::{Component, Path, PathBuf};
+
+use crate::{
+    compress::{read_page, rewrite_page},
+    escape_html,
+    formatters::localized_text_html,
+    properties::LocalizedText,
+    string_table::key_anchor,
+    AssetSummary,
+};
+
+/// The engine's package path for an asset file, e.g. `/Game/Weapons/Rifle` for
+/// `MyGame/Content/Weapons/Rifle.uasset`. Plugin and engine content mount
+/// under their own name; files outside any `Content` folder are placed under
+/// `/Game` relative to `root`.
+pub fn package_path(path: &Path, root: &Path) -> String {
+    let path = path.with_extension("");
+    let components: Vec<String> = path
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect();
+    let (mount, rest) = match components.iter().rposition(|c| c == "Content") {
+        Some(pos) if pos > 0 && components[..pos].iter().any(|c| c == "Plugins") => {
+            (components[pos - 1].as_str(), &components[pos + 1..])
+        }
+        Some(pos) if pos > 0 && components[pos - 1] == "Engine" => {
+            ("Engine", &components[pos + 1..])
+        }
+        Some(pos) => ("Game", &components[pos + 1..]),
+        None => {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            let skip = components.len() - relative.components().count();
+            ("Game", &components[skip..])
+        }
+    };
+    format!("/{}/{}", mount, rest.join("/"))
+}
+
+#[test]
+fn test_package_path() {
+    let root = Path::new("Extracted");
+    assert_eq!(
+        "/Game/Weapons/Rifle",
+        package_path(
+            Path::new("Extracted/MyGame/Content/Weapons/Rifle.uasset"),
+            root
+        )
+    );
+    assert_eq!(
+        "/Shooter/Maps/Arena",
+        package_path(
+            Path::new("Extracted/MyGame/Plugins/GameFeatures/Shooter/Content/Maps/Arena.umap"),
+            root
+        )
+    );
+    assert_eq!(
+        "/Engine/BasicShapes/Cube",
+        package_path(
+            Path::new("Extracted/Engine/Content/BasicShapes/Cube.uasset"),
+            root
+        )
+    );
+    assert_eq!(
+        "/Game/Loose/Thing",
+        package_path(Path::new("Extracted/Loose/Thing.uasset"), root)
+    );
+}
+
+/// A relative URL from the directory `from` to the directory `to`, both built
+/// from the same output root.
+pub fn relative_url(from: &Path, to: &Path) -> String {
+    let from: Vec<Component> = from.components().collect();
+    let to: Vec<Component> = to.components().collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+    let mut parts = vec!["..".to_string(); from.len() - common];
+    parts.extend(
+        to[common..]
+            .iter()
+            .map(|component| component.as_os_str().to_string_lossy().to_string()),
+    );
+    if parts.is_empty() {
+        ".".to_string()
+    } else {
+        parts.join("/")
+    }
+}
+
+#[test]
+fn test_relative_url() {
+    assert_eq!(
+        "../../../Rifle/exports/3",
+        relative_url(
+            Path::new("out/Weapons/Gun/imports/2"),
+            Path::new("out/Weapons/Rifle/exports/3")
+        )
+    );
+    assert_eq!(".", relative_url(Path::new("out/a"), Path::new("out/a")));
+}
+
+/// Where an import of an indexed asset points: the outermost package of its
+/// outer chain and the imported object's name.
+#[derive(Serialize, Deserialize)]
+pub struct ImportTarget {
+    pub package: String,
+    pub object_name: String,
+}
+
+/// The page for the export at position `i` of `asset`, or the asset's own
+/// page when `--filter-class` left the export without one.
+fn export_dir(asset: &AssetSummary, i: usize) -> PathBuf {
+    if asset.export_pages.get(i).copied().unwrap_or(true) {
+        asset.main_dir.join("exports").join((i + 1).to_string())
+    } else {
+        asset.main_dir.clone()
+    }
+}
+
+/// The assets of a run by their package path, lowercased, the way imports,
+/// soft references and string table entries name them. Copies shadowed by
+/// another file with the same package path are left out.
+fn packages_by_path<'a>(assets: &[&'a AssetSummary]) -> HashMap<String, &'a AssetSummary> {
+    assets
+        .iter()
+        .filter(|asset| !asset.shadowed)
+        .map(|asset| (asset.package_path.to_lowercase(), *asset))
+        .collect()
+}
+
+/// Files of a directory run that map to the same package path, such as a
+/// loose override and the original, or `Hero.uasset` next to `Hero.umap`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PackageCollision {
+    pub package_path: String,
+    /// Every file with the package path, in walk order.
+    pub files: Vec<PathBuf>,
+    /// The one of `files` that links from other assets resolve to.
+    pub resolved: PathBuf,
+}
+
+/// The package paths more than one of `files`, each given with its package
+/// path, maps to. They're compared case-insensitively, as the engine does.
+/// Links resolve to the file under the earliest of the `prefer` roots, given
+/// as walked or relative to `root`, and otherwise to the first file walked.
+pub fn package_collisions(
+    root: &Path,
+    files: &[(&Path, &str)],
+    prefer: &[PathBuf],
+) -> Vec<PackageCollision> {
+    let mut groups: Vec<(&str, Vec<&Path>)> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    for &(file, package_path) in files {
+        match positions.get(&package_path.to_lowercase()) {
+            Some(&i) => groups[i].1.push(file),
+            None => {
+                positions.insert(package_path.to_lowercase(), groups.len());
+                groups.push((package_path, vec![file]));
+            }
+        }
+    }
+    let rank = |file: &Path| {
+        let relative = file.strip_prefix(root).unwrap_or(file);
+        prefer
+            .iter()
+            .position(|preferred| file.starts_with(preferred) || relative.starts_with(preferred))
+            .unwrap_or(prefer.len())
+    };
+    groups
+        .into_iter()
+        .filter(|(_, files)| files.len() > 1)
+        .map(|(package_path, files)| PackageCollision {
+            package_path: package_path.to_string(),
+            resolved: files
+                .iter()
+                .copied()
+                .min_by_key(|file| rank(file))
+                .unwrap()
+                .to_path_buf(),
+            files: files.into_iter().map(Path::to_path_buf).collect(),
+        })
+        .collect()
+}
+
+#[test]
+fn test_package_collisions() {
+    let root = Path::new("Mods");
+    let files = [
+        (Path::new("Mods/Base/Content/Hero.uasset"), "/Game/Hero"),
+        (
+            Path::new("Mods/Base/Content/Maps/Hub.uasset"),
+            "/Game/Maps/Hub",
+        ),
+        (
+            Path::new("Mods/Base/Content/Maps/Hub.umap"),
+            "/Game/Maps/Hub",
+        ),
+        (Path::new("Mods/Base/Content/Rifle.uasset"), "/Game/Rifle"),
+        (Path::new("Mods/Override/Content/hero.uasset"), "/Game/hero"),
+    ];
+    let collisions = package_collisions(root, &files, &[]);
+    assert_eq!(
+        vec![
+            PackageCollision {
+                package_path: "/Game/Hero".to_string(),
+                files: vec![
+                    PathBuf::from("Mods/Base/Content/Hero.uasset"),
+                    PathBuf::from("Mods/Override/Content/hero.uasset"),
+                ],
+                resolved: PathBuf::from("Mods/Base/Content/Hero.uasset"),
+            },
+            PackageCollision {
+                package_path: "/Game/Maps/Hub".to_string(),
+                files: vec![
+                    PathBuf::from("Mods/Base/Content/Maps/Hub.uasset"),
+                    PathBuf::from("Mods/Base/Content/Maps/Hub.umap"),
+                ],
+                resolved: PathBuf::from("Mods/Base/Content/Maps/Hub.uasset"),
+            },
+        ],
+        collisions
+    );
+    // Roots count relative to the walked directory and as walked.
+    for preferred in ["Override", "Mods/Override"] {
+        let collisions = package_collisions(root, &files, &[PathBuf::from(preferred)]);
+        assert_eq!(
+            Path::new("Mods/Override/Content/hero.uasset"),
+            collisions[0].resolved
+        );
+        assert_eq!(
+            Path::new("Mods/Base/Content/Maps/Hub.uasset"),
+            collisions[1].resolved
+        );
+    }
+}
+
+/// The asset among `packages` that the object path `path` points into, e.g.
+/// `/Game/Maps/Hub.Hub:PersistentLevel.Actor_3`, with the position of its
+/// top-level export when the asset has it.
+fn resolve_object_path<'a>(
+    path: &str,
+    packages: &HashMap<String, &'a AssetSummary>,
+) -> Option<(&'a AssetSummary, Option<usize>)> {
+    let object_path = path.split(':').next().unwrap();
+    let (package, object_name) = match object_path.split_once('.') {
+        Some((package, object_name)) => (package, Some(object_name)),
+        None => (object_path, None),
+    };
+    let asset = *packages.get(&package.to_lowercase())?;
+    let export = object_name.and_then(|object_name| {
+        asset
+            .export_names
+            .iter()
+            .position(|name| name == object_name)
+    });
+    Some((asset, export))
+}
+
+/// Describes where an import resolves within the set of `packages` indexed in
+/// this run, as HTML for a page at `from_dir`.
+fn resolve_import(
+    target: &ImportTarget,
+    from_dir: &Path,
+    packages: &HashMap<String, &AssetSummary>,
+) -> String {
+    let Some(asset) = packages.get(&target.package.to_lowercase()) else {
+        return format!(
+            "<p>Resolves to external package {}</p>",
+            escape_html(&target.package)
+        );
+    };
+    if target.package == target.object_name {
+        return format!(
+            "<p>Resolves to <a href=\"{}\">{}</a></p>",
+            relative_url(from_dir, &asset.main_dir),
+            escape_html(&target.package)
+        );
+    }
+    match asset
+        .export_names
+        .iter()
+        .position(|name| *name == target.object_name)
+    {
+        Some(i) => format!(
+            "<p>Resolves to <a href=\"{}\">{}.{}</a></p>",
+            relative_url(from_dir, &export_dir(asset, i)),
+            escape_html(&target.package),
+            escape_html(&target.object_name)
+        ),
+        None => format!(
+            "<p>Resolves to package <a href=\"{}\">{}</a>, which has no export named {}</p>",
+            relative_url(from_dir, &asset.main_dir),
+            escape_html(&target.package),
+            escape_html(&target.object_name)
+        ),
+    }
+}
+
+/// Where the line `link_imports` adds to an import page starts.
+const RESOLVES_TO: &str = "<p>Resolves to ";
+
+/// `contents` with `html` in place of the "resolves to" line an earlier run
+/// added, or right after the page's heading when it has none yet.
+fn with_resolution(contents: &str, html: &str) -> String {
+    let mut contents = contents.to_string();
+    if let Some(start) = contents.find(RESOLVES_TO) {
+        let end = contents[start..]
+            .find("</p>")
+            .map_or(contents.len(), |i| start + i + "</p>".len());
+        contents.replace_range(start..end, html);
+    } else {
+        let position = contents.find("</h1>").map_or(0, |i| i + "</h1>".len());
+        contents.insert_str(position, html);
+    }
+    contents
+}
+
+#[test]
+fn test_with_resolution() {
+    let external = "<p>Resolves to external package /Game/Door</p>";
+    let page = with_resolution("<h1>1 (Door)</h1><span>", external);
+    assert_eq!(format!("<h1>1 (Door)</h1>{}<span>", external), page);
+    let indexed = "<p>Resolves to <a href=\"../../../Door\">/Game/Door</a></p>";
+    assert_eq!(
+        format!("<h1>1 (Door)</h1>{}<span>", indexed),
+        with_resolution(&page, indexed)
+    );
+}
+
+/// `contents` with the links an earlier run put around `html` taken off.
+fn unlinked(contents: &str, html: &str) -> String {
+    let link = Regex::new(&format!("<a href=\"[^\"]*\">{}</a>", regex::escape(html))).unwrap();
+    link.replace_all(contents, NoExpand(html)).into_owned()
+}
+
+#[test]
+fn test_unlinked() {
+    assert_eq!(
+        "<li><code>/Game/Door</code></li><code>/Game/Key</code>",
+        unlinked(
+            "<li><a href=\"../Door\"><code>/Game/Door</code></a></li><code>/Game/Key</code>",
+            "<code>/Game/Door</code>"
+        )
+    );
+}
+
+/// Second pass over a directory run: adds a "resolves to" line to every import
+/// page, linking into the other assets indexed in the same run. Up-to-date
+/// assets get theirs replaced too, since the assets they import may have been
+/// added, removed or moved since they were written.
+pub fn link_imports(assets: &[&AssetSummary]) -> Result<()> {
+    let packages = packages_by_path(assets);
+    for asset in assets {
+        for (i, target) in asset.imports.iter().enumerate() {
+            let dir: PathBuf = asset.main_dir.join("imports").join((i + 1).to_string());
+            let html = resolve_import(target, &dir, &packages);
+            let page = dir.join("index.html");
+            let contents = read_page(&page)?;
+            let linked = with_resolution(&contents, &html);
+            if linked != contents {
+                rewrite_page(&page, linked)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_link_imports_relinks_up_to_date_assets() {
+    let dir = tempfile::tempdir().unwrap();
+    let hub = AssetSummary {
+        main_dir: dir.path().join("Hub"),
+        package_path: "/Game/Hub".to_string(),
+        imports: vec![ImportTarget {
+            package: "/Game/Door".to_string(),
+            object_name: "/Game/Door".to_string(),
+        }],
+        up_to_date: true,
+        ..Default::default()
+    };
+    let page = hub.main_dir.join("imports").join("1").join("index.html");
+    std::fs::create_dir_all(page.parent().unwrap()).unwrap();
+    // As an earlier run left it, before Door was indexed too.
+    std::fs::write(
+        &page,
+        "<h1>1</h1><p>Resolves to external package /Game/Door</p><span>",
+    )
+    .unwrap();
+    let door = AssetSummary {
+        main_dir: dir.path().join("Door"),
+        package_path: "/Game/Door".to_string(),
+        ..Default::default()
+    };
+    link_imports(&[&hub, &door]).unwrap();
+    assert_eq!(
+        "<h1>1</h1><p>Resolves to <a href=\"../../../Door\">/Game/Door</a></p><span>",
+        std::fs::read_to_string(&page).unwrap()
+    );
+}
+
+/// Where the bar `link_neighbors` adds to an asset's page starts.
+const NEIGHBORS_NAV: &str = "<nav class=\"asset-nav\">";
+
+/// `&larr; Door | up | Key &rarr;`: links from the page in `from` to the
+/// pages of the assets before and after it, given by name and directory,
+/// and up to the directory listing them.
+fn neighbors_nav(
+    from: &Path,
+    previous: Option<(&str, &Path)>,
+    next: Option<(&str, &Path)>,
+) -> String {
+    let mut links = Vec::new();
+    if let Some((name, dir)) = previous {
+        links.push(format!(
+            "<a href=\"{}\" rel=\"prev\">&larr; {}</a>",
+            escape_html(&relative_url(from, dir)),
+            escape_html(name)
+        ));
+    }
+    links.push("<a href=\"..\">up</a>".to_string());
+    if let Some((name, dir)) = next {
+        links.push(format!(
+            "<a href=\"{}\" rel=\"next\">{} &rarr;</a>",
+            escape_html(&relative_url(from, dir)),
+            escape_html(name)
+        ));
+    }
+    format!("{}{}</nav>", NEIGHBORS_NAV, links.join(" | "))
+}
+
+#[test]
+fn test_neighbors_nav() {
+    assert_eq!(
+        "<nav class=\"asset-nav\"><a href=\"../Door\" rel=\"prev\">&larr; Door</a> | \
+         <a href=\"..\">up</a> | <a href=\"../Key\" rel=\"next\">Key &rarr;</a></nav>",
+        neighbors_nav(
+            Path::new("out/Maps/Hub"),
+            Some(("Door", Path::new("out/Maps/Door"))),
+            Some(("Key", Path::new("out/Maps/Key")))
+        )
+    );
+    assert_eq!(
+        "<nav class=\"asset-nav\"><a href=\"..\">up</a></nav>",
+        neighbors_nav(Path::new("out/Maps/Hub"), None, None)
+    );
+}
+
+/// `contents` with `nav` in place of the bar an earlier run added, or right
+/// after the page's heading when it has none yet.
+fn with_neighbors_nav(contents: &str, nav: &str) -> String {
+    let mut contents = contents.to_string();
+    if let Some(start) = contents.find(NEIGHBORS_NAV) {
+        let end = contents[start..]
+            .find("</nav>")
+            .map_or(contents.len(), |i| start + i + "</nav>".len());
+        contents.replace_range(start..end, nav);
+    } else {
+        let position = contents.find("</h1>").map_or(0, |i| i + "</h1>".len());
+        contents.insert_str(position, nav);
+    }
+    contents
+}
+
+#[test]
+fn test_with_neighbors_nav() {
+    let nav = neighbors_nav(Path::new("out/Hub"), None, None);
+    let page = with_neighbors_nav("<h1>Hub/</h1><ul></ul>", &nav);
+    assert_eq!(format!("<h1>Hub/</h1>{}<ul></ul>", nav), page);
+    let moved = neighbors_nav(
+        Path::new("out/Hub"),
+        Some(("Door", Path::new("out/Door"))),
+        None,
+    );
+    assert_eq!(
+        format!("<h1>Hub/</h1>{}<ul></ul>", moved),
+        with_neighbors_nav(&page, &moved)
+    );
+}
+
+/// Second pass over a directory run: links each asset's page to the assets
+/// listed before and after it on its directory's page, and up to that page.
+/// Up-to-date assets get their links replaced too, since the assets around
+/// them may have changed since they were written.
+pub fn link_neighbors(assets: &[&AssetSummary]) -> Result<()> {
+    let mut by_dir: HashMap<&Path, Vec<&AssetSummary>> = HashMap::new();
+    for asset in assets {
+        if let Some(dir) = asset.main_dir.parent() {
+            by_dir.entry(dir).or_default().push(asset);
+        }
+    }
+    for siblings in by_dir.values() {
+        for (i, asset) in siblings.iter().enumerate() {
+            let neighbor = |j: Option<usize>| {
+                j.and_then(|j| siblings.get(j))
+                    .map(|other| (other.name.as_str(), other.main_dir.as_path()))
+            };
+            let nav = neighbors_nav(
+                &asset.main_dir,
+                neighbor(i.checked_sub(1)),
+                neighbor(Some(i + 1)),
+            );
+            let page = asset.main_dir.join("index.html");
+            let contents = read_page(&page)?;
+            let linked = with_neighbors_nav(&contents, &nav);
+            if linked != contents {
+                rewrite_page(&page, linked)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The list item an export page shows for the soft reference `path` before
+/// `link_soft_references` resolves it.
+pub fn soft_reference_item(path: &str) -> String {
+    format!("<li><code>{}</code></li>", escape_html(path))
+}
+
+/// Where the soft reference `path` points within the set of `packages`
+/// indexed in this run, as a URL relative to `from_dir`. Links to the
+/// top-level export when the asset has it, otherwise to the asset's index.
+fn resolve_soft_reference(
+    path: &str,
+    from_dir: &Path,
+    packages: &HashMap<String, &AssetSummary>,
+) -> Option<String> {
+    let (asset, export) = resolve_object_path(path, packages)?;
+    Some(match export {
+        Some(i) => relative_url(from_dir, &export_dir(asset, i)),
+        None => relative_url(from_dir, &asset.main_dir),
+    })
+}
+
+#[test]
+fn test_resolve_soft_reference() {
+    let hub = AssetSummary {
+        name: "Hub".to_string(),
+        export_count: 2,
+        import_count: 0,
+        main_dir: PathBuf::from("out/Maps/Hub"),
+        package_path: "/Game/Maps/Hub".to_string(),
+        shadowed: false,
+        export_names: vec!["PersistentLevel".to_string(), "Hub".to_string()],
+        export_pages: vec![true, true],
+        imports: Vec::new(),
+        soft_references: Vec::new(),
+        texts: Vec::new(),
+        strings: Vec::new(),
+        external: Vec::new(),
+        string_table_keys: Default::default(),
+        tables: Default::default(),
+        thumbnail: None,
+        generated: Vec::new(),
+        up_to_date: false,
+        warnings: Vec::new(),
+        parse_time: std::time::Duration::ZERO,
+        write_time: std::time::Duration::ZERO,
+        phase_times: Default::default(),
+        deduped: Default::default(),
+        truncated_dumps: Vec::new(),
+    };
+    let packages = HashMap::from([("/game/maps/hub".to_string(), &hub)]);
+    let from = Path::new("out/Menu/exports/1");
+    assert_eq!(
+        Some("../../../Maps/Hub/exports/2".to_string()),
+        resolve_soft_reference(
+            "/Game/Maps/Hub.Hub:PersistentLevel.Actor_3",
+            from,
+            &packages
+        )
+    );
+    assert_eq!(
+        Some("../../../Maps/Hub".to_string()),
+        resolve_soft_reference("/Game/Maps/Hub", from, &packages)
+    );
+    assert_eq!(
+        None,
+        resolve_soft_reference("/Game/Maps/Other.Other", from, &packages)
+    );
+}
+
+/// Second pass over a directory run: turns the soft references listed on
+/// export pages into links when they point at assets indexed in the same run.
+/// The links of up-to-date assets are redone like those of `link_imports`.
+pub fn link_soft_references(assets: &[&AssetSummary]) -> Result<()> {
+    let packages = packages_by_path(assets);
+    for asset in assets {
+        for (i, paths) in asset.soft_references.iter().enumerate() {
+            if paths.is_empty() || !asset.export_pages.get(i).copied().unwrap_or(true) {
+                continue;
+            }
+            let dir = export_dir(asset, i);
+            let page = dir.join("index.html");
+            let contents = read_page(&page)?;
+            let mut linked = contents.clone();
+            for path in paths {
+                let code = format!("<code>{}</code>", escape_html(path));
+                linked = unlinked(&linked, &code);
+                if let Some(url) = resolve_soft_reference(path, &dir, &packages) {
+                    linked = linked.replacen(
+                        &soft_reference_item(path),
+                        &format!("<li><a href=\"{}\">{}</a></li>", url, code),
+                        1,
+                    );
+                }
+            }
+            if linked != contents {
+                rewrite_page(&page, linked)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Where the entry `key` of the string table at `table` points within the
+/// set of `packages` indexed in this run, as a URL relative to `from_dir`:
+/// the row of the table's page for the key. `None` unless the table was
+/// indexed and has the key.
+fn resolve_string_table_entry(
+    table: &str,
+    key: &str,
+    from_dir: &Path,
+    packages: &HashMap<String, &AssetSummary>,
+) -> Option<String> {
+    let (asset, Some(i)) = resolve_object_path(table, packages)? else {
+        return None;
+    };
+    if !asset.string_table_keys.get(&i)?.contains(key) {
+        return None;
+    }
+    if !asset.export_pages.get(i).copied().unwrap_or(true) {
+        return Some(relative_url(from_dir, &asset.main_dir));
+    }
+    Some(format!(
+        "{}#{}",
+        relative_url(from_dir, &export_dir(asset, i)),
+        key_anchor(key)
+    ))
+}
+
+#[test]
+fn test_resolve_string_table_entry() {
+    let table = AssetSummary {
+        main_dir: PathBuf::from("out/Text/ST_Dialogue"),
+        package_path: "/Game/Text/ST_Dialogue".to_string(),
+        shadowed: false,
+        export_names: vec!["ST_Dialogue".to_string()],
+        export_pages: vec![true],
+        string_table_keys: [(0, ["Greeting".to_string()].into())].into(),
+        ..Default::default()
+    };
+    let packages = HashMap::from([("/game/text/st_dialogue".to_string(), &table)]);
+    let from = Path::new("out/NPC/exports/2");
+    assert_eq!(
+        Some("../../../Text/ST_Dialogue/exports/1#key-Greeting".to_string()),
+        resolve_string_table_entry(
+            "/Game/Text/ST_Dialogue.ST_Dialogue",
+            "Greeting",
+            from,
+            &packages
+        )
+    );
+    assert_eq!(
+        None,
+        resolve_string_table_entry(
+            "/Game/Text/ST_Dialogue.ST_Dialogue",
+            "Farewell",
+            from,
+            &packages
+        )
+    );
+    assert_eq!(
+        None,
+        resolve_string_table_entry("/Game/Text/ST_Dialogue", "Greeting", from, &packages)
+    );
+}
+
+/// Third pass over a directory run: links the texts export pages show as a
+/// string table entry to the entry's row on the table's page, when the
+/// table was indexed in the same run. The links of up-to-date assets are
+/// redone like those of `link_imports`.
+pub fn link_string_table_entries(assets: &[&AssetSummary]) -> Result<()> {
+    let packages = packages_by_path(assets);
+    for asset in assets {
+        let mut links: HashMap<i32, Vec<(String, Option<String>)>> = HashMap::new();
+        for text in asset
+            .texts
+            .iter()
+            .filter(|text| text.kind == "string_table")
+        {
+            let i = text.export as usize - 1;
+            if !asset.export_pages.get(i).copied().unwrap_or(true) {
+                continue;
+            }
+            let dir = export_dir(asset, i);
+            let url = resolve_string_table_entry(&text.string_table, &text.key, &dir, &packages);
+            let html = localized_text_html(&LocalizedText::StringTableEntry {
+                table: text.string_table.clone(),
+                key: text.key.clone(),
+            });
+            links.entry(text.export).or_default().push((html, url));
+        }
+        for (export, links) in links {
+            let page = asset
+                .main_dir
+                .join("exports")
+                .join(export.to_string())
+                .join("index.html");
+            let contents = read_page(&page)?;
+            let mut linked = contents.clone();
+            for (html, url) in links {
+                linked = unlinked(&linked, &html);
+                if let Some(url) = url {
+                    linked = linked.replace(
+                        &html,
+                        &format!("<a href=\"{}\">{}</a>", escape_html(&url), html),
+                    );
+                }
+            }
+            if linked != contents {
+                rewrite_page(&page, linked)?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,238 @@
+use serde::{Deserialize, Serialize};
+use std::io::{BufWriter, Read, Result, Seek, Write};
+use std::path::Path;
+use unreal_asset::{exports::ExportBaseTrait, Asset};
+
+use crate::{display_name, export_class_name, flags, manifest::OutputFiles, IndexNames};
+
+const EXPORT_COLUMNS: &str =
+    "index,object_name,class,outer_index,serial_size,serial_offset,object_flags";
+const IMPORT_COLUMNS: &str = "index,class_package,class_name,object_name,outer_index";
+
+/// Quotes `field` for a CSV row when it holds a comma, quote or line break.
+pub fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[test]
+fn test_csv_field() {
+    assert_eq!("Dialogue", csv_field("Dialogue"));
+    assert_eq!("\"Hello, traveler.\"", csv_field("Hello, traveler."));
+    assert_eq!(
+        "\"Say \"\"hi\"\"\nthen go\"",
+        csv_field("Say \"hi\"\nthen go")
+    );
+}
+
+/// Writes `fields` as one CSV row.
+pub fn write_row<W: Write>(sink: &mut W, fields: &[String]) -> Result<()> {
+    let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+    writeln!(sink, "{}", row.join(","))
+}
+
+/// A row of `exports.csv`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExportRow {
+    pub index: i32,
+    pub object_name: String,
+    pub class: String,
+    pub outer_index: i32,
+    pub serial_size: i64,
+    pub serial_offset: i64,
+    /// Decoded, e.g. `RF_Public | RF_Standalone`.
+    pub object_flags: String,
+}
+
+impl ExportRow {
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.index.to_string(),
+            self.object_name.clone(),
+            self.class.clone(),
+            self.outer_index.to_string(),
+            self.serial_size.to_string(),
+            self.serial_offset.to_string(),
+            self.object_flags.clone(),
+        ]
+    }
+}
+
+/// A row of `imports.csv`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportRow {
+    pub index: i32,
+    pub class_package: String,
+    pub class_name: String,
+    pub object_name: String,
+    pub outer_index: i32,
+}
+
+impl ImportRow {
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.index.to_string(),
+            self.class_package.clone(),
+            self.class_name.clone(),
+            self.object_name.clone(),
+            self.outer_index.to_string(),
+        ]
+    }
+}
+
+/// The export and import tables of an asset, as `--format csv` lists them.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AssetTables {
+    pub exports: Vec<ExportRow>,
+    pub imports: Vec<ImportRow>,
+}
+
+impl AssetTables {
+    pub fn new<C: Read + Seek>(asset: &Asset<C>, names: &IndexNames) -> Self {
+        let exports = asset
+            .asset_data
+            .exports
+            .iter()
+            .enumerate()
+            .map(|(i, export)| {
+                let base = export.get_base_export();
+                ExportRow {
+                    index: i as i32 + 1,
+                    object_name: display_name(&base.object_name),
+                    class: export_class_name(names, base.class_index.index).to_string(),
+                    outer_index: base.outer_index.index,
+                    serial_size: base.serial_size,
+                    serial_offset: base.serial_offset,
+                    object_flags: flags::decode_object_flags(base.object_flags.bits()),
+                }
+            })
+            .collect();
+        let imports = asset
+            .imports
+            .iter()
+            .enumerate()
+            .map(|(i, import)| ImportRow {
+                index: -(i as i32 + 1),
+                class_package: import.class_package.get_owned_content(),
+                class_name: import.class_name.get_owned_content(),
+                object_name: display_name(&import.object_name),
+                outer_index: import.outer_index.index,
+            })
+            .collect();
+        AssetTables { exports, imports }
+    }
+}
+
+/// Writes `exports.csv` and `imports.csv` into `main_dir`.
+pub fn write_tables(
+    tables: &AssetTables,
+    main_dir: &Path,
+    outputs: &mut OutputFiles,
+) -> Result<()> {
+    let mut exports = BufWriter::new(outputs.create(&main_dir.join("exports.csv"))?);
+    writeln!(exports, "{}", EXPORT_COLUMNS)?;
+    for row in &tables.exports {
+        write_row(&mut exports, &row.fields())?;
+    }
+    exports.flush()?;
+    let mut imports = BufWriter::new(outputs.create(&main_dir.join("imports.csv"))?);
+    writeln!(imports, "{}", IMPORT_COLUMNS)?;
+    for row in &tables.imports {
+        write_row(&mut imports, &row.fields())?;
+    }
+    imports.flush()
+}
+
+/// Writes `exports.csv` and `imports.csv` for `--csv-combined` into
+/// `out_dir`: the tables of every asset indexed, each row starting with the
+/// package it's from.
+pub fn write_combined_tables(
+    out_dir: &Path,
+    assets: &[(&str, &AssetTables)],
+    outputs: &mut OutputFiles,
+) -> Result<()> {
+    let mut exports = BufWriter::new(outputs.create(&out_dir.join("exports.csv"))?);
+    writeln!(exports, "package,{}", EXPORT_COLUMNS)?;
+    for (package, tables) in assets {
+        for row in &tables.exports {
+            let mut fields = vec![package.to_string()];
+            fields.extend(row.fields());
+            write_row(&mut exports, &fields)?;
+        }
+    }
+    exports.flush()?;
+    let mut imports = BufWriter::new(outputs.create(&out_dir.join("imports.csv"))?);
+    writeln!(imports, "package,{}", IMPORT_COLUMNS)?;
+    for (package, tables) in assets {
+        for row in &tables.imports {
+            let mut fields = vec![package.to_string()];
+            fields.extend(row.fields());
+            write_row(&mut imports, &fields)?;
+        }
+    }
+    imports.flush()
+}
+
+#[cfg(test)]
+fn test_tables() -> AssetTables {
+    AssetTables {
+        exports: vec![ExportRow {
+            index: 1,
+            object_name: "Door, \"Front\"".to_string(),
+            class: "StaticMeshActor".to_string(),
+            outer_index: 0,
+            serial_size: 412,
+            serial_offset: 1024,
+            object_flags: "RF_Public | RF_Transactional".to_string(),
+        }],
+        imports: vec![ImportRow {
+            index: -1,
+            class_package: "/Script/CoreUObject".to_string(),
+            class_name: "Package".to_string(),
+            object_name: "/Script/Engine".to_string(),
+            outer_index: 0,
+        }],
+    }
+}
+
+#[test]
+fn test_write_tables() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut outputs = OutputFiles::default();
+    write_tables(&test_tables(), dir.path(), &mut outputs).unwrap();
+    outputs.finish().unwrap();
+    assert_eq!(
+        "index,object_name,class,outer_index,serial_size,serial_offset,object_flags\n\
+         1,\"Door, \"\"Front\"\"\",StaticMeshActor,0,412,1024,RF_Public | RF_Transactional\n",
+        std::fs::read_to_string(dir.path().join("exports.csv")).unwrap()
+    );
+    assert_eq!(
+        "index,class_package,class_name,object_name,outer_index\n\
+         -1,/Script/CoreUObject,Package,/Script/Engine,0\n",
+        std::fs::read_to_string(dir.path().join("imports.csv")).unwrap()
+    );
+}
+
+#[test]
+fn test_write_combined_tables() {
+    let dir = tempfile::tempdir().unwrap();
+    let tables = test_tables();
+    let mut outputs = OutputFiles::default();
+    write_combined_tables(
+        dir.path(),
+        &[("/Game/Maps/Hub", &tables), ("/Game/Maps/Arena", &tables)],
+        &mut outputs,
+    )
+    .unwrap();
+    outputs.finish().unwrap();
+    let exports = std::fs::read_to_string(dir.path().join("exports.csv")).unwrap();
+    let lines: Vec<&str> = exports.lines().collect();
+    assert_eq!(3, lines.len());
+    assert!(lines[0].starts_with("package,index,object_name,"));
+    assert!(lines[2].starts_with("/Game/Maps/Arena,1,\"Door, \"\"Front\"\"\","));
+    let imports = std::fs::read_to_string(dir.path().join("imports.csv")).unwrap();
+    assert!(imports.ends_with("/Game/Maps/Arena,-1,/Script/CoreUObject,Package,/Script/Engine,0\n"));
+}
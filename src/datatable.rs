@@ -0,0 +1,81 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{Result, Write};
+use unreal_asset::{
+    base::types::PackageIndex,
+    exports::data_table_export::DataTableExport,
+    properties::{Property, PropertyDataTrait},
+};
+
+use crate::{
+    escape_html,
+    formatters::{value_html, UserEnums},
+    link_and_transform_indices,
+};
+
+/// A column key for a struct member; static array elements share a name and
+/// are told apart by their duplication index.
+pub fn column_name(property: &Property) -> String {
+    let name = property.get_name().get_owned_content();
+    match property.get_duplication_index() {
+        0 => name,
+        i => format!("{}[{}]", name, i),
+    }
+}
+
+fn render_cell(property: &Property, annotate: impl Fn(i32) -> String, enums: &UserEnums) -> String {
+    value_html(property, &annotate, enums).unwrap_or_else(|| {
+        link_and_transform_indices(&escape_html(&format!("{:?}", property)), &annotate)
+    })
+}
+
+/// Writes the rows of a DataTable as an HTML table: one row per entry keyed by
+/// row name, one column per struct member.
+pub fn write_table<W: Write>(
+    sink: &mut W,
+    data_table: &DataTableExport<PackageIndex>,
+    annotate: impl Fn(i32) -> String,
+    enums: &UserEnums,
+) -> Result<()> {
+    let rows = &data_table.table.data;
+    let mut columns = Vec::new();
+    let mut seen = HashSet::new();
+    for row in rows {
+        for property in &row.value {
+            let column = column_name(property);
+            if seen.insert(column.clone()) {
+                columns.push(column);
+            }
+        }
+    }
+
+    sink.write_all(
+        b"<table border=\"1\" style=\"border-collapse:collapse;font-family:monospace\">",
+    )?;
+    sink.write_all(b"<tr><th>Row</th>")?;
+    for column in &columns {
+        write!(sink, "<th>{}</th>", escape_html(column))?;
+    }
+    sink.write_all(b"</tr>")?;
+    for row in rows {
+        let cells: HashMap<String, &Property> = row
+            .value
+            .iter()
+            .map(|property| (column_name(property), property))
+            .collect();
+        write!(
+            sink,
+            "<tr><th>{}</th>",
+            escape_html(&row.name.get_owned_content())
+        )?;
+        for column in &columns {
+            match cells.get(column) {
+                Some(property) => {
+                    write!(sink, "<td>{}</td>", render_cell(property, &annotate, enums))?
+                }
+                None => sink.write_all(b"<td></td>")?,
+            }
+        }
+        sink.write_all(b"</tr>")?;
+    }
+    sink.write_all(b"</table>")
+}
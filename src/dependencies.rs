@@ -0,0 +1,135 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{BufWriter, Result, Write};
+use std::path::Path;
+
+use crate::{crossref, escape_html, graph::escape_dot, manifest, AssetSummary};
+
+/// The packages each indexed asset imports from, by package path, taken
+/// from the outermost import of each chain. Keys are the indexed packages;
+/// anything else only appears as a dependency.
+fn package_dependencies<'a>(assets: &[&'a AssetSummary]) -> BTreeMap<&'a str, BTreeSet<&'a str>> {
+    let mut dependencies: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for asset in assets {
+        let package = asset.package_path.as_str();
+        let imported = dependencies.entry(package).or_default();
+        for import in &asset.imports {
+            if import.package != package {
+                imported.insert(&import.package);
+            }
+        }
+    }
+    dependencies
+}
+
+#[test]
+fn test_package_dependencies() {
+    let asset = |package_path: &str, packages: &[&str]| AssetSummary {
+        package_path: package_path.to_string(),
+        imports: packages
+            .iter()
+            .map(|package| crossref::ImportTarget {
+                package: package.to_string(),
+                object_name: "Object".to_string(),
+            })
+            .collect(),
+        ..Default::default()
+    };
+    let hero = asset(
+        "/Game/Hero",
+        &["/Script/Engine", "/Game/Rifle", "/Game/Hero"],
+    );
+    let rifle = asset("/Game/Rifle", &["/Script/Engine", "/Script/Engine"]);
+    let dependencies = package_dependencies(&[&hero, &rifle]);
+    assert_eq!(
+        vec!["/Game/Hero", "/Game/Rifle"],
+        dependencies.keys().copied().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        BTreeSet::from(["/Game/Rifle", "/Script/Engine"]),
+        dependencies["/Game/Hero"]
+    );
+    assert_eq!(
+        BTreeSet::from(["/Script/Engine"]),
+        dependencies["/Game/Rifle"]
+    );
+}
+
+/// Writes `dependencies.html`, listing what each indexed package depends
+/// on with links to the assets' pages, and `dependencies.dot` with the same
+/// graph for Graphviz, into `out_dir`. Packages outside the indexed tree
+/// are marked external.
+pub fn write_dependencies(
+    out_dir: &Path,
+    assets: &[&AssetSummary],
+    outputs: &mut manifest::OutputFiles,
+) -> Result<()> {
+    let dependencies = package_dependencies(assets);
+    let mut main_dirs: BTreeMap<&str, &Path> = BTreeMap::new();
+    for asset in assets {
+        main_dirs
+            .entry(asset.package_path.as_str())
+            .or_insert(&asset.main_dir);
+    }
+    let external: BTreeSet<&str> = dependencies
+        .values()
+        .flatten()
+        .copied()
+        .filter(|package| !dependencies.contains_key(package))
+        .collect();
+
+    let link = |package: &str| match main_dirs.get(package) {
+        Some(main_dir) => format!(
+            "<a href=\"{}\">{}</a>",
+            escape_html(&crossref::relative_url(out_dir, main_dir)),
+            escape_html(package)
+        ),
+        None => format!("{} (external)", escape_html(package)),
+    };
+    let mut html = BufWriter::new(outputs.create(&out_dir.join("dependencies.html"))?);
+    write!(
+        html,
+        "<link rel=\"stylesheet\" href=\"style.css\"><h1>Dependencies</h1>\
+        <p>{} packages indexed, {} external packages referenced. \
+        <a href=\"dependencies.dot\">dependencies.dot</a> has the same graph.</p>",
+        dependencies.len(),
+        external.len()
+    )?;
+    for (package, imported) in &dependencies {
+        write!(html, "<h2>{}</h2>", link(package))?;
+        if imported.is_empty() {
+            html.write_all(b"<p>No dependencies.</p>")?;
+            continue;
+        }
+        html.write_all(b"<ul>")?;
+        for dependency in imported {
+            write!(html, "<li>{}</li>", link(dependency))?;
+        }
+        html.write_all(b"</ul>")?;
+    }
+    html.flush()?;
+
+    let mut dot = BufWriter::new(outputs.create(&out_dir.join("dependencies.dot"))?);
+    writeln!(dot, "digraph dependencies {{")?;
+    for package in dependencies.keys() {
+        writeln!(dot, "  \"{}\" [shape=box];", escape_dot(package))?;
+    }
+    for package in &external {
+        writeln!(
+            dot,
+            "  \"{}\" [shape=ellipse, style=filled, fillcolor=lightgrey];",
+            escape_dot(package)
+        )?;
+    }
+    for (package, imported) in &dependencies {
+        for dependency in imported {
+            writeln!(
+                dot,
+                "  \"{}\" -> \"{}\";",
+                escape_dot(package),
+                escape_dot(dependency)
+            )?;
+        }
+    }
+    writeln!(dot, "}}")?;
+    dot.flush()
+}
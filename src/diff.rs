@@ -0,0 +1,316 @@
+use similar::TextDiff;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::io::{self, BufWriter, Read, Seek, Write};
+use std::path::Path;
+use unreal_asset::{engine_version::EngineVersion, exports::ExportBaseTrait, Asset};
+
+use crate::{
+    display_name, escape_html, parse_source, write_annotated_dump, AssetSource, Error, IndexNames,
+    Mappings, DEFAULT_STYLESHEET,
+};
+
+/// How a package index reads in the dumps being diffed: what it points at,
+/// without the index itself, so entries that only moved compare equal.
+fn resolved_name(names: &IndexNames, index: i32) -> String {
+    if index == 0 {
+        return "null".to_string();
+    }
+    if index > 0 {
+        return match names.get(index) {
+            Some(name) => format!("export {}", name),
+            None => "out of range".to_string(),
+        };
+    }
+    let chain = names.import_chain(index);
+    if chain.is_empty() {
+        return "out of range".to_string();
+    }
+    let path: Vec<&str> = chain
+        .iter()
+        .rev()
+        .map(|&index| names.get(index).unwrap())
+        .collect();
+    format!("import {}", path.join("."))
+}
+
+#[test]
+fn test_resolved_name() {
+    let names = crate::test_index_names();
+    assert_eq!("null", resolved_name(&names, 0));
+    assert_eq!("export Default__Foo", resolved_name(&names, 1));
+    assert_eq!("import CoreUObject.Texture2D", resolved_name(&names, -2));
+    assert_eq!("out of range", resolved_name(&names, -3));
+}
+
+/// Export fields that shift whenever anything before the export changes
+/// size, and so say nothing about the export itself.
+const POSITIONAL_FIELDS: &[&str] = &["serial_offset:", "first_export_dependency_offset:"];
+
+fn dump(value: &impl std::fmt::Debug, names: &IndexNames) -> String {
+    let mut out = Vec::new();
+    write_annotated_dump(&mut out, value, |index| resolved_name(names, index))
+        .expect("Failed to format dump.");
+    String::from_utf8_lossy(&out)
+        .lines()
+        .filter(|line| {
+            let line = line.trim_start();
+            !POSITIONAL_FIELDS
+                .iter()
+                .any(|field| line.starts_with(field))
+        })
+        .map(|line| format!("{}\n", line))
+        .collect()
+}
+
+/// The differences in one table between the old and the new asset.
+#[derive(Debug, Default, PartialEq)]
+pub struct TableDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// The key of each entry whose dump changed, with the unified diff.
+    pub changed: Vec<(String, String)>,
+}
+
+impl TableDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Lines up the `(key, dump)` entries of both sides by key and diffs the
+/// dumps of the ones on both. Entries sharing a key are paired in package
+/// order.
+fn diff_table(old: &[(String, String)], new: &[(String, String)]) -> TableDiff {
+    let mut unmatched: HashMap<&str, VecDeque<&str>> = HashMap::new();
+    for (key, dump) in new {
+        unmatched.entry(key.as_str()).or_default().push_back(dump);
+    }
+    let mut diff = TableDiff::default();
+    for (key, old_dump) in old {
+        match unmatched
+            .get_mut(key.as_str())
+            .and_then(VecDeque::pop_front)
+        {
+            Some(new_dump) if new_dump != old_dump.as_str() => {
+                let unified = TextDiff::from_lines(old_dump.as_str(), new_dump)
+                    .unified_diff()
+                    .context_radius(3)
+                    .to_string();
+                diff.changed.push((key.clone(), unified));
+            }
+            Some(_) => {}
+            None => diff.removed.push(key.clone()),
+        }
+    }
+    for (key, _) in new {
+        if let Some(dumps) = unmatched.get_mut(key.as_str()) {
+            if dumps.pop_front().is_some() {
+                diff.added.push(key.clone());
+            }
+        }
+    }
+    diff
+}
+
+#[test]
+fn test_diff_table() {
+    let entry = |key: &str, dump: &str| (key.to_string(), dump.to_string());
+    let old = vec![
+        entry("Door", "a\nb\n"),
+        entry("Light", "x\n"),
+        entry("Mesh", "m\n"),
+        entry("Mesh", "n\n"),
+    ];
+    let new = vec![
+        entry("Light", "x\n"),
+        entry("Door", "a\nc\n"),
+        entry("Mesh", "m\n"),
+        entry("Window", "w\n"),
+    ];
+    let diff = diff_table(&old, &new);
+    assert_eq!(vec!["Window".to_string()], diff.added);
+    assert_eq!(vec!["Mesh".to_string()], diff.removed);
+    assert_eq!(1, diff.changed.len());
+    assert_eq!("Door", diff.changed[0].0);
+    assert_eq!("@@ -1,2 +1,2 @@\n a\n-b\n+c\n", diff.changed[0].1);
+}
+
+/// Everything `uasset-index diff` found between two versions of an asset.
+#[derive(Debug, Default)]
+pub struct AssetDiff {
+    pub exports: TableDiff,
+    pub imports: TableDiff,
+    pub names_added: Vec<String>,
+    pub names_removed: Vec<String>,
+}
+
+impl AssetDiff {
+    pub fn is_empty(&self) -> bool {
+        self.exports.is_empty()
+            && self.imports.is_empty()
+            && self.names_added.is_empty()
+            && self.names_removed.is_empty()
+    }
+}
+
+fn export_entries<C: Read + Seek>(asset: &Asset<C>) -> Vec<(String, String)> {
+    let names = IndexNames::from_asset(asset);
+    asset
+        .asset_data
+        .exports
+        .iter()
+        .map(|export| {
+            let key = display_name(&export.get_base_export().object_name);
+            (key, dump(export, &names))
+        })
+        .collect()
+}
+
+fn import_entries<C: Read + Seek>(asset: &Asset<C>) -> Vec<(String, String)> {
+    let names = IndexNames::from_asset(asset);
+    asset
+        .imports
+        .iter()
+        .enumerate()
+        .map(|(i, import)| {
+            let key = resolved_name(&names, -(i as i32 + 1));
+            let key = key.strip_prefix("import ").unwrap_or(&key).to_string();
+            (key, dump(import, &names))
+        })
+        .collect()
+}
+
+fn name_map<C: Read + Seek>(asset: &Asset<C>) -> BTreeSet<String> {
+    asset
+        .get_name_map()
+        .get_ref()
+        .get_name_map_index_list()
+        .iter()
+        .cloned()
+        .collect()
+}
+
+/// Compares the exports (by object name), imports (by path) and name maps
+/// of `old` and `new`.
+pub fn diff_assets<C: Read + Seek, D: Read + Seek>(old: &Asset<C>, new: &Asset<D>) -> AssetDiff {
+    let old_names = name_map(old);
+    let new_names = name_map(new);
+    AssetDiff {
+        exports: diff_table(&export_entries(old), &export_entries(new)),
+        imports: diff_table(&import_entries(old), &import_entries(new)),
+        names_added: new_names.difference(&old_names).cloned().collect(),
+        names_removed: old_names.difference(&new_names).cloned().collect(),
+    }
+}
+
+/// Parses the assets at `old` and `new`, each with its `.uexp` when it has
+/// one, and compares them with `diff_assets`.
+pub fn diff_files(
+    old: &Path,
+    new: &Path,
+    engine_version: EngineVersion,
+    mappings: Option<&Mappings>,
+) -> Result<AssetDiff, Error> {
+    let parse = |path: &Path| {
+        let source = AssetSource::open(path)?;
+        parse_source(path, source, engine_version, mappings).map(|(asset, _)| asset)
+    };
+    Ok(diff_assets(&parse(old)?, &parse(new)?))
+}
+
+/// A unified diff with added and removed lines colored.
+fn unified_diff_html(unified: &str) -> String {
+    let mut html = String::from("<pre>");
+    for line in unified.lines() {
+        let color = match line.chars().next() {
+            Some('+') => Some("green"),
+            Some('-') => Some("red"),
+            Some('@') => Some("gray"),
+            _ => None,
+        };
+        match color {
+            Some(color) => {
+                html += &format!(
+                    "<span style=\"color:{}\">{}</span>\n",
+                    color,
+                    escape_html(line)
+                )
+            }
+            None => html += &format!("{}\n", escape_html(line)),
+        }
+    }
+    html += "</pre>";
+    html
+}
+
+#[test]
+fn test_unified_diff_html() {
+    assert_eq!(
+        "<pre><span style=\"color:gray\">@@ -1 +1 @@</span>\n\
+         <span style=\"color:red\">-a &lt;b&gt;</span>\n\
+         <span style=\"color:green\">+a</span>\n</pre>",
+        unified_diff_html("@@ -1 +1 @@\n-a <b>\n+a\n")
+    );
+}
+
+fn list_html(items: &[String]) -> String {
+    let items: String = items
+        .iter()
+        .map(|item| format!("<li>{}</li>", escape_html(item)))
+        .collect();
+    format!("<ul>{}</ul>", items)
+}
+
+fn table_html(title: &str, diff: &TableDiff) -> String {
+    let mut html = format!("<h2>{}</h2>", title);
+    if diff.is_empty() {
+        html += "<p>No changes.</p>";
+        return html;
+    }
+    if !diff.added.is_empty() {
+        html += &format!("<h3>Added ({})</h3>", diff.added.len());
+        html += &list_html(&diff.added);
+    }
+    if !diff.removed.is_empty() {
+        html += &format!("<h3>Removed ({})</h3>", diff.removed.len());
+        html += &list_html(&diff.removed);
+    }
+    if !diff.changed.is_empty() {
+        html += &format!("<h3>Changed ({})</h3>", diff.changed.len());
+        for (key, unified) in &diff.changed {
+            html += &format!("<h4>{}</h4>", escape_html(key));
+            html += &unified_diff_html(unified);
+        }
+    }
+    html
+}
+
+/// Writes the `diff.html` report of `diff` between the assets `old` and `new`.
+pub fn write_report(path: &Path, old: &Path, new: &Path, diff: &AssetDiff) -> io::Result<()> {
+    let mut file = BufWriter::new(std::fs::File::create(path)?);
+    write!(
+        file,
+        "<style>{}</style><h1>{} &rarr; {}</h1>",
+        DEFAULT_STYLESHEET,
+        escape_html(&old.display().to_string()),
+        escape_html(&new.display().to_string())
+    )?;
+    if diff.is_empty() {
+        file.write_all(b"<p>No differences.</p>")?;
+    }
+    file.write_all(table_html("Exports", &diff.exports).as_bytes())?;
+    file.write_all(table_html("Imports", &diff.imports).as_bytes())?;
+    file.write_all(b"<h2>Name map</h2>")?;
+    if diff.names_added.is_empty() && diff.names_removed.is_empty() {
+        file.write_all(b"<p>No changes.</p>")?;
+    }
+    if !diff.names_added.is_empty() {
+        write!(file, "<h3>Added ({})</h3>", diff.names_added.len())?;
+        file.write_all(list_html(&diff.names_added).as_bytes())?;
+    }
+    if !diff.names_removed.is_empty() {
+        write!(file, "<h3>Removed ({})</h3>", diff.names_removed.len())?;
+        file.write_all(list_html(&diff.names_removed).as_bytes())?;
+    }
+    file.flush()
+}
@@ -0,0 +1,189 @@
+use serde::Serialize;
+use std::io::{BufWriter, Result, Write};
+use std::path::Path;
+
+use crate::{crossref, escape_html, manifest, PackageCollision};
+
+#[derive(Serialize)]
+struct ErrorJson<'a> {
+    path: String,
+    category: &'a str,
+    message: String,
+    /// The asset's main page relative to the output root, for assets that
+    /// failed but still have the pages of an earlier run.
+    page: Option<String>,
+}
+
+/// Writes `errors.html`, a table of every asset that failed and every file
+/// or directory that was skipped, then of the package paths in `collisions`,
+/// and `errors.json` with the failed and skipped rows, into `out_dir`. Paths are shown relative to `root`, the directory that was
+/// walked, in the messages too, so the report doesn't change with where the
+/// inputs are.
+pub fn write_errors(
+    root: &Path,
+    out_dir: &Path,
+    failures: &[&manifest::IndexFailure],
+    collisions: &[PackageCollision],
+    outputs: &mut manifest::OutputFiles,
+) -> Result<()> {
+    let relative = |path: &Path| {
+        path.strip_prefix(root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string()
+    };
+    let root_prefix = format!("{}{}", root.display(), std::path::MAIN_SEPARATOR);
+    let rows: Vec<ErrorJson> = failures
+        .iter()
+        .map(|failure| ErrorJson {
+            path: relative(&failure.path),
+            category: failure.category,
+            message: if root.as_os_str().is_empty() {
+                failure.message.clone()
+            } else {
+                failure.message.replace(&root_prefix, "")
+            },
+            page: failure
+                .output
+                .as_ref()
+                .map(|output| crossref::relative_url(out_dir, output)),
+        })
+        .collect();
+
+    let mut html = BufWriter::new(outputs.create(&out_dir.join("errors.html"))?);
+    html.write_all(b"<link rel=\"stylesheet\" href=\"style.css\"><h1>Errors</h1>")?;
+    if rows.is_empty() {
+        html.write_all(b"<p>Every asset was indexed.</p>")?;
+    } else {
+        write!(
+            html,
+            "<p>{} problems. <a href=\"errors.json\">errors.json</a> lists the same.</p>\
+            <table border=\"1\" style=\"border-collapse:collapse\">\
+            <tr><th>File</th><th>Category</th><th>Error</th></tr>",
+            rows.len()
+        )?;
+        for row in &rows {
+            let path = escape_html(&row.path);
+            let file = match &row.page {
+                Some(page) => format!("<a href=\"{}\">{}</a>", escape_html(page), path),
+                None => path,
+            };
+            write!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                file,
+                row.category,
+                escape_html(&row.message)
+            )?;
+        }
+        html.write_all(b"</table>")?;
+    }
+    if !collisions.is_empty() {
+        write!(
+            html,
+            "<h2>Package collisions</h2>\
+            <p>{} package paths come from more than one file. \
+            Links from other assets go to the copy marked as resolved.</p>\
+            <table border=\"1\" style=\"border-collapse:collapse\">\
+            <tr><th>Package</th><th>Files</th></tr>",
+            collisions.len()
+        )?;
+        for collision in collisions {
+            let files: String = collision
+                .files
+                .iter()
+                .map(|file| {
+                    let path = escape_html(&relative(file));
+                    if *file == collision.resolved {
+                        format!("<li>{} (resolved)</li>", path)
+                    } else {
+                        format!("<li>{}</li>", path)
+                    }
+                })
+                .collect();
+            write!(
+                html,
+                "<tr><td><code>{}</code></td><td><ul>{}</ul></td></tr>",
+                escape_html(&collision.package_path),
+                files
+            )?;
+        }
+        html.write_all(b"</table>")?;
+    }
+    html.flush()?;
+
+    let json = BufWriter::new(outputs.create(&out_dir.join("errors.json"))?);
+    serde_json::to_writer_pretty(json, &rows)?;
+    Ok(())
+}
+
+#[test]
+fn test_write_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = Path::new("Mods");
+    let failures = [
+        manifest::IndexFailure {
+            path: root.join("Content").join("Broken.uasset"),
+            category: "open",
+            message: format!(
+                "failed to open {}: <eof>",
+                root.join("Content").join("Broken.uexp").display()
+            ),
+            output: Some(dir.path().join("Content").join("Broken")),
+            generated: Vec::new(),
+        },
+        manifest::IndexFailure {
+            path: root.join("Content").join("Locked"),
+            category: "unreadable",
+            message: "Permission denied (os error 13)".to_string(),
+            output: None,
+            generated: Vec::new(),
+        },
+    ];
+    let mut outputs = manifest::OutputFiles::default();
+    write_errors(
+        root,
+        dir.path(),
+        &failures.iter().collect::<Vec<_>>(),
+        &[],
+        &mut outputs,
+    )
+    .unwrap();
+    outputs.finish().unwrap();
+    let html = std::fs::read_to_string(dir.path().join("errors.html")).unwrap();
+    assert!(html.contains(
+        "<tr><td><a href=\"Content/Broken\">Content/Broken.uasset</a></td><td>open</td>\
+        <td>failed to open Content/Broken.uexp: &lt;eof&gt;</td></tr>"
+    ));
+    assert!(html.contains("<tr><td>Content/Locked</td><td>unreadable</td>"));
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.path().join("errors.json")).unwrap())
+            .unwrap();
+    assert_eq!("open", json[0]["category"]);
+    assert_eq!("Content/Locked", json[1]["path"]);
+    assert_eq!(serde_json::Value::Null, json[1]["page"]);
+    assert_eq!(2, outputs.files.len());
+
+    write_errors(root, dir.path(), &[], &[], &mut outputs).unwrap();
+    outputs.finish().unwrap();
+    let html = std::fs::read_to_string(dir.path().join("errors.html")).unwrap();
+    assert!(html.contains("<p>Every asset was indexed.</p>"));
+    assert!(!html.contains("Package collisions"));
+
+    let collisions = [PackageCollision {
+        package_path: "/Game/Hero".to_string(),
+        files: vec![
+            root.join("Base").join("Content").join("Hero.uasset"),
+            root.join("Override").join("Content").join("Hero.uasset"),
+        ],
+        resolved: root.join("Override").join("Content").join("Hero.uasset"),
+    }];
+    write_errors(root, dir.path(), &[], &collisions, &mut outputs).unwrap();
+    outputs.finish().unwrap();
+    let html = std::fs::read_to_string(dir.path().join("errors.html")).unwrap();
+    assert!(html.contains(&format!(
+        "<tr><td><code>/Game/Hero</code></td><td><ul><li>{}</li><li>{} (resolved)</li></ul></td></tr>",
+        Path::new("Base").join("Content").join("Hero.uasset").display(),
+        Path::new("Override").join("Content").join("Hero.uasset").display()
+    )));
+}
@@ -0,0 +1,94 @@
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+use std::path::Path;
+
+/// `--include` and `--exclude`: glob patterns matched against paths relative
+/// to the directory being indexed, where `*` stays within one path component
+/// and `**` spans any number of them.
+#[derive(Debug, Default)]
+pub struct PathFilter {
+    /// When set, only files matching one of these are indexed.
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+    /// The exclude patterns plus, for each ending in `/**`, the directory it
+    /// covers, so the walk can skip that directory instead of looking at
+    /// every file in it.
+    exclude_dirs: GlobSet,
+}
+
+fn glob(pattern: &str) -> Result<Glob, globset::Error> {
+    GlobBuilder::new(pattern).literal_separator(true).build()
+}
+
+fn glob_set<'a>(patterns: impl IntoIterator<Item = &'a str>) -> Result<GlobSet, globset::Error> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(glob(pattern)?);
+    }
+    builder.build()
+}
+
+impl PathFilter {
+    /// Fails on the first pattern that isn't a valid glob.
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self, globset::Error> {
+        let exclude_dirs = exclude.iter().flat_map(|pattern| {
+            let dir = pattern.strip_suffix("/**");
+            std::iter::once(pattern.as_str()).chain(dir)
+        });
+        Ok(PathFilter {
+            include: if include.is_empty() {
+                None
+            } else {
+                Some(glob_set(include.iter().map(String::as_str))?)
+            },
+            exclude: glob_set(exclude.iter().map(String::as_str))?,
+            exclude_dirs: glob_set(exclude_dirs)?,
+        })
+    }
+
+    /// Whether the walk descends into the directory at `relative`. Only
+    /// `--exclude` prunes directories, since a file deep inside any of them
+    /// could still match `--include`.
+    pub(crate) fn includes_dir(&self, relative: &Path) -> bool {
+        !self.exclude_dirs.is_match(relative)
+    }
+
+    /// Whether the asset at `relative` is indexed.
+    pub(crate) fn includes_file(&self, relative: &Path) -> bool {
+        !self.exclude.is_match(relative)
+            && self
+                .include
+                .as_ref()
+                .is_none_or(|include| include.is_match(relative))
+    }
+}
+
+#[test]
+fn test_path_filter_prunes_directories() {
+    let patterns = |patterns: &[&str]| patterns.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+    let filter = PathFilter::new(&[], &patterns(&["Movies/**", "**/WwiseAudio/**"])).unwrap();
+    assert!(!filter.includes_dir(Path::new("Movies")));
+    assert!(!filter.includes_dir(Path::new("WwiseAudio")));
+    assert!(!filter.includes_dir(Path::new("Sound/WwiseAudio")));
+    assert!(filter.includes_dir(Path::new("Maps")));
+    assert!(filter.includes_dir(Path::new("Maps/Movies")));
+    assert!(!filter.includes_file(Path::new("Movies/Intro.uasset")));
+    assert!(filter.includes_file(Path::new("Maps/Hub.umap")));
+}
+
+#[test]
+fn test_path_filter_matches_files() {
+    let patterns = |patterns: &[&str]| patterns.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+    let filter = PathFilter::new(&patterns(&["Maps/*.umap"]), &patterns(&["**/Test_*"])).unwrap();
+    // Files are filtered, but directories are only pruned by --exclude.
+    assert!(filter.includes_dir(Path::new("Characters")));
+    assert!(!filter.includes_file(Path::new("Characters/Hero.uasset")));
+    assert!(filter.includes_file(Path::new("Maps/Hub.umap")));
+    // `*` doesn't cross directories.
+    assert!(!filter.includes_file(Path::new("Maps/Sub/Arena.umap")));
+    assert!(!filter.includes_file(Path::new("Maps/Test_Arena.umap")));
+    assert!(PathFilter::new(&[], &patterns(&["Maps/[a"])).is_err());
+
+    let all = PathFilter::default();
+    assert!(all.includes_dir(Path::new("Movies")));
+    assert!(all.includes_file(Path::new("Movies/Intro.uasset")));
+}
@@ -0,0 +1,124 @@
+/// The object flag class default objects carry.
+pub const RF_CLASS_DEFAULT_OBJECT: u32 = 0x0000_0010;
+
+/// `EObjectFlags` bits by name, in bit order.
+const OBJECT_FLAGS: &[(u32, &str)] = &[
+    (0x0000_0001, "RF_Public"),
+    (0x0000_0002, "RF_Standalone"),
+    (0x0000_0004, "RF_MarkAsNative"),
+    (0x0000_0008, "RF_Transactional"),
+    (0x0000_0010, "RF_ClassDefaultObject"),
+    (0x0000_0020, "RF_ArchetypeObject"),
+    (0x0000_0040, "RF_Transient"),
+    (0x0000_0080, "RF_MarkAsRootSet"),
+    (0x0000_0100, "RF_TagGarbageTemp"),
+    (0x0000_0200, "RF_NeedInitialization"),
+    (0x0000_0400, "RF_NeedLoad"),
+    (0x0000_0800, "RF_KeepForCooker"),
+    (0x0000_1000, "RF_NeedPostLoad"),
+    (0x0000_2000, "RF_NeedPostLoadSubobjects"),
+    (0x0000_4000, "RF_NewerVersionExists"),
+    (0x0000_8000, "RF_BeginDestroyed"),
+    (0x0001_0000, "RF_FinishDestroyed"),
+    (0x0002_0000, "RF_BeingRegenerated"),
+    (0x0004_0000, "RF_DefaultSubObject"),
+    (0x0008_0000, "RF_WasLoaded"),
+    (0x0010_0000, "RF_TextExportTransient"),
+    (0x0020_0000, "RF_LoadCompleted"),
+    (0x0040_0000, "RF_InheritableComponentTemplate"),
+    (0x0080_0000, "RF_DuplicateTransient"),
+    (0x0100_0000, "RF_StrongRefOnFrame"),
+    (0x0200_0000, "RF_NonPIEDuplicateTransient"),
+    (0x0400_0000, "RF_Dynamic"),
+    (0x0800_0000, "RF_WillBeLoaded"),
+    (0x1000_0000, "RF_HasExternalPackage"),
+];
+
+/// `EPackageFlags` bits by name, in bit order.
+const PACKAGE_FLAGS: &[(u32, &str)] = &[
+    (0x0000_0001, "PKG_NewlyCreated"),
+    (0x0000_0002, "PKG_ClientOptional"),
+    (0x0000_0004, "PKG_ServerSideOnly"),
+    (0x0000_0010, "PKG_CompiledIn"),
+    (0x0000_0020, "PKG_ForDiffing"),
+    (0x0000_0040, "PKG_EditorOnly"),
+    (0x0000_0080, "PKG_Developer"),
+    (0x0000_0100, "PKG_UncookedOnly"),
+    (0x0000_0200, "PKG_Cooked"),
+    (0x0000_0400, "PKG_ContainsNoAsset"),
+    (0x0000_0800, "PKG_NotExternallyReferenceable"),
+    (0x0000_2000, "PKG_UnversionedProperties"),
+    (0x0000_4000, "PKG_ContainsMapData"),
+    (0x0000_8000, "PKG_IsSaving"),
+    (0x0001_0000, "PKG_Compiling"),
+    (0x0002_0000, "PKG_ContainsMap"),
+    (0x0004_0000, "PKG_RequiresLocalizationGather"),
+    (0x0010_0000, "PKG_PlayInEditor"),
+    (0x0020_0000, "PKG_ContainsScript"),
+    (0x0040_0000, "PKG_DisallowExport"),
+    (0x0800_0000, "PKG_CookGenerated"),
+    (0x1000_0000, "PKG_DynamicImports"),
+    (0x2000_0000, "PKG_RuntimeGenerated"),
+    (0x4000_0000, "PKG_ReloadingForCooker"),
+    (0x8000_0000, "PKG_FilterEditorOnly"),
+];
+
+/// Joins the names of the bits set in `bits`, with bits `table` doesn't name
+/// kept as a trailing hex remainder.
+fn decode_flags(bits: u32, table: &[(u32, &str)], no_flags: &str) -> String {
+    if bits == 0 {
+        return no_flags.to_string();
+    }
+    let mut names: Vec<String> = table
+        .iter()
+        .filter(|(bit, _)| bits & bit != 0)
+        .map(|(_, name)| name.to_string())
+        .collect();
+    let unknown = table
+        .iter()
+        .fold(bits, |remainder, (bit, _)| remainder & !bit);
+    if unknown != 0 {
+        names.push(format!("{:#x}", unknown));
+    }
+    names.join(" | ")
+}
+
+/// Decodes raw object flags into `RF_Public | RF_Standalone` form.
+pub fn decode_object_flags(bits: u32) -> String {
+    decode_flags(bits, OBJECT_FLAGS, "RF_NoFlags")
+}
+
+/// Decodes a package summary's flags into `PKG_Cooked | PKG_FilterEditorOnly` form.
+pub fn decode_package_flags(bits: u32) -> String {
+    decode_flags(bits, PACKAGE_FLAGS, "PKG_None")
+}
+
+#[test]
+fn test_decode_object_flags() {
+    assert_eq!("RF_NoFlags", decode_object_flags(0));
+    assert_eq!(
+        "RF_Public | RF_Standalone | RF_Transactional",
+        decode_object_flags(0x1 | 0x2 | 0x8)
+    );
+    assert_eq!(
+        "RF_Public | RF_ClassDefaultObject | RF_ArchetypeObject",
+        decode_object_flags(0x31)
+    );
+    assert_eq!(
+        "RF_WasLoaded | 0xe0000000",
+        decode_object_flags(0xe008_0000)
+    );
+}
+
+#[test]
+fn test_decode_package_flags() {
+    assert_eq!("PKG_None", decode_package_flags(0));
+    assert_eq!(
+        "PKG_Cooked | PKG_UnversionedProperties | PKG_FilterEditorOnly",
+        decode_package_flags(0x8000_2200)
+    );
+    assert_eq!(
+        "PKG_ContainsMap | 0x1000",
+        decode_package_flags(0x0002_1000)
+    );
+}
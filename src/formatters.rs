@@ -0,0 +1,1001 @@
+use std::collections::HashMap;
+use unreal_asset::{
+    properties::{
+        delegate_property::Delegate, int_property::BytePropertyValue, Property, PropertyDataTrait,
+    },
+    types::fname::FName,
+};
+
+use crate::{
+    datatable::column_name,
+    escape_html, link_and_transform_indices,
+    properties::{gameplay_tags, localized_text, scalar_value, walk_property, LocalizedText},
+    variant_name,
+};
+
+/// `(100.0, 0.0, 250.0)`.
+fn format_vector(x: f64, y: f64, z: f64) -> String {
+    format!("({:?}, {:?}, {:?})", x, y, z)
+}
+
+#[test]
+fn test_format_vector() {
+    assert_eq!("(100.0, 0.0, 250.0)", format_vector(100.0, 0.0, 250.0));
+    assert_eq!("(-0.5, 1e-7, 3.25)", format_vector(-0.5, 1e-7, 3.25));
+}
+
+/// `(Pitch=0, Yaw=90, Roll=0)`.
+fn format_rotator(pitch: f64, yaw: f64, roll: f64) -> String {
+    format!("(Pitch={}, Yaw={}, Roll={})", pitch, yaw, roll)
+}
+
+#[test]
+fn test_format_rotator() {
+    assert_eq!(
+        "(Pitch=0, Yaw=90, Roll=-12.5)",
+        format_rotator(0.0, 90.0, -12.5)
+    );
+}
+
+/// `(X=0, Y=0, Z=0.7071, W=0.7071)`.
+fn format_quat(x: f64, y: f64, z: f64, w: f64) -> String {
+    format!("(X={}, Y={}, Z={}, W={})", x, y, z, w)
+}
+
+#[test]
+fn test_format_quat() {
+    assert_eq!("(X=0, Y=0, Z=0.5, W=1)", format_quat(0.0, 0.0, 0.5, 1.0));
+}
+
+fn color_swatch(r: u8, g: u8, b: u8) -> String {
+    format!(
+        "<span style=\"background:#{:02x}{:02x}{:02x};border:1px solid gray\">&nbsp;&nbsp;&nbsp;</span>",
+        r, g, b
+    )
+}
+
+/// An 8-bit sRGB `FColor`, with a swatch before its values.
+fn format_color(r: u8, g: u8, b: u8, a: u8) -> String {
+    format!(
+        "{} (R={}, G={}, B={}, A={})",
+        color_swatch(r, g, b),
+        r,
+        g,
+        b,
+        a
+    )
+}
+
+#[test]
+fn test_format_color() {
+    assert_eq!(
+        "<span style=\"background:#ff8000;border:1px solid gray\">&nbsp;&nbsp;&nbsp;</span> \
+         (R=255, G=128, B=0, A=255)",
+        format_color(255, 128, 0, 255)
+    );
+}
+
+/// Converts a linear color channel to 8-bit sRGB, as `FLinearColor::ToFColor(true)` does.
+fn linear_to_srgb(channel: f32) -> u8 {
+    let channel = channel.clamp(0.0, 1.0);
+    let srgb = if channel <= 0.0031308 {
+        channel * 12.92
+    } else {
+        1.055 * channel.powf(1.0 / 2.4) - 0.055
+    };
+    (srgb * 255.0).round() as u8
+}
+
+/// A linear `FLinearColor`, with a swatch of its sRGB equivalent before its values.
+fn format_linear_color(r: f32, g: f32, b: f32, a: f32) -> String {
+    format!(
+        "{} (R={:?}, G={:?}, B={:?}, A={:?})",
+        color_swatch(linear_to_srgb(r), linear_to_srgb(g), linear_to_srgb(b)),
+        r,
+        g,
+        b,
+        a
+    )
+}
+
+#[test]
+fn test_format_linear_color() {
+    assert_eq!(
+        "<span style=\"background:#ffbc00;border:1px solid gray\">&nbsp;&nbsp;&nbsp;</span> \
+         (R=1.0, G=0.5, B=0.0, A=1.0)",
+        format_linear_color(1.0, 0.5, 0.0, 1.0)
+    );
+    // Out-of-range HDR values are clamped for the swatch only.
+    assert_eq!(
+        "<span style=\"background:#ffffff;border:1px solid gray\">&nbsp;&nbsp;&nbsp;</span> \
+         (R=4.0, G=2.0, B=1.5, A=1.0)",
+        format_linear_color(4.0, 2.0, 1.5, 1.0)
+    );
+}
+
+/// `(Translation=(..), Rotation=(X=.., ..), Scale3D=(..))`.
+fn format_transform(translation: [f64; 3], rotation: [f64; 4], scale: [f64; 3]) -> String {
+    format!(
+        "(Translation={}, Rotation={}, Scale3D={})",
+        format_vector(translation[0], translation[1], translation[2]),
+        format_quat(rotation[0], rotation[1], rotation[2], rotation[3]),
+        format_vector(scale[0], scale[1], scale[2])
+    )
+}
+
+#[test]
+fn test_format_transform() {
+    assert_eq!(
+        "(Translation=(0.0, 0.0, 100.0), Rotation=(X=0, Y=0, Z=0, W=1), Scale3D=(1.0, 1.0, 2.0))",
+        format_transform([0.0, 0.0, 100.0], [0.0, 0.0, 0.0, 1.0], [1.0, 1.0, 2.0])
+    );
+}
+
+fn vector_components(property: &Property) -> Option<[f64; 3]> {
+    match property {
+        Property::VectorProperty(p) => Some([p.value.x.0, p.value.y.0, p.value.z.0]),
+        _ => None,
+    }
+}
+
+fn quat_components(property: &Property) -> Option<[f64; 4]> {
+    match property {
+        Property::QuatProperty(p) => Some([p.value.x.0, p.value.y.0, p.value.z.0, p.value.w.0]),
+        _ => None,
+    }
+}
+
+/// The compact rendering of the well-known math and color structs, or `None`
+/// for any other property.
+fn struct_value_html(property: &Property) -> Option<String> {
+    let value = match property {
+        Property::VectorProperty(p) => format_vector(p.value.x.0, p.value.y.0, p.value.z.0),
+        Property::Vector2DProperty(p) => format!("({:?}, {:?})", p.value.x.0, p.value.y.0),
+        Property::Vector4Property(p) => format!(
+            "({:?}, {:?}, {:?}, {:?})",
+            p.value.x.0, p.value.y.0, p.value.z.0, p.value.w.0
+        ),
+        // Rotators are serialized pitch, yaw, roll.
+        Property::RotatorProperty(p) => format_rotator(p.value.x.0, p.value.y.0, p.value.z.0),
+        Property::QuatProperty(p) => {
+            format_quat(p.value.x.0, p.value.y.0, p.value.z.0, p.value.w.0)
+        }
+        Property::ColorProperty(p) => format_color(p.value.r, p.value.g, p.value.b, p.value.a),
+        Property::LinearColorProperty(p) => {
+            format_linear_color(p.value.r.0, p.value.g.0, p.value.b.0, p.value.a.0)
+        }
+        Property::StructProperty(p)
+            if p.struct_type
+                .as_ref()
+                .is_some_and(|struct_type| struct_type.get_owned_content() == "Transform") =>
+        {
+            let member = |name: &str| {
+                p.value
+                    .iter()
+                    .find(|member| member.get_name().get_owned_content() == name)
+            };
+            format_transform(
+                vector_components(member("Translation")?)?,
+                quat_components(member("Rotation")?)?,
+                vector_components(member("Scale3D")?)?,
+            )
+        }
+        _ => return None,
+    };
+    Some(value)
+}
+
+#[cfg(test)]
+fn dummy_name(name: &str) -> FName {
+    FName::new_dummy(name.to_string(), 0)
+}
+
+#[test]
+fn test_struct_value_html() {
+    use unreal_asset::{
+        properties::{color_property::ColorProperty, vector_property::VectorProperty},
+        types::vector::{Color, Vector},
+        unversioned::ancestry::Ancestry,
+    };
+    let vector = Property::VectorProperty(VectorProperty {
+        name: dummy_name("Offset"),
+        ancestry: Ancestry::default(),
+        property_guid: None,
+        duplication_index: 0,
+        value: Vector::new(100.0.into(), 0.0.into(), 250.0.into()),
+    });
+    assert_eq!(
+        Some("(100.0, 0.0, 250.0)".to_string()),
+        struct_value_html(&vector)
+    );
+    let color = Property::ColorProperty(ColorProperty {
+        name: dummy_name("Tint"),
+        ancestry: Ancestry::default(),
+        property_guid: None,
+        duplication_index: 0,
+        value: Color::new(255, 128, 0, 255),
+    });
+    assert_eq!(
+        Some(format_color(255, 128, 0, 255)),
+        struct_value_html(&color)
+    );
+}
+
+/// The enums an asset defines itself, by name, with the index of their
+/// `UserDefinedEnum` export.
+pub type UserEnums = HashMap<String, i32>;
+
+/// `EMovementMode::MOVE_Flying` for the value `MOVE_Flying` of
+/// `EMovementMode`. Values that already name their enum are kept as they are.
+fn qualify_enum_value(enum_type: &str, value: &str) -> String {
+    if value.contains("::") {
+        value.to_string()
+    } else {
+        format!("{}::{}", enum_type, value)
+    }
+}
+
+#[test]
+fn test_qualify_enum_value() {
+    assert_eq!(
+        "EMovementMode::MOVE_Flying",
+        qualify_enum_value("EMovementMode", "MOVE_Flying")
+    );
+    assert_eq!(
+        "E_Weather::NewEnumerator2",
+        qualify_enum_value("E_Weather", "E_Weather::NewEnumerator2")
+    );
+}
+
+/// The value of an enum property, or of a byte property holding an
+/// enumerator, qualified with its enum's name, followed by a link to the
+/// enum's export when the asset defines it. `None` for properties without
+/// enum metadata.
+fn enum_value_html(
+    property: &Property,
+    annotate: impl Fn(i32) -> String,
+    enums: &UserEnums,
+) -> Option<String> {
+    let (enum_type, value) = match property {
+        Property::EnumProperty(p) => (p.enum_type.as_ref()?, p.value.as_ref()?),
+        Property::ByteProperty(p) => match (&p.enum_type, &p.value) {
+            (Some(enum_type), BytePropertyValue::FName(value)) => (enum_type, value),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let enum_type = enum_type.get_owned_content();
+    if enum_type == "None" {
+        return None;
+    }
+    let value = escape_html(&qualify_enum_value(&enum_type, &value.get_owned_content()));
+    Some(match enums.get(&enum_type) {
+        Some(&index) => format!("{} ({})", value, annotate(index)),
+        None => value,
+    })
+}
+
+#[test]
+fn test_enum_value_html() {
+    use unreal_asset::{
+        properties::{enum_property::EnumProperty, int_property::ByteProperty},
+        unversioned::ancestry::Ancestry,
+    };
+    let enum_property = |enum_type: &str, value: &str| {
+        Property::EnumProperty(EnumProperty {
+            name: dummy_name("Weather"),
+            ancestry: Ancestry::default(),
+            property_guid: None,
+            duplication_index: 0,
+            enum_type: Some(dummy_name(enum_type)),
+            inner_type: None,
+            value: Some(dummy_name(value)),
+        })
+    };
+    let byte_property = |enum_type: Option<&str>, value| {
+        Property::ByteProperty(ByteProperty {
+            name: dummy_name("Channel"),
+            ancestry: Ancestry::default(),
+            property_guid: None,
+            duplication_index: 0,
+            enum_type: enum_type.map(dummy_name),
+            value,
+        })
+    };
+    let enums = UserEnums::from([("E_Weather".to_string(), 5)]);
+    let annotate = |index: i32| format!("{} (E_Weather)", index);
+    assert_eq!(
+        Some("E_Weather::NewEnumerator2 (5 (E_Weather))".to_string()),
+        enum_value_html(
+            &enum_property("E_Weather", "E_Weather::NewEnumerator2"),
+            annotate,
+            &enums
+        )
+    );
+    assert_eq!(
+        Some("EMovementMode::MOVE_Flying".to_string()),
+        enum_value_html(
+            &enum_property("EMovementMode", "MOVE_Flying"),
+            annotate,
+            &enums
+        )
+    );
+    assert_eq!(
+        None,
+        enum_value_html(&enum_property("None", "MOVE_Flying"), annotate, &enums)
+    );
+    assert_eq!(
+        Some("ECollisionChannel::ECC_Pawn".to_string()),
+        enum_value_html(
+            &byte_property(
+                Some("ECollisionChannel"),
+                BytePropertyValue::FName(dummy_name("ECC_Pawn"))
+            ),
+            annotate,
+            &enums
+        )
+    );
+    assert_eq!(
+        None,
+        enum_value_html(
+            &byte_property(None, BytePropertyValue::Byte(3)),
+            annotate,
+            &enums
+        )
+    );
+}
+
+/// Gameplay tags as a comma-separated list, each tag in a
+/// `<span class="gameplay-tag">`.
+fn gameplay_tags_html(tags: &[String]) -> String {
+    tags.iter()
+        .map(|tag| format!("<span class=\"gameplay-tag\">{}</span>", escape_html(tag)))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+#[test]
+fn test_gameplay_tags_html() {
+    assert_eq!(
+        "<span class=\"gameplay-tag\">Ability.Melee.Heavy</span>, \
+         <span class=\"gameplay-tag\">Status.Stunned</span>",
+        gameplay_tags_html(&[
+            "Ability.Melee.Heavy".to_string(),
+            "Status.Stunned".to_string()
+        ])
+    );
+    assert_eq!("", gameplay_tags_html(&[]));
+}
+
+/// Whether a delegate is bound to nothing, as unset delegates are saved.
+fn is_unbound(delegate: &Delegate) -> bool {
+    let function = delegate.delegate.get_owned_content();
+    delegate.object.index == 0 && (function.is_empty() || function == "None")
+}
+
+/// `&rarr; Door_2.OnOpened`: the bound object through `annotate` and the
+/// function's name, or `(unbound)`.
+fn delegate_binding_html(delegate: &Delegate, annotate: impl Fn(i32) -> String) -> String {
+    if is_unbound(delegate) {
+        return "(unbound)".to_string();
+    }
+    format!(
+        "&rarr; {}.{}",
+        annotate(delegate.object.index),
+        escape_html(&delegate.delegate.get_owned_content())
+    )
+}
+
+/// The bindings of a multicast delegate, counted and listed one per line,
+/// or `(unbound)` when nothing is bound.
+fn multicast_delegate_html(delegates: &[Delegate], annotate: impl Fn(i32) -> String) -> String {
+    let bound: Vec<&Delegate> = delegates.iter().filter(|d| !is_unbound(d)).collect();
+    if bound.is_empty() {
+        return "(unbound)".to_string();
+    }
+    let items: String = bound
+        .iter()
+        .map(|delegate| format!("<li>{}</li>", delegate_binding_html(delegate, &annotate)))
+        .collect();
+    format!(
+        "{} {}<ul>{}</ul>",
+        format_count(bound.len()),
+        if bound.len() == 1 {
+            "binding"
+        } else {
+            "bindings"
+        },
+        items
+    )
+}
+
+/// `Hero_C (3).Stats.MaxHealth`: the field a field path property points at,
+/// its owner through `annotate` when the path was resolved to one, then the
+/// names of the fields inside it. The engine saves the names innermost
+/// first, so they're read back to front. `None` for an empty path.
+fn field_path_html(path: &[FName], owner: i32, annotate: impl Fn(i32) -> String) -> Option<String> {
+    if path.is_empty() {
+        return None;
+    }
+    let mut parts: Vec<String> = Vec::new();
+    if owner != 0 {
+        parts.push(annotate(owner));
+    }
+    parts.extend(
+        path.iter()
+            .rev()
+            .map(|name| escape_html(&name.get_owned_content())),
+    );
+    Some(parts.join("."))
+}
+
+#[cfg(test)]
+fn field_names(names: &[&str]) -> Vec<FName> {
+    names
+        .iter()
+        .map(|name| FName::new_dummy(name.to_string(), 0))
+        .collect()
+}
+
+#[test]
+fn test_field_path_html() {
+    let annotate = |index: i32| format!("Hero_C ({})", index);
+    assert_eq!(
+        Some("Hero_C (3).Stats.MaxHealth".to_string()),
+        field_path_html(&field_names(&["MaxHealth", "Stats"]), 3, annotate)
+    );
+    assert_eq!(
+        Some("Stats.&lt;Max&gt;".to_string()),
+        field_path_html(&field_names(&["<Max>", "Stats"]), 0, annotate)
+    );
+    assert_eq!(None, field_path_html(&[], 3, annotate));
+}
+
+#[cfg(test)]
+fn delegate(object: i32, function: &str) -> Delegate {
+    Delegate {
+        object: unreal_asset::base::types::PackageIndex::new(object),
+        delegate: unreal_asset::base::types::fname::FName::new_dummy(function.to_string(), 0),
+    }
+}
+
+#[test]
+fn test_delegate_binding_html() {
+    let annotate = |index: i32| format!("{} (Door_2)", index);
+    assert_eq!(
+        "&rarr; 3 (Door_2).OnOpened",
+        delegate_binding_html(&delegate(3, "OnOpened"), annotate)
+    );
+    assert_eq!(
+        "(unbound)",
+        delegate_binding_html(&delegate(0, "None"), annotate)
+    );
+}
+
+#[test]
+fn test_multicast_delegate_html() {
+    let annotate = |index: i32| index.to_string();
+    assert_eq!(
+        "2 bindings<ul><li>&rarr; 3.OnOpened</li><li>&rarr; -2.PlaySound</li></ul>",
+        multicast_delegate_html(
+            &[
+                delegate(3, "OnOpened"),
+                delegate(0, "None"),
+                delegate(-2, "PlaySound")
+            ],
+            annotate
+        )
+    );
+    assert_eq!(
+        "1 binding<ul><li>&rarr; 3.OnOpened</li></ul>",
+        multicast_delegate_html(&[delegate(3, "OnOpened")], annotate)
+    );
+    assert_eq!("(unbound)", multicast_delegate_html(&[], annotate));
+}
+
+/// `Namespace="Dialogue" Key="NPC_Greeting_03" Source="Hello, traveler."`,
+/// or the source string of culture-invariant text, or the table and key of a
+/// string table entry.
+pub fn localized_text_html(text: &LocalizedText) -> String {
+    match text {
+        LocalizedText::Localized {
+            namespace,
+            key,
+            source,
+        } => format!(
+            "Namespace=\"{}\" Key=\"{}\" Source=\"{}\"",
+            escape_html(namespace),
+            escape_html(key),
+            escape_html(source)
+        ),
+        LocalizedText::CultureInvariant(source) => {
+            format!("Source=\"{}\" (culture invariant)", escape_html(source))
+        }
+        LocalizedText::StringTableEntry { table, key } => format!(
+            "StringTable=\"{}\" Key=\"{}\"",
+            escape_html(table),
+            escape_html(key)
+        ),
+    }
+}
+
+#[test]
+fn test_localized_text_html() {
+    assert_eq!(
+        "Namespace=\"Dialogue\" Key=\"NPC_Greeting_03\" Source=\"Hello, &lt;traveler&gt;.\"",
+        localized_text_html(&LocalizedText::Localized {
+            namespace: "Dialogue".to_string(),
+            key: "NPC_Greeting_03".to_string(),
+            source: "Hello, <traveler>.".to_string(),
+        })
+    );
+    assert_eq!(
+        "Source=\"42\" (culture invariant)",
+        localized_text_html(&LocalizedText::CultureInvariant("42".to_string()))
+    );
+    assert_eq!(
+        "StringTable=\"/Game/Text/ST_Dialogue.ST_Dialogue\" Key=\"Greeting\"",
+        localized_text_html(&LocalizedText::StringTableEntry {
+            table: "/Game/Text/ST_Dialogue.ST_Dialogue".to_string(),
+            key: "Greeting".to_string(),
+        })
+    );
+}
+
+/// Renders a property's value as HTML on one line: object references through
+/// `annotate`, delegates by what they're bound to, enum values by name, gameplay tags as a list, texts by their
+/// namespace, key and source string, scalars as text and well-known structs
+/// compactly. Returns `None` for everything else, which callers show as a
+/// Debug dump instead.
+pub fn value_html(
+    property: &Property,
+    annotate: impl Fn(i32) -> String,
+    enums: &UserEnums,
+) -> Option<String> {
+    match property {
+        Property::ObjectProperty(p) => Some(annotate(p.value.index)),
+        Property::InterfaceProperty(p) => Some(annotate(p.value.index)),
+        Property::FieldPathProperty(p) => {
+            field_path_html(&p.value.path, p.value.resolved_owner.index, annotate)
+        }
+        Property::DelegateProperty(p) => Some(delegate_binding_html(&p.value, annotate)),
+        Property::MulticastDelegateProperty(p) => Some(multicast_delegate_html(&p.value, annotate)),
+        Property::MulticastInlineDelegateProperty(p) => {
+            Some(multicast_delegate_html(&p.value, annotate))
+        }
+        Property::MulticastSparseDelegateProperty(p) => {
+            Some(multicast_delegate_html(&p.value, annotate))
+        }
+        property => enum_value_html(property, &annotate, enums)
+            .or_else(|| gameplay_tags(property).map(|tags| gameplay_tags_html(&tags)))
+            .or_else(|| localized_text(property).map(|text| localized_text_html(&text)))
+            .or_else(|| struct_value_html(property))
+            .or_else(|| scalar_value(property).map(|value| escape_html(&value))),
+    }
+}
+
+#[test]
+fn test_value_html_interface() {
+    let property = Property::InterfaceProperty(
+        unreal_asset::properties::object_property::InterfaceProperty {
+            name: dummy_name("Interactable"),
+            ancestry: unreal_asset::unversioned::ancestry::Ancestry::default(),
+            property_guid: None,
+            duplication_index: 0,
+            value: unreal_asset::types::PackageIndex::new(4),
+        },
+    );
+    assert_eq!(
+        Some("4 (Lever_C)".to_string()),
+        value_html(
+            &property,
+            |index| format!("{} (Lever_C)", index),
+            &UserEnums::new()
+        )
+    );
+}
+
+#[test]
+fn test_value_html_gameplay_tags() {
+    use unreal_asset::{
+        properties::gameplay_tag_container_property::GameplayTagContainerProperty,
+        unversioned::ancestry::Ancestry,
+    };
+    let property = Property::GameplayTagContainerProperty(GameplayTagContainerProperty {
+        name: dummy_name("Tags"),
+        ancestry: Ancestry::default(),
+        property_guid: None,
+        duplication_index: 0,
+        value: vec![
+            dummy_name("Ability.Melee.Heavy"),
+            dummy_name("None"),
+            dummy_name("Status.Stunned"),
+        ],
+    });
+    assert_eq!(
+        Some(
+            "<span class=\"gameplay-tag\">Ability.Melee.Heavy</span>, \
+             <span class=\"gameplay-tag\">Status.Stunned</span>"
+                .to_string()
+        ),
+        value_html(&property, |index| index.to_string(), &UserEnums::new())
+    );
+}
+
+/// `9,588`.
+fn format_count(n: usize) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(digit);
+    }
+    out
+}
+
+#[test]
+fn test_format_count() {
+    assert_eq!("0", format_count(0));
+    assert_eq!("412", format_count(412));
+    assert_eq!("9,588", format_count(9588));
+    assert_eq!("1,234,567", format_count(1234567));
+}
+
+/// `512 B`, `12.3 KiB` or `4.0 MiB`.
+pub fn format_size(bytes: i64) -> String {
+    const UNITS: &[&str] = &["KiB", "MiB", "GiB", "TiB"];
+    if bytes.abs() < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size.abs() >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+#[test]
+fn test_format_size() {
+    assert_eq!("0 B", format_size(0));
+    assert_eq!("512 B", format_size(512));
+    assert_eq!("1.0 KiB", format_size(1024));
+    assert_eq!("12.3 KiB", format_size(12595));
+    assert_eq!("4.0 MiB", format_size(4 * 1024 * 1024));
+}
+
+/// The note after the first `shown` of `len` elements, if any were left out.
+fn more_note(len: usize, shown: usize) -> Option<String> {
+    (len > shown).then(|| format!("&hellip; {} more", format_count(len - shown)))
+}
+
+#[test]
+fn test_more_note() {
+    assert_eq!(None, more_note(3, 1000));
+    assert_eq!(None, more_note(1000, 1000));
+    assert_eq!(
+        Some("&hellip; 9,588 more".to_string()),
+        more_note(10588, 1000)
+    );
+}
+
+/// The longest start of `s` at most `max_bytes` long that doesn't split a
+/// character.
+pub fn truncate_at_char_boundary(s: &str, max_bytes: usize) -> &str {
+    if s.len() <= max_bytes {
+        return s;
+    }
+    let mut end = max_bytes;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+#[test]
+fn test_truncate_at_char_boundary() {
+    assert_eq!("abcd", truncate_at_char_boundary("abcd", 4));
+    assert_eq!("abc", truncate_at_char_boundary("abcd", 3));
+    assert_eq!("", truncate_at_char_boundary("abcd", 0));
+    // "é" takes two bytes and "€" three.
+    assert_eq!("ab", truncate_at_char_boundary("abé", 3));
+    assert_eq!("abé", truncate_at_char_boundary("abé", 4));
+    assert_eq!("a", truncate_at_char_boundary("a€", 3));
+    assert_eq!("a€", truncate_at_char_boundary("a€b", 4));
+}
+
+/// `&hellip; (5.2 MiB in all)`, the note after a string cut short.
+pub fn long_string_note(len: usize) -> String {
+    format!("&hellip; ({} in all)", format_size(len as i64))
+}
+
+/// Cuts the lines of `text` longer than `max_bytes`, noting their full
+/// length, so a dump with a giant string in it stays readable.
+pub fn truncate_long_lines(text: &str, max_bytes: usize) -> String {
+    let mut out = String::with_capacity(text.len().min(max_bytes * 64));
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end_matches('\n');
+        if content.len() <= max_bytes {
+            out += line;
+            continue;
+        }
+        out += truncate_at_char_boundary(content, max_bytes);
+        out += &long_string_note(content.len()).replace("&hellip;", "\u{2026}");
+        if line.ends_with('\n') {
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[test]
+fn test_truncate_long_lines() {
+    assert_eq!(
+        "value: \"abc\u{2026} (16 B in all)\nindex: 3\n",
+        truncate_long_lines("value: \"abcdefg\"\nindex: 3\n", 11)
+    );
+    assert_eq!("index: 3", truncate_long_lines("index: 3", 8));
+}
+
+fn dump_html(
+    property: &Property,
+    annotate: &impl Fn(i32) -> String,
+    limits: &PropertyLimits,
+) -> String {
+    let dump = truncate_long_lines(&format!("{:#?}", property), limits.max_string_bytes);
+    format!(
+        "<span style=\"white-space-collapse:preserve;font-family:monospace\">{}</span>",
+        link_and_transform_indices(&escape_html(&dump), annotate)
+    )
+}
+
+/// How much of its properties an export page shows.
+pub struct PropertyLimits<'a> {
+    /// How many elements of an array, set or map are listed.
+    pub max_elements: usize,
+    /// How many bytes of a string are shown before it's cut, at a character
+    /// boundary, with a note of its full length.
+    pub max_string_bytes: usize,
+    /// The export the properties are of, which names the files in
+    /// `strings/`.
+    pub export: i32,
+    /// The URL of the asset's `strings/` folder from the page, when the full
+    /// values of long strings are written there.
+    pub strings_href: Option<&'a str>,
+}
+
+/// The file in `strings/` holding the full value of the long string at
+/// `path` in the export at `export`, e.g. `3_Rewards_2_.Payload.txt` for
+/// `Rewards[2].Payload`.
+pub fn long_string_file(export: i32, path: &str) -> String {
+    let name: String = path
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}_{}.txt", export, name)
+}
+
+#[test]
+fn test_long_string_file() {
+    assert_eq!("3_Payload.txt", long_string_file(3, "Payload"));
+    assert_eq!(
+        "3_Rewards_2_.Payload.txt",
+        long_string_file(3, "Rewards[2].Payload")
+    );
+    assert_eq!("12_Curve_1_.txt", long_string_file(12, "Curve[1]"));
+}
+
+/// The string properties in `properties` longer than `max_bytes`, with
+/// the path of each as `properties_html` names it, for writing their full
+/// values to `strings/`.
+pub fn long_strings(properties: &[Property], max_bytes: usize) -> Vec<(String, String)> {
+    let mut found = Vec::new();
+    for property in properties {
+        walk_property(property, &column_name(property), &mut |path, property| {
+            if let Property::StrProperty(p) = property {
+                if let Some(value) = p.value.as_ref().filter(|value| value.len() > max_bytes) {
+                    found.push((path.to_string(), value.clone()));
+                }
+            }
+        });
+    }
+    found
+}
+
+/// A string value longer than `limits.max_string_bytes`, cut there with a
+/// note of its full length, which links to the full value when it's written
+/// to `strings/`.
+fn long_string_html(value: &str, path: &str, limits: &PropertyLimits) -> String {
+    let note = long_string_note(value.len());
+    let note = match limits.strings_href {
+        Some(href) => format!(
+            "<a href=\"{}/{}\">{}</a>",
+            href,
+            escape_html(&long_string_file(limits.export, path)),
+            note
+        ),
+        None => note,
+    };
+    format!(
+        "{}{}",
+        escape_html(truncate_at_char_boundary(value, limits.max_string_bytes)),
+        note
+    )
+}
+
+#[test]
+fn test_long_string_html() {
+    let limits = PropertyLimits {
+        max_elements: 1000,
+        max_string_bytes: 5,
+        export: 3,
+        strings_href: Some("../../strings"),
+    };
+    assert_eq!(
+        "&lt;a€<a href=\"../../strings/3_Rewards_2_.Json.txt\">&hellip; (7 B in all)</a>",
+        long_string_html("<a€bc", "Rewards[2].Json", &limits)
+    );
+    let limits = PropertyLimits {
+        strings_href: None,
+        ..limits
+    };
+    assert_eq!(
+        "abcde&hellip; (6 B in all)",
+        long_string_html("abcdef", "Json", &limits)
+    );
+}
+
+/// An array, set or map as a collapsed `<details>` whose summary is `label`
+/// followed by the container's size and element type, e.g.
+/// `ArrayProperty[412] of ObjectProperty`. Only the first `max_elements`
+/// elements are listed. Returns `None` for any other property.
+fn container_html(
+    label: &str,
+    path: &str,
+    property: &Property,
+    annotate: &impl Fn(i32) -> String,
+    enums: &UserEnums,
+    limits: &PropertyLimits,
+) -> Option<String> {
+    let max_elements = limits.max_elements;
+    let elements = match property {
+        Property::ArrayProperty(p) => &p.value,
+        Property::SetProperty(p) => &p.value.value,
+        Property::MapProperty(p) => {
+            let entries: Vec<(&Property, &Property)> = p.value.iter().collect();
+            let mut summary = format!("{}MapProperty[{}]", label, format_count(entries.len()));
+            let Some((key, value)) = entries.first() else {
+                return Some(summary);
+            };
+            summary += &format!(" of {} &rarr; {}", variant_name(key), variant_name(value));
+            let rows: String = entries
+                .iter()
+                .take(max_elements)
+                .enumerate()
+                .map(|(i, (key, value))| {
+                    format!(
+                        "<tr><td>{}</td><td>{}</td></tr>",
+                        element_html(
+                            "",
+                            &format!("{}[{}].key", path, i),
+                            key,
+                            annotate,
+                            enums,
+                            limits
+                        ),
+                        element_html(
+                            "",
+                            &format!("{}[{}].value", path, i),
+                            value,
+                            annotate,
+                            enums,
+                            limits
+                        )
+                    )
+                })
+                .collect();
+            let more = more_note(entries.len(), max_elements)
+                .map(|note| format!("<p>{}</p>", note))
+                .unwrap_or_default();
+            return Some(format!(
+                "<details><summary>{}</summary><table>{}</table>{}</details>",
+                summary, rows, more
+            ));
+        }
+        _ => return None,
+    };
+    let mut summary = format!(
+        "{}{}[{}]",
+        label,
+        variant_name(property),
+        format_count(elements.len())
+    );
+    let Some(first) = elements.first() else {
+        return Some(summary);
+    };
+    summary += &format!(" of {}", variant_name(first));
+    let items: String = elements
+        .iter()
+        .take(max_elements)
+        .enumerate()
+        .map(|(i, element)| {
+            format!(
+                "<li>{}</li>",
+                element_html(
+                    &format!("{}: ", i),
+                    &format!("{}[{}]", path, i),
+                    element,
+                    annotate,
+                    enums,
+                    limits
+                )
+            )
+        })
+        .collect();
+    let more = more_note(elements.len(), max_elements)
+        .map(|note| format!("<li>{}</li>", note))
+        .unwrap_or_default();
+    Some(format!(
+        "<details><summary>{}</summary><ul>{}{}</ul></details>",
+        summary, items, more
+    ))
+}
+
+/// `label` followed by the property's value: containers through
+/// `container_html`, long strings cut short, what `value_html` can render on
+/// one line, and the linked Debug dump of anything else. `path` is where the
+/// property sits, as `properties::walk_property` names it.
+fn element_html(
+    label: &str,
+    path: &str,
+    property: &Property,
+    annotate: &impl Fn(i32) -> String,
+    enums: &UserEnums,
+    limits: &PropertyLimits,
+) -> String {
+    if let Property::StrProperty(p) = property {
+        if let Some(value) = p
+            .value
+            .as_deref()
+            .filter(|value| value.len() > limits.max_string_bytes)
+        {
+            return format!("{}{}", label, long_string_html(value, path, limits));
+        }
+    }
+    container_html(label, path, property, annotate, enums, limits).unwrap_or_else(|| {
+        let value = value_html(property, annotate, enums)
+            .unwrap_or_else(|| dump_html(property, annotate, limits));
+        format!("{}{}", label, value)
+    })
+}
+
+/// The "Properties" section of an export page, one entry per property,
+/// shown as far as `limits` allow.
+pub fn properties_html(
+    properties: &[Property],
+    annotate: impl Fn(i32) -> String,
+    enums: &UserEnums,
+    limits: &PropertyLimits,
+) -> String {
+    let items: String = properties
+        .iter()
+        .map(|property| {
+            let path = column_name(property);
+            let label = format!("{}: ", escape_html(&path));
+            format!(
+                "<li>{}</li>",
+                element_html(&label, &path, property, &annotate, enums, limits)
+            )
+        })
+        .collect();
+    format!("<h2>Properties</h2><ul>{}</ul>", items)
+}
@@ -0,0 +1,399 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufWriter, Read, Seek, Write};
+use std::path::Path;
+use unreal_asset::{
+    base::types::PackageIndex,
+    exports::{struct_export::StructExport, Export, ExportBaseTrait, ExportNormalTrait},
+    properties::{Property, PropertyDataTrait},
+    Asset,
+};
+
+use crate::{properties, IndexNames};
+
+/// Node cap used when `--graph-max-nodes` isn't given. Graphviz still lays
+/// out a few hundred nodes in reasonable time.
+pub const DEFAULT_MAX_NODES: usize = 500;
+
+/// An edge between two package indices, labeled with what made it.
+#[derive(Debug, PartialEq)]
+pub struct Edge {
+    pub from: i32,
+    pub to: i32,
+    pub label: String,
+}
+
+/// Collects the edges of `asset`'s reference graph: each import's outer and
+/// the edges of each export, see [`collect_export_edges`].
+pub fn collect_edges<C: Read + Seek>(asset: &Asset<C>) -> Vec<Edge> {
+    let mut edges = Vec::new();
+    for (i, import) in asset.imports.iter().enumerate() {
+        if import.outer_index.index != 0 {
+            edges.push(Edge {
+                from: -(i as i32 + 1),
+                to: import.outer_index.index,
+                label: "outer".to_string(),
+            });
+        }
+    }
+    for (i, export) in asset.asset_data.exports.iter().enumerate() {
+        edges.extend(collect_export_edges(i as i32 + 1, export));
+    }
+    edges
+}
+
+/// The edges from `export`, the export at index `from`: its outer, class,
+/// super and template, the exports and imports it has to be loaded after,
+/// every ObjectProperty found in its properties, and for a class, struct or
+/// function the property classes and structs of its fields and the
+/// interfaces a class implements.
+fn collect_export_edges(from: i32, export: &Export<PackageIndex>) -> Vec<Edge> {
+    let mut edges = Vec::new();
+    let mut push = |to: i32, label: &str| {
+        if to != 0 {
+            edges.push(Edge {
+                from,
+                to,
+                label: label.to_string(),
+            });
+        }
+    };
+    let base = export.get_base_export();
+    push(base.outer_index.index, "outer");
+    push(base.class_index.index, "class");
+    push(base.super_index.index, "super");
+    push(base.template_index.index, "template");
+    for dependency in base
+        .serialization_before_serialization_dependencies
+        .iter()
+        .chain(&base.create_before_serialization_dependencies)
+        .chain(&base.serialization_before_create_dependencies)
+        .chain(&base.create_before_create_dependencies)
+    {
+        push(dependency.index, "dependency");
+    }
+    if let Some(normal) = export.get_normal_export() {
+        for property in &normal.properties {
+            let mut references = Vec::new();
+            collect_object_references(property, &mut references);
+            let label = property.get_name().get_owned_content();
+            for to in references {
+                push(to, &label);
+            }
+        }
+    }
+    if let Some(struct_export) = struct_export(export) {
+        // FProperty has a variant per property type, each holding its
+        // classes and structs in differently named fields.
+        for to in crate::dump_indices(&format!("{:?}", struct_export.loaded_properties)) {
+            push(to, "field");
+        }
+    }
+    if let Export::ClassExport(class) = export {
+        for interface in &class.interfaces {
+            push(interface.class, "interface");
+        }
+    }
+    edges
+}
+
+#[test]
+fn test_collect_export_edges_of_blueprint_class() {
+    use unreal_asset::{
+        base::types::fname::FName,
+        containers::IndexedMap,
+        exports::{
+            base_export::BaseExport,
+            class_export::{ClassExport, SerializedInterfaceReference},
+            normal_export::NormalExport,
+            properties::uproperty::UField,
+        },
+        flags::EClassFlags,
+    };
+    // A Blueprint class: its parent class -3 is only named as its super and
+    // as a dependency, and the interface -5 only in its interface list.
+    let class = Export::ClassExport(ClassExport {
+        struct_export: StructExport {
+            normal_export: NormalExport {
+                base_export: BaseExport {
+                    class_index: PackageIndex::new(-1),
+                    super_index: PackageIndex::new(-3),
+                    create_before_serialization_dependencies: vec![PackageIndex::new(-3)],
+                    ..Default::default()
+                },
+                extras: Vec::new(),
+                properties: Vec::new(),
+            },
+            field: UField { next: None },
+            super_struct: PackageIndex::new(-3),
+            children: Vec::new(),
+            loaded_properties: Vec::new(),
+            script_bytecode: None,
+            script_bytecode_size: 0,
+            script_bytecode_raw: None,
+        },
+        func_map: IndexedMap::new(),
+        class_flags: EClassFlags::CLASS_NONE,
+        class_within: PackageIndex::new(0),
+        class_config_name: FName::new_dummy("Engine".to_string(), 0),
+        interfaces: vec![SerializedInterfaceReference::new(-5, 0, true)],
+        class_generated_by: PackageIndex::new(0),
+        deprecated_force_script_order: false,
+        cooked: None,
+        class_default_object: PackageIndex::new(2),
+    });
+    let edges = collect_export_edges(1, &class);
+    let labeled: Vec<(i32, &str)> = edges
+        .iter()
+        .map(|edge| (edge.to, edge.label.as_str()))
+        .collect();
+    assert_eq!(
+        vec![
+            (-1, "class"),
+            (-3, "super"),
+            (-3, "dependency"),
+            (-5, "interface"),
+        ],
+        labeled
+    );
+    let referrers = referrers(&edges);
+    assert_eq!(
+        vec![-2, -4],
+        unreferenced_imports(5, &referrers, &HashSet::new())
+    );
+}
+
+/// The struct part of `export` when it's a class, struct or function.
+fn struct_export(export: &Export<PackageIndex>) -> Option<&StructExport<PackageIndex>> {
+    match export {
+        Export::ClassExport(class) => Some(&class.struct_export),
+        Export::StructExport(struct_export) => Some(struct_export),
+        Export::FunctionExport(function) => Some(&function.struct_export),
+        _ => None,
+    }
+}
+
+/// Appends every non-null ObjectProperty value inside `property`, descending
+/// into structs and containers.
+fn collect_object_references(property: &Property, references: &mut Vec<i32>) {
+    properties::walk_property(property, "", &mut |_, property| {
+        if let Property::ObjectProperty(p) = property {
+            if p.value.index != 0 {
+                references.push(p.value.index);
+            }
+        }
+    });
+}
+
+fn node_id(index: i32) -> String {
+    if index < 0 {
+        format!("i{}", -index)
+    } else {
+        format!("e{}", index)
+    }
+}
+
+pub fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Writes the graph in DOT format. Exports are boxes, imports are grey
+/// ellipses. Once `max_nodes` nodes are placed (exports first), every other
+/// node and its edges are left out and the graph label says so.
+pub fn write_dot<W: Write>(
+    sink: &mut W,
+    names: &IndexNames,
+    edges: &[Edge],
+    max_nodes: usize,
+) -> std::io::Result<()> {
+    let total = names.exports.len() + names.imports.len();
+    let included = |index: i32| {
+        if index > 0 {
+            (index as usize) <= max_nodes.min(names.exports.len())
+        } else {
+            let export_slots = max_nodes.min(names.exports.len());
+            (-index as usize) <= max_nodes - export_slots && names.get(index).is_some()
+        }
+    };
+    writeln!(sink, "digraph asset {{")?;
+    if total > max_nodes {
+        writeln!(
+            sink,
+            "  label=\"Showing {} of {} nodes\";",
+            max_nodes, total
+        )?;
+    }
+    for (i, name) in names.exports.iter().enumerate() {
+        let index = i as i32 + 1;
+        if included(index) {
+            writeln!(
+                sink,
+                "  {} [shape=box, label=\"{}: {}\"];",
+                node_id(index),
+                index,
+                escape_dot(name)
+            )?;
+        }
+    }
+    for (i, name) in names.imports.iter().enumerate() {
+        let index = -(i as i32 + 1);
+        if included(index) {
+            writeln!(
+                sink,
+                "  {} [shape=ellipse, style=filled, fillcolor=lightgrey, label=\"{}: {}\"];",
+                node_id(index),
+                index,
+                escape_dot(name)
+            )?;
+        }
+    }
+    for edge in edges {
+        if included(edge.from) && included(edge.to) {
+            writeln!(
+                sink,
+                "  {} -> {} [label=\"{}\"];",
+                node_id(edge.from),
+                node_id(edge.to),
+                escape_dot(&edge.label)
+            )?;
+        }
+    }
+    writeln!(sink, "}}")
+}
+
+/// The edges pointing at each index, in the order they were collected.
+pub fn referrers(edges: &[Edge]) -> HashMap<i32, Vec<&Edge>> {
+    let mut referrers: HashMap<i32, Vec<&Edge>> = HashMap::new();
+    for edge in edges {
+        referrers.entry(edge.to).or_default().push(edge);
+    }
+    referrers
+}
+
+/// The imports nothing else in the asset refers to, in package order: no
+/// edge from an export or another import, see [`collect_edges`], and none
+/// of `script_references`, which the graph leaves out.
+pub fn unreferenced_imports(
+    import_count: usize,
+    referrers: &HashMap<i32, Vec<&Edge>>,
+    script_references: &HashSet<i32>,
+) -> Vec<i32> {
+    (1..=import_count as i32)
+        .map(|i| -i)
+        .filter(|index| {
+            let referenced = referrers
+                .get(index)
+                .is_some_and(|edges| edges.iter().any(|edge| edge.from != *index));
+            !referenced && !script_references.contains(index)
+        })
+        .collect()
+}
+
+#[test]
+fn test_unreferenced_imports() {
+    let edge = |from: i32, to: i32| Edge {
+        from,
+        to,
+        label: "outer".to_string(),
+    };
+    // -1 is the package of -2, which an export uses; -3 is its own outer and
+    // -4 is only called from bytecode.
+    let edges = vec![edge(-2, -1), edge(1, -2), edge(-3, -3), edge(-5, -5)];
+    let referrers = referrers(&edges);
+    assert_eq!(
+        vec![-3, -5],
+        unreferenced_imports(5, &referrers, &HashSet::from([-4]))
+    );
+    assert!(unreferenced_imports(0, &referrers, &HashSet::new()).is_empty());
+}
+
+/// The edges starting at each index, in the order they were collected.
+pub fn references(edges: &[Edge]) -> HashMap<i32, Vec<&Edge>> {
+    let mut references: HashMap<i32, Vec<&Edge>> = HashMap::new();
+    for edge in edges {
+        references.entry(edge.from).or_default().push(edge);
+    }
+    references
+}
+
+#[test]
+fn test_referrers() {
+    let edge = |from, to, label: &str| Edge {
+        from,
+        to,
+        label: label.to_string(),
+    };
+    let edges = vec![
+        edge(1, -2, "class"),
+        edge(2, 1, "outer"),
+        edge(3, -2, "class"),
+        edge(3, 1, "Owner"),
+    ];
+    let referrers = referrers(&edges);
+    assert_eq!(vec![&edges[0], &edges[2]], referrers[&-2]);
+    assert_eq!(vec![&edges[1], &edges[3]], referrers[&1]);
+    assert!(!referrers.contains_key(&2));
+}
+
+/// Writes the reference graph made of `edges` to `path`.
+pub fn write_graph(
+    names: &IndexNames,
+    edges: &[Edge],
+    path: &Path,
+    max_nodes: usize,
+) -> std::io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    write_dot(&mut file, names, edges, max_nodes)?;
+    file.flush()
+}
+
+#[test]
+fn test_write_dot() {
+    let names = crate::test_index_names();
+    let edges = vec![
+        Edge {
+            from: 1,
+            to: -2,
+            label: "class".to_string(),
+        },
+        Edge {
+            from: -2,
+            to: -1,
+            label: "outer".to_string(),
+        },
+    ];
+    let mut out = Vec::new();
+    write_dot(&mut out, &names, &edges, DEFAULT_MAX_NODES).unwrap();
+    let dot = String::from_utf8(out).unwrap();
+    assert!(dot.contains("e1 [shape=box, label=\"1: Default__Foo\"];"));
+    assert!(dot.contains(
+        "i2 [shape=ellipse, style=filled, fillcolor=lightgrey, label=\"-2: Texture2D\"];"
+    ));
+    assert!(dot.contains("e1 -> i2 [label=\"class\"];"));
+    assert!(dot.contains("i2 -> i1 [label=\"outer\"];"));
+    assert!(!dot.contains("label=\"Showing"));
+}
+
+#[test]
+fn test_write_dot_caps_nodes() {
+    let names = crate::test_index_names();
+    let edges = vec![
+        Edge {
+            from: 1,
+            to: -1,
+            label: "outer".to_string(),
+        },
+        Edge {
+            from: 1,
+            to: -2,
+            label: "class".to_string(),
+        },
+    ];
+    let mut out = Vec::new();
+    write_dot(&mut out, &names, &edges, 2).unwrap();
+    let dot = String::from_utf8(out).unwrap();
+    assert!(dot.contains("label=\"Showing 2 of 3 nodes\";"));
+    assert!(dot.contains("e1 -> i1"));
+    assert!(!dot.contains("i2 "));
+    assert!(!dot.contains("e1 -> i2"));
+}
@@ -0,0 +1,200 @@
+use rayon::prelude::*;
+use regex::{Regex, RegexBuilder};
+use std::io::{Read, Seek};
+use std::path::Path;
+use unreal_asset::{
+    exports::{ExportBaseTrait, ExportNormalTrait},
+    properties::{Property, PropertyDataTrait},
+    Asset,
+};
+
+use crate::{
+    catch_panics, check, export_class_name, is_valid_extension, matches_wildcard, pak, parse_file,
+    properties::{self, LocalizedText},
+    DirWalk, Error, IndexNames, IndexOptions,
+};
+
+/// What `uasset-index grep` looks for.
+pub struct GrepOptions {
+    pub pattern: Regex,
+    /// Class name patterns, as for `--filter-class`. Only exports whose class
+    /// matches one are searched; empty means every export is.
+    pub class_filter: Vec<String>,
+    /// Also matches the paths soft object and class properties point at.
+    pub object_paths: bool,
+}
+
+impl GrepOptions {
+    /// Matches `pattern` literally unless `regex` is set.
+    pub fn new(
+        pattern: &str,
+        regex: bool,
+        ignore_case: bool,
+        class_filter: Vec<String>,
+        object_paths: bool,
+    ) -> Result<Self, regex::Error> {
+        let pattern = if regex {
+            pattern.to_string()
+        } else {
+            regex::escape(pattern)
+        };
+        Ok(GrepOptions {
+            pattern: RegexBuilder::new(&pattern)
+                .case_insensitive(ignore_case)
+                .build()?,
+            class_filter,
+            object_paths,
+        })
+    }
+}
+
+/// A property whose value matched.
+#[derive(Debug, PartialEq)]
+pub struct GrepMatch {
+    pub export: i32,
+    /// Where the property sits in the export, e.g. `Rewards[2].QuestId`.
+    pub property: String,
+    pub value: String,
+}
+
+/// The text `grep` matches against for `property`: the contents of string
+/// and name properties, the source string of text properties or the key of
+/// a string table entry, and soft object paths with `object_paths`.
+fn searchable_text(property: &Property, object_paths: bool) -> Option<String> {
+    match property {
+        Property::StrProperty(p) => p.value.clone(),
+        Property::NameProperty(p) => Some(p.value.get_owned_content()),
+        Property::TextProperty(_) => match properties::localized_text(property)? {
+            LocalizedText::Localized { source, .. } => Some(source),
+            LocalizedText::CultureInvariant(text) => Some(text),
+            LocalizedText::StringTableEntry { key, .. } => Some(key),
+        },
+        _ if object_paths => properties::soft_reference(property),
+        _ => None,
+    }
+}
+
+/// Every match of `options.pattern` in the properties of the asset's exports.
+fn grep_asset<C: Read + Seek>(
+    asset: &Asset<C>,
+    names: &IndexNames,
+    options: &GrepOptions,
+) -> Vec<GrepMatch> {
+    let mut matches = Vec::new();
+    for (i, export) in asset.asset_data.exports.iter().enumerate() {
+        let class_index = export.get_base_export().class_index.index;
+        let class_name = export_class_name(names, class_index);
+        if !options.class_filter.is_empty()
+            && !options
+                .class_filter
+                .iter()
+                .any(|pattern| matches_wildcard(pattern, class_name))
+        {
+            continue;
+        }
+        let Some(normal) = export.get_normal_export() else {
+            continue;
+        };
+        for property in &normal.properties {
+            let name = property.get_name().get_owned_content();
+            properties::walk_property(property, &name, &mut |path, property| {
+                let Some(text) = searchable_text(property, options.object_paths) else {
+                    return;
+                };
+                if options.pattern.is_match(&text) {
+                    matches.push(GrepMatch {
+                        export: i as i32 + 1,
+                        property: path.to_string(),
+                        value: text,
+                    });
+                }
+            });
+        }
+    }
+    matches
+}
+
+fn grep_file(
+    path: &Path,
+    options: &IndexOptions,
+    grep_options: &GrepOptions,
+) -> Result<Vec<GrepMatch>, Error> {
+    let (asset, _) = parse_file(path, options)?;
+    let names = IndexNames::from_asset(&asset);
+    Ok(grep_asset(&asset, &names, grep_options))
+}
+
+/// What `grep` found.
+#[derive(Debug, Default)]
+pub struct GrepReport {
+    pub matches: usize,
+    pub failed: usize,
+}
+
+impl GrepReport {
+    pub fn add(&mut self, other: GrepReport) {
+        self.matches += other.matches;
+        self.failed += other.failed;
+    }
+}
+
+/// Searches the properties of the asset at `path`, or of every asset under
+/// the directory `path`, printing each match as
+/// `path:export_index:property: value`. Nothing is written.
+pub fn grep(path: &Path, options: &IndexOptions, grep_options: &GrepOptions) -> GrepReport {
+    let mut assets = Vec::new();
+    if pak::is_pak(path) {
+        eprintln!("{}: grep doesn't read .pak files", path.display());
+        return GrepReport {
+            matches: 0,
+            failed: 1,
+        };
+    } else if path.is_dir() {
+        let mut walk = DirWalk::new(path, options);
+        check::find_assets(path, &mut walk, &mut assets);
+    } else if !is_valid_extension(path.extension(), &options.extensions) {
+        eprintln!("{}", Error::InvalidExtension(path.to_path_buf()));
+        return GrepReport {
+            matches: 0,
+            failed: 1,
+        };
+    } else {
+        assets.push(path.to_path_buf());
+    }
+    let results: Vec<Result<Vec<GrepMatch>, Error>> = assets
+        .par_iter()
+        .map(|asset| catch_panics(asset, || grep_file(asset, options, grep_options)))
+        .collect();
+    let mut report = GrepReport::default();
+    for (asset, result) in assets.iter().zip(results) {
+        match result {
+            Ok(matches) => {
+                report.matches += matches.len();
+                for found in matches {
+                    options.log(format_args!(
+                        "{}:{}:{}: {}",
+                        asset.display(),
+                        found.export,
+                        found.property,
+                        found.value
+                    ));
+                }
+            }
+            Err(err) => {
+                report.failed += 1;
+                eprintln!("{}", err);
+            }
+        }
+    }
+    report
+}
+
+#[test]
+fn test_grep_options() {
+    let literal = GrepOptions::new("Quest.017", false, false, Vec::new(), false).unwrap();
+    assert!(literal.pattern.is_match("QuestID_Quest.017"));
+    assert!(!literal.pattern.is_match("Quest_017"));
+    let regex = GrepOptions::new("quest.017", true, true, Vec::new(), false).unwrap();
+    assert!(regex.pattern.is_match("Quest_017"));
+    assert!(GrepOptions::new("[a", true, false, Vec::new(), false).is_err());
+}
@@ -0,0 +1,173 @@
+use std::io::{Result, Write};
+use unreal_asset::exports::Export;
+
+/// How many bytes of an export `raw.html` dumps unless `--raw-max-bytes`
+/// says otherwise.
+pub const DEFAULT_MAX_BYTES: usize = 1 << 20;
+
+const ROW_BYTES: usize = 16;
+
+/// `--raw-dumps`: how much of each export's serialized data `raw.html`
+/// shows.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RawDumps {
+    /// Exports with more bytes than this get no `raw.html`, or with `full`
+    /// one that stops here.
+    pub max_bytes: usize,
+    pub full: bool,
+}
+
+/// The bytes of an asset's `.uasset` and `.uexp`, read before parsing.
+pub struct RawData {
+    pub uasset: Vec<u8>,
+    pub uexp: Option<Vec<u8>>,
+}
+
+/// Where an export's serialized data is in the asset's files.
+#[derive(Debug, PartialEq)]
+pub struct ExportBytes<'a> {
+    /// Whether it's in the `.uexp` rather than the `.uasset`.
+    pub in_uexp: bool,
+    /// The offset of the first byte within that file.
+    pub offset: u64,
+    pub bytes: &'a [u8],
+}
+
+impl RawData {
+    /// The `size` bytes at `serial_offset`, which counts from the start of
+    /// the `.uasset` and carries on into the `.uexp` past its end. `None`
+    /// when they're beyond the end of the data.
+    pub fn export_bytes(&self, serial_offset: i64, size: i64) -> Option<ExportBytes<'_>> {
+        let offset = u64::try_from(serial_offset).ok()?;
+        let size = usize::try_from(size).ok()?;
+        let uasset_len = self.uasset.len() as u64;
+        let (in_uexp, data, offset) = match &self.uexp {
+            Some(uexp) if offset >= uasset_len => (true, uexp, offset - uasset_len),
+            _ => (false, &self.uasset, offset),
+        };
+        let start = usize::try_from(offset).ok()?;
+        let end = start.checked_add(size)?;
+        Some(ExportBytes {
+            in_uexp,
+            offset,
+            bytes: data.get(start..end)?,
+        })
+    }
+}
+
+#[test]
+fn test_export_bytes() {
+    let raw = RawData {
+        uasset: vec![0, 1, 2, 3],
+        uexp: Some(vec![4, 5, 6]),
+    };
+    assert_eq!(
+        Some(ExportBytes {
+            in_uexp: false,
+            offset: 1,
+            bytes: &[1, 2]
+        }),
+        raw.export_bytes(1, 2)
+    );
+    assert_eq!(
+        Some(ExportBytes {
+            in_uexp: true,
+            offset: 1,
+            bytes: &[5, 6]
+        }),
+        raw.export_bytes(5, 2)
+    );
+    assert_eq!(None, raw.export_bytes(5, 3));
+    assert_eq!(None, raw.export_bytes(-1, 1));
+}
+
+/// How many bytes at the end of `export`'s serialized data unreal_asset
+/// didn't interpret: all of them for a raw export, and the extras after
+/// the properties of a normal one.
+pub fn unparsed_tail_len(export: &Export) -> usize {
+    match export {
+        Export::RawExport(raw) => raw.data.len(),
+        Export::NormalExport(normal) => normal.extras.len(),
+        _ => 0,
+    }
+}
+
+fn ascii(byte: u8) -> String {
+    match byte {
+        b'<' => "&lt;".to_string(),
+        b'>' => "&gt;".to_string(),
+        b'&' => "&amp;".to_string(),
+        0x20..=0x7e => (byte as char).to_string(),
+        _ => ".".to_string(),
+    }
+}
+
+/// Writes `bytes` as a `hexdump -C` style dump, 16 to a row behind the
+/// offset of the row's first byte, `start` being that of `bytes[0]`, with
+/// the printable ones spelled out on the right. The bytes from
+/// `highlight_from` on are marked.
+pub fn write_hex_dump<W: Write>(
+    sink: &mut W,
+    bytes: &[u8],
+    start: u64,
+    highlight_from: usize,
+) -> Result<()> {
+    sink.write_all(b"<pre class=\"hexdump\">")?;
+    for (row, chunk) in bytes.chunks(ROW_BYTES).enumerate() {
+        let first = row * ROW_BYTES;
+        let mut hex = String::new();
+        let mut text = String::new();
+        for column in 0..ROW_BYTES {
+            if column > 0 {
+                hex.push(' ');
+            }
+            if column == ROW_BYTES / 2 {
+                hex.push(' ');
+            }
+            if first + column == highlight_from.max(first) && column < chunk.len() {
+                hex += "<mark>";
+                text += "<mark>";
+            }
+            match chunk.get(column) {
+                Some(byte) => {
+                    hex += &format!("{:02x}", byte);
+                    text += &ascii(*byte);
+                }
+                None => hex += "  ",
+            }
+            // Close the mark at the end of the row, before any padding.
+            if column + 1 == chunk.len() && highlight_from <= first + column {
+                hex += "</mark>";
+                text += "</mark>";
+            }
+        }
+        writeln!(sink, "{:08x}  {}  |{}|", start + first as u64, hex, text)?;
+    }
+    sink.write_all(b"</pre>")
+}
+
+#[test]
+fn test_write_hex_dump() {
+    let bytes: Vec<u8> = (0x3c..0x4e).collect();
+    let mut dump = Vec::new();
+    write_hex_dump(&mut dump, &bytes, 0x1a0, 17).unwrap();
+    assert_eq!(
+        "<pre class=\"hexdump\">\
+         000001a0  3c 3d 3e 3f 40 41 42 43  44 45 46 47 48 49 4a 4b  |&lt;=&gt;?@ABCDEFGHIJK|\n\
+         000001b0  4c <mark>4d</mark>                                             |L<mark>M</mark>|\n\
+         </pre>",
+        String::from_utf8(dump).unwrap()
+    );
+
+    let mut dump = Vec::new();
+    write_hex_dump(&mut dump, &[0, 0x41], 0, 0).unwrap();
+    assert_eq!(
+        "<pre class=\"hexdump\">\
+         00000000  <mark>00 41</mark>                                             |<mark>.A</mark>|\n\
+         </pre>",
+        String::from_utf8(dump).unwrap()
+    );
+    let mut dump = Vec::new();
+    write_hex_dump(&mut dump, &[0, 0x41], 0, 2).unwrap();
+    assert!(!String::from_utf8(dump).unwrap().contains("<mark>"));
+}
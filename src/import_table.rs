@@ -0,0 +1,231 @@
+use std::io::{BufReader, Error, ErrorKind, Read, Result, Seek, SeekFrom};
+use std::path::Path;
+use unreal_asset::engine_version::EngineVersion;
+
+use crate::summary::{self, read_fstring, read_i32, PackageHeader, PackageVersions, TableOffsets};
+
+/// The object versions that changed how names and imports are saved.
+const VER_UE4_NAME_HASHES_SERIALIZED: i32 = 504;
+const VER_UE4_NON_OUTER_PACKAGE_IMPORT: i32 = 520;
+const VER_UE5_OPTIONAL_RESOURCES: i32 = 1003;
+
+/// More names or imports than any package has, so a corrupt count fails
+/// early.
+const MAX_ENTRIES: i32 = 1 << 24;
+
+/// An import as `read_import_table` reads it, names resolved.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImportEntry {
+    pub class_package: String,
+    pub class_name: String,
+    pub outer_index: i32,
+    pub object_name: String,
+}
+
+/// The import table of a package, from the summary and the name table
+/// alone, without parsing the exports the way `unreal_asset` does. Reads of
+/// whole directories stay fast this way. Unversioned packages are read as
+/// `engine_version` saves them.
+pub fn read_import_table<R: Read + Seek>(
+    reader: &mut R,
+    engine_version: EngineVersion,
+) -> Result<Vec<ImportEntry>> {
+    let header = summary::read_package_header(reader)?;
+    let versions = summary::table_versions(&header.versions, engine_version)?;
+    let offsets = summary::read_table_offsets(reader, &header, &versions)?;
+    let names = read_names(reader, header.name_count, offsets.name_offset, &versions)?;
+    read_imports(reader, &names, &header, &versions, &offsets)
+}
+
+/// Reads the import table where `offsets` says it is, names resolved with
+/// `names`.
+pub(crate) fn read_imports<R: Read + Seek>(
+    reader: &mut R,
+    names: &[String],
+    header: &PackageHeader,
+    versions: &PackageVersions,
+    offsets: &TableOffsets,
+) -> Result<Vec<ImportEntry>> {
+    check_count("import", offsets.import_count)?;
+    reader.seek(SeekFrom::Start(offsets.import_offset as u64))?;
+    let mut imports = Vec::with_capacity(offsets.import_count as usize);
+    for _ in 0..offsets.import_count {
+        imports.push(read_import(reader, names, header, versions)?);
+    }
+    Ok(imports)
+}
+
+/// Reads one entry of the import table, whose layout depends on the
+/// versions and on whether the package was saved without editor-only data.
+pub(crate) fn read_import<R: Read>(
+    reader: &mut R,
+    names: &[String],
+    header: &PackageHeader,
+    versions: &PackageVersions,
+) -> Result<ImportEntry> {
+    let class_package = read_name(reader, names)?;
+    let class_name = read_name(reader, names)?;
+    let outer_index = read_i32(reader)?;
+    let object_name = read_name(reader, names)?;
+    if versions.file_version_ue4 >= VER_UE4_NON_OUTER_PACKAGE_IMPORT
+        && !header.is_filter_editor_only()
+    {
+        let _package_name = read_name(reader, names)?;
+    }
+    if versions.file_version_ue5 >= VER_UE5_OPTIONAL_RESOURCES {
+        let _import_optional = read_i32(reader)?;
+    }
+    Ok(ImportEntry {
+        class_package,
+        class_name,
+        outer_index,
+        object_name,
+    })
+}
+
+/// `read_import_table` of the asset at `path`.
+pub fn read_import_table_of(
+    path: &Path,
+    engine_version: EngineVersion,
+) -> Result<Vec<ImportEntry>> {
+    let mut reader = BufReader::new(std::fs::File::open(path)?);
+    read_import_table(&mut reader, engine_version)
+}
+
+/// Fails on counts no package has, which only a corrupt summary gives.
+pub(crate) fn check_count(table: &str, count: i32) -> Result<()> {
+    if (0..=MAX_ENTRIES).contains(&count) {
+        Ok(())
+    } else {
+        Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("{} table has {} entries", table, count),
+        ))
+    }
+}
+
+/// Reads the name map, `count` entries from `offset`.
+pub(crate) fn read_names<R: Read + Seek>(
+    reader: &mut R,
+    count: i32,
+    offset: i32,
+    versions: &PackageVersions,
+) -> Result<Vec<String>> {
+    check_count("name", count)?;
+    reader.seek(SeekFrom::Start(offset as u64))?;
+    let mut names = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        names.push(read_name_entry(reader, versions)?);
+    }
+    Ok(names)
+}
+
+/// Reads one entry of the name map.
+pub(crate) fn read_name_entry<R: Read>(
+    reader: &mut R,
+    versions: &PackageVersions,
+) -> Result<String> {
+    let name = read_fstring(reader)?;
+    if versions.file_version_ue4 >= VER_UE4_NAME_HASHES_SERIALIZED {
+        // The case-insensitive and case-preserving hashes.
+        read_i32(reader)?;
+    }
+    Ok(name)
+}
+
+/// Reads an FName, an index into `names` and an instance number, the way
+/// the editor shows it, e.g. `StaticMeshComponent_2` for number 3.
+pub(crate) fn read_name<R: Read>(reader: &mut R, names: &[String]) -> Result<String> {
+    let index = read_i32(reader)?;
+    let number = read_i32(reader)?;
+    let Some(name) = usize::try_from(index).ok().and_then(|i| names.get(i)) else {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("name index {} is out of range", index),
+        ));
+    };
+    Ok(match number {
+        0 => name.clone(),
+        number => format!("{}_{}", name, number - 1),
+    })
+}
+
+/// The object path of import `index`, e.g.
+/// `/Game/Characters/Hero/Hero_BP.Hero_BP_C`: its outers from the package
+/// down, the first joined by `.` and the rest by `:`. `None` when the outer
+/// chain leaves the table or loops.
+pub fn import_object_path(imports: &[ImportEntry], index: i32) -> Option<String> {
+    let mut chain = Vec::new();
+    let mut current = index;
+    while current != 0 {
+        let import = imports.get(usize::try_from(-current).ok()?.checked_sub(1)?)?;
+        if chain.len() > imports.len() {
+            return None;
+        }
+        chain.push(import.object_name.as_str());
+        current = import.outer_index;
+    }
+    chain.reverse();
+    let mut path = chain.first()?.to_string();
+    for (depth, name) in chain.iter().enumerate().skip(1) {
+        path.push(if depth == 1 { '.' } else { ':' });
+        path += name;
+    }
+    Some(path)
+}
+
+#[cfg(test)]
+fn import(class_name: &str, outer_index: i32, object_name: &str) -> ImportEntry {
+    ImportEntry {
+        class_package: "/Script/CoreUObject".to_string(),
+        class_name: class_name.to_string(),
+        outer_index,
+        object_name: object_name.to_string(),
+    }
+}
+
+#[test]
+fn test_import_object_path() {
+    let imports = [
+        import("Package", 0, "/Game/Characters/Hero/Hero_BP"),
+        import("BlueprintGeneratedClass", -1, "Hero_BP_C"),
+        import("SceneComponent", -2, "Root"),
+        import("Object", -5, "Loop"),
+        import("Object", -4, "Loop"),
+    ];
+    assert_eq!(
+        Some("/Game/Characters/Hero/Hero_BP".to_string()),
+        import_object_path(&imports, -1)
+    );
+    assert_eq!(
+        Some("/Game/Characters/Hero/Hero_BP.Hero_BP_C:Root".to_string()),
+        import_object_path(&imports, -3)
+    );
+    assert_eq!(None, import_object_path(&imports, -4));
+    assert_eq!(None, import_object_path(&imports, -9));
+}
+
+#[test]
+fn test_read_import_table() {
+    use crate::test_support::TestPackage;
+
+    let package = TestPackage {
+        names: &["/Script/CoreUObject", "Package", "/Game/Hero"],
+        imports: &[(0, 2, 0), (-1, 1, 3)],
+        exports: &[],
+        ..Default::default()
+    }
+    .bytes();
+    let imports = read_import_table(
+        &mut std::io::Cursor::new(&package),
+        EngineVersion::VER_UE4_27,
+    )
+    .unwrap();
+    assert_eq!(
+        vec![
+            import("Package", 0, "/Game/Hero"),
+            import("Package", -1, "Package_2"),
+        ],
+        imports
+    );
+}
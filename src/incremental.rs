@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use std::io::Result;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use crate::AssetSummary;
+
+/// The size and modification time of an input file.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct FileStamp {
+    size: u64,
+    modified_secs: u64,
+    modified_nanos: u32,
+}
+
+impl FileStamp {
+    fn of(path: &Path) -> Result<Option<FileStamp>> {
+        let metadata = match std::fs::metadata(path) {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let modified = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        Ok(Some(FileStamp {
+            size: metadata.len(),
+            modified_secs: modified.as_secs(),
+            modified_nanos: modified.subsec_nanos(),
+        }))
+    }
+}
+
+/// What the previous run saw, stored in the marker file of the asset's
+/// output directory once the asset was fully indexed.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct SourceRecord {
+    uasset: Option<FileStamp>,
+    /// The `.uexp` can change while the `.uasset` header stays the same.
+    uexp: Option<FileStamp>,
+    /// `IndexOptions::fingerprint` of the run, since different options
+    /// generate different files.
+    options: String,
+}
+
+impl SourceRecord {
+    /// Stamps the asset at `path` and its companion `.uexp`.
+    pub fn for_asset(path: &Path, options: String) -> Result<SourceRecord> {
+        Ok(SourceRecord {
+            uasset: FileStamp::of(path)?,
+            uexp: FileStamp::of(&path.with_extension("uexp"))?,
+            options,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredRecord {
+    source: SourceRecord,
+    summary: AssetSummary,
+}
+
+/// Saves `source` and `summary` into `marker`, handing the summary back.
+pub fn write_record(
+    marker: &Path,
+    source: SourceRecord,
+    summary: AssetSummary,
+) -> Result<AssetSummary> {
+    let stored = StoredRecord { source, summary };
+    crate::manifest::write_atomically(marker, serde_json::to_vec(&stored)?)?;
+    Ok(stored.summary)
+}
+
+/// The summary saved by the previous run, if the marker holds a record that
+/// matches `source`. Markers from older or interrupted runs never match.
+pub fn up_to_date_summary(marker: &Path, source: &SourceRecord) -> Option<AssetSummary> {
+    let contents = std::fs::read(marker).ok()?;
+    let stored: StoredRecord = serde_json::from_slice(&contents).ok()?;
+    (stored.source == *source).then_some(stored.summary)
+}
+
+#[test]
+fn test_up_to_date_summary() {
+    let dir = tempfile::tempdir().unwrap();
+    let asset = dir.path().join("Hero.uasset");
+    let uexp = dir.path().join("Hero.uexp");
+    let marker = dir.path().join("marker");
+    std::fs::write(&asset, "header").unwrap();
+    std::fs::write(&uexp, "exports").unwrap();
+    std::fs::write(&marker, "Hero.uasset").unwrap();
+
+    let record = || SourceRecord::for_asset(&asset, "html".to_string()).unwrap();
+    assert!(up_to_date_summary(&marker, &record()).is_none());
+
+    let summary = AssetSummary {
+        name: "Hero".to_string(),
+        export_count: 1,
+        ..Default::default()
+    };
+    write_record(&marker, record(), summary).unwrap();
+    let cached = up_to_date_summary(&marker, &record()).unwrap();
+    assert_eq!("Hero", cached.name);
+    assert_eq!(1, cached.export_count);
+
+    let other_options = SourceRecord::for_asset(&asset, "html,json".to_string()).unwrap();
+    assert!(up_to_date_summary(&marker, &other_options).is_none());
+
+    // Only the .uexp changes, as happens when just the export data is recooked.
+    std::fs::write(&uexp, "exports, recooked").unwrap();
+    assert!(up_to_date_summary(&marker, &record()).is_none());
+}
@@ -0,0 +1,268 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fs::File;
+use std::io::{BufWriter, Read, Seek};
+use std::path::Path;
+use unreal_asset::{
+    exports::ExportBaseTrait,
+    exports::ExportNormalTrait,
+    properties::{int_property::BytePropertyValue, Property, PropertyDataTrait},
+    Asset,
+};
+
+use crate::{display_name, summary, variant_name, IndexNames};
+
+/// A package index together with the name it resolves to, matching the
+/// annotation the HTML links carry.
+#[derive(Serialize)]
+struct IndexRef {
+    index: i32,
+    name: Option<String>,
+}
+
+impl IndexRef {
+    fn new(names: &IndexNames, index: i32) -> Self {
+        IndexRef {
+            index,
+            name: names.get(index).map(str::to_string),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct ImportJson {
+    index: i32,
+    object_name: String,
+    class_package: String,
+    class_name: String,
+    outer_index: IndexRef,
+}
+
+#[derive(Serialize)]
+pub(crate) struct ExportJson {
+    pub(crate) index: i32,
+    pub(crate) object_name: String,
+    /// E.g. `/Game/Maps/Hub.Hub:PersistentLevel.Door_2`, unless the outer
+    /// chain is broken.
+    pub(crate) object_path: Option<String>,
+    pub(crate) class_name: Option<String>,
+    class_index: IndexRef,
+    outer_index: IndexRef,
+    pub(crate) properties: Vec<Value>,
+}
+
+/// An entry of the package's custom version container.
+#[derive(Serialize)]
+struct CustomVersionJson {
+    guid: String,
+    version: i32,
+    /// E.g. `FCoreObjectVersion`, for the engine's own versions.
+    name: Option<&'static str>,
+}
+
+/// What `asset.json`, `asset.yaml` and the NDJSON lines hold, so the formats
+/// can't drift apart.
+#[derive(Serialize)]
+pub struct AssetJson {
+    custom_versions: Vec<CustomVersionJson>,
+    pub(crate) imports: Vec<ImportJson>,
+    pub(crate) exports: Vec<ExportJson>,
+    /// The imports nothing in the package refers to, for cleaning them up.
+    unreferenced_imports: Vec<IndexRef>,
+}
+
+pub fn asset_json<C: Read + Seek>(
+    asset: &Asset<C>,
+    names: &IndexNames,
+    object_paths: &[Option<String>],
+    unreferenced_imports: &[i32],
+    custom_versions: &[summary::CustomVersionEntry],
+) -> AssetJson {
+    let custom_versions = custom_versions
+        .iter()
+        .map(|entry| CustomVersionJson {
+            guid: summary::format_guid(&entry.guid),
+            version: entry.version,
+            name: entry.friendly_name(),
+        })
+        .collect();
+    let imports = asset
+        .imports
+        .iter()
+        .enumerate()
+        .map(|(i, import)| ImportJson {
+            index: -(i as i32 + 1),
+            object_name: display_name(&import.object_name),
+            class_package: import.class_package.get_owned_content(),
+            class_name: import.class_name.get_owned_content(),
+            outer_index: IndexRef::new(names, import.outer_index.index),
+        })
+        .collect();
+    let exports = asset
+        .asset_data
+        .exports
+        .iter()
+        .enumerate()
+        .map(|(i, export)| {
+            let base = export.get_base_export();
+            let properties = match export.get_normal_export() {
+                Some(normal) => normal
+                    .properties
+                    .iter()
+                    .map(|property| property_json(property, names))
+                    .collect(),
+                None => Vec::new(),
+            };
+            ExportJson {
+                index: i as i32 + 1,
+                object_name: display_name(&base.object_name),
+                object_path: object_paths[i].clone(),
+                class_name: names.get(base.class_index.index).map(str::to_string),
+                class_index: IndexRef::new(names, base.class_index.index),
+                outer_index: IndexRef::new(names, base.outer_index.index),
+                properties,
+            }
+        })
+        .collect();
+    let unreferenced_imports = unreferenced_imports
+        .iter()
+        .map(|&index| IndexRef::new(names, index))
+        .collect();
+    AssetJson {
+        custom_versions,
+        imports,
+        exports,
+        unreferenced_imports,
+    }
+}
+
+/// Writes `asset` as pretty-printed JSON, for `asset.json`.
+pub fn write_asset_json(asset: &AssetJson, path: &Path) -> std::io::Result<()> {
+    let file = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(file, asset)?;
+    Ok(())
+}
+
+/// Writes `asset` as YAML, for `asset.yaml`. Keys keep the order of the JSON
+/// and multi-line strings become block scalars.
+pub fn write_asset_yaml(asset: &AssetJson, path: &Path) -> std::io::Result<()> {
+    let file = BufWriter::new(File::create(path)?);
+    serde_yaml::to_writer(file, asset).map_err(std::io::Error::other)
+}
+
+#[test]
+fn test_asset_yaml_round_trips() {
+    let asset = AssetJson {
+        custom_versions: vec![CustomVersionJson {
+            guid: "375EC13C-06E4-48FB-B500-84F0262A717E".to_string(),
+            version: 42,
+            name: Some("FCoreObjectVersion"),
+        }],
+        imports: vec![ImportJson {
+            index: -1,
+            object_name: "/Script/Engine".to_string(),
+            class_package: "/Script/CoreUObject".to_string(),
+            class_name: "Package".to_string(),
+            outer_index: IndexRef {
+                index: 0,
+                name: None,
+            },
+        }],
+        exports: vec![ExportJson {
+            index: 1,
+            object_name: "Sign".to_string(),
+            object_path: Some("/Game/Sign.Sign".to_string()),
+            class_name: Some("Package".to_string()),
+            class_index: IndexRef {
+                index: -1,
+                name: Some("/Script/Engine".to_string()),
+            },
+            outer_index: IndexRef {
+                index: 0,
+                name: None,
+            },
+            properties: vec![
+                json!({"name": "Lines", "type": "StrProperty", "value": "Keep out.\nBy order of the mayor."}),
+                json!({"name": "Scale", "type": "FloatProperty", "value": 1.5}),
+            ],
+        }],
+        unreferenced_imports: Vec::new(),
+    };
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("asset.yaml");
+    write_asset_yaml(&asset, &path).unwrap();
+    let yaml = std::fs::read_to_string(&path).unwrap();
+    assert!(yaml.starts_with("custom_versions:\n"));
+    assert!(yaml.contains("value: |-\n      Keep out.\n      By order of the mayor.\n"));
+    let parsed: Value = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(serde_json::to_value(&asset).unwrap(), parsed);
+}
+
+fn property_json(property: &Property, names: &IndexNames) -> Value {
+    json!({
+        "name": property.get_name().get_owned_content(),
+        "type": variant_name(property),
+        "value": property_value_json(property, names),
+    })
+}
+
+/// Converts the value of a property into JSON. Property types without a
+/// dedicated mapping fall back to their Debug representation.
+fn property_value_json(property: &Property, names: &IndexNames) -> Value {
+    match property {
+        Property::BoolProperty(p) => json!(p.value),
+        Property::Int8Property(p) => json!(p.value),
+        Property::Int16Property(p) => json!(p.value),
+        Property::IntProperty(p) => json!(p.value),
+        Property::Int64Property(p) => json!(p.value),
+        Property::UInt16Property(p) => json!(p.value),
+        Property::UInt32Property(p) => json!(p.value),
+        Property::UInt64Property(p) => json!(p.value),
+        Property::FloatProperty(p) => json!(p.value.0),
+        Property::DoubleProperty(p) => json!(p.value.0),
+        Property::StrProperty(p) => json!(p.value),
+        Property::NameProperty(p) => json!(p.value.get_owned_content()),
+        Property::ObjectProperty(p) => json!(IndexRef::new(names, p.value.index)),
+        Property::EnumProperty(p) => json!({
+            "enum_type": p.enum_type.as_ref().map(|name| name.get_owned_content()),
+            "value": p.value.as_ref().map(|name| name.get_owned_content()),
+        }),
+        Property::ByteProperty(p) => match &p.value {
+            BytePropertyValue::Byte(byte) => json!(byte),
+            BytePropertyValue::FName(name) => json!(name.get_owned_content()),
+        },
+        Property::StructProperty(p) => json!({
+            "struct_type": p.struct_type.as_ref().map(|name| name.get_owned_content()),
+            "properties": p
+                .value
+                .iter()
+                .map(|property| property_json(property, names))
+                .collect::<Vec<_>>(),
+        }),
+        Property::ArrayProperty(p) => Value::Array(
+            p.value
+                .iter()
+                .map(|element| property_value_json(element, names))
+                .collect(),
+        ),
+        Property::SetProperty(p) => Value::Array(
+            p.value
+                .value
+                .iter()
+                .map(|element| property_value_json(element, names))
+                .collect(),
+        ),
+        Property::MapProperty(p) => Value::Array(
+            p.value
+                .iter()
+                .map(|(key, value)| {
+                    json!({
+                        "key": property_value_json(key, names),
+                        "value": property_value_json(value, names),
+                    })
+                })
+                .collect(),
+        ),
+        other => json!({ "debug": format!("{:?}", other) }),
+    }
+}
@@ -0,0 +1,210 @@
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use std::collections::HashSet;
+use std::io::{Cursor, Read, Result, Seek, Write};
+use unreal_asset::{
+    base::types::PackageIndex,
+    exports::Export,
+    kismet::KismetExpression,
+    reader::{archive_trait::ArchiveTrait, raw_writer::RawWriter},
+    Asset,
+};
+
+use crate::{dump_indices, escape_html, link_and_transform_indices, variant_name};
+
+lazy_static! {
+    // The operands that hold a position in the bytecode: where a jump goes,
+    // where execution resumes after a pushed flow, and where the next case
+    // of a switch and its end are.
+    static ref RE_CODE_OFFSET: Regex =
+        Regex::new(r"((?:code_offset|pushing_address|next_offset|end_goto_offset): )([0-9]+)")
+            .unwrap();
+}
+
+/// The compiled bytecode of a function export.
+pub enum Script<'a> {
+    Parsed(&'a [KismetExpression]),
+    /// unreal_asset couldn't parse the bytecode and kept the bytes instead.
+    Raw(&'a [u8]),
+}
+
+/// The script of `export`, or `None` when it isn't a function or has no
+/// bytecode.
+pub fn function_script(export: &Export<PackageIndex>) -> Option<Script> {
+    let Export::FunctionExport(function) = export else {
+        return None;
+    };
+    let struct_export = &function.struct_export;
+    match (
+        &struct_export.script_bytecode,
+        &struct_export.script_bytecode_raw,
+    ) {
+        (Some(bytecode), _) if !bytecode.is_empty() => Some(Script::Parsed(bytecode)),
+        (None, Some(raw)) if !raw.is_empty() => Some(Script::Raw(raw)),
+        _ => None,
+    }
+}
+
+/// Every package index the bytecode of the function exports among `exports`
+/// mentions, such as the functions it calls and the classes it casts to.
+pub fn script_references(exports: &[Export<PackageIndex>]) -> HashSet<i32> {
+    let mut references = HashSet::new();
+    for export in exports {
+        if let Some(Script::Parsed(statements)) = function_script(export) {
+            references.extend(dump_indices(&format!("{:?}", statements)));
+        }
+    }
+    references
+}
+
+/// `EX_JumpIfNot` for the expression variant `ExJumpIfNot`, the way the
+/// engine names its opcodes.
+fn opcode_name(variant: &str) -> String {
+    match variant.strip_prefix("Ex") {
+        Some(rest) => format!("EX_{}", rest),
+        None => variant.to_string(),
+    }
+}
+
+#[test]
+fn test_opcode_name() {
+    assert_eq!("EX_JumpIfNot", opcode_name("ExJumpIfNot"));
+    assert_eq!("EX_ObjectConst", opcode_name("ExObjectConst"));
+    assert_eq!("Unknown", opcode_name("Unknown"));
+}
+
+/// Where each statement of `script` starts in the bytecode as the engine
+/// loads it, which is what jump operands count in, or `None` for bytecode
+/// that wasn't disassembled. unreal_asset doesn't keep these, so the
+/// statements are written out again the way it saves them and their sizes
+/// added up; `None` as well when that fails.
+pub fn statement_offsets<C: Read + Seek>(asset: &Asset<C>, script: &Script) -> Option<Vec<u32>> {
+    let Script::Parsed(statements) = script else {
+        return None;
+    };
+    let mut cursor = Cursor::new(Vec::new());
+    let mut writer = RawWriter::new(
+        &mut cursor,
+        asset.get_object_version(),
+        asset.get_object_version_ue5(),
+        asset.use_event_driven_loader(),
+        asset.get_name_map(),
+    );
+    let mut offset = 0;
+    let mut offsets = Vec::with_capacity(statements.len());
+    for statement in statements.iter() {
+        offsets.push(offset);
+        offset += KismetExpression::write(statement, &mut writer).ok()? as u32;
+    }
+    Some(offsets)
+}
+
+/// Links the bytecode positions among the operands in `html` to the anchor
+/// of the statement starting there, when one does.
+fn link_code_offsets(html: &str, offsets: &[u32], anchor_prefix: &str) -> String {
+    RE_CODE_OFFSET
+        .replace_all(html, |caps: &Captures| {
+            let target = caps[2]
+                .parse()
+                .ok()
+                .and_then(|offset: u32| offsets.iter().position(|&start| start == offset));
+            match target {
+                Some(i) => format!(
+                    "{}<a href=\"#{}{}\">{}</a>",
+                    &caps[1], anchor_prefix, i, &caps[2]
+                ),
+                None => caps[0].to_string(),
+            }
+        })
+        .into_owned()
+}
+
+/// Writes the statements of `script` as a numbered list, each with its
+/// opcode and its operands, package indices linked through `annotate`.
+/// Each statement's anchor is its number after `anchor_prefix`, which keeps
+/// them apart when several scripts share a page. With the `offsets` of the
+/// statements, see [`statement_offsets`], jump operands link to the
+/// statement they land on. Unparsed bytecode is shown as a note with its
+/// size instead.
+pub fn write_script<W: Write>(
+    sink: &mut W,
+    script: &Script,
+    offsets: Option<&[u32]>,
+    anchor_prefix: &str,
+    annotate: impl Fn(i32) -> String,
+) -> Result<()> {
+    let statements = match script {
+        Script::Parsed(statements) => statements,
+        Script::Raw(bytes) => {
+            return write!(
+                sink,
+                "<p>The bytecode ({} bytes) couldn't be disassembled.</p>",
+                bytes.len()
+            );
+        }
+    };
+    sink.write_all(b"<ol start=\"0\" style=\"font-family:monospace\">")?;
+    for (i, statement) in statements.iter().enumerate() {
+        let mut operands =
+            link_and_transform_indices(&escape_html(&format!("{:?}", statement)), &annotate);
+        if let Some(offsets) = offsets {
+            operands = link_code_offsets(&operands, offsets, anchor_prefix);
+        }
+        write!(
+            sink,
+            "<li id=\"{id}\"><a href=\"#{id}\">#</a> <b>{}</b> {}</li>",
+            opcode_name(&variant_name(statement)),
+            operands,
+            id = format!("{}{}", anchor_prefix, i)
+        )?;
+    }
+    sink.write_all(b"</ol>")
+}
+
+#[test]
+fn test_write_script() {
+    use unreal_asset::kismet::{EExprToken, ExEndOfScript, ExJump, ExNothing};
+
+    // A jump over a statement that does nothing, to the end of the script.
+    let statements = [
+        KismetExpression::ExJump(ExJump {
+            token: EExprToken::ExJump,
+            code_offset: 6,
+        }),
+        KismetExpression::ExNothing(ExNothing {
+            token: EExprToken::ExNothing,
+        }),
+        KismetExpression::ExEndOfScript(ExEndOfScript {
+            token: EExprToken::ExEndOfScript,
+        }),
+    ];
+    let script = Script::Parsed(&statements);
+    let html = |offsets: Option<&[u32]>| {
+        let mut html = Vec::new();
+        write_script(&mut html, &script, offsets, "export-3-", |index| {
+            index.to_string()
+        })
+        .unwrap();
+        String::from_utf8(html).unwrap()
+    };
+    let linked = html(Some(&[0, 5, 6]));
+    assert!(linked.starts_with("<ol start=\"0\""));
+    assert!(
+        linked.contains("<li id=\"export-3-1\"><a href=\"#export-3-1\">#</a> <b>EX_Nothing</b>")
+    );
+    assert!(linked.contains("code_offset: <a href=\"#export-3-2\">6</a>"));
+    // Without the offsets, or jumping into the middle of a statement, the
+    // operand stays a number.
+    assert!(html(None).contains("code_offset: 6 "));
+    assert!(html(Some(&[0, 5, 7])).contains("code_offset: 6 "));
+
+    let mut note = Vec::new();
+    write_script(&mut note, &Script::Raw(&[0x4B; 12]), None, "", |index| {
+        index.to_string()
+    })
+    .unwrap();
+    assert_eq!(
+        "<p>The bytecode (12 bytes) couldn't be disassembled.</p>",
+        String::from_utf8(note).unwrap()
+    );
+}
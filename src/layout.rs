@@ -0,0 +1,388 @@
+use serde::Serialize;
+use std::io::{BufWriter, Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+use std::path::Path;
+use unreal_asset::engine_version::EngineVersion;
+
+use crate::{
+    escape_html,
+    import_table::{self, check_count},
+    manifest::OutputFiles,
+    summary::{self, read_fstring, read_i32, PackageVersions},
+};
+
+/// The object versions that changed the size of an export table entry.
+const VER_UE4_LOAD_FOR_EDITOR_GAME: i32 = 365;
+const VER_UE4_COOKED_ASSETS_IN_EDITOR_SUPPORT: i32 = 485;
+const VER_UE4_PRELOAD_DEPENDENCIES_IN_COOKED_EXPORTS: i32 = 507;
+pub(crate) const VER_UE4_TEMPLATE_INDEX_IN_COOKED_EXPORTS: i32 = 508;
+pub(crate) const VER_UE4_64BIT_EXPORTMAP_SERIALSIZES: i32 = 511;
+const VER_UE5_OPTIONAL_RESOURCES: i32 = 1003;
+const VER_UE5_REMOVE_OBJECT_EXPORT_PACKAGE_GUID: i32 = 1005;
+const VER_UE5_TRACK_OBJECT_EXPORT_IS_INHERITED: i32 = 1006;
+const VER_UE5_SCRIPT_SERIALIZATION_OFFSET: i32 = 1010;
+
+/// A reader that counts the bytes read through it, so the summary reader
+/// knows where each field starts.
+struct Tracked<R> {
+    inner: R,
+    position: u64,
+}
+
+impl<R: Read> Read for Tracked<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl<R: Seek> Tracked<R> {
+    fn seek_to(&mut self, offset: u64) -> Result<()> {
+        self.inner.seek(SeekFrom::Start(offset))?;
+        self.position = offset;
+        Ok(())
+    }
+}
+
+/// The offset of a table as the summary gives it, which a corrupt one can
+/// give as negative.
+fn table_offset(table: &str, offset: i32) -> Result<u64> {
+    u64::try_from(offset).map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("{} table is at offset {}", table, offset),
+        )
+    })
+}
+
+/// A field of the package summary and where it is in the `.uasset`.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct SummaryField {
+    /// The field's name in `FPackageFileSummary`, e.g. `NameOffset`.
+    pub name: &'static str,
+    pub offset: u64,
+    pub length: u64,
+    pub value: i64,
+}
+
+/// An entry of the name map, import table or export table and where it is
+/// in the `.uasset`.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct TableEntry {
+    /// The name map index, or the package index of an import or export.
+    pub index: i32,
+    /// The name, or the object name of an import or export.
+    pub name: String,
+    pub offset: u64,
+    pub length: u64,
+}
+
+/// Where the summary fields that locate the tables are, and every entry of
+/// the tables, for `--layout`.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Layout {
+    pub summary: Vec<SummaryField>,
+    pub names: Vec<TableEntry>,
+    pub imports: Vec<TableEntry>,
+    pub exports: Vec<TableEntry>,
+}
+
+/// The size of an export table entry, which only depends on the versions.
+pub(crate) fn export_entry_size(versions: &PackageVersions) -> u64 {
+    let ue4 = versions.file_version_ue4;
+    let ue5 = versions.file_version_ue5;
+    let serial_fields: u64 = if ue4 >= VER_UE4_64BIT_EXPORTMAP_SERIALSIZES {
+        16
+    } else {
+        8
+    };
+    let fields: [(bool, u64); 12] = [
+        // The class, super, outer index, object name and flags.
+        (true, 4 + 4 + 4 + 8 + 4),
+        (ue4 >= VER_UE4_TEMPLATE_INDEX_IN_COOKED_EXPORTS, 4),
+        (true, serial_fields),
+        // Forced export, not for client and not for server.
+        (true, 4 * 3),
+        (ue5 < VER_UE5_REMOVE_OBJECT_EXPORT_PACKAGE_GUID, 16),
+        (ue5 >= VER_UE5_TRACK_OBJECT_EXPORT_IS_INHERITED, 4),
+        // The package flags.
+        (true, 4),
+        (ue4 >= VER_UE4_LOAD_FOR_EDITOR_GAME, 4),
+        (ue4 >= VER_UE4_COOKED_ASSETS_IN_EDITOR_SUPPORT, 4),
+        (ue5 >= VER_UE5_OPTIONAL_RESOURCES, 4),
+        // The first dependency and the four dependency counts.
+        (ue4 >= VER_UE4_PRELOAD_DEPENDENCIES_IN_COOKED_EXPORTS, 4 * 5),
+        (ue5 >= VER_UE5_SCRIPT_SERIALIZATION_OFFSET, 8 * 2),
+    ];
+    fields
+        .iter()
+        .filter(|(present, _)| *present)
+        .map(|(_, size)| size)
+        .sum()
+}
+
+#[test]
+fn test_export_entry_size() {
+    let versions = |file_version_ue4, file_version_ue5| PackageVersions {
+        legacy_file_version: -8,
+        file_version_ue4,
+        file_version_ue5,
+        file_version_licensee_ue4: 0,
+    };
+    assert_eq!(104, export_entry_size(&versions(522, 0)));
+    assert_eq!(68, export_entry_size(&versions(400, 0)));
+    assert_eq!(96, export_entry_size(&versions(522, 1009)));
+}
+
+/// Reads the layout of the package `reader` holds, from its start. The
+/// summary is read again here rather than taken from `unreal_asset`, which
+/// doesn't keep where things were. Unversioned packages are read as
+/// `engine_version` saves them.
+pub fn read_layout<R: Read + Seek>(reader: R, engine_version: EngineVersion) -> Result<Layout> {
+    let mut reader = Tracked {
+        inner: reader,
+        position: 0,
+    };
+    let header_versions = summary::read_package_versions(&mut reader)?;
+    let custom_versions =
+        summary::read_custom_versions(&mut reader, header_versions.legacy_file_version)?;
+    let versions = summary::table_versions(&header_versions, engine_version)?;
+    let mut fields = Vec::new();
+    let mut field = |reader: &mut Tracked<R>, name: &'static str| -> Result<i32> {
+        let offset = reader.position;
+        let value = read_i32(reader)?;
+        fields.push(SummaryField {
+            name,
+            offset,
+            length: 4,
+            value: value.into(),
+        });
+        Ok(value)
+    };
+    let skip = |reader: &mut Tracked<R>, count: usize| -> Result<()> {
+        for _ in 0..count {
+            read_i32(reader)?;
+        }
+        Ok(())
+    };
+    // As in `summary::read_table_offsets`, with the position of each field.
+    let total_header_size = field(&mut reader, "TotalHeaderSize")?;
+    let folder_name = read_fstring(&mut reader)?;
+    let package_flags = field(&mut reader, "PackageFlags")? as u32;
+    let name_count = field(&mut reader, "NameCount")?;
+    let name_offset = field(&mut reader, "NameOffset")?;
+    if versions.file_version_ue5 >= summary::VER_UE5_ADD_SOFTOBJECTPATH_LIST {
+        skip(&mut reader, 2)?;
+    }
+    let header = summary::PackageHeader {
+        versions: header_versions,
+        custom_versions,
+        total_header_size,
+        folder_name,
+        package_flags,
+        name_count,
+    };
+    if !header.is_filter_editor_only()
+        && versions.file_version_ue4 >= summary::VER_UE4_ADDED_PACKAGE_SUMMARY_LOCALIZATION_ID
+    {
+        let _localization_id = read_fstring(&mut reader)?;
+    }
+    if versions.file_version_ue4 >= summary::VER_UE4_SERIALIZE_TEXT_IN_PACKAGES {
+        skip(&mut reader, 2)?;
+    }
+    let export_count = field(&mut reader, "ExportCount")?;
+    let export_offset = field(&mut reader, "ExportOffset")?;
+    let import_count = field(&mut reader, "ImportCount")?;
+    let import_offset = field(&mut reader, "ImportOffset")?;
+
+    check_count("name", name_count)?;
+    reader.seek_to(table_offset("name", name_offset)?)?;
+    let mut names = Vec::with_capacity(name_count as usize);
+    let mut name_entries = Vec::with_capacity(name_count as usize);
+    for index in 0..name_count {
+        let offset = reader.position;
+        let name = import_table::read_name_entry(&mut reader, &versions)?;
+        name_entries.push(TableEntry {
+            index,
+            name: name.clone(),
+            offset,
+            length: reader.position - offset,
+        });
+        names.push(name);
+    }
+
+    check_count("import", import_count)?;
+    reader.seek_to(table_offset("import", import_offset)?)?;
+    let mut imports = Vec::with_capacity(import_count as usize);
+    for i in 0..import_count {
+        let offset = reader.position;
+        let import = import_table::read_import(&mut reader, &names, &header, &versions)?;
+        imports.push(TableEntry {
+            index: -(i + 1),
+            name: import.object_name,
+            offset,
+            length: reader.position - offset,
+        });
+    }
+
+    check_count("export", export_count)?;
+    let export_offset = table_offset("export", export_offset)?;
+    let entry_size = export_entry_size(&versions);
+    let mut exports = Vec::with_capacity(export_count as usize);
+    for i in 0..export_count {
+        let offset = export_offset + i as u64 * entry_size;
+        reader.seek_to(offset)?;
+        // The class, super and template indexes and the outer index come
+        // before the object name.
+        let indexes = if versions.file_version_ue4 >= VER_UE4_TEMPLATE_INDEX_IN_COOKED_EXPORTS {
+            4
+        } else {
+            3
+        };
+        skip(&mut reader, indexes)?;
+        exports.push(TableEntry {
+            index: i + 1,
+            name: import_table::read_name(&mut reader, &names)?,
+            offset,
+            length: entry_size,
+        });
+    }
+    Ok(Layout {
+        summary: fields,
+        names: name_entries,
+        imports,
+        exports,
+    })
+}
+
+#[test]
+fn test_read_layout() {
+    use crate::test_support::{self, TestPackage};
+
+    // The class, super, template and outer indexes, then the object name.
+    let mut export = vec![0; 4 * 4];
+    export.extend(test_support::fname_bytes(2, 0));
+    let package = TestPackage {
+        names: &["/Script/CoreUObject", "Package", "/Game/Hero"],
+        imports: &[(0, 2, 0), (-1, 1, 3)],
+        exports: &[export],
+        ..Default::default()
+    }
+    .bytes();
+
+    let layout = read_layout(std::io::Cursor::new(&package), EngineVersion::VER_UE4_27).unwrap();
+    let field = |name, offset, value| SummaryField {
+        name,
+        offset,
+        length: 4,
+        value,
+    };
+    assert_eq!(
+        vec![
+            field("TotalHeaderSize", 24, 0),
+            field("PackageFlags", 37, 0),
+            field("NameCount", 41, 3),
+            field("NameOffset", 45, 200),
+            field("ExportCount", 61, 1),
+            field("ExportOffset", 65, 400),
+            field("ImportCount", 69, 2),
+            field("ImportOffset", 73, 300),
+        ],
+        layout.summary
+    );
+    let entry = |index, name: &str, offset, length| TableEntry {
+        index,
+        name: name.to_string(),
+        offset,
+        length,
+    };
+    assert_eq!(
+        vec![
+            entry(0, "/Script/CoreUObject", 200, 28),
+            entry(1, "Package", 228, 16),
+            entry(2, "/Game/Hero", 244, 19),
+        ],
+        layout.names
+    );
+    assert_eq!(
+        vec![
+            entry(-1, "/Game/Hero", 300, 36),
+            entry(-2, "Package_2", 336, 36),
+        ],
+        layout.imports
+    );
+    assert_eq!(vec![entry(1, "/Game/Hero", 400, 104)], layout.exports);
+}
+
+/// `0x0000042F`, how offsets are shown, as hex editors do.
+fn hex(offset: u64) -> String {
+    format!("0x{:08X}", offset)
+}
+
+fn write_entries<W: Write>(
+    html: &mut W,
+    heading: &str,
+    index_heading: &str,
+    entries: &[TableEntry],
+) -> Result<()> {
+    write!(
+        html,
+        "<h2>{} ({})</h2><table border=\"1\" style=\"border-collapse:collapse\">\
+         <tr><th>{}</th><th>Name</th><th>Offset</th><th>Length</th></tr>",
+        heading,
+        entries.len(),
+        index_heading
+    )?;
+    for entry in entries {
+        write!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            entry.index,
+            escape_html(&entry.name),
+            hex(entry.offset),
+            entry.length
+        )?;
+    }
+    html.write_all(b"</table>")
+}
+
+/// Writes `layout.html` and `layout.json` into `main_dir`, the layout of the
+/// `.uasset` `uasset_name` stands for. Offsets are absolute, from the start
+/// of the file, and lengths in bytes.
+pub fn write_layout(
+    layout: &Layout,
+    uasset_name: &str,
+    main_dir: &Path,
+    style_link: &str,
+    outputs: &mut OutputFiles,
+) -> Result<()> {
+    let json = BufWriter::new(outputs.create(&main_dir.join("layout.json"))?);
+    serde_json::to_writer_pretty(json, layout)?;
+
+    let mut html = BufWriter::new(outputs.create(&main_dir.join("layout.html"))?);
+    write!(
+        html,
+        "{}<h1>{} layout</h1><p><a href=\"index.html\">Back to the asset</a>. \
+         Offsets count from the start of the .uasset. \
+         <a href=\"layout.json\">layout.json</a> lists the same.</p>\
+         <h2>Summary</h2><table border=\"1\" style=\"border-collapse:collapse\">\
+         <tr><th>Field</th><th>Offset</th><th>Length</th><th>Value</th></tr>",
+        style_link,
+        escape_html(uasset_name)
+    )?;
+    for field in &layout.summary {
+        write!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            field.name,
+            hex(field.offset),
+            field.length,
+            field.value
+        )?;
+    }
+    html.write_all(b"</table>")?;
+    write_entries(&mut html, "Names", "Index", &layout.names)?;
+    write_entries(&mut html, "Imports", "Package index", &layout.imports)?;
+    write_entries(&mut html, "Exports", "Package index", &layout.exports)?;
+    html.flush()
+}
@@ -0,0 +1,387 @@
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Result, Seek, Write};
+use std::path::Path;
+use unreal_asset::{
+    exports::{ExportBaseTrait, ExportNormalTrait},
+    properties::{Property, PropertyDataTrait},
+    Asset,
+};
+
+use crate::{
+    compress::{read_page, rewrite_page},
+    crossref::relative_url,
+    escape_html, export_class_name,
+    formatters::{value_html, UserEnums},
+    index_href,
+    manifest::OutputFiles,
+    relative_prefix, stylesheet_link, text, AssetSummary, IndexNames, LinkTarget,
+};
+
+/// Whether `path` is a map, which gets a `level.html` overview.
+pub fn is_level(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("umap"))
+}
+
+/// The export indices of the actors placed in the level: the exports whose
+/// outer is the `PersistentLevel` export, or in an external actor package the
+/// `PersistentLevel` import of its map, given each export's outer index in
+/// package order.
+fn actor_indices(names: &IndexNames, outers: &[i32]) -> Vec<i32> {
+    let level = |table: &[String]| table.iter().position(|name| name == "PersistentLevel");
+    let level = match (level(&names.exports), level(&names.imports)) {
+        (Some(i), _) => i as i32 + 1,
+        (None, Some(i)) => -(i as i32 + 1),
+        (None, None) => return Vec::new(),
+    };
+    (1..=outers.len() as i32)
+        .filter(|&index| outers[index as usize - 1] == level)
+        .collect()
+}
+
+#[test]
+fn test_actor_indices() {
+    let names = IndexNames {
+        imports: Vec::new(),
+        exports: vec![
+            "Hub".to_string(),
+            "PersistentLevel".to_string(),
+            "Lamp_1".to_string(),
+            "LightComponent0".to_string(),
+            "Door".to_string(),
+        ],
+        import_outers: Vec::new(),
+        export_outers: vec![0, 1, 2, 3, 2],
+        export_classes: Vec::new(),
+    };
+    assert_eq!(vec![3, 5], actor_indices(&names, &[0, 1, 2, 3, 2]));
+    assert!(actor_indices(&crate::test_index_names(), &[0]).is_empty());
+
+    // An external actor package, whose actor's outer is the map's level.
+    let names = IndexNames {
+        imports: vec![
+            "/Game/Maps/Hub".to_string(),
+            "Hub".to_string(),
+            "PersistentLevel".to_string(),
+        ],
+        exports: vec!["Lamp_1".to_string(), "LightComponent0".to_string()],
+        import_outers: vec![0, -1, -2],
+        export_outers: vec![-3, 1],
+        export_classes: Vec::new(),
+    };
+    assert_eq!(vec![1], actor_indices(&names, &[-3, 1]));
+}
+
+fn find_property<'a>(properties: &'a [Property], name: &str) -> Option<&'a Property> {
+    properties
+        .iter()
+        .find(|property| property.get_name().get_owned_content() == name)
+}
+
+/// An actor's row on the level page.
+struct PlacedActor {
+    index: i32,
+    class_name: String,
+    /// The export its `RootComponent` property points at, if it's one.
+    root_component: Option<i32>,
+    /// `RelativeLocation`, `RelativeRotation` and `RelativeScale3D` of the
+    /// root component, as far as they're set.
+    transform: [Option<String>; 3],
+}
+
+fn placed_actors<C: Read + Seek>(asset: &Asset<C>, names: &IndexNames) -> Vec<PlacedActor> {
+    let exports = &asset.asset_data.exports;
+    let outers: Vec<i32> = exports
+        .iter()
+        .map(|export| export.get_base_export().outer_index.index)
+        .collect();
+    let properties = |index: i32| {
+        exports
+            .get(index as usize - 1)
+            .and_then(|export| export.get_normal_export())
+            .map(|normal| normal.properties.as_slice())
+            .unwrap_or_default()
+    };
+    actor_indices(names, &outers)
+        .into_iter()
+        .map(|index| {
+            let class_index = exports[index as usize - 1]
+                .get_base_export()
+                .class_index
+                .index;
+            let root_component = match find_property(properties(index), "RootComponent") {
+                Some(Property::ObjectProperty(p)) if p.value.index > 0 => Some(p.value.index),
+                _ => None,
+            };
+            let transform =
+                ["RelativeLocation", "RelativeRotation", "RelativeScale3D"].map(|name| {
+                    let property = find_property(properties(root_component?), name)?;
+                    // Tagged math structs hold the value as their only member.
+                    let value = match property {
+                        Property::StructProperty(p) if p.value.len() == 1 => &p.value[0],
+                        property => property,
+                    };
+                    value_html(value, |index| index.to_string(), &UserEnums::new())
+                });
+            PlacedActor {
+                index,
+                class_name: export_class_name(names, class_index).to_string(),
+                root_component,
+                transform,
+            }
+        })
+        .collect()
+}
+
+/// The folders World Partition saves the actors and other objects of a map
+/// in, one package each, e.g.
+/// `Content/__ExternalActors__/Maps/Hub/0/AB/3CDEF.uasset` for
+/// `Content/Maps/Hub.umap`.
+const EXTERNAL_FOLDERS: [&str; 2] = ["__ExternalActors__", "__ExternalObjects__"];
+
+/// Whether `path` is an external actor or object package, from the folder
+/// it's in.
+pub fn is_external_package(path: &Path) -> bool {
+    path.components().any(|component| {
+        EXTERNAL_FOLDERS
+            .iter()
+            .any(|folder| component.as_os_str() == *folder)
+    })
+}
+
+/// The package path prefixes of the external actors and objects of the map
+/// `map_package`, e.g. `/Game/__ExternalActors__/Maps/Hub/` for
+/// `/Game/Maps/Hub`: the map's path with the folder after its mount point.
+fn external_prefixes(map_package: &str) -> Option<[String; 2]> {
+    let (mount, rest) = map_package.strip_prefix('/')?.split_once('/')?;
+    Some(EXTERNAL_FOLDERS.map(|folder| format!("/{}/{}/{}/", mount, folder, rest).to_lowercase()))
+}
+
+#[test]
+fn test_external_prefixes() {
+    assert_eq!(
+        Some([
+            "/game/__externalactors__/maps/hub/".to_string(),
+            "/game/__externalobjects__/maps/hub/".to_string()
+        ]),
+        external_prefixes("/Game/Maps/Hub")
+    );
+    assert_eq!(None, external_prefixes("/Hub"));
+    assert!(is_external_package(Path::new(
+        "Content/__ExternalActors__/Maps/Hub/0/AB/3CDEF.uasset"
+    )));
+    assert!(!is_external_package(Path::new("Content/Maps/Hub.umap")));
+}
+
+/// An actor or object of an external package, as its map's `actors.html`
+/// lists it.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExternalObject {
+    pub index: i32,
+    pub name: String,
+    pub class_name: String,
+    /// The actor's location, rotation and scale, as the level page shows
+    /// them.
+    pub transform: [Option<String>; 3],
+}
+
+/// What an external package holds: the actors it places in its map's level,
+/// or its top-level exports when it places none.
+pub fn external_objects<C: Read + Seek>(
+    asset: &Asset<C>,
+    names: &IndexNames,
+) -> Vec<ExternalObject> {
+    let name = |index: i32| names.get(index).unwrap_or_default().to_string();
+    let actors = placed_actors(asset, names);
+    if !actors.is_empty() {
+        return actors
+            .into_iter()
+            .map(|actor| ExternalObject {
+                index: actor.index,
+                name: name(actor.index),
+                class_name: actor.class_name,
+                transform: actor.transform,
+            })
+            .collect();
+    }
+    (1..=names.export_outers.len() as i32)
+        .filter(|&index| names.export_outers[index as usize - 1] == 0)
+        .map(|index| {
+            let class_index = asset.asset_data.exports[index as usize - 1]
+                .get_base_export()
+                .class_index
+                .index;
+            ExternalObject {
+                index,
+                name: name(index),
+                class_name: export_class_name(names, class_index).to_string(),
+                transform: Default::default(),
+            }
+        })
+        .collect()
+}
+
+/// Writes `actors.html` for the map `map` when any of `assets` is one of its
+/// external actor or object packages: a table of actors with their class and
+/// transform, and one of other objects, each linking to its package. The
+/// map's page gets a link to it. Pages link to the `style.css` in `out_dir`.
+pub fn write_external_actors(
+    map: &AssetSummary,
+    assets: &[&AssetSummary],
+    out_dir: &Path,
+    outputs: &mut OutputFiles,
+) -> Result<()> {
+    let Some(prefixes) = external_prefixes(&map.package_path) else {
+        return Ok(());
+    };
+    let of_folder = |prefix: &str| {
+        let mut packages: Vec<&AssetSummary> = assets
+            .iter()
+            .copied()
+            .filter(|asset| asset.package_path.to_lowercase().starts_with(prefix))
+            .collect();
+        packages.sort_by(|a, b| a.package_path.cmp(&b.package_path));
+        packages
+    };
+    let (actors, objects) = (of_folder(&prefixes[0]), of_folder(&prefixes[1]));
+    if actors.is_empty() && objects.is_empty() {
+        return Ok(());
+    }
+    let mut page = std::io::BufWriter::new(outputs.create(&map.main_dir.join("actors.html"))?);
+    page.write_all(stylesheet_link(&relative_prefix(&map.main_dir, out_dir)).as_bytes())?;
+    write!(
+        page,
+        "<h1>
+        <a href=\"..\">.</a>/
+        <a href=\".\">{}</a>/
+        actors
+        </h1>
+        <p>The actors and objects World Partition saved in packages of their own, under {} and {}.</p>",
+        escape_html(&map.name),
+        EXTERNAL_FOLDERS[0],
+        EXTERNAL_FOLDERS[1]
+    )?;
+    let links = |package: &AssetSummary, object: &ExternalObject| {
+        let package_url = relative_url(&map.main_dir, &package.main_dir);
+        let object_url = if package
+            .export_pages
+            .get(object.index as usize - 1)
+            .copied()
+            .unwrap_or(false)
+        {
+            format!("{}/exports/{}", package_url, object.index)
+        } else {
+            package_url.clone()
+        };
+        (
+            format!(
+                "<a href=\"{}\">{}</a>",
+                escape_html(&object_url),
+                escape_html(&object.name)
+            ),
+            format!(
+                "<a href=\"{}\">{}</a>",
+                escape_html(&package_url),
+                escape_html(&package.name)
+            ),
+        )
+    };
+    let count: usize = actors.iter().map(|package| package.external.len()).sum();
+    write!(page, "<h2>External actors ({})</h2>", count)?;
+    page.write_all(
+        b"<table border=\"1\" style=\"border-collapse:collapse\">\
+        <tr><th>Actor</th><th>Class</th><th>Location</th><th>Rotation</th><th>Scale</th>\
+        <th>Package</th></tr>",
+    )?;
+    for package in &actors {
+        for actor in &package.external {
+            let (actor_link, package_link) = links(package, actor);
+            write!(
+                page,
+                "<tr><td>{}</td><td>{}</td>",
+                actor_link,
+                escape_html(&actor.class_name)
+            )?;
+            for value in &actor.transform {
+                write!(page, "<td>{}</td>", value.as_deref().unwrap_or_default())?;
+            }
+            write!(page, "<td>{}</td></tr>", package_link)?;
+        }
+    }
+    page.write_all(b"</table>")?;
+    let count: usize = objects.iter().map(|package| package.external.len()).sum();
+    write!(page, "<h2>External objects ({})</h2>", count)?;
+    page.write_all(
+        b"<table border=\"1\" style=\"border-collapse:collapse\">\
+        <tr><th>Object</th><th>Class</th><th>Package</th></tr>",
+    )?;
+    for package in &objects {
+        for object in &package.external {
+            let (object_link, package_link) = links(package, object);
+            write!(
+                page,
+                "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                object_link,
+                escape_html(&object.class_name),
+                package_link
+            )?;
+        }
+    }
+    page.write_all(b"</table>")?;
+    page.flush()?;
+
+    // Up-to-date maps kept the link from an earlier run.
+    let map_page = map.main_dir.join("index.html");
+    let mut contents = read_page(&map_page)?;
+    if !contents.contains("<a href=\"actors.html\">") {
+        let position = contents.find("</h1>").map_or(0, |i| i + "</h1>".len());
+        contents.insert_str(
+            position,
+            "<p><a href=\"actors.html\">External actors and objects</a></p>",
+        );
+        rewrite_page(&map_page, contents)?;
+    }
+    Ok(())
+}
+
+/// Writes the table of actors in the level, linking each actor and root
+/// component to its export page in `exports/` when it has one.
+pub fn write_level<W: Write, C: Read + Seek>(
+    sink: &mut W,
+    asset: &Asset<C>,
+    names: &IndexNames,
+    export_pages: &[bool],
+) -> Result<()> {
+    let actors = placed_actors(asset, names);
+    if actors.is_empty() {
+        return sink.write_all(b"<p>No actors are placed in this level.</p>");
+    }
+    // level.html sits next to the asset's main page.
+    let href = index_href(export_pages, LinkTarget::Pages { depth: 0 });
+    let export_link = |index: i32| {
+        let label = escape_html(&text::annotate_index(names, index));
+        match href(index) {
+            Some(href) => format!("<a href=\"{}\">{}</a>", href, label),
+            None => label,
+        }
+    };
+    sink.write_all(
+        b"<table border=\"1\" style=\"border-collapse:collapse\">\
+        <tr><th>Actor</th><th>Class</th><th>Root component</th>\
+        <th>Location</th><th>Rotation</th><th>Scale</th></tr>",
+    )?;
+    for actor in &actors {
+        write!(
+            sink,
+            "<tr><td>{}</td><td>{}</td><td>{}</td>",
+            export_link(actor.index),
+            escape_html(&actor.class_name),
+            actor.root_component.map(export_link).unwrap_or_default()
+        )?;
+        for value in &actor.transform {
+            write!(sink, "<td>{}</td>", value.as_deref().unwrap_or_default())?;
+        }
+        sink.write_all(b"</tr>")?;
+    }
+    sink.write_all(b"</table>")
+}
@@ -0,0 +1,5188 @@
+//! Generates browsable HTML pages, JSON and text dumps of the imports and
+//! exports of Unreal Engine assets. [`index`] is what the `uasset-index`
+//! command runs for each input; [`index_file`], [`index_dir`] and
+//! [`pak::index_pak`] return their errors instead of printing them.
+
+use lazy_static::lazy_static;
+use rayon::prelude::*;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{hash_map::DefaultHasher, BTreeMap, BTreeSet, HashMap, HashSet};
+use std::ffi::OsStr;
+use std::fs::{create_dir_all, File};
+use std::hash::{Hash, Hasher};
+use std::io::prelude::Write;
+use std::io::Error as IOError;
+use std::io::{BufReader, BufWriter, Read, Seek};
+use std::ops::Range;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use unreal_asset::{
+    base::types::{fname::FName, PackageIndex},
+    exports::{
+        base_export::BaseExport, data_table_export::DataTableExport, Export, ExportBaseTrait,
+        ExportNormalTrait,
+    },
+    unversioned::Usmap,
+    Asset,
+};
+
+pub use crossref::PackageCollision;
+pub use graph::DEFAULT_MAX_NODES as DEFAULT_GRAPH_MAX_NODES;
+pub use summary::BadTag;
+pub use unreal_asset::engine_version::EngineVersion;
+
+pub mod check;
+pub mod clean;
+mod collapse;
+pub mod compress;
+pub mod config;
+mod crossref;
+mod csv;
+mod datatable;
+mod dependencies;
+pub mod diff;
+mod errors;
+pub mod filter;
+mod flags;
+mod formatters;
+mod graph;
+pub mod grep;
+pub mod hexdump;
+pub mod import_table;
+mod incremental;
+mod json;
+mod kismet;
+mod layout;
+mod level;
+pub mod list;
+pub mod manifest;
+pub mod ndjson;
+mod neighborhood;
+pub mod pak;
+pub mod progress;
+mod properties;
+pub mod references;
+mod search;
+pub mod serve;
+mod single_file;
+pub mod stats;
+mod string_table;
+pub mod strings;
+mod summary;
+#[doc(hidden)]
+pub mod test_support;
+mod text;
+pub mod texts;
+mod thumbnail;
+pub mod tui;
+pub mod version_map;
+
+lazy_static! {
+    // Matches both `outer_index: PackageIndex { index: 5 }` and `PackageIndex(5)`,
+    // but not fields like `some_index: 5` that merely end in "index".
+    static ref RE_INDEX: Regex =
+        Regex::new(r"(?:([^_]index: )|(PackageIndex\())(-?[1-9][0-9]*)").unwrap();
+}
+
+/// The extensions of the files read as assets, besides those registered
+/// with `--extensions`.
+pub const SUPPORTED_EXTENSIONS: &[&str] = &["uasset", "umap"];
+
+/// How many elements of an array, set or map export pages list unless
+/// `--max-array-elements` says otherwise.
+pub const DEFAULT_MAX_ARRAY_ELEMENTS: usize = 1000;
+
+/// How many bytes of a string value export pages show unless
+/// `--max-string-bytes` says otherwise.
+pub const DEFAULT_MAX_STRING_BYTES: usize = 4096;
+
+/// How many bytes of an export's dump are formatted before it's cut short,
+/// unless `--max-dump-size` says otherwise.
+pub const DEFAULT_MAX_DUMP_SIZE: usize = 256 << 20;
+
+/// How deep the blocks of an export or import dump are nested before they
+/// start collapsed, unless `--collapse-depth` says otherwise.
+pub const DEFAULT_COLLAPSE_DEPTH: usize = 2;
+
+/// How many exports or imports a page of their listing holds unless
+/// `--listing-page-size` says otherwise. Browsers take seconds to lay out
+/// lists much longer than this.
+pub const DEFAULT_LISTING_PAGE_SIZE: usize = 2000;
+
+/// What `style.css` holds for `--theme light`.
+const LIGHT_STYLESHEET: &str = "a{text-decoration:none}a:visited{color:darkmagenta}\n";
+
+/// What `style.css` holds for `--theme dark`. Visited links get a lighter
+/// purple, since darkmagenta all but vanishes on a dark background.
+const DARK_STYLESHEET: &str = ":root{color-scheme:dark}\
+    body{background:#1e1e1e;color:#d4d4d4}\
+    a{text-decoration:none;color:#8ab4f8}a:visited{color:#d7a8ff}\
+    table,th,td{border-color:#555}\n";
+
+/// What `style.css` holds unless `--theme` or `--style` says otherwise: the
+/// light palette, and the dark one for browsers that prefer it.
+pub const DEFAULT_STYLESHEET: &str = "a{text-decoration:none}a:visited{color:darkmagenta}\n\
+    @media (prefers-color-scheme:dark){\
+    :root{color-scheme:dark}\
+    body{background:#1e1e1e;color:#d4d4d4}\
+    a{text-decoration:none;color:#8ab4f8}a:visited{color:#d7a8ff}\
+    table,th,td{border-color:#555}\n}\n";
+
+/// The built-in stylesheets `--theme` picks from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Theme {
+    /// Light or dark, following the browser's preference.
+    Auto,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub fn stylesheet(self) -> &'static str {
+        match self {
+            Theme::Auto => DEFAULT_STYLESHEET,
+            Theme::Light => LIGHT_STYLESHEET,
+            Theme::Dark => DARK_STYLESHEET,
+        }
+    }
+}
+
+#[test]
+fn test_auto_theme_holds_both_palettes() {
+    assert_eq!(
+        format!(
+            "{}@media (prefers-color-scheme:dark){{{}}}\n",
+            LIGHT_STYLESHEET, DARK_STYLESHEET
+        ),
+        Theme::Auto.stylesheet()
+    );
+}
+
+/// The `<link>` every page starts with. `prefix` leads from the page's
+/// directory up to the one holding `style.css`.
+fn stylesheet_link(prefix: &str) -> String {
+    format!("<link rel=\"stylesheet\" href=\"{}style.css\">", prefix)
+}
+
+/// The relative URL from `dir` up to its ancestor `root`, e.g. `../../` two
+/// levels below it.
+fn relative_prefix(dir: &Path, root: &Path) -> String {
+    let depth = dir
+        .strip_prefix(root)
+        .map_or(0, |relative| relative.components().count());
+    "../".repeat(depth)
+}
+
+#[test]
+fn test_relative_prefix() {
+    let root = Path::new("out");
+    assert_eq!("", relative_prefix(root, root));
+    assert_eq!(
+        "../../",
+        relative_prefix(&root.join("Maps").join("Hub"), root)
+    );
+}
+
+/// Writes `style.css` into `dir` for the pages below it.
+fn write_stylesheet(
+    dir: &Path,
+    options: &IndexOptions,
+    outputs: &mut manifest::OutputFiles,
+) -> Result<(), IOError> {
+    outputs
+        .create(&dir.join("style.css"))?
+        .write_all(&options.stylesheet)
+}
+
+/// What `--format` can ask for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    Html,
+    Json,
+    /// `asset.json`'s data as `asset.yaml`.
+    Yaml,
+    Text,
+    Csv,
+    /// A JSON line per asset, export and failure, written to
+    /// `IndexOptions::ndjson` as each asset is indexed.
+    Ndjson,
+}
+
+/// The name of the enum variant `value` holds, e.g. `ObjectProperty` for a
+/// `Property::ObjectProperty(..)`, taken from the derived Debug output.
+fn variant_name<T: std::fmt::Debug>(value: &T) -> String {
+    struct UntilParen(String);
+    impl std::fmt::Write for UntilParen {
+        fn write_str(&mut self, s: &str) -> std::fmt::Result {
+            match s.find(|c: char| c == '(' || c == ' ' || c == '{') {
+                Some(end) => {
+                    self.0.push_str(&s[..end]);
+                    // Stop formatting, the rest of the value isn't needed.
+                    Err(std::fmt::Error)
+                }
+                None => {
+                    self.0.push_str(s);
+                    Ok(())
+                }
+            }
+        }
+    }
+    let mut name = UntilParen(String::new());
+    let _ = std::fmt::write(&mut name, format_args!("{:?}", value));
+    name.0
+}
+
+#[test]
+fn test_variant_name() {
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    enum Example {
+        Unit,
+        Tuple(i32),
+        Struct { a: i32 },
+    }
+    assert_eq!("Unit", variant_name(&Example::Unit));
+    assert_eq!("Tuple", variant_name(&Example::Tuple(1)));
+    assert_eq!("Struct", variant_name(&Example::Struct { a: 1 }));
+}
+
+/// Used for assets that don't record their engine version unless another is given.
+pub const DEFAULT_ENGINE_VERSION: EngineVersion = EngineVersion::VER_UE5_1;
+
+/// The engine versions `--engine-version` accepts, by their short names.
+pub const ENGINE_VERSIONS: &[(&str, EngineVersion)] = &[
+    ("4.0", EngineVersion::VER_UE4_0),
+    ("4.1", EngineVersion::VER_UE4_1),
+    ("4.2", EngineVersion::VER_UE4_2),
+    ("4.3", EngineVersion::VER_UE4_3),
+    ("4.4", EngineVersion::VER_UE4_4),
+    ("4.5", EngineVersion::VER_UE4_5),
+    ("4.6", EngineVersion::VER_UE4_6),
+    ("4.7", EngineVersion::VER_UE4_7),
+    ("4.8", EngineVersion::VER_UE4_8),
+    ("4.9", EngineVersion::VER_UE4_9),
+    ("4.10", EngineVersion::VER_UE4_10),
+    ("4.11", EngineVersion::VER_UE4_11),
+    ("4.12", EngineVersion::VER_UE4_12),
+    ("4.13", EngineVersion::VER_UE4_13),
+    ("4.14", EngineVersion::VER_UE4_14),
+    ("4.15", EngineVersion::VER_UE4_15),
+    ("4.16", EngineVersion::VER_UE4_16),
+    ("4.17", EngineVersion::VER_UE4_17),
+    ("4.18", EngineVersion::VER_UE4_18),
+    ("4.19", EngineVersion::VER_UE4_19),
+    ("4.20", EngineVersion::VER_UE4_20),
+    ("4.21", EngineVersion::VER_UE4_21),
+    ("4.22", EngineVersion::VER_UE4_22),
+    ("4.23", EngineVersion::VER_UE4_23),
+    ("4.24", EngineVersion::VER_UE4_24),
+    ("4.25", EngineVersion::VER_UE4_25),
+    ("4.26", EngineVersion::VER_UE4_26),
+    ("4.27", EngineVersion::VER_UE4_27),
+    ("5.0", EngineVersion::VER_UE5_0),
+    ("5.1", EngineVersion::VER_UE5_1),
+    ("5.2", EngineVersion::VER_UE5_2),
+];
+
+/// Accepts both the short form (`4.27`) and the enum variant name (`VER_UE4_27`).
+pub fn parse_engine_version(s: &str) -> Option<EngineVersion> {
+    ENGINE_VERSIONS
+        .iter()
+        .find(|(short, version)| *short == s || format!("{:?}", version) == s)
+        .map(|(_, version)| *version)
+}
+
+/// The short form of `engine_version`, e.g. `4.27`.
+fn engine_version_name(engine_version: EngineVersion) -> String {
+    ENGINE_VERSIONS
+        .iter()
+        .find(|(_, version)| *version == engine_version)
+        .map_or_else(
+            || format!("{:?}", engine_version),
+            |(short, _)| short.to_string(),
+        )
+}
+
+#[test]
+fn test_parse_engine_version() {
+    assert_eq!(
+        Some(EngineVersion::VER_UE4_27),
+        parse_engine_version("4.27")
+    );
+    assert_eq!(
+        Some(EngineVersion::VER_UE4_27),
+        parse_engine_version("VER_UE4_27")
+    );
+    assert_eq!(Some(EngineVersion::VER_UE5_1), parse_engine_version("5.1"));
+    assert_eq!(None, parse_engine_version("4.99"));
+    assert_eq!(None, parse_engine_version("UE4_27"));
+    assert_eq!("4.27", engine_version_name(EngineVersion::VER_UE4_27));
+}
+
+fn escape_html(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '&' => result.push_str("&amp;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&#39;"),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+/// The package indices a Debug dump mentions, annotated or not, in order.
+fn dump_indices(dump: &str) -> Vec<i32> {
+    RE_INDEX
+        .captures_iter(dump)
+        .filter_map(|caps| caps.get(3)?.as_str().parse().ok())
+        .collect()
+}
+
+#[test]
+fn test_dump_indices() {
+    assert_eq!(
+        vec![-3, 12],
+        dump_indices(
+            "    outer_index: PackageIndex { index: -3 (Engine) }, PackageIndex(12 (Door))"
+        )
+    );
+    assert!(dump_indices("    serial_index: 4,").is_empty());
+}
+
+fn link_and_transform_indices(haystack: &str, transform: impl Fn(i32) -> String) -> String {
+    let mut result = String::with_capacity(haystack.len());
+    let mut last_match = 0;
+    for caps in RE_INDEX.captures_iter(haystack) {
+        let m = caps.get(0).unwrap();
+        result.push_str(&haystack[last_match..m.start()]);
+        let prefix = caps.get(1).or_else(|| caps.get(2)).unwrap();
+        result.push_str(prefix.as_str());
+        let index = i32::from_str_radix(caps.get(3).unwrap().as_str(), 10).unwrap();
+        result += &transform(index);
+        last_match = m.end();
+    }
+    result.push_str(&haystack[last_match..]);
+    result
+}
+
+#[test]
+fn test_link_and_transform_indices() {
+    assert_eq!(
+        " index: 42  _index: 1  index: -42  etc".to_string(),
+        link_and_transform_indices(" index: 21  _index: 1  index: -21  etc", |i| (i * 2)
+            .to_string())
+    );
+    assert_eq!(
+        "outer: PackageIndex(42) class: PackageIndex(-42) some_index: 3".to_string(),
+        link_and_transform_indices(
+            "outer: PackageIndex(21) class: PackageIndex(-21) some_index: 3",
+            |i| (i * 2).to_string()
+        )
+    );
+    assert_eq!(
+        "outer_index: PackageIndex { index: 42 }".to_string(),
+        link_and_transform_indices("outer_index: PackageIndex { index: 21 }", |i| (i * 2)
+            .to_string())
+    );
+}
+
+/// Streaming counterpart of `link_and_transform_indices`, writing straight into `sink`.
+fn write_linked_indices<W: Write>(
+    sink: &mut W,
+    haystack: &str,
+    transform: impl Fn(i32) -> String,
+) -> std::io::Result<()> {
+    let mut last_match = 0;
+    for caps in RE_INDEX.captures_iter(haystack) {
+        let m = caps.get(0).unwrap();
+        sink.write_all(haystack[last_match..m.start()].as_bytes())?;
+        let prefix = caps.get(1).or_else(|| caps.get(2)).unwrap();
+        sink.write_all(prefix.as_str().as_bytes())?;
+        let index = i32::from_str_radix(caps.get(3).unwrap().as_str(), 10).unwrap();
+        sink.write_all(transform(index).as_bytes())?;
+        last_match = m.end();
+    }
+    sink.write_all(haystack[last_match..].as_bytes())
+}
+
+/// Adapts a `Write` sink so Debug output can be escaped and linked as it is
+/// formatted, one line at a time, without holding the whole dump in memory.
+/// Pretty-printed Debug output always indents fields, so an index pattern
+/// never straddles a line break.
+struct LinkingWriter<'a, W: Write, F: Fn(i32) -> String> {
+    sink: &'a mut W,
+    transform: F,
+    /// Whether lines are HTML-escaped before indices are transformed.
+    escape: bool,
+    /// Wraps nested blocks in `<details>` once lines are linked, when set.
+    blocks: Option<collapse::DumpBlocks>,
+    /// What an `outer_index` of 0 becomes, when set. Top-level exports have
+    /// one; null references elsewhere stay unlinked zeros.
+    package_root: Option<&'a str>,
+    /// Whether the last line opened an `outer_index` block.
+    after_outer_index: bool,
+    /// How many bytes of Debug output may be formatted, when set.
+    max_bytes: Option<usize>,
+    /// How many bytes have been formatted so far.
+    formatted: usize,
+    /// Whether formatting stopped at `max_bytes`.
+    truncated: bool,
+    line: String,
+    error: Option<std::io::Error>,
+}
+
+/// Writes one line of a dump, with `package_root` in place of its index
+/// when set, or its indices transformed.
+fn write_linked_line<W: Write>(
+    sink: &mut W,
+    line: &str,
+    transform: impl Fn(i32) -> String,
+    package_root: Option<&str>,
+) -> std::io::Result<()> {
+    match package_root {
+        Some(root) => {
+            let line = line.replacen("index: 0", &format!("index: {}", root), 1);
+            sink.write_all(line.as_bytes())
+        }
+        None => write_linked_indices(sink, line, transform),
+    }
+}
+
+impl<'a, W: Write, F: Fn(i32) -> String> LinkingWriter<'a, W, F> {
+    fn flush_line(&mut self) -> std::io::Result<()> {
+        let package_root = self
+            .package_root
+            .filter(|_| self.after_outer_index && self.line.trim() == "index: 0,");
+        self.after_outer_index = self
+            .line
+            .trim_end()
+            .ends_with("outer_index: PackageIndex {");
+        let result = if let Some(blocks) = &mut self.blocks {
+            let mut html = Vec::new();
+            write_linked_line(
+                &mut html,
+                &escape_html(&self.line),
+                &self.transform,
+                package_root,
+            )?;
+            blocks.push_line(self.sink, &self.line, &String::from_utf8_lossy(&html))
+        } else if self.escape {
+            write_linked_line(
+                self.sink,
+                &escape_html(&self.line),
+                &self.transform,
+                package_root,
+            )
+        } else {
+            write_linked_line(self.sink, &self.line, &self.transform, package_root)
+        };
+        self.line.clear();
+        result
+    }
+}
+
+impl<'a, W: Write, F: Fn(i32) -> String> std::fmt::Write for LinkingWriter<'a, W, F> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.formatted += s.len();
+        if self
+            .max_bytes
+            .is_some_and(|max_bytes| self.formatted > max_bytes)
+        {
+            // Failing stops the Debug impls from formatting the rest.
+            self.truncated = true;
+            return Err(std::fmt::Error);
+        }
+        for piece in s.split_inclusive('\n') {
+            self.line.push_str(piece);
+            if piece.ends_with('\n') {
+                if let Err(error) = self.flush_line() {
+                    self.error = Some(error);
+                    return Err(std::fmt::Error);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes the pretty-printed Debug dump of `value` to `sink`, HTML-escaped and
+/// with package indices transformed. With `collapse_depth`, nested blocks
+/// become `<details>`, collapsed from that depth down. With `package_root`,
+/// an `outer_index` of 0 becomes it. With `max_bytes`, formatting stops
+/// once the dump grows past it, and `true` is returned.
+fn write_linked_dump<W: Write>(
+    sink: &mut W,
+    value: &impl std::fmt::Debug,
+    transform: impl Fn(i32) -> String,
+    collapse_depth: Option<usize>,
+    package_root: Option<&str>,
+    max_bytes: Option<usize>,
+) -> std::io::Result<bool> {
+    write_dump(
+        sink,
+        value,
+        transform,
+        true,
+        collapse_depth,
+        package_root,
+        max_bytes,
+    )
+}
+
+/// Like `write_linked_dump`, but for plain text: nothing is escaped.
+fn write_annotated_dump<W: Write>(
+    sink: &mut W,
+    value: &impl std::fmt::Debug,
+    transform: impl Fn(i32) -> String,
+) -> std::io::Result<()> {
+    write_dump(sink, value, transform, false, None, None, None).map(|_| ())
+}
+
+fn write_dump<W: Write>(
+    sink: &mut W,
+    value: &impl std::fmt::Debug,
+    transform: impl Fn(i32) -> String,
+    escape: bool,
+    collapse_depth: Option<usize>,
+    package_root: Option<&str>,
+    max_bytes: Option<usize>,
+) -> std::io::Result<bool> {
+    let mut writer = LinkingWriter {
+        sink,
+        transform,
+        escape,
+        blocks: collapse_depth.map(collapse::DumpBlocks::new),
+        package_root,
+        after_outer_index: false,
+        max_bytes,
+        formatted: 0,
+        truncated: false,
+        line: String::new(),
+        error: None,
+    };
+    if std::fmt::write(&mut writer, format_args!("{:#?}", value)).is_err() && !writer.truncated {
+        return Err(writer
+            .error
+            .take()
+            .unwrap_or_else(|| std::io::Error::other("formatting failed")));
+    }
+    writer.flush_line()?;
+    if let Some(blocks) = &mut writer.blocks {
+        blocks.finish(writer.sink)?;
+    }
+    Ok(writer.truncated)
+}
+
+/// The note under a dump `write_linked_dump` cut short at `max_bytes`.
+fn truncated_dump_html(max_bytes: usize) -> String {
+    format!(
+        "<p class=\"truncated\"><strong>Dump truncated at {} bytes, use --max-dump-size to \
+         raise the limit.</strong></p>",
+        max_bytes
+    )
+}
+
+#[test]
+fn test_write_linked_dump_matches_string_version() {
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct PackageIndex {
+        index: i32,
+    }
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct Sample {
+        outer_index: PackageIndex,
+        class_index: PackageIndex,
+        name: String,
+        serial_index: i32,
+        children: Vec<PackageIndex>,
+    }
+    let sample = Sample {
+        outer_index: PackageIndex { index: 0 },
+        class_index: PackageIndex { index: -3 },
+        name: "<Door & \"Frame\">".to_string(),
+        serial_index: 4,
+        children: vec![PackageIndex { index: 1 }, PackageIndex { index: 12 }],
+    };
+    let transform = |i: i32| format!("<a href=\"{i}\">{i}</a>");
+
+    let expected = link_and_transform_indices(&escape_html(&format!("{:#?}", sample)), transform);
+    let mut streamed = Vec::new();
+    write_linked_dump(&mut streamed, &sample, transform, None, None, None).unwrap();
+    assert_eq!(expected, String::from_utf8(streamed).unwrap());
+
+    // Collapsing only adds tags around the linked lines.
+    let mut collapsed = Vec::new();
+    write_linked_dump(&mut collapsed, &sample, transform, Some(1), None, None).unwrap();
+    let collapsed = String::from_utf8(collapsed).unwrap();
+    assert!(collapsed.contains(
+        "<details><summary>    class_index: PackageIndex { (1)</summary>        \
+        index: <a href=\"-3\">-3</a>,\n    },</details>"
+    ));
+    assert!(collapsed.contains("<summary>    children: [ (2)</summary>"));
+
+    // Only the outer of 0 becomes the package root.
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct Reference {
+        outer_index: PackageIndex,
+        object: PackageIndex,
+    }
+    let reference = Reference {
+        outer_index: PackageIndex { index: 0 },
+        object: PackageIndex { index: 0 },
+    };
+    let mut rooted = Vec::new();
+    write_linked_dump(
+        &mut rooted,
+        &reference,
+        transform,
+        None,
+        Some("<a>root</a>"),
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        "Reference {\n    outer_index: PackageIndex {\n        index: <a>root</a>,\n    },\n    \
+        object: PackageIndex {\n        index: 0,\n    },\n}",
+        String::from_utf8(rooted).unwrap()
+    );
+}
+
+#[test]
+fn test_write_linked_dump_stops_at_max_bytes() {
+    let values: Vec<i32> = (0..1000).collect();
+    let transform = |i: i32| i.to_string();
+    let mut whole = Vec::new();
+    assert!(!write_linked_dump(&mut whole, &values, transform, None, None, Some(1 << 20)).unwrap());
+    let mut cut = Vec::new();
+    assert!(write_linked_dump(&mut cut, &values, transform, None, None, Some(100)).unwrap());
+    assert!(!cut.is_empty() && cut.len() <= 100);
+    assert!(whole.starts_with(&cut));
+
+    // The block open where formatting stopped is still written out.
+    let mut collapsed = Vec::new();
+    let nested = vec![values];
+    assert!(
+        write_linked_dump(&mut collapsed, &nested, transform, Some(1), None, Some(100)).unwrap()
+    );
+    assert!(String::from_utf8(collapsed)
+        .unwrap()
+        .contains("    [\n        0,\n        1,\n"));
+}
+
+#[test]
+fn test_escape_html_before_linking() {
+    let dump = escape_html("name: \"<b>&\" index: 3");
+    assert_eq!(
+        "name: &quot;&lt;b&gt;&amp;&quot; index: <a href=\"3\">3</a>".to_string(),
+        link_and_transform_indices(&dump, |i| format!("<a href=\"{i}\">{i}</a>"))
+    );
+}
+
+/// How the engine shows `name`: instance number `n` is stored as `n + 1`,
+/// with 0 for none, so `StaticMeshComponent` numbered 4 is
+/// `StaticMeshComponent_3`.
+fn display_name(name: &FName) -> String {
+    match name.get_number() {
+        number if number > 0 => format!("{}_{}", name.get_owned_content(), number - 1),
+        _ => name.get_owned_content(),
+    }
+}
+
+#[test]
+fn test_display_name() {
+    assert_eq!(
+        "StaticMeshComponent",
+        display_name(&FName::new_dummy("StaticMeshComponent".to_string(), 0))
+    );
+    assert_eq!(
+        "StaticMeshComponent_0",
+        display_name(&FName::new_dummy("StaticMeshComponent".to_string(), 1))
+    );
+    assert_eq!(
+        "StaticMeshComponent_3",
+        display_name(&FName::new_dummy("StaticMeshComponent".to_string(), 4))
+    );
+}
+
+/// Object names of an asset's imports and exports, in table order, with
+/// their instance numbers.
+struct IndexNames {
+    imports: Vec<String>,
+    exports: Vec<String>,
+    /// The `outer_index` of each import.
+    import_outers: Vec<i32>,
+    /// The `outer_index` of each export.
+    export_outers: Vec<i32>,
+    /// The `class_index` of each export.
+    export_classes: Vec<i32>,
+}
+
+/// How many outers `IndexNames::object_path` follows before giving up, far
+/// more than any real object is nested.
+const MAX_OUTER_DEPTH: usize = 64;
+
+impl IndexNames {
+    fn from_asset<C: Read + Seek>(asset: &Asset<C>) -> Self {
+        IndexNames {
+            imports: asset
+                .imports
+                .iter()
+                .map(|import| display_name(&import.object_name))
+                .collect(),
+            exports: asset
+                .asset_data
+                .exports
+                .iter()
+                .map(|export| display_name(&export.get_base_export().object_name))
+                .collect(),
+            import_outers: asset
+                .imports
+                .iter()
+                .map(|import| import.outer_index.index)
+                .collect(),
+            export_outers: asset
+                .asset_data
+                .exports
+                .iter()
+                .map(|export| export.get_base_export().outer_index.index)
+                .collect(),
+            export_classes: asset
+                .asset_data
+                .exports
+                .iter()
+                .map(|export| export.get_base_export().class_index.index)
+                .collect(),
+        }
+    }
+
+    /// What the pages show for import or export `index` after the index: an
+    /// import's name, or an export's name and class, e.g. `DoorMesh :
+    /// StaticMeshComponent`.
+    fn label(&self, index: i32) -> Option<String> {
+        let name = self.get(index)?;
+        if index < 0 {
+            return Some(name.to_string());
+        }
+        let class_index = self
+            .export_classes
+            .get(index as usize - 1)
+            .copied()
+            .unwrap_or(0);
+        Some(format!(
+            "{} : {}",
+            name,
+            export_class_name(self, class_index)
+        ))
+    }
+
+    /// The path the engine knows the export or import `index` by, e.g.
+    /// `/Game/Maps/Hub.Hub:PersistentLevel.Door_2` for an export of the
+    /// package `package`: the names along its outer chain, outermost first,
+    /// joined by `.`, except for the `:` after the asset the object is a
+    /// subobject of. A chain that crosses into the imports ends at their
+    /// package instead. `None` when the chain leaves the tables or loops.
+    fn object_path(&self, package: &str, index: i32) -> Option<String> {
+        let mut chain = Vec::new();
+        let mut current = index;
+        let root = loop {
+            if chain.len() > MAX_OUTER_DEPTH {
+                return None;
+            }
+            let name = self.get(current)?;
+            let position = (current.unsigned_abs() - 1) as usize;
+            if current < 0 {
+                let outer = self.import_outers[position];
+                // An import without an outer is a package.
+                if outer == 0 {
+                    break name;
+                }
+                chain.push(name);
+                current = outer;
+            } else {
+                chain.push(name);
+                current = self.export_outers[position];
+                if current == 0 {
+                    break package;
+                }
+            }
+        };
+        let mut path = root.to_string();
+        for (depth, name) in chain.iter().rev().enumerate() {
+            path.push(if depth == 1 { ':' } else { '.' });
+            path += name;
+        }
+        Some(path)
+    }
+
+    /// Follows the outer chain of the import `index` through the import table,
+    /// returning the indices from the import itself up to its outermost
+    /// import. Stops at cycles and out-of-range outers.
+    fn import_chain(&self, index: i32) -> Vec<i32> {
+        let mut chain = Vec::new();
+        let mut current = index;
+        while current < 0 && self.get(current).is_some() && !chain.contains(&current) {
+            chain.push(current);
+            current = self.import_outers[(-current - 1) as usize];
+        }
+        chain
+    }
+
+    /// Looks up a package index: negative values are imports, positive values are exports.
+    fn get(&self, index: i32) -> Option<&str> {
+        let names = if index < 0 {
+            &self.imports
+        } else {
+            &self.exports
+        };
+        let position = (index.unsigned_abs() as usize).checked_sub(1)?;
+        names.get(position).map(String::as_str)
+    }
+}
+
+/// Where the links to imports and exports go: their own pages, from a page
+/// `depth` folders below the asset's folder, or their sections of a
+/// `--single-file` page.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum LinkTarget {
+    Pages { depth: usize },
+    Sections,
+}
+
+impl LinkTarget {
+    /// For the pages of exports and imports, two levels down.
+    const ENTRY_PAGES: LinkTarget = LinkTarget::Pages { depth: 2 };
+
+    fn href(self, index: i32) -> String {
+        match (self, index < 0) {
+            (LinkTarget::Pages { depth }, true) => {
+                format!("{}imports/{}", "../".repeat(depth), -index)
+            }
+            (LinkTarget::Pages { depth }, false) => {
+                format!("{}exports/{}", "../".repeat(depth), index)
+            }
+            (LinkTarget::Sections, true) => format!("#import-{}", -index),
+            (LinkTarget::Sections, false) => format!("#export-{}", index),
+        }
+    }
+
+    /// The asset's own page, which stands for the package itself.
+    fn root_href(self) -> String {
+        match self {
+            LinkTarget::Pages { depth: 0 } => ".".to_string(),
+            LinkTarget::Pages { depth } => vec![".."; depth].join("/"),
+            LinkTarget::Sections => "#".to_string(),
+        }
+    }
+}
+
+#[test]
+fn test_link_target_hrefs() {
+    assert_eq!("imports/2", LinkTarget::Pages { depth: 0 }.href(-2));
+    assert_eq!("../exports/1", LinkTarget::Pages { depth: 1 }.href(1));
+    assert_eq!("../../imports/2", LinkTarget::ENTRY_PAGES.href(-2));
+    assert_eq!(".", LinkTarget::Pages { depth: 0 }.root_href());
+    assert_eq!("..", LinkTarget::Pages { depth: 1 }.root_href());
+    assert_eq!("../..", LinkTarget::ENTRY_PAGES.root_href());
+    assert_eq!("#export-1", LinkTarget::Sections.href(1));
+}
+
+/// The `href` of every link in `html`.
+#[cfg(test)]
+fn hrefs(html: &str) -> Vec<&str> {
+    html.split("href=\"")
+        .skip(1)
+        .filter_map(|rest| rest.split('"').next())
+        .collect()
+}
+
+#[test]
+fn test_links_resolve_at_every_depth() {
+    // An asset folder with the pages of export 1 and import 2.
+    let dir = tempfile::tempdir().unwrap();
+    let asset = dir.path().join("Hero");
+    for page in ["", "exports", "exports/1", "imports", "imports/2"] {
+        std::fs::create_dir_all(asset.join(page)).unwrap();
+        std::fs::write(asset.join(page).join("index.html"), "").unwrap();
+    }
+    let names = test_index_names();
+    // The main page and level.html, listings, and export and import pages.
+    for (depth, page) in [(0, ""), (1, "exports"), (2, "exports/1"), (2, "imports/2")] {
+        let links = LinkTarget::Pages { depth };
+        let html = [
+            link_and_annotate_index(&names, -2, links),
+            link_and_annotate_index(&names, 1, links),
+            package_root_html(links),
+        ]
+        .concat();
+        let hrefs = hrefs(&html);
+        assert_eq!(3, hrefs.len());
+        for href in hrefs {
+            let target = asset.join(page).join(href).join("index.html");
+            assert!(target.exists(), "{} from {:?}", href, page);
+        }
+    }
+}
+
+/// How the outer of a top-level export reads: the package itself, linked
+/// to the asset's page.
+fn package_root_html(links: LinkTarget) -> String {
+    format!("<a href=\"{}\">0 (package root)</a>", links.root_href())
+}
+
+#[test]
+fn test_package_root_html() {
+    assert_eq!(
+        "<a href=\"../..\">0 (package root)</a>",
+        package_root_html(LinkTarget::ENTRY_PAGES)
+    );
+    assert_eq!(
+        "<a href=\"#\">0 (package root)</a>",
+        package_root_html(LinkTarget::Sections)
+    );
+}
+
+/// Renders a package index as a link to its import or export, or as a red
+/// annotation when it doesn't point anywhere in this asset.
+fn link_and_annotate_index(names: &IndexNames, index: i32, links: LinkTarget) -> String {
+    if index == 0 {
+        return "0 (null)".to_string();
+    }
+    let Some(label) = names.label(index) else {
+        return format!(
+            "<span style=\"color:red\">{index} (out of range)</span>",
+            index = index
+        );
+    };
+    format!(
+        "<a href=\"{href}\">{index} ({label})</a>",
+        href = links.href(index),
+        label = escape_html(&label),
+        index = index
+    )
+}
+
+#[cfg(test)]
+fn test_index_names() -> IndexNames {
+    IndexNames {
+        imports: vec!["CoreUObject".to_string(), "Texture2D".to_string()],
+        exports: vec!["Default__Foo".to_string()],
+        import_outers: vec![0, -1],
+        export_outers: vec![0],
+        export_classes: vec![-2],
+    }
+}
+
+#[test]
+fn test_object_path() {
+    let mut names = IndexNames {
+        imports: vec![
+            "/Script/Engine".to_string(),
+            "Default__Actor".to_string(),
+            "/Game/Shared/Door".to_string(),
+            "Door".to_string(),
+        ],
+        exports: vec![
+            "Hub".to_string(),
+            "PersistentLevel".to_string(),
+            "Door_2".to_string(),
+            "DoorMesh".to_string(),
+            "Hinge".to_string(),
+        ],
+        import_outers: vec![0, -1, 0, -3],
+        export_outers: vec![0, 1, 2, 3, -4],
+        export_classes: Vec::new(),
+    };
+    let path = |names: &IndexNames, index| names.object_path("/Game/Maps/Hub", index);
+    assert_eq!(Some("/Game/Maps/Hub.Hub".to_string()), path(&names, 1));
+    assert_eq!(
+        Some("/Game/Maps/Hub.Hub:PersistentLevel.Door_2.DoorMesh".to_string()),
+        path(&names, 4)
+    );
+    assert_eq!(
+        Some("/Game/Shared/Door.Door:Hinge".to_string()),
+        path(&names, 5)
+    );
+    assert_eq!(
+        Some("/Script/Engine.Default__Actor".to_string()),
+        path(&names, -2)
+    );
+    assert_eq!(None, path(&names, 6));
+    names.export_outers[1] = 3;
+    assert_eq!(None, path(&names, 4));
+    names.export_outers[1] = 9;
+    assert_eq!(None, path(&names, 2));
+}
+
+#[test]
+fn test_import_chain() {
+    let mut names = test_index_names();
+    assert_eq!(vec![-2, -1], names.import_chain(-2));
+    assert_eq!(vec![-1], names.import_chain(-1));
+    names.import_outers = vec![-2, -1];
+    assert_eq!(vec![-2, -1], names.import_chain(-2));
+}
+
+#[test]
+fn test_link_and_annotate_index() {
+    let names = test_index_names();
+    assert_eq!(
+        "<a href=\"../../imports/2\">-2 (Texture2D)</a>",
+        link_and_annotate_index(&names, -2, LinkTarget::ENTRY_PAGES)
+    );
+    assert_eq!(
+        "<a href=\"../../exports/1\">1 (Default__Foo : Texture2D)</a>",
+        link_and_annotate_index(&names, 1, LinkTarget::ENTRY_PAGES)
+    );
+}
+
+#[test]
+fn test_index_label() {
+    let mut names = test_index_names();
+    assert_eq!(Some("Texture2D".to_string()), names.label(-2));
+    assert_eq!(Some("Default__Foo : Texture2D".to_string()), names.label(1));
+    assert_eq!(None, names.label(2));
+    for class_index in [0, 7] {
+        names.export_classes = vec![class_index];
+        assert_eq!(
+            Some("Default__Foo : UnknownClass".to_string()),
+            names.label(1)
+        );
+    }
+}
+
+#[test]
+fn test_link_and_annotate_index_to_sections() {
+    let names = test_index_names();
+    assert_eq!(
+        "<a href=\"#import-2\">-2 (Texture2D)</a>",
+        link_and_annotate_index(&names, -2, LinkTarget::Sections)
+    );
+    assert_eq!(
+        "<a href=\"#export-1\">1 (Default__Foo : Texture2D)</a>",
+        link_and_annotate_index(&names, 1, LinkTarget::Sections)
+    );
+}
+
+#[test]
+fn test_link_and_annotate_negative_out_of_range_index() {
+    assert_eq!(
+        "<span style=\"color:red\">-3 (out of range)</span>",
+        link_and_annotate_index(&test_index_names(), -3, LinkTarget::ENTRY_PAGES)
+    );
+}
+
+#[test]
+fn test_link_and_annotate_positive_out_of_range_index() {
+    assert_eq!(
+        "<span style=\"color:red\">2 (out of range)</span>",
+        link_and_annotate_index(&test_index_names(), 2, LinkTarget::ENTRY_PAGES)
+    );
+}
+
+#[test]
+fn test_link_and_annotate_zero_index() {
+    assert_eq!(
+        "0 (null)",
+        link_and_annotate_index(&test_index_names(), 0, LinkTarget::ENTRY_PAGES)
+    );
+}
+
+/// Creates the directory along with any missing parents.
+fn try_create_dir<P: AsRef<Path>>(path: P) -> std::io::Result<()> {
+    create_dir_all(path)
+}
+
+/// Written into every directory `index_file` generates so later runs (and
+/// `clean`) can tell generated output apart from the user's own folders.
+const MARKER_FILE: &str = ".uasset-index";
+
+/// Creates an asset's output directory and marks it as generated.
+fn create_output_dir(main_dir: &Path, file_name: &str) -> Result<(), IOError> {
+    try_create_dir(main_dir)?;
+    manifest::write_atomically(&main_dir.join(MARKER_FILE), file_name)
+}
+
+/// Whether `dir` was created by `create_output_dir`.
+fn is_output_dir(dir: &Path) -> bool {
+    dir.join(MARKER_FILE).is_file()
+}
+
+/// Whether any asset file, one of `SUPPORTED_EXTENSIONS` or of `extensions`,
+/// lives somewhere under `dir`.
+fn contains_assets(dir: &Path, extensions: &[String]) -> Result<bool, IOError> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_file() && is_valid_extension(entry.path().extension(), extensions) {
+            return Ok(true);
+        }
+        if file_type.is_dir() && contains_assets(&entry.path(), extensions)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Whether an asset next to `dir` is named like it, as `Weapons.uasset` is
+/// next to `Weapons/`.
+fn names_an_asset(dir: &Path, extensions: &[String]) -> bool {
+    let (Some(parent), Some(name)) = (dir.parent(), dir.file_name()) else {
+        return false;
+    };
+    SUPPORTED_EXTENSIONS
+        .iter()
+        .copied()
+        .chain(extensions.iter().map(String::as_str))
+        .any(|extension| {
+            let mut file_name = name.to_os_string();
+            file_name.push(".");
+            file_name.push(extension);
+            parent.join(file_name).is_file()
+        })
+}
+
+/// Whether `dir` is a directory of the user's own rather than one
+/// `create_output_dir` made. A marked directory named like an asset next to
+/// it counts as the user's when it holds assets: it's what runs before
+/// generated output was deconflicted left in such a folder. Only those are
+/// searched for assets, so the walk doesn't read through every generated
+/// directory.
+fn is_user_dir(dir: &Path, extensions: &[String]) -> bool {
+    dir.is_dir()
+        && (!is_output_dir(dir)
+            || (names_an_asset(dir, extensions)
+                && contains_assets(dir, extensions).unwrap_or(true)))
+}
+
+#[test]
+fn test_is_user_dir() {
+    let fixture = tempfile::tempdir().unwrap();
+    let dir = fixture.path().join("Weapons");
+    create_dir_all(dir.join("Rifles")).unwrap();
+    std::fs::write(dir.join("Rifles").join("AK.gasset"), "").unwrap();
+    assert!(is_user_dir(&dir, &[]));
+    std::fs::write(dir.join(MARKER_FILE), "Weapons.gasset").unwrap();
+    // Marked and named like no asset, so it's generated whatever it holds.
+    assert!(!is_user_dir(&dir, &[]));
+    std::fs::write(fixture.path().join("Weapons.gasset"), "").unwrap();
+    assert!(!is_user_dir(&dir, &[]));
+    assert!(is_user_dir(&dir, &["gasset".to_string()]));
+}
+
+/// The name of the directory generated for the asset at `path`: its
+/// sanitized file stem, unless `taken` says one of the user's directories
+/// is called that, as `Weapons/` is next to `Weapons.uasset`. Then it's the
+/// whole file name with `-index` appended, `Weapons.uasset-index`.
+fn asset_dir_name(path: &Path, taken: impl Fn(&str) -> bool) -> String {
+    let name = sanitize_dir_name(&path.file_stem().unwrap().to_string_lossy());
+    if taken(&name) {
+        sanitize_dir_name(&format!(
+            "{}-index",
+            path.file_name().unwrap().to_string_lossy()
+        ))
+    } else {
+        name
+    }
+}
+
+#[test]
+fn test_asset_dir_name() {
+    let path = Path::new("Content/Weapons.uasset");
+    assert_eq!("Weapons", asset_dir_name(path, |_| false));
+    assert_eq!(
+        "Weapons.uasset-index",
+        asset_dir_name(path, |name| name == "Weapons")
+    );
+}
+
+/// The directory that `index_file` generates for the asset at `path` when writing into `out_dir`.
+fn asset_output_dir(path: &Path, out_dir: &Path, extensions: &[String]) -> PathBuf {
+    out_dir.join(asset_dir_name(path, |name| {
+        is_user_dir(&out_dir.join(name), extensions)
+    }))
+}
+
+/// Longest directory name we generate, leaving room for the nested
+/// `exports/<n>/index.html` below it.
+const MAX_DIR_NAME_LEN: usize = 128;
+
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Turns an asset stem into a directory name that's valid on every platform:
+/// characters Windows rejects become `_`, reserved device names get a `_`
+/// suffix, trailing dots and spaces are replaced and long names are truncated.
+fn sanitize_dir_name(name: &str) -> String {
+    let mut result: String = name
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    // Windows treats everything before the first dot as the device name.
+    let base_len = result.find('.').unwrap_or(result.len());
+    if RESERVED_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(&result[..base_len]))
+    {
+        result.insert(base_len, '_');
+    }
+    if result.len() > MAX_DIR_NAME_LEN {
+        let mut end = MAX_DIR_NAME_LEN;
+        while !result.is_char_boundary(end) {
+            end -= 1;
+        }
+        result.truncate(end);
+    }
+    let trimmed_len = result.trim_end_matches(['.', ' ']).len();
+    let trailing = result.len() - trimmed_len;
+    result.truncate(trimmed_len);
+    result.extend(std::iter::repeat('_').take(trailing));
+    if result.is_empty() {
+        result.push('_');
+    }
+    result
+}
+
+#[test]
+fn test_sanitize_dir_name() {
+    assert_eq!("Rifle", sanitize_dir_name("Rifle"));
+    assert_eq!("CON_", sanitize_dir_name("CON"));
+    assert_eq!("nul_", sanitize_dir_name("nul"));
+    assert_eq!("Com1_.backup", sanitize_dir_name("Com1.backup"));
+    assert_eq!("CONSOLE", sanitize_dir_name("CONSOLE"));
+    assert_eq!("a_b_c_", sanitize_dir_name("a:b?c*"));
+    assert_eq!("Trailing__", sanitize_dir_name("Trailing. "));
+    assert_eq!(MAX_DIR_NAME_LEN, sanitize_dir_name(&"é".repeat(100)).len());
+}
+
+/// Picks `name`, or `name_2`, `name_3`, ... if an earlier asset in the same
+/// directory already took it. Compared case-insensitively, like Windows does.
+fn unique_dir_name(name: String, used: &mut HashSet<String>) -> String {
+    let mut candidate = name.clone();
+    let mut suffix = 2;
+    while !used.insert(candidate.to_lowercase()) {
+        candidate = format!("{}_{}", name, suffix);
+        suffix += 1;
+    }
+    candidate
+}
+
+fn is_same_dir(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Whether `ext` is one of `SUPPORTED_EXTENSIONS` or of `extensions`, those
+/// registered with `--extensions`, ignoring case. Compared as an `OsStr` so
+/// the rest of the file name needn't be UTF-8. Every check of whether a file
+/// is an asset goes through here.
+fn is_valid_extension(ext: Option<&OsStr>, extensions: &[String]) -> bool {
+    match ext {
+        Some(ext) => SUPPORTED_EXTENSIONS
+            .iter()
+            .copied()
+            .chain(extensions.iter().map(String::as_str))
+            .any(|supported| ext.eq_ignore_ascii_case(supported)),
+        None => false,
+    }
+}
+
+#[test]
+fn test_is_valid_extension() {
+    let ext = |path: &str| Path::new(path).extension().map(OsStr::to_os_string);
+    let valid =
+        |path: &str, extensions: &[String]| is_valid_extension(ext(path).as_deref(), extensions);
+    assert!(valid("Content/Hero.uasset", &[]));
+    assert!(valid("Content/Maps/Hub.UMAP", &[]));
+    assert!(valid("Content/Hero.UAsset", &[]));
+    assert!(!valid("Content/Hero.uexp", &[]));
+    assert!(!valid("Content/Hero", &[]));
+    let extensions = vec!["gasset".to_string()];
+    assert!(valid("Content/Hero.gasset", &extensions));
+    assert!(valid("Content/Hero.GAsset", &extensions));
+    assert!(valid("Content/Hero.uasset", &extensions));
+    assert!(!valid("Content/Hero.gasset", &[]));
+}
+
+/// Everything that controls how assets are indexed, shared by every file in
+/// a run.
+pub struct IndexOptions {
+    /// Where generated folders go. `None` writes them next to each asset.
+    pub output_dir: Option<PathBuf>,
+    /// Used for assets that don't record their own engine version.
+    pub engine_version: EngineVersion,
+    pub mappings: Option<Mappings>,
+    pub formats: Vec<OutputFormat>,
+    /// Writes `graph.dot` with at most this many nodes when set.
+    pub graph_max_nodes: Option<usize>,
+    /// How many elements of an array, set or map export pages list.
+    pub max_array_elements: usize,
+    /// How many bytes of a string export pages show before cutting it, with
+    /// its full value written to `strings/`.
+    pub max_string_bytes: usize,
+    /// How many bytes of an export's dump are formatted before it's cut
+    /// short with a note, so one huge export doesn't stall the run.
+    pub max_dump_size: usize,
+    /// Splits the exports and imports listings into pages of about this
+    /// many entries when they have more.
+    pub listing_page_size: usize,
+    /// Skips assets whose output from a previous run is still up to date.
+    pub incremental: bool,
+    /// Class name patterns for `--filter-class`. Only exports whose class
+    /// matches one get a page; empty means every export does.
+    pub class_filter: Vec<String>,
+    /// Gives each export page a hex dump of the export's serialized bytes
+    /// when set, for `--raw-dumps`.
+    pub raw_dumps: Option<hexdump::RawDumps>,
+    /// The contents of `style.css`: `DEFAULT_STYLESHEET` or the `--style` file.
+    pub stylesheet: Vec<u8>,
+    /// Decrypts the index and entries of encrypted `.pak` files.
+    pub aes_key: Option<pak::AesKey>,
+    /// Entry path patterns for `--pak-filter`; empty means every asset in a
+    /// `.pak` is indexed.
+    pub pak_filter: Vec<String>,
+    /// Descends into symlinked directories when walking a directory.
+    pub follow_symlinks: bool,
+    /// How many levels of subdirectories below a directory given to
+    /// `index_dir` are walked. `Some(0)` indexes only the files directly in
+    /// it; `None` has no limit.
+    pub max_depth: Option<usize>,
+    /// `--include` and `--exclude`, for the assets of a directory.
+    pub path_filter: filter::PathFilter,
+    /// Writes each asset's pages as one self-contained `<asset>.html` in its
+    /// folder instead of a page per import and export.
+    pub single_file: bool,
+    /// `--version-map`, which takes precedence over `engine_version` for the
+    /// directories it lists.
+    pub version_map: version_map::VersionMap,
+    /// The engine versions and mappings of the `uasset-index.toml` files
+    /// found, which take precedence over `engine_version` and `mappings`
+    /// below their directories, but not over `version_map`.
+    pub directory_configs: config::DirectoryConfigs,
+    /// How deep the blocks of export and import dumps are nested before
+    /// they start collapsed. `None` doesn't wrap them at all.
+    pub collapse_depth: Option<usize>,
+    /// Replaces the line printed for each asset with a numbered one, or a bar,
+    /// when set.
+    pub progress: Option<progress::Progress>,
+    /// With `OutputFormat::Csv`, also writes the tables of every asset of a
+    /// directory or `.pak` into one `exports.csv` and `imports.csv` at the
+    /// top of the output.
+    pub csv_combined: bool,
+    /// Writes import pages whose body is the same as an earlier import's of
+    /// the asset as redirects to that one, for `--dedupe-pages`. Export pages
+    /// are left alone: each dumps its own object name, so no two have the
+    /// same body.
+    pub dedupe_pages: bool,
+    /// Stores every generated file but thumbnails compressed when set, for
+    /// `--compress`.
+    pub compression: Option<compress::Compression>,
+    /// Extensions read as assets besides `SUPPORTED_EXTENSIONS`, without
+    /// the dot, for `--extensions`.
+    pub extensions: Vec<String>,
+    /// Writes the string-like property values of each asset to
+    /// `strings.txt`, and of a directory run to `strings.csv`, when set.
+    pub extract_strings: Option<strings::ExtractStrings>,
+    /// The roots whose copy of a package wins, earliest first, when several
+    /// files map to it, for `--prefer`.
+    pub prefer: Vec<PathBuf>,
+    /// Where `OutputFormat::Ndjson` writes its lines; without it they're
+    /// not written. Assets `incremental` finds up to date get none.
+    pub ndjson: Option<ndjson::NdjsonOutput>,
+    /// Writes `layout.html` and `layout.json` per asset, with where the
+    /// summary fields and the entries of its tables are in the `.uasset`.
+    pub layout: bool,
+}
+
+impl Default for IndexOptions {
+    /// HTML pages next to each asset, with no mappings or filters.
+    fn default() -> Self {
+        IndexOptions {
+            output_dir: None,
+            engine_version: DEFAULT_ENGINE_VERSION,
+            mappings: None,
+            formats: vec![OutputFormat::Html],
+            graph_max_nodes: None,
+            max_array_elements: DEFAULT_MAX_ARRAY_ELEMENTS,
+            max_string_bytes: DEFAULT_MAX_STRING_BYTES,
+            max_dump_size: DEFAULT_MAX_DUMP_SIZE,
+            listing_page_size: DEFAULT_LISTING_PAGE_SIZE,
+            incremental: false,
+            class_filter: Vec::new(),
+            raw_dumps: None,
+            stylesheet: DEFAULT_STYLESHEET.as_bytes().to_vec(),
+            aes_key: None,
+            pak_filter: Vec::new(),
+            follow_symlinks: false,
+            max_depth: None,
+            path_filter: filter::PathFilter::default(),
+            single_file: false,
+            version_map: version_map::VersionMap::default(),
+            directory_configs: config::DirectoryConfigs::default(),
+            collapse_depth: Some(DEFAULT_COLLAPSE_DEPTH),
+            progress: None,
+            csv_combined: false,
+            dedupe_pages: false,
+            compression: None,
+            extensions: Vec::new(),
+            extract_strings: None,
+            prefer: Vec::new(),
+            ndjson: None,
+            layout: false,
+        }
+    }
+}
+
+impl IndexOptions {
+    fn has_format(&self, format: OutputFormat) -> bool {
+        self.formats.contains(&format)
+    }
+
+    /// The NDJSON output, when `OutputFormat::Ndjson` is asked for.
+    fn ndjson(&self) -> Option<&ndjson::NdjsonOutput> {
+        self.ndjson
+            .as_ref()
+            .filter(|_| self.has_format(OutputFormat::Ndjson))
+    }
+
+    /// Whether the run's log lines go to stderr, because stdout carries the
+    /// NDJSON lines.
+    fn logs_to_stderr(&self) -> bool {
+        self.ndjson().is_some_and(ndjson::NdjsonOutput::is_stdout)
+    }
+
+    /// Prints a line of the run's log, such as which asset is being
+    /// indexed: on stdout, or on stderr when the NDJSON lines go there.
+    pub fn log(&self, line: std::fmt::Arguments) {
+        log_line(self.logs_to_stderr(), line);
+    }
+
+    /// The engine version for the asset at `path` when it doesn't record one.
+    fn engine_version_for(&self, path: &Path) -> EngineVersion {
+        self.version_map
+            .engine_version(path)
+            .or_else(|| self.directory_configs.engine_version(path))
+            .unwrap_or(self.engine_version)
+    }
+
+    /// The mappings for the asset at `path`, if it needs any.
+    fn mappings_for(&self, path: &Path) -> Option<&Mappings> {
+        self.directory_configs
+            .mappings(path)
+            .or(self.mappings.as_ref())
+    }
+
+    /// Whether an export of the class `class_name` gets its own page.
+    fn wants_export_page(&self, class_name: &str) -> bool {
+        self.class_filter.is_empty()
+            || self
+                .class_filter
+                .iter()
+                .any(|pattern| matches_wildcard(pattern, class_name))
+    }
+
+    /// The options that change what gets generated, so `--incremental` can
+    /// tell when a previous run's output no longer applies.
+    fn fingerprint(&self) -> String {
+        format!(
+            "{:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?}",
+            self.formats,
+            self.single_file,
+            self.graph_max_nodes,
+            self.max_array_elements,
+            self.max_string_bytes,
+            self.max_dump_size,
+            self.listing_page_size,
+            self.collapse_depth,
+            self.engine_version,
+            self.version_map,
+            self.directory_configs,
+            self.mappings.as_ref().map(|mappings| &mappings.name),
+            self.class_filter,
+            self.raw_dumps,
+            self.csv_combined,
+            self.dedupe_pages,
+            self.compression,
+            self.extract_strings,
+            self.layout
+        )
+    }
+}
+
+/// Property mappings loaded from a `.usmap` file, needed to read assets with
+/// unversioned properties.
+pub struct Mappings {
+    name: String,
+    usmap: Usmap,
+}
+
+impl Mappings {
+    /// Loads a compressed or uncompressed `.usmap`.
+    pub fn load(path: &Path) -> Result<Self, unreal_asset::Error> {
+        let file = File::open(path)?;
+        Ok(Mappings {
+            name: path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            usmap: Usmap::new(BufReader::new(file))?,
+        })
+    }
+}
+
+/// How many asset files a run indexed and how many it gave up on, along with
+/// the files it generated for `--manifest` and the warnings it printed.
+#[derive(Debug, Default)]
+pub struct IndexReport {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub generated: Vec<manifest::GeneratedFile>,
+    pub errors: Vec<manifest::IndexFailure>,
+    /// Problems that didn't stop an asset from being indexed, such as package
+    /// indices that point nowhere.
+    pub warnings: Vec<String>,
+    /// Directories `index_dir` couldn't list, so whatever is in them wasn't
+    /// indexed.
+    pub unreadable_dirs: Vec<PathBuf>,
+    /// The entries `index_dir` left out because they couldn't be read, these
+    /// directories included, with why.
+    pub skipped: Vec<manifest::IndexFailure>,
+    /// Export and import counts summed over the assets indexed.
+    pub exports: usize,
+    pub imports: usize,
+    /// How long each asset that was parsed took.
+    pub timings: Vec<stats::AssetTiming>,
+    /// The text properties of the assets indexed, for `--texts-csv`.
+    pub texts: Vec<(PathBuf, texts::TextEntry)>,
+    /// The pages `--dedupe-pages` replaced with redirects.
+    pub deduped: stats::DedupedPages,
+    /// The package paths that more than one file of a directory maps to.
+    pub collisions: Vec<PackageCollision>,
+    /// The exports whose dump stopped at `--max-dump-size`.
+    pub truncated_dumps: Vec<stats::TruncatedDump>,
+}
+
+impl IndexReport {
+    /// A report of nothing but `path` failing with `err`.
+    fn failure(path: &Path, err: Error) -> Self {
+        let mut report = IndexReport::default();
+        report.add_result(path, Err(manifest::IndexFailure::new(path, err)));
+        report
+    }
+
+    pub fn add(&mut self, other: IndexReport) {
+        self.succeeded += other.succeeded;
+        self.failed += other.failed;
+        self.generated.extend(other.generated);
+        self.errors.extend(other.errors);
+        self.warnings.extend(other.warnings);
+        self.unreadable_dirs.extend(other.unreadable_dirs);
+        self.skipped.extend(other.skipped);
+        self.exports += other.exports;
+        self.imports += other.imports;
+        self.timings.extend(other.timings);
+        self.texts.extend(other.texts);
+        self.deduped.add(other.deduped);
+        self.collisions.extend(other.collisions);
+        self.truncated_dumps.extend(other.truncated_dumps);
+    }
+
+    /// Counts the outcome of indexing the asset at `path`.
+    fn add_result(&mut self, path: &Path, result: Result<AssetSummary, manifest::IndexFailure>) {
+        match result {
+            Ok(summary) => {
+                self.succeeded += 1;
+                self.exports += summary.export_count;
+                self.imports += summary.import_count;
+                if !summary.up_to_date {
+                    self.timings.push(stats::AssetTiming {
+                        path: path.to_path_buf(),
+                        parse: summary.parse_time,
+                        write: summary.write_time,
+                        phases: summary.phase_times,
+                    });
+                }
+                self.deduped.add(summary.deduped);
+                self.truncated_dumps
+                    .extend(summary.truncated_dumps.into_iter().map(|export| {
+                        stats::TruncatedDump {
+                            path: path.to_path_buf(),
+                            export,
+                        }
+                    }));
+                self.generated.extend(summary.generated);
+                self.warnings.extend(summary.warnings);
+                self.texts.extend(
+                    summary
+                        .texts
+                        .into_iter()
+                        .map(|text| (path.to_path_buf(), text)),
+                );
+            }
+            Err(mut failure) => {
+                self.failed += 1;
+                self.generated.append(&mut failure.generated);
+                self.errors.push(failure);
+            }
+        }
+    }
+}
+
+/// Why an asset, directory or `.pak` couldn't be indexed.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("{}: invalid extension. Valid extensions are: 'umap', 'uasset'", file_name(.0))]
+    InvalidExtension(PathBuf),
+    #[error("file does not exist: {}", .0.display())]
+    NotFound(PathBuf),
+    #[error("failed to open {}: {}", .0.display(), .1)]
+    Open(PathBuf, #[source] IOError),
+    #[error("failed to read package summary of {}: {}", file_name(.0), .1)]
+    Summary(PathBuf, #[source] IOError),
+    #[error("{}: {}", file_name(.0), .1)]
+    BadTag(PathBuf, summary::BadTag),
+    #[error(
+        "{} is compressed as a whole (PKG_StoreCompressed), which can't be read. \
+         Resave it without package compression",
+        file_name(.0)
+    )]
+    CompressedPackage(PathBuf),
+    #[error(
+        "{} uses unversioned properties, which can't be read without mappings. \
+         Pass the game's mappings with --usmap <file>",
+        file_name(.0)
+    )]
+    MissingMappings(PathBuf),
+    /// The header says the export data is split off, but the `.uexp` at the
+    /// second path isn't there.
+    #[error(
+        "{} keeps its export data in a separate file, but {} doesn't exist",
+        file_name(.0),
+        .1.display()
+    )]
+    MissingUexp(PathBuf, PathBuf),
+    #[error("failed to open {}: {}", .0.display(), .1)]
+    OpenUexp(PathBuf, #[source] IOError),
+    #[error("failed to parse {}: {}", file_name(.0), .1)]
+    Parse(PathBuf, #[source] unreal_asset::Error),
+    /// Writing the output of the asset at the path failed, after writing the
+    /// files listed last, which stay in place.
+    #[error("failed to write the output of {}: {}", file_name(.0), .1)]
+    Write(PathBuf, #[source] IOError, Vec<manifest::GeneratedFile>),
+    /// The asset at the path panicked the parser or a writer.
+    #[error("{}: failed to index", file_name(.0))]
+    Panic(PathBuf),
+    /// Walking the directory or `.pak` at the path, or writing its directory
+    /// pages, failed.
+    #[error("failed to index {}: {}", .0.display(), .1)]
+    Dir(PathBuf, #[source] IOError),
+    #[error("failed to index pak {}: {}", .0.display(), .1)]
+    OpenPak(PathBuf, #[source] repak::Error),
+    /// The entry at the path couldn't be read out of its `.pak`.
+    #[error("failed to read {} from the pak: {}", .0.display(), .1)]
+    Pak(PathBuf, #[source] repak::Error),
+}
+
+impl Error {
+    /// A one-word kind of failure for `errors.html` and `errors.json`.
+    pub fn category(&self) -> &'static str {
+        match self {
+            Error::InvalidExtension(_) => "extension",
+            Error::NotFound(_) => "missing",
+            Error::Open(..) | Error::OpenUexp(..) => "open",
+            Error::BadTag(_, summary::BadTag::Encrypted) => "encrypted",
+            Error::Summary(..) | Error::BadTag(..) => "summary",
+            Error::CompressedPackage(_) => "compressed",
+            Error::MissingMappings(_) => "mappings",
+            Error::MissingUexp(..) => "uexp",
+            Error::Parse(..) => "parse",
+            Error::Write(..) => "write",
+            Error::Panic(_) => "panic",
+            Error::Dir(..) => "directory",
+            Error::OpenPak(..) | Error::Pak(..) => "pak",
+        }
+    }
+}
+
+fn file_name(path: &Path) -> std::borrow::Cow<str> {
+    path.file_name().unwrap_or_default().to_string_lossy()
+}
+
+/// Prints `line` on stdout, or with `to_stderr` on stderr.
+fn log_line(to_stderr: bool, line: std::fmt::Arguments) {
+    if to_stderr {
+        eprintln!("{}", line);
+    } else {
+        println!("{}", line);
+    }
+}
+
+/// Indexes `path`, whether it's an asset, a directory of assets or a `.pak`,
+/// into the options' output directory, or next to the input when no output
+/// directory is given. Errors are printed and counted in the report rather
+/// than returned, so one bad input doesn't end a run over several.
+pub fn index(path: &Path, options: &IndexOptions) -> IndexReport {
+    let result = if pak::is_pak(path) {
+        pak::index_pak(path, options)
+    } else if path.is_dir() {
+        index_dir(path, options)
+    } else {
+        with_progress(path, options, || index_file(path, options))
+    };
+    result.unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        let report = IndexReport::failure(path, err);
+        write_ndjson_failures(&report.errors, options);
+        report
+    })
+}
+
+/// Writes the NDJSON line of each failure, if the options ask for them. A
+/// line that can't be written is reported rather than failing the run.
+fn write_ndjson_failures(failures: &[manifest::IndexFailure], options: &IndexOptions) {
+    let Some(ndjson) = options.ndjson() else {
+        return;
+    };
+    for failure in failures {
+        if let Err(err) = ndjson.write_failure(failure) {
+            eprintln!("failed to write NDJSON line: {}", err);
+        }
+    }
+}
+
+/// Indexes the asset at `path` into a directory named after it, inside the
+/// options' output directory or next to the asset.
+pub fn index_file(path: &Path, options: &IndexOptions) -> Result<IndexReport, Error> {
+    let main_dir = asset_output_dir(
+        path,
+        options
+            .output_dir
+            .as_deref()
+            .unwrap_or(path.parent().unwrap()),
+        &options.extensions,
+    );
+    manifest::remove_stale_temp_files(&main_dir);
+    let root = path.parent().unwrap_or(Path::new(""));
+    let summary = catch_panics(path, || {
+        index_asset(path, root, &main_dir, &main_dir, options)
+    })?;
+    let mut report = IndexReport::default();
+    if options.has_format(OutputFormat::Html) && !options.single_file {
+        let mut outputs = manifest::OutputFiles {
+            compression: options.compression,
+            ..Default::default()
+        };
+        if let Err(err) =
+            write_stylesheet(&main_dir, options, &mut outputs).and_then(|_| outputs.finish())
+        {
+            let mut written = summary.generated;
+            written.extend(outputs.take_written());
+            return Err(Error::Write(path.to_path_buf(), err, written));
+        }
+        report.generated.extend(std::mem::take(&mut outputs.files));
+    }
+    report.add_result(path, Ok(summary));
+    Ok(report)
+}
+
+/// How many assets `index` will find at `path`, for `progress::Progress`: one
+/// for an asset and those `index_dir` would find under a directory. A `.pak`
+/// counts as none, since listing its entries means opening it.
+pub fn count_assets(path: &Path, options: &IndexOptions) -> usize {
+    fn count_dir(dir: &Path, walk: &mut DirWalk) -> usize {
+        let Ok(entries) = walk.read_dir(dir) else {
+            return 0;
+        };
+        let subdirs: usize = entries
+            .subdirs
+            .iter()
+            .map(|subdir| count_dir(subdir, walk))
+            .sum();
+        entries.assets.len() + subdirs
+    }
+    if pak::is_pak(path) {
+        0
+    } else if path.is_dir() {
+        count_dir(path, &mut DirWalk::new(path, options))
+    } else {
+        1
+    }
+}
+
+#[test]
+fn test_count_assets() {
+    let dir = tempfile::tempdir().unwrap();
+    for name in ["a.uasset", "b.umap", "b.uexp", "notes.txt"] {
+        File::create(dir.path().join(name)).unwrap();
+    }
+    try_create_dir(dir.path().join("sub")).unwrap();
+    File::create(dir.path().join("sub").join("c.UASSET")).unwrap();
+    // Output of an earlier run isn't walked.
+    create_output_dir(&dir.path().join("a"), "a.uasset").unwrap();
+    File::create(dir.path().join("a").join("stale.uasset")).unwrap();
+    let options = IndexOptions::default();
+    assert_eq!(3, count_assets(dir.path(), &options));
+    assert_eq!(1, count_assets(&dir.path().join("a.uasset"), &options));
+}
+
+/// Reads the paths of a `--files-from` list: one per line, skipping blank
+/// lines and lines starting with `#`, or with `nul_delimited` separated by
+/// NUL bytes as `find -print0` writes them, where only empty entries are
+/// skipped. Relative paths stay relative to the current directory, like
+/// paths given as arguments.
+pub fn read_path_list(
+    mut reader: impl std::io::BufRead,
+    nul_delimited: bool,
+) -> std::io::Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    if nul_delimited {
+        let mut entry = Vec::new();
+        while reader.read_until(b'\0', &mut entry)? > 0 {
+            if entry.last() == Some(&b'\0') {
+                entry.pop();
+            }
+            if !entry.is_empty() {
+                paths.push(path_from_bytes(std::mem::take(&mut entry))?);
+            }
+        }
+        return Ok(paths);
+    }
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        paths.push(PathBuf::from(line));
+    }
+    Ok(paths)
+}
+
+/// The path a NUL-separated list entry names. On unix any bytes make a path,
+/// as the file system takes them; elsewhere they must be UTF-8.
+#[cfg(unix)]
+fn path_from_bytes(bytes: Vec<u8>) -> std::io::Result<PathBuf> {
+    use std::os::unix::ffi::OsStringExt;
+
+    Ok(PathBuf::from(std::ffi::OsString::from_vec(bytes)))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: Vec<u8>) -> std::io::Result<PathBuf> {
+    String::from_utf8(bytes)
+        .map(PathBuf::from)
+        .map_err(|err| IOError::new(std::io::ErrorKind::InvalidData, err.utf8_error()))
+}
+
+#[test]
+fn test_read_path_list() {
+    let list = "Content/Hub.umap\r\n\n  # changed today\nContent/Door.uasset\n   \n/abs/Key.uasset";
+    assert_eq!(
+        vec![
+            PathBuf::from("Content/Hub.umap"),
+            PathBuf::from("Content/Door.uasset"),
+            PathBuf::from("/abs/Key.uasset")
+        ],
+        read_path_list(list.as_bytes(), false).unwrap()
+    );
+    let list = b"./#Hub.umap\0./With\nNewline.uasset\0\0./Door.uasset\0";
+    assert_eq!(
+        vec![
+            PathBuf::from("./#Hub.umap"),
+            PathBuf::from("./With\nNewline.uasset"),
+            PathBuf::from("./Door.uasset")
+        ],
+        read_path_list(&list[..], true).unwrap()
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_read_path_list_keeps_non_utf8_paths() {
+    use std::ffi::OsString;
+    use std::os::unix::ffi::OsStringExt;
+
+    assert_eq!(
+        vec![PathBuf::from(OsString::from_vec(
+            b"Caf\xe9.uasset".to_vec()
+        ))],
+        read_path_list(&b"Caf\xe9.uasset\0"[..], true).unwrap()
+    );
+}
+
+/// Runs `index` for the asset at `path`, keeping the options' progress
+/// display, if any, up to date.
+fn with_progress<T, E>(
+    path: &Path,
+    options: &IndexOptions,
+    index: impl FnOnce() -> Result<T, E>,
+) -> Result<T, E> {
+    let Some(progress) = &options.progress else {
+        return index();
+    };
+    if let Some(line) = progress.start(path) {
+        options.log(format_args!("{}", line));
+    }
+    let result = index();
+    progress.finish(result.is_ok());
+    result
+}
+
+/// Runs `index_asset`, reporting any error or panic instead of propagating it
+/// so one bad asset doesn't stop the rest of the run. The error is kept for
+/// the manifest and `errors.html`.
+fn try_index_file(
+    path: &Path,
+    root: &Path,
+    main_dir: &Path,
+    style_root: &Path,
+    options: &IndexOptions,
+) -> Result<AssetSummary, manifest::IndexFailure> {
+    report_index_errors(path, options, || {
+        index_asset(path, root, main_dir, style_root, options)
+    })
+}
+
+/// Turns a panic while indexing the asset at `path` into `Error::Panic`.
+fn catch_panics<T>(path: &Path, index: impl FnOnce() -> Result<T, Error>) -> Result<T, Error> {
+    catch_unwind(AssertUnwindSafe(index)).unwrap_or_else(|_| Err(Error::Panic(path.to_path_buf())))
+}
+
+/// The error handling of `try_index_file`, for any way of indexing the asset
+/// at `path`.
+fn report_index_errors(
+    path: &Path,
+    options: &IndexOptions,
+    index: impl FnOnce() -> Result<AssetSummary, Error>,
+) -> Result<AssetSummary, manifest::IndexFailure> {
+    catch_panics(path, index).map_err(|err| {
+        eprintln!("{}", err);
+        let failure = manifest::IndexFailure::new(path, err);
+        write_ndjson_failures(std::slice::from_ref(&failure), options);
+        failure
+    })
+}
+
+/// Indexes every asset under the directory `path` into the options' output
+/// directory, or into `path` itself, spreading the files over the current
+/// rayon thread pool. Assets that fail and subdirectories that can't be read
+/// are listed in the report; only problems with the directory itself are
+/// returned.
+pub fn index_dir(path: &Path, options: &IndexOptions) -> Result<IndexReport, Error> {
+    let out_dir = options.output_dir.as_deref().unwrap_or(path);
+    let dir_error = |err: IOError| Error::Dir(path.to_path_buf(), err);
+    manifest::remove_stale_temp_files(out_dir);
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    let mut walk = DirWalk::new(path, options);
+    collect_dir(path, out_dir, &mut walk, &mut files, &mut dirs).map_err(dir_error)?;
+    let summaries: Vec<Result<AssetSummary, manifest::IndexFailure>> = files
+        .par_iter()
+        .map(|(file, main_dir)| {
+            with_progress(file, options, || {
+                try_index_file(file, path, main_dir, out_dir, options)
+            })
+        })
+        .collect();
+    let mut report = write_tree_pages(
+        path,
+        out_dir,
+        &files,
+        &dirs,
+        summaries,
+        walk.skipped,
+        options,
+    )
+    .map_err(dir_error)?;
+    report.warnings.extend(walk.warnings);
+    report.unreadable_dirs = walk.unreadable_dirs;
+    Ok(report)
+}
+
+/// Finishes indexing the assets found under `root`: links the pages of
+/// `files` to each other, writes the pages of `dirs`, the package dependency
+/// graph, the error report and the shared stylesheet into `out_dir` and
+/// tallies the results. `skipped` lists what the walk left out.
+fn write_tree_pages(
+    root: &Path,
+    out_dir: &Path,
+    files: &[(PathBuf, PathBuf)],
+    dirs: &[CollectedDir],
+    mut summaries: Vec<Result<AssetSummary, manifest::IndexFailure>>,
+    skipped: Vec<manifest::IndexFailure>,
+    options: &IndexOptions,
+) -> Result<IndexReport, IOError> {
+    for ((file, main_dir), summary) in files.iter().zip(&mut summaries) {
+        match summary {
+            Ok(summary) => summary.package_path = crossref::package_path(file, root),
+            // Linked from `errors.html` when an earlier run left its pages,
+            // since a failed asset's own pages are never put in place.
+            Err(failure) if compress::page_exists(&main_dir.join("index.html")) => {
+                failure.output = Some(main_dir.clone())
+            }
+            Err(_) => {}
+        }
+    }
+    let collisions = {
+        let package_paths: Vec<(&Path, &str)> = files
+            .iter()
+            .zip(&summaries)
+            .filter_map(|((file, _), summary)| {
+                let summary = summary.as_ref().ok()?;
+                Some((file.as_path(), summary.package_path.as_str()))
+            })
+            .collect();
+        crossref::package_collisions(root, &package_paths, &options.prefer)
+    };
+    for ((file, _), summary) in files.iter().zip(&mut summaries) {
+        if let Ok(summary) = summary {
+            summary.shadowed = collisions
+                .iter()
+                .any(|collision| collision.resolved != *file && collision.files.contains(file));
+        }
+    }
+    // A single-file page has no import and export pages to add the links to.
+    if options.has_format(OutputFormat::Html) && !options.single_file {
+        let assets: Vec<&AssetSummary> = summaries.iter().flatten().collect();
+        crossref::link_imports(&assets)?;
+        crossref::link_soft_references(&assets)?;
+        crossref::link_string_table_entries(&assets)?;
+        crossref::link_neighbors(&assets)?;
+    }
+
+    let mut assets_by_dir: HashMap<&Path, Vec<&AssetSummary>> = HashMap::new();
+    for summary in summaries.iter().flatten() {
+        assets_by_dir
+            .entry(summary.main_dir.parent().unwrap())
+            .or_default()
+            .push(summary);
+    }
+    // `collect_dir` visits the root first, and the root has no parent page to link to.
+    let mut dir_pages = manifest::OutputFiles {
+        compression: options.compression,
+        ..Default::default()
+    };
+    for (i, dir) in dirs.iter().enumerate() {
+        let assets = assets_by_dir
+            .get(dir.out_dir.as_path())
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let style_prefix = relative_prefix(&dir.out_dir, out_dir);
+        write_dir_index(
+            dir,
+            assets,
+            i != 0,
+            &style_prefix,
+            options.single_file,
+            &mut dir_pages,
+        )?;
+    }
+    let assets: Vec<&AssetSummary> = summaries.iter().flatten().collect();
+    dependencies::write_dependencies(out_dir, &assets, &mut dir_pages)?;
+    let failures: Vec<&manifest::IndexFailure> = summaries
+        .iter()
+        .filter_map(|summary| summary.as_ref().err())
+        .chain(&skipped)
+        .collect();
+    errors::write_errors(root, out_dir, &failures, &collisions, &mut dir_pages)?;
+    if options.has_format(OutputFormat::Html) && !options.single_file {
+        for ((file, _), summary) in files.iter().zip(&summaries) {
+            if let Ok(map) = summary {
+                if level::is_level(file) {
+                    level::write_external_actors(map, &assets, out_dir, &mut dir_pages)?;
+                }
+            }
+        }
+    }
+    if options.has_format(OutputFormat::Csv) && options.csv_combined {
+        let tables: Vec<(&str, &csv::AssetTables)> = assets
+            .iter()
+            .map(|summary| (summary.package_path.as_str(), &summary.tables))
+            .collect();
+        csv::write_combined_tables(out_dir, &tables, &mut dir_pages)?;
+    }
+    if options.extract_strings.is_some() {
+        let asset_strings: Vec<(&Path, &[strings::StringEntry])> = files
+            .iter()
+            .zip(&summaries)
+            .filter_map(|((file, _), summary)| {
+                let summary = summary.as_ref().ok()?;
+                Some((file.as_path(), summary.strings.as_slice()))
+            })
+            .collect();
+        strings::write_combined_strings(out_dir, &asset_strings, &mut dir_pages)?;
+    }
+    write_stylesheet(out_dir, options, &mut dir_pages)?;
+    dir_pages.finish()?;
+
+    let mut report = IndexReport {
+        skipped,
+        collisions,
+        ..Default::default()
+    };
+    for ((file, _), summary) in files.iter().zip(summaries) {
+        report.add_result(file, summary);
+    }
+    report
+        .generated
+        .extend(std::mem::take(&mut dir_pages.files));
+    Ok(report)
+}
+
+/// What `index_asset` reports back about an asset it indexed. `--incremental`
+/// saves it alongside the output so up-to-date assets don't need parsing.
+#[derive(Default, Serialize, Deserialize)]
+struct AssetSummary {
+    /// The file stem, which is also the name of the asset's output directory.
+    name: String,
+    export_count: usize,
+    import_count: usize,
+    #[serde(skip)]
+    main_dir: PathBuf,
+    /// The engine package path, e.g. `/Game/Weapons/Rifle`. Only known once
+    /// `index_dir` relates the file to the root it walked.
+    #[serde(skip)]
+    package_path: String,
+    /// Whether another file of the run has the same package path and is the
+    /// one links resolve to.
+    #[serde(skip)]
+    shadowed: bool,
+    export_names: Vec<String>,
+    /// Whether each export has a page, which `--filter-class` can rule out.
+    export_pages: Vec<bool>,
+    imports: Vec<crossref::ImportTarget>,
+    /// The soft object and class paths found in each export's properties.
+    soft_references: Vec<Vec<String>>,
+    /// The text properties of every export.
+    #[serde(default)]
+    texts: Vec<texts::TextEntry>,
+    /// What `--extract-strings` found, kept for `strings.csv`.
+    #[serde(default)]
+    strings: Vec<strings::StringEntry>,
+    /// The actors or objects of an external package of a World Partition
+    /// map, for the map's `actors.html`.
+    #[serde(default)]
+    external: Vec<level::ExternalObject>,
+    /// The keys of each StringTable export, by position, so texts in other
+    /// assets can link to their entries.
+    #[serde(default)]
+    string_table_keys: BTreeMap<usize, BTreeSet<String>>,
+    /// The export and import tables, kept for `--csv-combined`.
+    #[serde(default)]
+    tables: csv::AssetTables,
+    /// The file the asset's thumbnail was saved as in its output directory.
+    #[serde(default)]
+    thumbnail: Option<String>,
+    /// Every file `index_asset` wrote for the asset.
+    #[serde(skip)]
+    generated: Vec<manifest::GeneratedFile>,
+    /// Set when `--incremental` found the output from a previous run still
+    /// matches the input, so nothing was regenerated.
+    #[serde(skip)]
+    up_to_date: bool,
+    /// Printed while writing the pages, and kept so `--incremental` runs
+    /// report them again.
+    #[serde(default)]
+    warnings: Vec<String>,
+    /// How long parsing the asset and writing its output took.
+    #[serde(skip)]
+    parse_time: Duration,
+    #[serde(skip)]
+    write_time: Duration,
+    /// The part of `write_time` spent on each kind of page.
+    #[serde(skip)]
+    phase_times: stats::PhaseTimes,
+    #[serde(skip)]
+    deduped: stats::DedupedPages,
+    /// The exports whose dump stopped at `--max-dump-size`.
+    #[serde(skip)]
+    truncated_dumps: Vec<i32>,
+}
+
+/// A directory visited by `collect_dir` and the subdirectories it descended into.
+struct CollectedDir {
+    out_dir: PathBuf,
+    subdirs: Vec<String>,
+}
+
+fn write_dir_index(
+    dir: &CollectedDir,
+    assets: &[&AssetSummary],
+    link_parent: bool,
+    style_prefix: &str,
+    single_file: bool,
+    outputs: &mut manifest::OutputFiles,
+) -> Result<(), IOError> {
+    try_create_dir(&dir.out_dir)?;
+    let mut contents = String::new();
+    contents += &stylesheet_link(style_prefix);
+    contents += &format!(
+        "<h1>
+        {}
+        {}/
+        </h1>
+        <ul>",
+        if link_parent {
+            "<a href=\"..\">.</a>/"
+        } else {
+            ""
+        },
+        escape_html(
+            &dir.out_dir
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+        )
+    );
+    // The root, where `write_tree_pages` puts the dependency graph and the
+    // error report.
+    if !link_parent {
+        contents += "<li><a href=\"dependencies.html\">Package dependencies</a></li>\
+            <li><a href=\"errors.html\">Errors</a></li>";
+    }
+    for subdir in &dir.subdirs {
+        contents += &format!(
+            "<li><a href=\"{name}\">{name}/</a></li>",
+            name = escape_html(subdir)
+        );
+    }
+    for asset in assets {
+        let dir_name = asset.main_dir.file_name().unwrap().to_string_lossy();
+        let href = if single_file {
+            format!("{0}/{0}.html", dir_name)
+        } else {
+            dir_name.to_string()
+        };
+        contents += &format!(
+            "<li>{thumbnail}<a href=\"{href}\">{name}</a> ({exports} exports, {imports} imports){renamed}</li>",
+            thumbnail = match &asset.thumbnail {
+                Some(thumbnail) => format!(
+                    "<img class=\"thumbnail\" src=\"{}/{}\" height=\"32\" alt=\"\"> ",
+                    escape_html(&dir_name),
+                    thumbnail
+                ),
+                None => String::new(),
+            },
+            href = escape_html(&href),
+            name = escape_html(&asset.name),
+            exports = asset.export_count,
+            imports = asset.import_count,
+            renamed = if dir_name != asset.name {
+                format!(" (output in {}/)", escape_html(&dir_name))
+            } else {
+                String::new()
+            }
+        );
+    }
+    contents += "</ul>";
+    outputs
+        .create(&dir.out_dir.join("index.html"))?
+        .write_all(contents.as_bytes())
+}
+
+#[test]
+fn test_write_dir_index() {
+    let dir = tempfile::tempdir().unwrap();
+    let collected = CollectedDir {
+        out_dir: dir.path().join("Content"),
+        subdirs: vec!["Maps".to_string()],
+    };
+    let asset = AssetSummary {
+        name: "Hero".to_string(),
+        export_count: 3,
+        import_count: 7,
+        main_dir: dir.path().join("Content").join("Hero"),
+        package_path: "/Game/Hero".to_string(),
+        shadowed: false,
+        export_names: Vec::new(),
+        export_pages: Vec::new(),
+        imports: Vec::new(),
+        soft_references: Vec::new(),
+        texts: Vec::new(),
+        strings: Vec::new(),
+        external: Vec::new(),
+        string_table_keys: BTreeMap::new(),
+        tables: csv::AssetTables::default(),
+        thumbnail: None,
+        generated: Vec::new(),
+        up_to_date: false,
+        warnings: Vec::new(),
+        parse_time: Duration::ZERO,
+        write_time: Duration::ZERO,
+        phase_times: stats::PhaseTimes::default(),
+        deduped: stats::DedupedPages::default(),
+        truncated_dumps: Vec::new(),
+    };
+    let mut outputs = manifest::OutputFiles::default();
+    write_dir_index(&collected, &[&asset], true, "../", false, &mut outputs).unwrap();
+    outputs.finish().unwrap();
+    let page = std::fs::read_to_string(dir.path().join("Content").join("index.html")).unwrap();
+    assert!(page.starts_with("<link rel=\"stylesheet\" href=\"../style.css\">"));
+    assert!(page.contains("<a href=\"..\">.</a>/"));
+    assert!(page.contains("<li><a href=\"Maps\">Maps/</a></li>"));
+    assert!(page.contains("<li><a href=\"Hero\">Hero</a> (3 exports, 7 imports)</li>"));
+
+    write_dir_index(&collected, &[&asset], true, "../", true, &mut outputs).unwrap();
+    outputs.finish().unwrap();
+    let page = std::fs::read_to_string(dir.path().join("Content").join("index.html")).unwrap();
+    assert!(page.contains("<li><a href=\"Hero/Hero.html\">Hero</a>"));
+
+    let asset = AssetSummary {
+        thumbnail: Some("thumbnail.png".to_string()),
+        ..asset
+    };
+    write_dir_index(&collected, &[&asset], true, "../", false, &mut outputs).unwrap();
+    outputs.finish().unwrap();
+    let page = std::fs::read_to_string(dir.path().join("Content").join("index.html")).unwrap();
+    assert!(page.contains(
+        "<li><img class=\"thumbnail\" src=\"Hero/thumbnail.png\" height=\"32\" alt=\"\"> \
+         <a href=\"Hero\">Hero</a>"
+    ));
+}
+
+/// What a directory run visits: which entries of each directory are assets
+/// or directories to descend into, shared by `collect_dir` and
+/// `count_assets` so both see the same tree.
+struct DirWalk<'a> {
+    /// The directory the run started from, which `--include` and `--exclude`
+    /// patterns are relative to.
+    root: PathBuf,
+    output_root: Option<&'a Path>,
+    filter: &'a filter::PathFilter,
+    /// Whether symlinked directories are descended into. Symlinked files
+    /// are always indexed.
+    follow_symlinks: bool,
+    /// How many levels below `root` the walk descends, for `--max-depth`.
+    max_depth: Option<usize>,
+    /// Extensions read as assets besides `SUPPORTED_EXTENSIONS`.
+    extensions: &'a [String],
+    /// Whether the directories walked are logged on stderr rather than
+    /// stdout, as `IndexOptions::logs_to_stderr`.
+    log_to_stderr: bool,
+    /// The canonical paths of the directories entered so far, so a symlink
+    /// back up the tree isn't followed around in circles.
+    visited_dirs: HashSet<PathBuf>,
+    /// The canonical paths of the assets found so far, so an asset reached
+    /// through several links is indexed once.
+    assets: HashSet<PathBuf>,
+    /// Entries that couldn't be read, such as ones without permission, as
+    /// messages for the report.
+    warnings: Vec<String>,
+    /// The same entries and the subdirectories that couldn't be listed, for
+    /// `errors.html`.
+    skipped: Vec<manifest::IndexFailure>,
+    /// Subdirectories that couldn't be listed at all, and so were skipped.
+    unreadable_dirs: Vec<PathBuf>,
+}
+
+/// The entries of one directory `DirWalk` visits, each sorted by name.
+struct DirEntries {
+    assets: Vec<PathBuf>,
+    subdirs: Vec<PathBuf>,
+    /// Subdirectories left out because the walk already entered them
+    /// through another path.
+    revisited: Vec<PathBuf>,
+    /// Subdirectories left out because they're deeper than `--max-depth`.
+    pruned: Vec<PathBuf>,
+    /// The lowercased names of every subdirectory of the user's, walked or
+    /// not, which assets' output directories mustn't take.
+    taken_names: HashSet<String>,
+}
+
+impl<'a> DirWalk<'a> {
+    /// A walk from `root` with the options' output directory, filter and
+    /// symlink handling.
+    fn new(root: &Path, options: &'a IndexOptions) -> Self {
+        DirWalk {
+            root: root.to_path_buf(),
+            output_root: options.output_dir.as_deref(),
+            filter: &options.path_filter,
+            follow_symlinks: options.follow_symlinks,
+            max_depth: options.max_depth,
+            extensions: &options.extensions,
+            log_to_stderr: options.logs_to_stderr(),
+            visited_dirs: HashSet::from([canonical_path(root)]),
+            assets: HashSet::new(),
+            warnings: Vec::new(),
+            skipped: Vec::new(),
+            unreadable_dirs: Vec::new(),
+        }
+    }
+
+    /// Notes a problem with one entry and carries on with the rest.
+    fn warn(&mut self, path: &Path, err: IOError) {
+        let message = format!("{}: {}", path.display(), err);
+        eprintln!("warning: {}", message);
+        self.warnings.push(message);
+        self.skipped
+            .push(manifest::IndexFailure::skipped(path, &err));
+    }
+
+    /// Lists `dir`, which fails only if the directory itself can't be read.
+    /// Entries that can't be are left out with a warning.
+    fn read_dir(&mut self, dir: &Path) -> Result<DirEntries, IOError> {
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            match entry {
+                Ok(entry) => entries.push(entry),
+                Err(err) => self.warn(dir, err),
+            }
+        }
+        // Sorted so the walk, and with it the log, the `unique_dir_name`
+        // suffixes and the order of subdirectories on the page, doesn't
+        // depend on the filesystem.
+        entries.sort_by_key(|entry| entry.file_name());
+        let mut found = DirEntries {
+            assets: Vec::new(),
+            subdirs: Vec::new(),
+            revisited: Vec::new(),
+            pruned: Vec::new(),
+            taken_names: HashSet::new(),
+        };
+        let depth = dir
+            .strip_prefix(&self.root)
+            .map_or(0, |relative| relative.components().count());
+        for entry in entries {
+            let path = entry.path();
+            let mut file_type = match entry.file_type() {
+                Ok(file_type) => file_type,
+                Err(err) => {
+                    self.warn(&path, err);
+                    continue;
+                }
+            };
+            if file_type.is_symlink() {
+                match std::fs::metadata(&path) {
+                    Ok(target) if target.is_dir() && !self.follow_symlinks => continue,
+                    Ok(target) => file_type = target.file_type(),
+                    // Dangling.
+                    Err(_) => continue,
+                }
+            }
+            let relative = path.strip_prefix(&self.root).unwrap_or(&path);
+            if file_type.is_file() {
+                if is_valid_extension(path.extension(), self.extensions)
+                    && self.filter.includes_file(relative)
+                    && self.assets.insert(canonical_path(&path))
+                {
+                    found.assets.push(path);
+                }
+            } else if file_type.is_dir() {
+                if !is_user_dir(&path, self.extensions) {
+                    continue;
+                }
+                found
+                    .taken_names
+                    .insert(entry.file_name().to_string_lossy().to_lowercase());
+                if !self.filter.includes_dir(relative)
+                    || self
+                        .output_root
+                        .is_some_and(|root| is_same_dir(&path, root))
+                {
+                    continue;
+                }
+                if self.max_depth.is_some_and(|max_depth| depth >= max_depth) {
+                    found.pruned.push(path);
+                } else if self.visited_dirs.insert(canonical_path(&path)) {
+                    found.subdirs.push(path);
+                } else {
+                    found.revisited.push(path);
+                }
+            }
+        }
+        Ok(found)
+    }
+}
+
+fn canonical_path(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Walks the directory `path`, collecting each asset along with the directory
+/// its output goes into. `out_dir` mirrors `path` inside the walk's output
+/// root when output is redirected. Assets are visited in file name order so
+/// the `unique_dir_name` suffixes are stable across runs. Only failing to
+/// list `path` itself is an error: subdirectories that can't be listed are
+/// skipped with a warning and noted in `walk`.
+fn collect_dir(
+    path: &Path,
+    out_dir: &Path,
+    walk: &mut DirWalk,
+    files: &mut Vec<(PathBuf, PathBuf)>,
+    dirs: &mut Vec<CollectedDir>,
+) -> Result<(), IOError> {
+    log_line(
+        walk.log_to_stderr,
+        format_args!("Indexing directory: {}", path.display()),
+    );
+    let entries = walk.read_dir(path)?;
+    let dir_index = dirs.len();
+    dirs.push(CollectedDir {
+        out_dir: out_dir.to_path_buf(),
+        subdirs: Vec::new(),
+    });
+    for revisited in &entries.revisited {
+        eprintln!(
+            "warning: skipping {}: already visited through another path",
+            revisited.display()
+        );
+    }
+    for pruned in &entries.pruned {
+        eprintln!(
+            "note: not descending into {}: it's below --max-depth",
+            pruned.display()
+        );
+    }
+    // Subdirectories keep their names in the output, so assets named like
+    // one go elsewhere rather than mixing their pages into it.
+    let mut used_dir_names = entries.taken_names.clone();
+    for path in entries.assets {
+        let dir_name = unique_dir_name(
+            asset_dir_name(&path, |name| {
+                entries.taken_names.contains(&name.to_lowercase())
+            }),
+            &mut used_dir_names,
+        );
+        files.push((path, out_dir.join(dir_name)));
+    }
+    for path in entries.subdirs {
+        let name = path.file_name().unwrap();
+        let sub_out_dir = out_dir.join(name);
+        match collect_dir(&path, &sub_out_dir, walk, files, dirs) {
+            Ok(()) => dirs[dir_index]
+                .subdirs
+                .push(name.to_string_lossy().to_string()),
+            Err(err) => {
+                eprintln!("warning: skipping {}: {}", path.display(), err);
+                walk.skipped
+                    .push(manifest::IndexFailure::skipped(&path, &err));
+                walk.unreadable_dirs.push(path);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_collect_dir_skips_symlink_cycles() {
+    use std::os::unix::fs::symlink;
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().join("Content");
+    try_create_dir(root.join("Maps")).unwrap();
+    File::create(root.join("Hero.uasset")).unwrap();
+    File::create(root.join("Maps").join("Hub.umap")).unwrap();
+    // A link back up the tree, and a second name for an asset.
+    symlink(&root, root.join("Maps").join("Loop")).unwrap();
+    symlink(
+        root.join("Hero.uasset"),
+        root.join("Maps").join("Hero.uasset"),
+    )
+    .unwrap();
+    let elsewhere = dir.path().join("Elsewhere");
+    try_create_dir(&elsewhere).unwrap();
+    File::create(elsewhere.join("Rifle.uasset")).unwrap();
+    symlink(&elsewhere, root.join("Weapons")).unwrap();
+
+    let collect = |follow_symlinks| {
+        let mut files = Vec::new();
+        let options = IndexOptions {
+            follow_symlinks,
+            ..Default::default()
+        };
+        let mut walk = DirWalk::new(&root, &options);
+        collect_dir(&root, &root, &mut walk, &mut files, &mut Vec::new()).unwrap();
+        files
+            .into_iter()
+            .map(|(path, _)| path.strip_prefix(&root).unwrap().to_path_buf())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        vec![PathBuf::from("Hero.uasset"), PathBuf::from("Maps/Hub.umap")],
+        collect(false)
+    );
+    assert_eq!(
+        vec![
+            PathBuf::from("Hero.uasset"),
+            PathBuf::from("Maps/Hub.umap"),
+            PathBuf::from("Weapons/Rifle.uasset")
+        ],
+        collect(true)
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_collect_dir_skips_unreadable_dirs() {
+    use std::os::unix::fs::PermissionsExt;
+    let dir = tempfile::tempdir().unwrap();
+    for sub in ["Locked", "Maps"] {
+        try_create_dir(dir.path().join(sub)).unwrap();
+    }
+    File::create(dir.path().join("Locked").join("Secret.uasset")).unwrap();
+    File::create(dir.path().join("Maps").join("Hub.umap")).unwrap();
+    let locked = dir.path().join("Locked");
+    std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o000)).unwrap();
+    let readable = std::fs::read_dir(&locked).is_ok();
+
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    let options = IndexOptions::default();
+    let mut walk = DirWalk::new(dir.path(), &options);
+    let result = collect_dir(dir.path(), dir.path(), &mut walk, &mut files, &mut dirs);
+    std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755)).unwrap();
+    result.unwrap();
+    // Permissions don't stop root.
+    if readable {
+        return;
+    }
+    let paths: Vec<&PathBuf> = files.iter().map(|(path, _)| path).collect();
+    assert_eq!(vec![&dir.path().join("Maps").join("Hub.umap")], paths);
+    assert_eq!(vec![locked], walk.unreadable_dirs);
+    assert_eq!(vec!["Maps".to_string()], dirs[0].subdirs);
+    assert_eq!(2, dirs.len());
+}
+
+#[test]
+fn test_collect_dir_applies_path_filter() {
+    let dir = tempfile::tempdir().unwrap();
+    for sub in ["Movies", "Maps", "Characters"] {
+        try_create_dir(dir.path().join(sub)).unwrap();
+    }
+    File::create(dir.path().join("Movies").join("Intro.uasset")).unwrap();
+    File::create(dir.path().join("Maps").join("Hub.umap")).unwrap();
+    File::create(dir.path().join("Characters").join("Hero.uasset")).unwrap();
+    let strings = |patterns: &[&str]| patterns.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+    let options = IndexOptions {
+        path_filter: filter::PathFilter::new(&strings(&["Maps/**"]), &strings(&["Movies/**"]))
+            .unwrap(),
+        ..Default::default()
+    };
+
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    let mut walk = DirWalk::new(dir.path(), &options);
+    collect_dir(dir.path(), dir.path(), &mut walk, &mut files, &mut dirs).unwrap();
+    let paths: Vec<&PathBuf> = files.iter().map(|(path, _)| path).collect();
+    assert_eq!(vec![&dir.path().join("Maps").join("Hub.umap")], paths);
+    // The excluded directory isn't entered at all, while the one whose
+    // files --include leaves out still is.
+    assert_eq!(vec!["Characters", "Maps"], dirs[0].subdirs);
+    assert_eq!(3, dirs.len());
+}
+
+#[test]
+fn test_collect_dir_stops_at_max_depth() {
+    let dir = tempfile::tempdir().unwrap();
+    let second = dir.path().join("Maps").join("Arena");
+    try_create_dir(second.join("Props")).unwrap();
+    File::create(dir.path().join("Hero.uasset")).unwrap();
+    File::create(dir.path().join("Maps").join("Hub.umap")).unwrap();
+    File::create(second.join("Arena.umap")).unwrap();
+    File::create(second.join("Props").join("Crate.uasset")).unwrap();
+    let indexed = |max_depth| {
+        let options = IndexOptions {
+            max_depth,
+            ..Default::default()
+        };
+        let mut files = Vec::new();
+        let mut dirs = Vec::new();
+        let mut walk = DirWalk::new(dir.path(), &options);
+        collect_dir(dir.path(), dir.path(), &mut walk, &mut files, &mut dirs).unwrap();
+        let names: Vec<String> = files
+            .iter()
+            .map(|(path, _)| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        (names, dirs.len())
+    };
+    assert_eq!((vec!["Hero.uasset".to_string()], 1), indexed(Some(0)));
+    assert_eq!(
+        (vec!["Hero.uasset".to_string(), "Hub.umap".to_string()], 2),
+        indexed(Some(1))
+    );
+    let (names, dirs) = indexed(Some(2));
+    assert_eq!((3, 3), (names.len(), dirs));
+    assert_eq!(4, indexed(None).0.len());
+}
+
+#[test]
+fn test_collect_dir_skips_non_assets() {
+    let dir = tempfile::tempdir().unwrap();
+    for name in [
+        "a.uasset",
+        "B.UASSET",
+        "a.uexp",
+        "LICENSE",
+        ".DS_Store",
+        "notes.txt",
+    ] {
+        File::create(dir.path().join(name)).unwrap();
+    }
+    try_create_dir(dir.path().join("sub")).unwrap();
+    File::create(dir.path().join("sub").join("c.umap")).unwrap();
+    File::create(dir.path().join("sub").join("README")).unwrap();
+
+    let mut files = Vec::new();
+    collect_dir(
+        dir.path(),
+        dir.path(),
+        &mut DirWalk::new(dir.path(), &IndexOptions::default()),
+        &mut files,
+        &mut Vec::new(),
+    )
+    .unwrap();
+    let mut names: Vec<String> = files
+        .iter()
+        .map(|(path, _)| path.file_name().unwrap().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    assert_eq!(vec!["B.UASSET", "a.uasset", "c.umap"], names);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_collect_dir_handles_non_utf8_names() {
+    use std::ffi::OsString;
+    use std::os::unix::ffi::OsStringExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let asset_name = OsString::from_vec(b"Caf\xe9.uasset".to_vec());
+    File::create(dir.path().join(&asset_name)).unwrap();
+    File::create(dir.path().join(OsString::from_vec(b"notes.\xff".to_vec()))).unwrap();
+    let subdir = dir.path().join(OsString::from_vec(b"Ma\xffps".to_vec()));
+    try_create_dir(&subdir).unwrap();
+    File::create(subdir.join("Arena.umap")).unwrap();
+
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    collect_dir(
+        dir.path(),
+        dir.path(),
+        &mut DirWalk::new(dir.path(), &IndexOptions::default()),
+        &mut files,
+        &mut dirs,
+    )
+    .unwrap();
+    let mut paths: Vec<&PathBuf> = files.iter().map(|(path, _)| path).collect();
+    paths.sort();
+    assert_eq!(
+        vec![&dir.path().join(&asset_name), &subdir.join("Arena.umap")],
+        paths
+    );
+    let main_dir = &files
+        .iter()
+        .find(|(path, _)| path.file_name() == Some(&asset_name))
+        .unwrap()
+        .1;
+    assert_eq!(
+        Some("Caf\u{FFFD}"),
+        main_dir.file_name().and_then(OsStr::to_str)
+    );
+    assert_eq!(2, dirs.len());
+}
+
+#[test]
+fn test_collect_dir_skips_generated_output_on_rerun() {
+    let dir = tempfile::tempdir().unwrap();
+    File::create(dir.path().join("Hero.uasset")).unwrap();
+    try_create_dir(dir.path().join("Weapons")).unwrap();
+    File::create(dir.path().join("Weapons").join("Sword.uasset")).unwrap();
+
+    let collect = || {
+        let mut files = Vec::new();
+        let mut dirs = Vec::new();
+        collect_dir(
+            dir.path(),
+            dir.path(),
+            &mut DirWalk::new(dir.path(), &IndexOptions::default()),
+            &mut files,
+            &mut dirs,
+        )
+        .unwrap();
+        (files, dirs)
+    };
+    let (first_files, first_dirs) = collect();
+    assert_eq!(2, first_files.len());
+    for (path, main_dir) in &first_files {
+        let file_name = path.file_name().unwrap().to_str().unwrap();
+        create_output_dir(main_dir, file_name).unwrap();
+    }
+    // Skipping only the directories named after the assets found would
+    // descend into the output of one since renamed.
+    std::fs::rename(
+        dir.path().join("Hero.uasset"),
+        dir.path().join("Knight.uasset"),
+    )
+    .unwrap();
+
+    let (second_files, second_dirs) = collect();
+    let second_paths: Vec<&PathBuf> = second_files.iter().map(|(path, _)| path).collect();
+    assert_eq!(
+        vec![
+            &dir.path().join("Knight.uasset"),
+            &dir.path().join("Weapons").join("Sword.uasset")
+        ],
+        second_paths
+    );
+    let out_dirs: Vec<&PathBuf> = second_dirs.iter().map(|dir| &dir.out_dir).collect();
+    assert_eq!(
+        first_dirs
+            .iter()
+            .map(|dir| &dir.out_dir)
+            .collect::<Vec<_>>(),
+        out_dirs
+    );
+    assert!(!out_dirs.contains(&&dir.path().join("Hero")));
+    assert!(!out_dirs.contains(&&dir.path().join("Weapons").join("Sword")));
+}
+
+#[test]
+fn test_collect_dir_deconflicts_sanitized_names() {
+    let dir = tempfile::tempdir().unwrap();
+    for name in [
+        "Foo.uasset",
+        "Foo.umap",
+        "a_b.uasset",
+        "a:b.uasset",
+        "CON.uasset",
+    ] {
+        File::create(dir.path().join(name)).unwrap();
+    }
+    let mut files = Vec::new();
+    collect_dir(
+        dir.path(),
+        dir.path(),
+        &mut DirWalk::new(dir.path(), &IndexOptions::default()),
+        &mut files,
+        &mut Vec::new(),
+    )
+    .unwrap();
+    let mut dir_names: Vec<String> = files
+        .iter()
+        .map(|(_, main_dir)| main_dir.file_name().unwrap().to_string_lossy().to_string())
+        .collect();
+    dir_names.sort();
+    assert_eq!(vec!["CON_", "Foo", "Foo_2", "a_b", "a_b_2"], dir_names);
+}
+
+/// Indexes the asset at `path`, found under `root`, into `main_dir`. Its
+/// pages link to the `style.css` in `style_root`, which is `main_dir` or one
+/// of its ancestors.
+fn index_asset(
+    path: &Path,
+    root: &Path,
+    main_dir: &Path,
+    style_root: &Path,
+    options: &IndexOptions,
+) -> Result<AssetSummary, Error> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    if !is_valid_extension(path.extension(), &options.extensions) {
+        return Err(Error::InvalidExtension(path.to_path_buf()));
+    }
+    if !path.exists() {
+        return Err(Error::NotFound(path.to_path_buf()));
+    }
+    let marker_path = main_dir.join(MARKER_FILE);
+    let record = incremental::SourceRecord::for_asset(path, options.fingerprint())
+        .map_err(|err| Error::Open(path.to_path_buf(), err))?;
+    if options.incremental {
+        if let Some(summary) = incremental::up_to_date_summary(&marker_path, &record) {
+            options.log(format_args!("Up to date: {}", file_name));
+            return Ok(AssetSummary {
+                main_dir: main_dir.to_path_buf(),
+                up_to_date: true,
+                ..summary
+            });
+        }
+    }
+
+    let source = AssetSource::open(path)?;
+    index_source(
+        path,
+        root,
+        source,
+        Some(record),
+        main_dir,
+        style_root,
+        options,
+    )
+}
+
+/// The `.uasset` of an asset and, when its export data is split off, the
+/// `.uexp`, along with their lengths. They are files on disk or entries read
+/// out of a `.pak`.
+struct AssetSource<R: Read + Seek> {
+    uasset: R,
+    uasset_len: u64,
+    uexp: Option<(R, u64)>,
+}
+
+impl AssetSource<File> {
+    /// Opens the asset file at `path` and the `.uexp` next to it, if any.
+    fn open(path: &Path) -> Result<Self, Error> {
+        let uexp_path = path.with_extension("uexp");
+        let uasset = File::open(path).map_err(|err| Error::Open(path.to_path_buf(), err))?;
+        let uasset_len = uasset
+            .metadata()
+            .map_err(|err| Error::Open(path.to_path_buf(), err))?
+            .len();
+        let uexp = match File::open(&uexp_path) {
+            Ok(file) => match file.metadata() {
+                Ok(metadata) => Some((file, metadata.len())),
+                Err(err) => return Err(Error::OpenUexp(uexp_path, err)),
+            },
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+            Err(err) => return Err(Error::OpenUexp(uexp_path, err)),
+        };
+        Ok(AssetSource {
+            uasset,
+            uasset_len,
+            uexp,
+        })
+    }
+}
+
+impl<R: Read + Seek> AssetSource<R> {
+    /// Reads the thumbnail of the asset's object `object_name` from the
+    /// `.uasset`'s thumbnail table, leaving the file at its start again.
+    /// Cooked and unversioned packages have none.
+    fn read_thumbnail(
+        &mut self,
+        object_name: &str,
+    ) -> Result<Option<thumbnail::Thumbnail>, IOError> {
+        self.uasset.rewind()?;
+        let mut reader = BufReader::new(&mut self.uasset);
+        let thumbnail = summary::read_package_header(&mut reader).and_then(|header| {
+            if header.is_filter_editor_only() || header.versions.is_unversioned() {
+                return Ok(None);
+            }
+            let offset = summary::read_thumbnail_table_offset(&mut reader, &header)?;
+            thumbnail::read_thumbnail(&mut reader, offset, object_name)
+        });
+        drop(reader);
+        self.uasset.rewind()?;
+        thumbnail
+    }
+
+    /// Reads where the summary fields and table entries of the `.uasset`
+    /// are for `--layout`, leaving the file at its start again.
+    fn read_layout(&mut self, engine_version: EngineVersion) -> Result<layout::Layout, IOError> {
+        self.uasset.rewind()?;
+        let layout = layout::read_layout(BufReader::new(&mut self.uasset), engine_version);
+        self.uasset.rewind()?;
+        layout
+    }
+
+    /// Reads all of the `.uasset` and `.uexp` into memory for `--raw-dumps`,
+    /// leaving both at their start again for parsing.
+    fn read_raw(&mut self, path: &Path) -> Result<hexdump::RawData, Error> {
+        fn read_all<R: Read + Seek>(file: &mut R) -> Result<Vec<u8>, IOError> {
+            let mut data = Vec::new();
+            file.rewind()?;
+            file.read_to_end(&mut data)?;
+            file.rewind()?;
+            Ok(data)
+        }
+        let uasset =
+            read_all(&mut self.uasset).map_err(|err| Error::Open(path.to_path_buf(), err))?;
+        let uexp = match &mut self.uexp {
+            Some((uexp, _)) => Some(
+                read_all(uexp).map_err(|err| Error::OpenUexp(path.with_extension("uexp"), err))?,
+            ),
+            None => None,
+        };
+        Ok(hexdump::RawData { uasset, uexp })
+    }
+}
+
+/// Reads the package summary of `source`, picks the engine version and
+/// parses the asset. `path` stands for the asset in messages; the `.uexp` is
+/// expected next to it.
+fn parse_source<R: Read + Seek>(
+    path: &Path,
+    mut source: AssetSource<R>,
+    engine_version: EngineVersion,
+    mappings: Option<&Mappings>,
+) -> Result<(Asset<R>, summary::ParseInfo), Error> {
+    let uexp_path = path.with_extension("uexp");
+    let header = summary::read_package_header(&mut BufReader::new(&mut source.uasset))
+        .map_err(|err| Error::Summary(path.to_path_buf(), err));
+    let mut start = Vec::new();
+    source
+        .uasset
+        .rewind()
+        .and_then(|_| (&mut source.uasset).take(4096).read_to_end(&mut start))
+        .and_then(|_| source.uasset.rewind())
+        .map_err(|err| Error::Open(path.to_path_buf(), err))?;
+    // Encrypted and foreign files fail on the tag; tell the user why.
+    if let Some(bad_tag) = summary::diagnose_bad_tag(&start) {
+        return Err(Error::BadTag(path.to_path_buf(), bad_tag));
+    }
+    let header = header?;
+    if header.is_store_compressed() {
+        return Err(Error::CompressedPackage(path.to_path_buf()));
+    }
+    if header.has_unversioned_properties() && mappings.is_none() {
+        return Err(Error::MissingMappings(path.to_path_buf()));
+    }
+    if source.uexp.is_none() && header.has_separate_export_data(source.uasset_len) {
+        return Err(Error::MissingUexp(path.to_path_buf(), uexp_path));
+    }
+    let detected_version = summary::detect_engine_version(&header.versions);
+    let parse_info = summary::ParseInfo {
+        header,
+        engine_version: detected_version.unwrap_or(engine_version),
+        detected: detected_version.is_some(),
+        mappings: mappings.map(|mappings| mappings.name.clone()),
+        uexp: source.uexp.as_ref().map(|_| {
+            uexp_path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string()
+        }),
+        uasset_size: source.uasset_len,
+        uexp_size: source.uexp.as_ref().map(|(_, len)| *len),
+    };
+    let asset = Asset::new(
+        source.uasset,
+        source.uexp.map(|(uexp, _)| uexp),
+        parse_info.engine_version,
+        mappings.map(|mappings| mappings.usmap.clone()),
+    )
+    .map_err(|err| Error::Parse(path.to_path_buf(), err))?;
+    Ok((asset, parse_info))
+}
+
+/// Opens and parses the asset at `path`, for the commands that read assets
+/// without indexing them.
+fn parse_file(
+    path: &Path,
+    options: &IndexOptions,
+) -> Result<(Asset<File>, summary::ParseInfo), Error> {
+    let source = AssetSource::open(path)?;
+    parse_source(
+        path,
+        source,
+        options.engine_version_for(path),
+        options.mappings_for(path),
+    )
+}
+
+/// Parses `source` and generates its output in `main_dir`, whichever way it
+/// was opened. `path` stands for the asset in messages and the manifest, and
+/// names its package with `root`, as `crossref::package_path` does.
+/// `record` is saved for `--incremental` when given.
+fn index_source<R: Read + Seek>(
+    path: &Path,
+    root: &Path,
+    mut source: AssetSource<R>,
+    record: Option<incremental::SourceRecord>,
+    main_dir: &Path,
+    style_root: &Path,
+    options: &IndexOptions,
+) -> Result<AssetSummary, Error> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let marker_path = main_dir.join(MARKER_FILE);
+    let parse_start = Instant::now();
+    let uasset_name = path.file_stem().unwrap().to_string_lossy();
+    let mut warnings = Vec::new();
+    let mut page_stats = PageStats::default();
+    let mut thumbnail = None;
+    if options.has_format(OutputFormat::Html) && !options.single_file {
+        // A thumbnail that can't be read is left out rather than failing
+        // the asset.
+        match source.read_thumbnail(&uasset_name) {
+            Ok(found) => thumbnail = found,
+            Err(err) => warnings.push(format!("{}: failed to read thumbnail: {}", file_name, err)),
+        }
+    }
+    let mut layout = None;
+    if options.layout {
+        // Like a thumbnail, a layout that can't be read is left out.
+        match source.read_layout(options.engine_version_for(path)) {
+            Ok(found) => layout = Some(found),
+            Err(err) => warnings.push(format!("{}: failed to read layout: {}", file_name, err)),
+        }
+    }
+    let raw_data = match options.raw_dumps {
+        Some(_) if options.has_format(OutputFormat::Html) && !options.single_file => {
+            Some(source.read_raw(path)?)
+        }
+        _ => None,
+    };
+    let (asset, parse_info) = parse_source(
+        path,
+        source,
+        options.engine_version_for(path),
+        options.mappings_for(path),
+    )?;
+    let parse_time = parse_start.elapsed();
+    if let Some(warning) = parse_info.version_warning() {
+        warnings.push(format!("{}: {}", file_name, warning));
+    }
+    let write_start = Instant::now();
+    if options.progress.is_none() {
+        options.log(format_args!(
+            "Indexing uasset file: {} ({:?}, {})",
+            file_name,
+            parse_info.engine_version,
+            parse_info.source()
+        ));
+    }
+
+    let main_dir = main_dir.to_path_buf();
+    // The files already in place are kept for the manifest.
+    let write_error = |outputs: &mut manifest::OutputFiles, err: IOError| {
+        Error::Write(path.to_path_buf(), err, outputs.take_written())
+    };
+    let mut outputs = manifest::OutputFiles::for_asset(path);
+    outputs.compression = options.compression;
+    outputs.record(&marker_path);
+    create_output_dir(&main_dir, &file_name).map_err(|err| write_error(&mut outputs, err))?;
+    if let Some(thumbnail) = &thumbnail {
+        outputs
+            .create(&main_dir.join(thumbnail.file_name()))
+            .and_then(|mut file| file.write_all(&thumbnail.data))
+            .map_err(|err| write_error(&mut outputs, err))?;
+    }
+
+    // Everything the pages need to know about the whole asset is collected
+    // before any page is written.
+    let names = IndexNames::from_asset(&asset);
+    let package = crossref::package_path(path, root);
+    let object_paths: Vec<Option<String>> = (1..=asset.asset_data.exports.len() as i32)
+        .map(|index| names.object_path(&package, index))
+        .collect();
+    let edges = graph::collect_edges(&asset);
+    let referrers = graph::referrers(&edges);
+    let references = graph::references(&edges);
+    let unreferenced_imports = graph::unreferenced_imports(
+        asset.imports.len(),
+        &referrers,
+        &kismet::script_references(&asset.asset_data.exports),
+    );
+    let export_pages: Vec<bool> = asset
+        .asset_data
+        .exports
+        .iter()
+        .map(|export| {
+            let class_index = export.get_base_export().class_index.index;
+            options.wants_export_page(export_class_name(&names, class_index))
+        })
+        .collect();
+    let soft_references: Vec<Vec<String>> = asset
+        .asset_data
+        .exports
+        .iter()
+        .map(|export| {
+            let mut paths = Vec::new();
+            if let Some(normal) = export.get_normal_export() {
+                for property in &normal.properties {
+                    properties::collect_soft_references(property, &mut paths);
+                }
+            }
+            paths
+        })
+        .collect();
+    if options.has_format(OutputFormat::Html) {
+        let context = HtmlContext {
+            names: &names,
+            export_pages: &export_pages,
+            object_paths: &object_paths,
+            referrers: &referrers,
+            references: &references,
+            unreferenced_imports: &unreferenced_imports,
+            soft_references: &soft_references,
+            uasset_name: &uasset_name,
+            main_dir: &main_dir,
+            file_name: &file_name,
+            parse_info: &parse_info,
+            style_prefix: &relative_prefix(&main_dir, style_root),
+            link_layout: layout.is_some(),
+            options,
+        };
+        let page_warnings = if options.single_file {
+            single_file::write_single_file(&asset, &context, &mut outputs, &mut page_stats)
+        } else {
+            write_html(
+                &asset,
+                &context,
+                raw_data.as_ref(),
+                thumbnail.as_ref(),
+                &mut outputs,
+                &mut page_stats,
+            )
+        };
+        warnings.extend(page_warnings.map_err(|err| write_error(&mut outputs, err))?);
+    }
+    for &export in &page_stats.truncated_dumps {
+        warnings.push(format!(
+            "{}: the dump of export {} was truncated at {} bytes",
+            file_name, export, options.max_dump_size
+        ));
+    }
+    for warning in &warnings {
+        eprintln!("warning: {}", warning);
+    }
+    if let Some(max_nodes) = options.graph_max_nodes {
+        let graph_path = outputs.stage(&main_dir.join("graph.dot"));
+        graph::write_graph(&names, &edges, &graph_path, max_nodes)
+            .map_err(|err| write_error(&mut outputs, err))?;
+    }
+    if options.has_format(OutputFormat::Text) {
+        text::write_text(&asset, &names, &main_dir, &mut outputs)
+            .map_err(|err| write_error(&mut outputs, err))?;
+    }
+    if let Some(layout) = &layout {
+        layout::write_layout(
+            layout,
+            &uasset_name,
+            &main_dir,
+            &stylesheet_link(&relative_prefix(&main_dir, style_root)),
+            &mut outputs,
+        )
+        .map_err(|err| write_error(&mut outputs, err))?;
+    }
+    if options.has_format(OutputFormat::Json)
+        || options.has_format(OutputFormat::Yaml)
+        || options.ndjson().is_some()
+    {
+        let asset_json = json::asset_json(
+            &asset,
+            &names,
+            &object_paths,
+            &unreferenced_imports,
+            &parse_info.header.custom_versions,
+        );
+        if options.has_format(OutputFormat::Json) {
+            let json_path = outputs.stage(&main_dir.join("asset.json"));
+            json::write_asset_json(&asset_json, &json_path)
+                .map_err(|err| write_error(&mut outputs, err))?;
+        }
+        if options.has_format(OutputFormat::Yaml) {
+            let yaml_path = outputs.stage(&main_dir.join("asset.yaml"));
+            json::write_asset_yaml(&asset_json, &yaml_path)
+                .map_err(|err| write_error(&mut outputs, err))?;
+        }
+        if let Some(ndjson) = options.ndjson() {
+            let engine_version = engine_version_name(parse_info.engine_version);
+            ndjson
+                .write_asset(path, &engine_version, &asset_json)
+                .map_err(|err| write_error(&mut outputs, err))?;
+        }
+    }
+    let mut extracted_strings = Vec::new();
+    if let Some(extract) = options.extract_strings {
+        extracted_strings = asset
+            .asset_data
+            .exports
+            .iter()
+            .enumerate()
+            .flat_map(|(i, export)| strings::export_strings(export, i as i32 + 1, extract))
+            .collect();
+        if extract.dedupe {
+            extracted_strings = strings::dedupe_strings(extracted_strings);
+        }
+        strings::write_strings(&extracted_strings, &main_dir, &mut outputs)
+            .map_err(|err| write_error(&mut outputs, err))?;
+    }
+    let external = if level::is_external_package(path) {
+        level::external_objects(&asset, &names)
+    } else {
+        Vec::new()
+    };
+    let mut tables = csv::AssetTables::default();
+    if options.has_format(OutputFormat::Csv) {
+        tables = csv::AssetTables::new(&asset, &names);
+        csv::write_tables(&tables, &main_dir, &mut outputs)
+            .map_err(|err| write_error(&mut outputs, err))?;
+        if !options.csv_combined {
+            tables = csv::AssetTables::default();
+        }
+    }
+    // Whatever was there before stays until every file is complete.
+    outputs
+        .finish()
+        .map_err(|err| write_error(&mut outputs, err))?;
+    let summary = AssetSummary {
+        name: uasset_name.to_string(),
+        export_count: asset.asset_data.exports.len(),
+        import_count: asset.imports.len(),
+        main_dir,
+        package_path: String::new(),
+        shadowed: false,
+        imports: (1..=asset.imports.len() as i32)
+            .map(|i| {
+                let chain = names.import_chain(-i);
+                crossref::ImportTarget {
+                    package: names.get(chain[chain.len() - 1]).unwrap().to_string(),
+                    object_name: names.get(-i).unwrap().to_string(),
+                }
+            })
+            .collect(),
+        export_names: names.exports,
+        export_pages,
+        soft_references,
+        texts: asset
+            .asset_data
+            .exports
+            .iter()
+            .enumerate()
+            .flat_map(|(i, export)| texts::export_texts(export, i as i32 + 1))
+            .collect(),
+        strings: extracted_strings,
+        external,
+        string_table_keys: asset
+            .asset_data
+            .exports
+            .iter()
+            .enumerate()
+            .filter_map(|(i, export)| match export {
+                Export::StringTableExport(string_table) => {
+                    let (_, entries) = string_table::table_entries(string_table);
+                    let keys = entries.iter().map(|(key, _)| key.to_string()).collect();
+                    Some((i, keys))
+                }
+                _ => None,
+            })
+            .collect(),
+        tables,
+        thumbnail: thumbnail.map(|thumbnail| thumbnail.file_name().to_string()),
+        generated: Vec::new(),
+        up_to_date: false,
+        warnings,
+        parse_time,
+        write_time: write_start.elapsed(),
+        phase_times: page_stats.phase_times,
+        deduped: page_stats.deduped,
+        truncated_dumps: page_stats.truncated_dumps,
+    };
+    // Written last so an interrupted run never looks up to date.
+    let mut summary = match record {
+        Some(record) => incremental::write_record(&marker_path, record, summary)
+            .map_err(|err| write_error(&mut outputs, err))?,
+        None => summary,
+    };
+    summary.generated = std::mem::take(&mut outputs.files);
+    Ok(summary)
+}
+
+/// The readable name of an export's class, through the imports for a
+/// negative class index and the exports for a positive one, or
+/// "UnknownClass" when the class index is null or doesn't resolve. The
+/// listings, the class groups and `--filter-class` all go by it.
+fn export_class_name(names: &IndexNames, class_index: i32) -> &str {
+    match class_index {
+        0 => "UnknownClass",
+        index => names.get(index).unwrap_or("UnknownClass"),
+    }
+}
+
+/// Whether `name` matches `pattern`, ignoring case. `*` in the pattern
+/// matches any run of characters.
+fn matches_wildcard(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let name = name.to_lowercase();
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = name.strip_prefix(parts.next().unwrap()) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[test]
+fn test_matches_wildcard() {
+    assert!(matches_wildcard("DataTable", "datatable"));
+    assert!(!matches_wildcard("DataTable", "DataTableRow"));
+    assert!(matches_wildcard("Sound*", "SoundCue"));
+    assert!(matches_wildcard("*Cue", "SoundCue"));
+    assert!(matches_wildcard("*mesh*", "StaticMeshActor"));
+    assert!(matches_wildcard("S*c*e", "SoundCue"));
+    assert!(!matches_wildcard("S*c*e", "SoundCues"));
+    assert!(matches_wildcard("*", "Anything"));
+    assert!(!matches_wildcard("a*a", "a"));
+}
+
+/// Groups exports by the name of their class, given each export's class index
+/// in package order. Classes with the most exports come first; within a class
+/// exports are sorted by object name. Returns positions into `classes`.
+fn group_exports_by_class(names: &IndexNames, classes: &[i32]) -> Vec<(String, Vec<usize>)> {
+    let mut groups: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, class_index) in classes.iter().enumerate() {
+        groups
+            .entry(export_class_name(names, *class_index))
+            .or_default()
+            .push(i);
+    }
+    let mut groups: Vec<(String, Vec<usize>)> = groups
+        .into_iter()
+        .map(|(class, mut exports)| {
+            exports.sort_by(|a, b| names.exports[*a].cmp(&names.exports[*b]).then(a.cmp(b)));
+            (class.to_string(), exports)
+        })
+        .collect();
+    groups.sort_by(|(a_class, a), (b_class, b)| b.len().cmp(&a.len()).then(a_class.cmp(b_class)));
+    groups
+}
+
+/// The enums the asset defines, given each export's class index in package
+/// order, so enum values can link to their definition.
+fn user_defined_enums(names: &IndexNames, classes: &[i32]) -> formatters::UserEnums {
+    classes
+        .iter()
+        .enumerate()
+        .filter(|(_, class_index)| export_class_name(names, **class_index) == "UserDefinedEnum")
+        .map(|(i, _)| (names.exports[i].clone(), i as i32 + 1))
+        .collect()
+}
+
+#[test]
+fn test_user_defined_enums() {
+    let names = IndexNames {
+        imports: vec!["UserDefinedEnum".to_string(), "Blueprint".to_string()],
+        exports: vec!["E_Weather".to_string(), "BP_Sky".to_string()],
+        import_outers: vec![0, 0],
+        export_outers: vec![0, 0],
+        export_classes: Vec::new(),
+    };
+    assert_eq!(
+        formatters::UserEnums::from([("E_Weather".to_string(), 1)]),
+        user_defined_enums(&names, &[-1, -2])
+    );
+}
+
+/// Positions of the exports by descending serial size, given each export's
+/// size in package order. Exports of the same size keep package order.
+fn exports_by_size(sizes: &[i64]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by(|a, b| sizes[*b].cmp(&sizes[*a]).then(a.cmp(b)));
+    order
+}
+
+#[test]
+fn test_exports_by_size() {
+    assert_eq!(vec![1, 0, 3, 2], exports_by_size(&[64, 4096, 0, 64]));
+    assert!(exports_by_size(&[]).is_empty());
+}
+
+/// How many exports the main page lists as the largest.
+const LARGEST_EXPORTS: usize = 10;
+
+/// The line at the top of an export's page or section with its object path.
+fn object_path_html(object_path: Option<&str>) -> String {
+    match object_path {
+        Some(path) => format!(
+            "<p class=\"object-path\"><code>{}</code></p>",
+            escape_html(path)
+        ),
+        None => String::new(),
+    }
+}
+
+/// How many templates an export's template chain follows before it stops.
+const MAX_TEMPLATE_DEPTH: usize = 16;
+
+/// The "Template chain" line of an export's page or section: the export
+/// `index`, its template, that template's template and so on, each put
+/// through `annotate`, given each export's template index. The chain ends at
+/// an export without a template, at an import, whose own templates are in
+/// the package it comes from, at a template already in the chain or after
+/// `MAX_TEMPLATE_DEPTH` hops. Empty when the export has no template.
+fn template_chain_html(templates: &[i32], index: i32, annotate: impl Fn(i32) -> String) -> String {
+    let mut chain = vec![index];
+    let mut end = String::new();
+    let mut current = index;
+    while current > 0 {
+        let template = templates
+            .get(current as usize - 1)
+            .copied()
+            .unwrap_or_default();
+        if template == 0 {
+            break;
+        }
+        if chain.contains(&template) {
+            end = format!(
+                " → <span style=\"color:red\">loops back to {}</span>",
+                annotate(template)
+            );
+            break;
+        }
+        if chain.len() > MAX_TEMPLATE_DEPTH {
+            end = format!(" → … (stopped after {} templates)", MAX_TEMPLATE_DEPTH);
+            break;
+        }
+        chain.push(template);
+        current = template;
+    }
+    if chain.len() == 1 && end.is_empty() {
+        return String::new();
+    }
+    if current < 0 {
+        end = ", imported from another package".to_string();
+    }
+    let parts: Vec<String> = chain.into_iter().map(annotate).collect();
+    format!(
+        "<p class=\"template-chain\">Template chain: {}{}</p>",
+        parts.join(" → "),
+        end
+    )
+}
+
+#[test]
+fn test_template_chain_html() {
+    let annotate = |i: i32| format!("[{}]", i);
+    assert_eq!(
+        "<p class=\"template-chain\">Template chain: [1] → [3] → [-2], imported from another package</p>",
+        template_chain_html(&[3, 0, -2], 1, annotate)
+    );
+    assert_eq!(
+        "<p class=\"template-chain\">Template chain: [1] → [2]</p>",
+        template_chain_html(&[2, 0], 1, annotate)
+    );
+    assert_eq!("", template_chain_html(&[0, 1], 1, annotate));
+    assert_eq!(
+        "<p class=\"template-chain\">Template chain: [1] → [2] → \
+        <span style=\"color:red\">loops back to [1]</span></p>",
+        template_chain_html(&[2, 1], 1, annotate)
+    );
+    let long: Vec<i32> = (2..=40).collect();
+    assert!(template_chain_html(&long, 1, annotate)
+        .ends_with(" → [17] → … (stopped after 16 templates)</p>"));
+}
+
+/// What the exports listing shows after a class default object, so they
+/// stand out among the exports of their class.
+fn class_default_object_badge(object_flags: u32) -> &'static str {
+    if object_flags & flags::RF_CLASS_DEFAULT_OBJECT != 0 {
+        " <strong>class default object</strong>"
+    } else {
+        ""
+    }
+}
+
+/// `12.3 KiB at 0x1a2b`, where an export's serialized data is and how much
+/// of it there is.
+fn serial_html(base: &BaseExport<PackageIndex>) -> String {
+    format!(
+        "{} at <code>{:#x}</code>",
+        formatters::format_size(base.serial_size),
+        base.serial_offset
+    )
+}
+
+#[test]
+fn test_group_exports_by_class() {
+    let names = IndexNames {
+        imports: vec!["StaticMeshActor".to_string(), "SceneComponent".to_string()],
+        exports: vec![
+            "Wall".to_string(),
+            "Root".to_string(),
+            "Floor".to_string(),
+            "Mystery".to_string(),
+            "Broken".to_string(),
+        ],
+        import_outers: vec![0, 0],
+        export_outers: vec![0, 1, 1, 0, 0],
+        export_classes: Vec::new(),
+    };
+    assert_eq!(
+        vec![
+            ("StaticMeshActor".to_string(), vec![2, 0]),
+            ("UnknownClass".to_string(), vec![4, 3]),
+            ("SceneComponent".to_string(), vec![1]),
+        ],
+        group_exports_by_class(&names, &[-1, -2, -1, 0, 9])
+    );
+}
+
+/// The definition list on an asset's main page with what the package file
+/// summary says about it, then its custom versions, named when they're the
+/// engine's own.
+fn package_summary_html(
+    parse_info: &summary::ParseInfo,
+    import_count: usize,
+    unreferenced_imports: usize,
+    export_count: usize,
+    package_guid: &[u8; 16],
+) -> String {
+    let header = &parse_info.header;
+    let sizes = match parse_info.uexp_size {
+        Some(uexp_size) => format!(
+            "{} bytes (.uasset), {} bytes (.uexp)",
+            parse_info.uasset_size, uexp_size
+        ),
+        None => format!("{} bytes", parse_info.uasset_size),
+    };
+    let custom_versions = if header.custom_versions.is_empty() {
+        "<p>None.</p>".to_string()
+    } else {
+        let rows: String = header
+            .custom_versions
+            .iter()
+            .map(|entry| {
+                format!(
+                    "<tr><td>{}</td><td><code>{}</code></td><td>{}</td></tr>",
+                    entry.friendly_name().unwrap_or_default(),
+                    summary::format_guid(&entry.guid),
+                    entry.version
+                )
+            })
+            .collect();
+        format!(
+            "<table><tr><th>Name</th><th>GUID</th><th>Version</th></tr>{}</table>",
+            rows
+        )
+    };
+    let version_warning = match parse_info.version_warning() {
+        Some(warning) => format!(
+            "<p class=\"version-warning\" style=\"color:red\"><strong>Warning: {}.</strong> \
+            Its properties may have been read wrong.</p>",
+            escape_html(&warning)
+        ),
+        None => String::new(),
+    };
+    format!(
+        "{}<dl>\
+        <dt>Package flags</dt><dd><code>{:#010x}</code> {}</dd>\
+        <dt>Engine version</dt><dd>{:?} ({})</dd>\
+        <dt>File version</dt><dd>UE4 {}, UE5 {}, licensee {}</dd>\
+        <dt>Counts</dt><dd>{} names, {} imports, {} exports</dd>\
+        <dt>Unreferenced imports</dt><dd>{}</dd>\
+        <dt>File size</dt><dd>{}</dd>\
+        <dt>Export data</dt><dd>{}</dd>\
+        <dt>Package GUID</dt><dd><code>{}</code></dd>\
+        {}\
+        </dl>\
+        <h2>Custom versions</h2>{}",
+        version_warning,
+        header.package_flags,
+        escape_html(&flags::decode_package_flags(header.package_flags)),
+        parse_info.engine_version,
+        parse_info.source(),
+        header.versions.file_version_ue4,
+        header.versions.file_version_ue5,
+        header.versions.file_version_licensee_ue4,
+        header.name_count,
+        import_count,
+        export_count,
+        match unreferenced_imports {
+            0 => "none".to_string(),
+            count => format!("{}, nothing in the package refers to them", count),
+        },
+        sizes,
+        match &parse_info.uexp {
+            Some(name) => format!("from {}", escape_html(name)),
+            None => "in the .uasset, no .uexp used".to_string(),
+        },
+        summary::format_guid(package_guid),
+        match &parse_info.mappings {
+            Some(name) => format!("<dt>Mappings</dt><dd>{}</dd>", escape_html(name)),
+            None => String::new(),
+        },
+        custom_versions
+    )
+}
+
+#[test]
+fn test_package_summary_html() {
+    let mut guid = [0; 16];
+    guid[0] = 0x01;
+    let parse_info = summary::ParseInfo {
+        header: summary::PackageHeader {
+            versions: summary::PackageVersions {
+                legacy_file_version: -8,
+                file_version_ue4: 522,
+                file_version_ue5: 1009,
+                file_version_licensee_ue4: 0,
+            },
+            custom_versions: vec![summary::CustomVersionEntry { guid, version: 3 }],
+            total_header_size: 1024,
+            folder_name: "None".to_string(),
+            package_flags: 0x8000_0200,
+            name_count: 17,
+        },
+        engine_version: EngineVersion::VER_UE5_1,
+        detected: true,
+        mappings: None,
+        uexp: Some("Hero.uexp".to_string()),
+        uasset_size: 1024,
+        uexp_size: Some(4096),
+    };
+    let html = package_summary_html(&parse_info, 2, 1, 1, &[0; 16]);
+    // UE5.1 saves UE5 object version 1008.
+    assert!(html.starts_with(
+        "<p class=\"version-warning\" style=\"color:red\"><strong>Warning: \
+        asset appears to be 5.2 but was parsed as 5.1.</strong>"
+    ));
+    assert!(html.contains(
+        "<dt>Package flags</dt><dd><code>0x80000200</code> PKG_Cooked | PKG_FilterEditorOnly</dd>"
+    ));
+    assert!(html.contains("<dt>Counts</dt><dd>17 names, 2 imports, 1 exports</dd>"));
+    assert!(html.contains(
+        "<dt>Unreferenced imports</dt><dd>1, nothing in the package refers to them</dd>"
+    ));
+    assert!(html.contains("<dd>1024 bytes (.uasset), 4096 bytes (.uexp)</dd>"));
+    assert!(html.contains("<dd><code>00000000000000000000000000000000</code></dd>"));
+    assert!(html.contains(
+        "<h2>Custom versions</h2><table><tr><th>Name</th><th>GUID</th><th>Version</th></tr>\
+        <tr><td></td><td><code>00000001000000000000000000000000</code></td><td>3</td></tr></table>"
+    ));
+    assert!(!html.contains("Mappings"));
+}
+
+/// The "Referenced by" section of an import or export page, listing the
+/// exports and imports with an edge to it and what kind of edge it is.
+fn referenced_by_html(
+    referrers: Option<&Vec<&graph::Edge>>,
+    annotate_index: impl Fn(i32) -> String,
+) -> String {
+    let Some(referrers) = referrers else {
+        return "<h2>Referenced by</h2><p>Nothing in this package references it.</p>".to_string();
+    };
+    let items: String = referrers
+        .iter()
+        .map(|edge| {
+            format!(
+                "<li>{} ({})</li>",
+                annotate_index(edge.from),
+                escape_html(&edge.label)
+            )
+        })
+        .collect();
+    format!("<h2>Referenced by</h2><ul>{}</ul>", items)
+}
+
+#[test]
+fn test_referenced_by_html() {
+    let names = test_index_names();
+    let edges = vec![
+        graph::Edge {
+            from: 1,
+            to: -2,
+            label: "class".to_string(),
+        },
+        graph::Edge {
+            from: -2,
+            to: -1,
+            label: "outer".to_string(),
+        },
+    ];
+    let referrers = graph::referrers(&edges);
+    assert_eq!(
+        "<h2>Referenced by</h2><ul><li><a href=\"../../exports/1\">1 (Default__Foo : Texture2D)</a> (class)</li></ul>",
+        referenced_by_html(referrers.get(&-2), |i| link_and_annotate_index(&names, i, LinkTarget::ENTRY_PAGES))
+    );
+    assert_eq!(
+        "<h2>Referenced by</h2><p>Nothing in this package references it.</p>",
+        referenced_by_html(referrers.get(&1), |i| link_and_annotate_index(
+            &names,
+            i,
+            LinkTarget::ENTRY_PAGES
+        ))
+    );
+}
+
+/// Where the pages next to an export's page go, and what they start with.
+struct PageContext<'a> {
+    /// The directory of the export's page.
+    dir: &'a Path,
+    uasset_name: &'a str,
+    style_link: &'a str,
+    /// The user-defined enums of the asset, for property values.
+    enums: &'a formatters::UserEnums,
+    outputs: &'a mut manifest::OutputFiles,
+}
+
+/// Writes `script.html`, the disassembled bytecode, next to a function
+/// export's page. `offsets` are those of its statements, for linking jumps.
+fn write_script_page(
+    page: &mut PageContext,
+    index: usize,
+    script: &kismet::Script,
+    offsets: Option<&[u32]>,
+    annotate_index: impl Fn(i32) -> String,
+) -> Result<(), IOError> {
+    let mut file = BufWriter::new(page.outputs.create(&page.dir.join("script.html"))?);
+    file.write_all(page.style_link.as_bytes())?;
+    write!(
+        file,
+        "<h1>
+        <a href=\"../../..\">.</a>/
+        <a href=\"../..\">{base}</a>/
+        <a href=\"..\">exports</a>/
+        <a href=\".\">{index}</a>/
+        script
+        </h1>",
+        base = escape_html(page.uasset_name),
+        index = index
+    )?;
+    kismet::write_script(&mut file, script, offsets, "", annotate_index)?;
+    file.flush()
+}
+
+/// Writes `raw.html`, a hex dump of an export's serialized bytes, next to
+/// its page. `in_file` is the file they're in. The last `unparsed` bytes
+/// are marked, and at most `max_bytes` are dumped.
+fn write_raw_page(
+    page: &mut PageContext,
+    index: usize,
+    bytes: &hexdump::ExportBytes,
+    in_file: &str,
+    unparsed: usize,
+    max_bytes: usize,
+) -> Result<(), IOError> {
+    let mut file = BufWriter::new(page.outputs.create(&page.dir.join("raw.html"))?);
+    file.write_all(page.style_link.as_bytes())?;
+    write!(
+        file,
+        "<h1>
+        <a href=\"../../..\">.</a>/
+        <a href=\"../..\">{base}</a>/
+        <a href=\"..\">exports</a>/
+        <a href=\".\">{index}</a>/
+        raw
+        </h1>
+        <p>{len} bytes at offset {offset:#x} of {in_file}.</p>",
+        base = escape_html(page.uasset_name),
+        index = index,
+        len = bytes.bytes.len(),
+        offset = bytes.offset,
+        in_file = escape_html(in_file)
+    )?;
+    let highlight_from = bytes.bytes.len().saturating_sub(unparsed);
+    if unparsed > 0 {
+        write!(
+            file,
+            "<p>The last {} bytes, marked, weren't parsed.</p>",
+            bytes.bytes.len() - highlight_from
+        )?;
+    }
+    let shown = &bytes.bytes[..bytes.bytes.len().min(max_bytes)];
+    if shown.len() < bytes.bytes.len() {
+        write!(
+            file,
+            "<p>Truncated: only the first {} bytes are shown.</p>",
+            shown.len()
+        )?;
+    }
+    hexdump::write_hex_dump(&mut file, shown, bytes.offset, highlight_from)?;
+    file.flush()
+}
+
+/// Writes `table.html` next to a DataTable export's page.
+fn write_table_page(
+    page: &mut PageContext,
+    index: usize,
+    data_table: &DataTableExport<PackageIndex>,
+    annotate_index: impl Fn(i32) -> String,
+) -> Result<(), IOError> {
+    let mut file = BufWriter::new(page.outputs.create(&page.dir.join("table.html"))?);
+    file.write_all(page.style_link.as_bytes())?;
+    write!(
+        file,
+        "<h1>
+        <a href=\"../../..\">.</a>/
+        <a href=\"../..\">{base}</a>/
+        <a href=\"..\">exports</a>/
+        <a href=\".\">{index}</a>/
+        table
+        </h1>",
+        base = escape_html(page.uasset_name),
+        index = index
+    )?;
+    datatable::write_table(&mut file, data_table, annotate_index, page.enums)?;
+    file.flush()
+}
+
+/// The positions of the exports whose outer is the package itself, usually
+/// the asset object and its class default object.
+fn top_level_exports(export_outers: &[i32]) -> Vec<usize> {
+    (0..export_outers.len())
+        .filter(|&i| export_outers[i] == 0)
+        .collect()
+}
+
+#[test]
+fn test_top_level_exports() {
+    assert_eq!(vec![0, 3], top_level_exports(&[0, 1, 1, 0, 2]));
+}
+
+/// Writes the "Top-level exports" section of the exports listing, unless
+/// there are none.
+fn write_top_level_exports<W: Write>(
+    sink: &mut W,
+    items: &[String],
+    top_level: &[usize],
+) -> Result<(), IOError> {
+    if top_level.is_empty() {
+        return Ok(());
+    }
+    write!(
+        sink,
+        "<h2 id=\"top-level\">Top-level exports ({})</h2><ul>",
+        top_level.len()
+    )?;
+    for &i in top_level {
+        sink.write_all(items[i].as_bytes())?;
+    }
+    sink.write_all(b"</ul>")?;
+    Ok(())
+}
+
+/// Writes the listing on `exports/index.html`: the top-level exports, the
+/// exports grouped by class, then all of them in package order and by size.
+/// `items` is each export's `<li>`, in package order.
+fn write_exports_listing<W: Write>(
+    sink: &mut W,
+    items: &[String],
+    top_level: &[usize],
+    groups: &[(String, Vec<usize>)],
+    by_size: &[usize],
+) -> Result<(), IOError> {
+    sink.write_all(
+        b"<p>Top-level exports first, sorted by class below, then \
+        <a href=\"#package-order\">in package order</a> and \
+        <a href=\"#by-size\">by size</a>.</p>",
+    )?;
+    write_top_level_exports(sink, items, top_level)?;
+    for (class, exports) in groups {
+        write!(
+            sink,
+            "<h2>{} ({})</h2><ul>",
+            escape_html(class),
+            exports.len()
+        )?;
+        for &i in exports {
+            sink.write_all(items[i].as_bytes())?;
+        }
+        sink.write_all(b"</ul>")?;
+    }
+    sink.write_all(b"<h2 id=\"package-order\">All exports in package order</h2><ul>")?;
+    for item in items {
+        sink.write_all(item.as_bytes())?;
+    }
+    sink.write_all(b"</ul><h2 id=\"by-size\">All exports by size</h2><ul>")?;
+    for &i in by_size {
+        sink.write_all(items[i].as_bytes())?;
+    }
+    sink.write_all(b"</ul>")
+}
+
+#[test]
+fn test_write_exports_listing() {
+    let items = ["<li>1</li>", "<li>2</li>", "<li>3</li>"].map(String::from);
+    let groups = [
+        ("Blueprint".to_string(), vec![1]),
+        ("Function".to_string(), vec![0, 2]),
+    ];
+    let mut listing = Vec::new();
+    write_exports_listing(&mut listing, &items, &[0], &groups, &[2, 0, 1]).unwrap();
+    assert_eq!(
+        "<p>Top-level exports first, sorted by class below, then \
+        <a href=\"#package-order\">in package order</a> and \
+        <a href=\"#by-size\">by size</a>.</p>\
+        <h2 id=\"top-level\">Top-level exports (1)</h2><ul><li>1</li></ul>\
+        <h2>Blueprint (1)</h2><ul><li>2</li></ul>\
+        <h2>Function (2)</h2><ul><li>1</li><li>3</li></ul>\
+        <h2 id=\"package-order\">All exports in package order</h2><ul>\
+        <li>1</li><li>2</li><li>3</li></ul>\
+        <h2 id=\"by-size\">All exports by size</h2><ul><li>3</li><li>1</li><li>2</li></ul>",
+        String::from_utf8(listing).unwrap()
+    );
+}
+
+/// What the imports listing shows after an import the asset never refers
+/// to, one of `unreferenced`.
+fn unreferenced_badge(unreferenced: &HashSet<i32>, index: i32) -> &'static str {
+    if unreferenced.contains(&index) {
+        " <strong>(unreferenced)</strong>"
+    } else {
+        ""
+    }
+}
+
+/// The file name of page `page`, counting from 0, of a paginated listing.
+fn listing_page_file(page: usize) -> String {
+    match page {
+        0 => "index.html".to_string(),
+        _ => format!("page{}.html", page + 1),
+    }
+}
+
+/// Where the other pages of a paginated listing link to page `page`.
+fn listing_page_href(page: usize) -> String {
+    match page {
+        0 => ".".to_string(),
+        _ => listing_page_file(page),
+    }
+}
+
+/// The links from page `page` of a paginated listing of `pages` pages to
+/// the ones before and after it.
+fn page_nav_html(page: usize, pages: usize) -> String {
+    let previous = match page {
+        0 => String::new(),
+        _ => format!(
+            "<a href=\"{}\">← previous</a> ",
+            listing_page_href(page - 1)
+        ),
+    };
+    let next = if page + 1 < pages {
+        format!(" <a href=\"{}\">next →</a>", listing_page_href(page + 1))
+    } else {
+        String::new()
+    };
+    format!(
+        "<p class=\"page-nav\">{}Page {} of {}{}</p>",
+        previous,
+        page + 1,
+        pages,
+        next
+    )
+}
+
+#[test]
+fn test_page_nav_html() {
+    assert_eq!(
+        "<p class=\"page-nav\">Page 1 of 3 <a href=\"page2.html\">next →</a></p>",
+        page_nav_html(0, 3)
+    );
+    assert_eq!(
+        "<p class=\"page-nav\"><a href=\".\">← previous</a> Page 2 of 3 \
+        <a href=\"page3.html\">next →</a></p>",
+        page_nav_html(1, 3)
+    );
+    assert_eq!(
+        "<p class=\"page-nav\"><a href=\"page2.html\">← previous</a> Page 3 of 3</p>",
+        page_nav_html(2, 3)
+    );
+}
+
+/// Splits the exports of `groups` into pages of `page_size` for a listing
+/// too long for one. Each page holds the groups it covers, by their
+/// position in `groups`, with the stretch of each group's exports on it, so
+/// a big class runs on over the pages after it. A `page_size` of 0 is taken
+/// as 1.
+fn paginate_exports(
+    groups: &[(String, Vec<usize>)],
+    page_size: usize,
+) -> Vec<Vec<(usize, Range<usize>)>> {
+    let page_size = page_size.max(1);
+    let mut pages = vec![Vec::new()];
+    let mut room = page_size;
+    for (group, (_, exports)) in groups.iter().enumerate() {
+        let mut start = 0;
+        while start < exports.len() {
+            if room == 0 {
+                pages.push(Vec::new());
+                room = page_size;
+            }
+            let end = exports.len().min(start + room);
+            if let Some(page) = pages.last_mut() {
+                page.push((group, start..end));
+            }
+            room -= end - start;
+            start = end;
+        }
+    }
+    pages
+}
+
+#[test]
+fn test_paginate_exports() {
+    let groups = [
+        ("Actor".to_string(), vec![0, 3, 4]),
+        ("Brush".to_string(), vec![1]),
+        ("Model".to_string(), vec![2, 5]),
+    ];
+    assert_eq!(
+        vec![vec![(0, 0..2)], vec![(0, 2..3), (1, 0..1)], vec![(2, 0..2)]],
+        paginate_exports(&groups, 2)
+    );
+    assert_eq!(
+        vec![vec![(0, 0..3), (1, 0..1), (2, 0..2)]],
+        paginate_exports(&groups, 6)
+    );
+    assert_eq!(6, paginate_exports(&groups, 0).len());
+}
+
+/// Writes page `page` of the exports listing when it's split into `pages`
+/// by `paginate_exports`: a jump table from every class, A to Z, to the
+/// page its exports start on, then this page's share of the exports
+/// grouped by class, between links to the pages before and after. The
+/// top-level exports head the first page. The listings in package order
+/// and by size are left out, the main page shows the largest exports and
+/// its search covers every export.
+fn write_exports_listing_page<W: Write>(
+    sink: &mut W,
+    items: &[String],
+    top_level: &[usize],
+    groups: &[(String, Vec<usize>)],
+    pages: &[Vec<(usize, Range<usize>)>],
+    page: usize,
+) -> Result<(), IOError> {
+    write!(
+        sink,
+        "<p>{} exports sorted by class over {} pages. \
+        The search on the asset's page covers all of them.</p>",
+        items.len(),
+        pages.len()
+    )?;
+    let mut starts: Vec<(&str, usize, usize)> = Vec::new();
+    for (i, groups_on_page) in pages.iter().enumerate() {
+        for (group, range) in groups_on_page {
+            if range.start == 0 {
+                starts.push((&groups[*group].0, *group, i));
+            }
+        }
+    }
+    starts.sort_by_key(|(class, _, _)| class.to_lowercase());
+    let jumps: Vec<String> = starts
+        .iter()
+        .map(|(class, group, i)| {
+            format!(
+                "<a href=\"{}#class-{}\">{}</a> ({})",
+                listing_page_href(*i),
+                group,
+                escape_html(class),
+                groups[*group].1.len()
+            )
+        })
+        .collect();
+    write!(sink, "<p class=\"jump\">{}</p>", jumps.join(" · "))?;
+    let nav = page_nav_html(page, pages.len());
+    sink.write_all(nav.as_bytes())?;
+    if page == 0 {
+        write_top_level_exports(sink, items, top_level)?;
+    }
+    for (group, range) in &pages[page] {
+        let (class, exports) = &groups[*group];
+        if range.start == 0 {
+            write!(
+                sink,
+                "<h2 id=\"class-{}\">{} ({})</h2><ul>",
+                group,
+                escape_html(class),
+                exports.len()
+            )?;
+        } else {
+            write!(
+                sink,
+                "<h2>{} ({}, continued)</h2><ul>",
+                escape_html(class),
+                exports.len()
+            )?;
+        }
+        for &i in &exports[range.clone()] {
+            sink.write_all(items[i].as_bytes())?;
+        }
+        sink.write_all(b"</ul>")?;
+    }
+    sink.write_all(nav.as_bytes())
+}
+
+#[test]
+fn test_write_exports_listing_page() {
+    let items = ["<li>1</li>", "<li>2</li>", "<li>3</li>"].map(String::from);
+    let groups = [
+        ("function".to_string(), vec![0, 2]),
+        ("Blueprint".to_string(), vec![1]),
+    ];
+    let pages = paginate_exports(&groups, 1);
+    let mut listing = Vec::new();
+    write_exports_listing_page(&mut listing, &items, &[0], &groups, &pages, 1).unwrap();
+    assert_eq!(
+        "<p>3 exports sorted by class over 3 pages. \
+        The search on the asset's page covers all of them.</p>\
+        <p class=\"jump\"><a href=\"page3.html#class-1\">Blueprint</a> (1) · \
+        <a href=\".#class-0\">function</a> (2)</p>\
+        <p class=\"page-nav\"><a href=\".\">← previous</a> Page 2 of 3 \
+        <a href=\"page3.html\">next →</a></p>\
+        <h2>function (2, continued)</h2><ul><li>3</li></ul>\
+        <p class=\"page-nav\"><a href=\".\">← previous</a> Page 2 of 3 \
+        <a href=\"page3.html\">next →</a></p>",
+        String::from_utf8(listing).unwrap()
+    );
+}
+
+/// The class of each import of `asset`, as its class package and class
+/// name, e.g. `/Script/Engine` and `Material`.
+fn import_classes<C: Read + Seek>(asset: &Asset<C>) -> Vec<(String, String)> {
+    asset
+        .imports
+        .iter()
+        .map(|import| {
+            (
+                import.class_package.get_owned_content(),
+                import.class_name.get_owned_content(),
+            )
+        })
+        .collect()
+}
+
+/// The top of an import's page or section: its outer chain, outermost
+/// first, each import by its class and name, e.g. `Package /Game/Core/Mats
+/// → Material Default__M_Base`, with the others linked through `links`,
+/// then its full class. A chain that doesn't end at a package starts with
+/// the outer it got stuck on.
+fn import_header_html(
+    names: &IndexNames,
+    classes: &[(String, String)],
+    index: i32,
+    links: LinkTarget,
+) -> String {
+    let chain = names.import_chain(index);
+    let mut parts = Vec::new();
+    if let Some(&last) = chain.last() {
+        let outer = names.import_outers[(-last - 1) as usize];
+        if chain.contains(&outer) {
+            parts.push(format!(
+                "<span style=\"color:red\">loops back to {}</span>",
+                outer
+            ));
+        } else if outer != 0 {
+            parts.push(format!(
+                "<span style=\"color:red\">{}</span>",
+                escape_html(&text::annotate_index(names, outer))
+            ));
+        }
+    }
+    for &import in chain.iter().rev() {
+        let class = classes
+            .get((-import - 1) as usize)
+            .map_or("", |(_, class)| class.as_str());
+        let label = escape_html(&format!(
+            "{} {}",
+            class,
+            names.get(import).unwrap_or_default()
+        ));
+        parts.push(if import == index {
+            label
+        } else {
+            format!("<a href=\"{}\">{}</a>", links.href(import), label)
+        });
+    }
+    let mut html = format!("<p class=\"import-chain\">{}</p>", parts.join(" → "));
+    if let Some((package, class)) = classes.get((-index - 1) as usize) {
+        html += &format!(
+            "<p class=\"import-class\">Class <code>{}.{}</code></p>",
+            escape_html(package),
+            escape_html(class)
+        );
+    }
+    html
+}
+
+#[test]
+fn test_import_header_html() {
+    let names = test_index_names();
+    let classes = vec![
+        ("/Script/CoreUObject".to_string(), "Package".to_string()),
+        ("/Script/Engine".to_string(), "Texture2D".to_string()),
+    ];
+    assert_eq!(
+        "<p class=\"import-chain\"><a href=\"../../imports/1\">Package CoreUObject</a> → \
+        Texture2D Texture2D</p>\
+        <p class=\"import-class\">Class <code>/Script/Engine.Texture2D</code></p>",
+        import_header_html(&names, &classes, -2, LinkTarget::ENTRY_PAGES)
+    );
+    let looping = IndexNames {
+        import_outers: vec![-2, -1],
+        ..test_index_names()
+    };
+    assert!(
+        import_header_html(&looping, &classes, -2, LinkTarget::Sections).starts_with(
+            "<p class=\"import-chain\"><span style=\"color:red\">loops back to -2</span> → \
+        <a href=\"#import-1\">Package CoreUObject</a> → Texture2D Texture2D</p>"
+        )
+    );
+}
+
+/// The imports in the order `imports/index.html` lists them, each with how
+/// deep it's nested: every import under its outer, so each package heads a
+/// list of what the asset imports from it. Imports whose outer chain loops
+/// or leaves the table are listed at the top level.
+fn imports_listing_order(names: &IndexNames) -> Vec<(i32, usize)> {
+    let mut children: HashMap<i32, Vec<i32>> = HashMap::new();
+    let mut roots = Vec::new();
+    for i in 1..=names.imports.len() as i32 {
+        let outer = names.import_outers[i as usize - 1];
+        if outer < 0 && outer != -i && names.get(outer).is_some() {
+            children.entry(outer).or_default().push(-i);
+        } else {
+            roots.push(-i);
+        }
+    }
+
+    fn visit(
+        children: &HashMap<i32, Vec<i32>>,
+        listed: &mut HashSet<i32>,
+        order: &mut Vec<(i32, usize)>,
+        index: i32,
+        depth: usize,
+    ) {
+        listed.insert(index);
+        order.push((index, depth));
+        for &inner in children.get(&index).into_iter().flatten() {
+            if !listed.contains(&inner) {
+                visit(children, listed, order, inner, depth + 1);
+            }
+        }
+    }
+
+    let mut listed = HashSet::new();
+    let mut order = Vec::with_capacity(names.imports.len());
+    for &root in &roots {
+        visit(&children, &mut listed, &mut order, root, 0);
+    }
+    // Imports in a loop of outers aren't under any root.
+    for i in 1..=names.imports.len() as i32 {
+        if !listed.contains(&-i) {
+            visit(&children, &mut listed, &mut order, -i, 0);
+        }
+    }
+    order
+}
+
+/// Writes `entries`, a stretch of `imports_listing_order` starting at the
+/// top level, as nested lists, each import with its class and the
+/// `unreferenced` ones marked.
+fn write_imports_entries<W: Write>(
+    sink: &mut W,
+    names: &IndexNames,
+    classes: &[(String, String)],
+    unreferenced: &HashSet<i32>,
+    entries: &[(i32, usize)],
+) -> Result<(), IOError> {
+    sink.write_all(b"<ul>")?;
+    // The depth of the last item, whose `<li>` is still open.
+    let mut open: Option<usize> = None;
+    for &(index, depth) in entries {
+        match open {
+            Some(previous) if depth > previous => sink.write_all(b"<ul>")?,
+            Some(previous) => {
+                sink.write_all(b"</li>")?;
+                for _ in depth..previous {
+                    sink.write_all(b"</ul></li>")?;
+                }
+            }
+            None => {}
+        }
+        let (package, class) = classes
+            .get((-index - 1) as usize)
+            .map_or(("", ""), |(package, class)| {
+                (package.as_str(), class.as_str())
+            });
+        write!(
+            sink,
+            "<li><a href=\"{i}\">{i} ({name})</a> <code>{class}</code>{badge}",
+            i = -index,
+            name = escape_html(names.get(index).unwrap_or_default()),
+            class = escape_html(&format!("{}.{}", package, class)),
+            badge = unreferenced_badge(unreferenced, index)
+        )?;
+        open = Some(depth);
+    }
+    if let Some(previous) = open {
+        sink.write_all(b"</li>")?;
+        for _ in 0..previous {
+            sink.write_all(b"</ul></li>")?;
+        }
+    }
+    sink.write_all(b"</ul>")
+}
+
+/// Writes the listing on `imports/index.html`, every import in
+/// `imports_listing_order`.
+fn write_imports_listing<W: Write>(
+    sink: &mut W,
+    names: &IndexNames,
+    classes: &[(String, String)],
+    unreferenced: &HashSet<i32>,
+) -> Result<(), IOError> {
+    let entries = imports_listing_order(names);
+    write_imports_entries(sink, names, classes, unreferenced, &entries)
+}
+
+#[test]
+fn test_write_imports_listing() {
+    let mut names = test_index_names();
+    names.imports[1] = "<T>".to_string();
+    let classes = vec![
+        ("/Script/CoreUObject".to_string(), "Package".to_string()),
+        ("/Script/Engine".to_string(), "Texture2D".to_string()),
+    ];
+    let mut listing = Vec::new();
+    write_imports_listing(&mut listing, &names, &classes, &HashSet::new()).unwrap();
+    assert_eq!(
+        "<ul><li><a href=\"1\">1 (CoreUObject)</a> <code>/Script/CoreUObject.Package</code>\
+        <ul><li><a href=\"2\">2 (&lt;T&gt;)</a> <code>/Script/Engine.Texture2D</code></li></ul>\
+        </li></ul>",
+        String::from_utf8(listing).unwrap()
+    );
+    let mut listing = Vec::new();
+    write_imports_listing(&mut listing, &names, &classes, &HashSet::from([-2])).unwrap();
+    assert!(String::from_utf8(listing)
+        .unwrap()
+        .contains("<code>/Script/Engine.Texture2D</code> <strong>(unreferenced)</strong></li>"));
+
+    // A loop is listed from where it's first found.
+    names.import_outers = vec![-2, -1];
+    let mut listing = Vec::new();
+    write_imports_listing(&mut listing, &names, &classes, &HashSet::new()).unwrap();
+    assert_eq!(
+        "<ul><li><a href=\"1\">1 (CoreUObject)</a> <code>/Script/CoreUObject.Package</code>\
+        <ul><li><a href=\"2\">2 (&lt;T&gt;)</a> <code>/Script/Engine.Texture2D</code></li></ul>\
+        </li></ul>",
+        String::from_utf8(listing).unwrap()
+    );
+
+    let mut listing = Vec::new();
+    let empty = IndexNames {
+        imports: Vec::new(),
+        exports: Vec::new(),
+        import_outers: Vec::new(),
+        export_outers: Vec::new(),
+        export_classes: Vec::new(),
+    };
+    write_imports_listing(&mut listing, &empty, &[], &HashSet::new()).unwrap();
+    assert_eq!("<ul></ul>", String::from_utf8(listing).unwrap());
+}
+
+/// Splits `entries`, the imports in `imports_listing_order`, into pages of
+/// about `page_size` for a listing too long for one. Pages only start at a
+/// top-level import, so one that holds more imports than `page_size` makes
+/// its page longer.
+fn paginate_imports(entries: &[(i32, usize)], page_size: usize) -> Vec<Range<usize>> {
+    let mut pages = Vec::new();
+    let mut start = 0;
+    for (i, &(_, depth)) in entries.iter().enumerate() {
+        if depth == 0 && i - start >= page_size {
+            pages.push(start..i);
+            start = i;
+        }
+    }
+    pages.push(start..entries.len());
+    pages
+}
+
+#[test]
+fn test_paginate_imports() {
+    let entries = [(-1, 0), (-2, 1), (-3, 1), (-4, 0), (-5, 0), (-6, 1)];
+    assert_eq!(vec![0..3, 3..4, 4..6], paginate_imports(&entries, 1));
+    assert_eq!(vec![0..4, 4..6], paginate_imports(&entries, 4));
+    assert_eq!(vec![0..0], paginate_imports(&[], 4));
+}
+
+/// Writes page `page` of the imports listing when it's split into `pages`
+/// by `paginate_imports`: a jump table from each page to the top-level
+/// imports it starts and ends with, then this page's imports, between links
+/// to the pages before and after.
+fn write_imports_listing_page<W: Write>(
+    sink: &mut W,
+    names: &IndexNames,
+    classes: &[(String, String)],
+    unreferenced: &HashSet<i32>,
+    entries: &[(i32, usize)],
+    pages: &[Range<usize>],
+    page: usize,
+) -> Result<(), IOError> {
+    write!(
+        sink,
+        "<p>{} imports over {} pages.</p>",
+        entries.len(),
+        pages.len()
+    )?;
+    let name = |i: usize| escape_html(names.get(entries[i].0).unwrap_or_default());
+    let jumps: Vec<String> = pages
+        .iter()
+        .enumerate()
+        .filter(|(_, range)| !range.is_empty())
+        .map(|(i, range)| {
+            let last = entries[..range.end]
+                .iter()
+                .rposition(|&(_, depth)| depth == 0)
+                .unwrap_or(range.start);
+            format!(
+                "<a href=\"{}\">{} – {}</a>",
+                listing_page_href(i),
+                name(range.start),
+                name(last)
+            )
+        })
+        .collect();
+    write!(sink, "<p class=\"jump\">{}</p>", jumps.join(" · "))?;
+    let nav = page_nav_html(page, pages.len());
+    sink.write_all(nav.as_bytes())?;
+    write_imports_entries(
+        sink,
+        names,
+        classes,
+        unreferenced,
+        &entries[pages[page].clone()],
+    )?;
+    sink.write_all(nav.as_bytes())
+}
+
+#[test]
+fn test_write_imports_listing_page() {
+    let names = test_index_names();
+    let classes = vec![
+        ("/Script/CoreUObject".to_string(), "Package".to_string()),
+        ("/Script/Engine".to_string(), "Texture2D".to_string()),
+    ];
+    let entries = imports_listing_order(&names);
+    let pages = paginate_imports(&entries, 1);
+    assert_eq!(vec![0..2], pages);
+    let mut listing = Vec::new();
+    write_imports_listing_page(
+        &mut listing,
+        &names,
+        &classes,
+        &HashSet::new(),
+        &entries,
+        &pages,
+        0,
+    )
+    .unwrap();
+    assert_eq!(
+        "<p>2 imports over 1 pages.</p>\
+        <p class=\"jump\"><a href=\".\">CoreUObject – CoreUObject</a></p>\
+        <p class=\"page-nav\">Page 1 of 1</p>\
+        <ul><li><a href=\"1\">1 (CoreUObject)</a> <code>/Script/CoreUObject.Package</code>\
+        <ul><li><a href=\"2\">2 (Texture2D)</a> <code>/Script/Engine.Texture2D</code></li></ul>\
+        </li></ul>\
+        <p class=\"page-nav\">Page 1 of 1</p>",
+        String::from_utf8(listing).unwrap()
+    );
+}
+
+/// What the pages of an asset are written from: everything `index_source`
+/// collects about the asset before any page is written, and the run's
+/// options.
+struct HtmlContext<'a> {
+    names: &'a IndexNames,
+    export_pages: &'a [bool],
+    object_paths: &'a [Option<String>],
+    referrers: &'a HashMap<i32, Vec<&'a graph::Edge>>,
+    references: &'a HashMap<i32, Vec<&'a graph::Edge>>,
+    unreferenced_imports: &'a [i32],
+    soft_references: &'a [Vec<String>],
+    uasset_name: &'a str,
+    main_dir: &'a Path,
+    file_name: &'a str,
+    parse_info: &'a summary::ParseInfo,
+    /// Where the stylesheet is, relative to `main_dir`.
+    style_prefix: &'a str,
+    /// Whether `layout.html` is written, for the main page to link.
+    link_layout: bool,
+    options: &'a IndexOptions,
+}
+
+/// What writing the pages of an asset counted, for the summary of the run.
+#[derive(Default)]
+struct PageStats {
+    phase_times: stats::PhaseTimes,
+    deduped: stats::DedupedPages,
+    /// The exports whose dump stopped at `--max-dump-size`.
+    truncated_dumps: Vec<i32>,
+}
+
+/// Writes the browsable pages: the asset's main index, the import and export
+/// listings, one page per import and export, and for maps `level.html`.
+/// `raw` is the asset's bytes with `--raw-dumps`. Returns a warning for each
+/// package index the pages refer to that points nowhere.
+fn write_html<C: Read + Seek>(
+    asset: &Asset<C>,
+    context: &HtmlContext,
+    raw: Option<&hexdump::RawData>,
+    thumbnail: Option<&thumbnail::Thumbnail>,
+    outputs: &mut manifest::OutputFiles,
+    stats: &mut PageStats,
+) -> Result<Vec<String>, IOError> {
+    let HtmlContext {
+        names,
+        export_pages,
+        object_paths,
+        referrers,
+        references,
+        unreferenced_imports,
+        soft_references,
+        uasset_name,
+        main_dir,
+        file_name,
+        parse_info,
+        style_prefix,
+        link_layout,
+        options,
+    } = *context;
+    let PageStats {
+        phase_times,
+        deduped,
+        truncated_dumps,
+    } = stats;
+    let mut phase_start = Instant::now();
+    // The main page sits in `main_dir`, index pages one level down and
+    // export and import pages two.
+    let main_style_link = stylesheet_link(style_prefix);
+    let index_style_link = stylesheet_link(&format!("../{}", style_prefix));
+    let nested_style_link = stylesheet_link(&format!("../../{}", style_prefix));
+    let exports_dir = main_dir.join("exports");
+    let imports_dir = main_dir.join("imports");
+    try_create_dir(&exports_dir)?;
+    try_create_dir(&imports_dir)?;
+
+    let search_entries = search::search_entries(asset, names, export_pages, object_paths);
+    search::write_search_json(&search_entries, main_dir, outputs)?;
+
+    let serial_sizes: Vec<i64> = asset
+        .asset_data
+        .exports
+        .iter()
+        .map(|export| export.get_base_export().serial_size)
+        .collect();
+    let by_size = exports_by_size(&serial_sizes);
+    let mut largest_exports_html = String::new();
+    if !by_size.is_empty() {
+        largest_exports_html += "<h2>Largest exports</h2><ol>";
+        for &i in by_size.iter().take(LARGEST_EXPORTS) {
+            let name = escape_html(&names.exports[i]);
+            let size = formatters::format_size(serial_sizes[i]);
+            largest_exports_html += &if export_pages[i] {
+                format!(
+                    "<li><a href=\"exports/{i}\">{i} ({name})</a> {size}</li>",
+                    i = i + 1
+                )
+            } else {
+                format!("<li>{i} ({name}) {size}</li>", i = i + 1)
+            };
+        }
+        largest_exports_html += "</ol>";
+    }
+
+    let is_level = level::is_level(Path::new(file_name));
+    if is_level {
+        let mut level_page = BufWriter::new(outputs.create(&main_dir.join("level.html"))?);
+        level_page.write_all(main_style_link.as_bytes())?;
+        write!(
+            level_page,
+            "<h1>
+            <a href=\"..\">.</a>/
+            <a href=\".\">{}</a>/
+            level
+            </h1>",
+            escape_html(uasset_name)
+        )?;
+        level::write_level(&mut level_page, asset, names, export_pages)?;
+        level_page.flush()?;
+    }
+
+    let mut main_index = outputs.create(&main_dir.join("index.html"))?;
+    main_index.write_all(main_style_link.as_bytes())?;
+    main_index.write_all(
+        format!(
+            "<h1>
+        <a href=\"..\">.</a>/
+        {}/
+        </h1>
+        {}
+        <ul>
+        <li><a href=\"imports\">imports</a></li>
+        <li><a href=\"exports\">exports</a></li>
+        {}
+        {}
+        {}
+        {}
+        {}
+        </ul>
+        {}
+        {}
+        {}",
+            escape_html(uasset_name),
+            thumbnail
+                .map(|thumbnail| format!(
+                    "<img class=\"thumbnail\" src=\"{}\" width=\"{}\" height=\"{}\" alt=\"Thumbnail\">",
+                    thumbnail.file_name(),
+                    thumbnail.width,
+                    thumbnail.height
+                ))
+                .unwrap_or_default(),
+            if options.has_format(OutputFormat::Json) {
+                "<li><a href=\"asset.json\">asset.json</a></li>"
+            } else {
+                ""
+            },
+            if options.has_format(OutputFormat::Yaml) {
+                "<li><a href=\"asset.yaml\">asset.yaml</a></li>"
+            } else {
+                ""
+            },
+            if options.graph_max_nodes.is_some() {
+                "<li><a href=\"graph.dot\">graph.dot</a></li>"
+            } else {
+                ""
+            },
+            if link_layout {
+                "<li><a href=\"layout.html\">layout</a></li>"
+            } else {
+                ""
+            },
+            if is_level {
+                "<li><a href=\"level.html\">level</a></li>"
+            } else {
+                ""
+            },
+            search::search_box_html(&search_entries),
+            package_summary_html(
+                parse_info,
+                asset.imports.len(),
+                unreferenced_imports.len(),
+                asset.asset_data.exports.len(),
+                &asset.package_guid.0
+            ),
+            largest_exports_html
+        )
+        .as_bytes(),
+    )?;
+
+    let out_of_range = RefCell::new(BTreeSet::new());
+    let annotate_index =
+        index_annotator(names, export_pages, LinkTarget::ENTRY_PAGES, &out_of_range);
+    let index_href = index_href(export_pages, LinkTarget::ENTRY_PAGES);
+
+    let export_items: Vec<String> = asset
+        .asset_data
+        .exports
+        .iter()
+        .enumerate()
+        .map(|(i, export)| {
+            let base = export.get_base_export();
+            let name = escape_html(&format!(
+                "{} : {}",
+                display_name(&base.object_name),
+                export_class_name(names, base.class_index.index)
+            ));
+            let flags = flags::decode_object_flags(base.object_flags.bits());
+            let cdo = class_default_object_badge(base.object_flags.bits());
+            let serial = serial_html(base);
+            let title = match &object_paths[i] {
+                Some(path) => format!(" title=\"{}\"", escape_html(path)),
+                None => String::new(),
+            };
+            if export_pages[i] {
+                format!(
+                    "<li{title}><a href=\"{i}\">{i} ({name})</a>{cdo} <code>{flags}</code> {serial}</li>",
+                    i = i + 1
+                )
+            } else {
+                format!(
+                    "<li{title}>{i} ({name}){cdo} <code>{flags}</code> {serial}</li>",
+                    i = i + 1
+                )
+            }
+        })
+        .collect();
+    let classes: Vec<i32> = asset
+        .asset_data
+        .exports
+        .iter()
+        .map(|export| export.get_base_export().class_index.index)
+        .collect();
+    let user_enums = user_defined_enums(names, &classes);
+    let templates: Vec<i32> = asset
+        .asset_data
+        .exports
+        .iter()
+        .map(|export| export.get_base_export().template_index.index)
+        .collect();
+    let listing_heading = |kind: &str| {
+        format!(
+            "{}<h1>
+                <a href=\"../..\">.</a>/
+                <a href=\"..\">{}</a>/
+                {}
+                </h1>",
+            index_style_link,
+            escape_html(uasset_name),
+            kind
+        )
+    };
+    let groups = group_exports_by_class(names, &classes);
+    let top_level = top_level_exports(&names.export_outers);
+    if export_items.len() > options.listing_page_size {
+        let pages = paginate_exports(&groups, options.listing_page_size);
+        for page in 0..pages.len() {
+            let path = exports_dir.join(listing_page_file(page));
+            let mut exports_index = BufWriter::new(outputs.create(&path)?);
+            exports_index.write_all(listing_heading("exports").as_bytes())?;
+            write_exports_listing_page(
+                &mut exports_index,
+                &export_items,
+                &top_level,
+                &groups,
+                &pages,
+                page,
+            )?;
+            exports_index.flush()?;
+        }
+    } else {
+        let mut exports_index = BufWriter::new(outputs.create(&exports_dir.join("index.html"))?);
+        exports_index.write_all(listing_heading("exports").as_bytes())?;
+        write_exports_listing(
+            &mut exports_index,
+            &export_items,
+            &top_level,
+            &groups,
+            &by_size,
+        )?;
+        exports_index.flush()?;
+    }
+    phase_times.listing_pages += phase_start.elapsed();
+    phase_start = Instant::now();
+    for (i, export) in asset.asset_data.exports.iter().enumerate() {
+        if !export_pages[i] {
+            continue;
+        }
+        let dir = exports_dir.join((i + 1).to_string());
+        try_create_dir(&dir)?;
+        let mut file = BufWriter::new(outputs.create(&dir.join("index.html"))?);
+        file.write_all(nested_style_link.as_bytes())?;
+        file.write_all(
+            format!(
+                "<h1>
+                    <a href=\"../../..\">.</a>/
+                    <a href=\"../..\">{base}</a>/
+                    <a href=\"..\">exports</a>/
+                    {i} ({label})
+                    </h1>",
+                base = escape_html(uasset_name),
+                i = i + 1,
+                label = escape_html(&names.label(i as i32 + 1).unwrap_or_default())
+            )
+            .as_bytes(),
+        )?;
+        file.write_all(object_path_html(object_paths[i].as_deref()).as_bytes())?;
+        file.write_all(template_chain_html(&templates, i as i32 + 1, annotate_index).as_bytes())?;
+        file.write_all(
+            neighborhood::neighborhood_html(
+                names,
+                i as i32 + 1,
+                referrers.get(&(i as i32 + 1)),
+                references.get(&(i as i32 + 1)),
+                index_href,
+                annotate_index,
+            )
+            .as_bytes(),
+        )?;
+        file.write_all(
+            referenced_by_html(referrers.get(&(i as i32 + 1)), annotate_index).as_bytes(),
+        )?;
+        if !soft_references[i].is_empty() {
+            let items: String = soft_references[i]
+                .iter()
+                .map(|path| crossref::soft_reference_item(path))
+                .collect();
+            write!(file, "<h2>Soft references</h2><ul>{}</ul>", items)?;
+        }
+        if let Some(normal) = export.get_normal_export() {
+            if !normal.properties.is_empty() {
+                let long_strings =
+                    formatters::long_strings(&normal.properties, options.max_string_bytes);
+                if !long_strings.is_empty() {
+                    let strings_dir = main_dir.join("strings");
+                    try_create_dir(&strings_dir)?;
+                    for (path, value) in &long_strings {
+                        let file_name = formatters::long_string_file(i as i32 + 1, path);
+                        outputs
+                            .create(&strings_dir.join(file_name))?
+                            .write_all(value.as_bytes())?;
+                    }
+                }
+                let limits = formatters::PropertyLimits {
+                    max_elements: options.max_array_elements,
+                    max_string_bytes: options.max_string_bytes,
+                    export: i as i32 + 1,
+                    strings_href: (!long_strings.is_empty()).then_some("../../strings"),
+                };
+                file.write_all(
+                    formatters::properties_html(
+                        &normal.properties,
+                        annotate_index,
+                        &user_enums,
+                        &limits,
+                    )
+                    .as_bytes(),
+                )?;
+            }
+        }
+        let mut page = PageContext {
+            dir: &dir,
+            uasset_name,
+            style_link: &nested_style_link,
+            enums: &user_enums,
+            outputs: &mut *outputs,
+        };
+        if let Some(script) = kismet::function_script(export) {
+            file.write_all(b"<p><a href=\"script.html\">Script</a></p>")?;
+            let offsets = kismet::statement_offsets(asset, &script);
+            write_script_page(
+                &mut page,
+                i + 1,
+                &script,
+                offsets.as_deref(),
+                annotate_index,
+            )?;
+        }
+        if let Export::DataTableExport(data_table) = export {
+            file.write_all(b"<p><a href=\"table.html\">Table view</a></p>")?;
+            write_table_page(&mut page, i + 1, data_table, annotate_index)?;
+        }
+        if let Export::StringTableExport(string_table) = export {
+            let (namespace, entries) = string_table::table_entries(string_table);
+            file.write_all(string_table::string_table_html(namespace, &entries).as_bytes())?;
+        }
+        if let Some((raw, dumps)) = raw.zip(options.raw_dumps) {
+            let base = export.get_base_export();
+            if base.serial_size > dumps.max_bytes as i64 && !dumps.full {
+                write!(
+                    file,
+                    "<p>Raw bytes not dumped: the export is {} bytes, more than --raw-max-bytes \
+                     allows.</p>",
+                    base.serial_size
+                )?;
+            } else if let Some(bytes) = raw.export_bytes(base.serial_offset, base.serial_size) {
+                file.write_all(b"<p><a href=\"raw.html\">Raw bytes</a></p>")?;
+                let in_file = if bytes.in_uexp {
+                    parse_info.uexp.as_deref().unwrap_or_default()
+                } else {
+                    file_name
+                };
+                write_raw_page(
+                    &mut page,
+                    i + 1,
+                    &bytes,
+                    in_file,
+                    hexdump::unparsed_tail_len(export),
+                    dumps.max_bytes,
+                )?;
+            } else {
+                file.write_all(
+                    b"<p>Raw bytes not dumped: the export's serial offset and size point \
+                      past the end of the file.</p>",
+                )?;
+            }
+        }
+        file.write_all(b"<span style=\"white-space-collapse:preserve;font-family:monospace\">")?;
+        let package_root = package_root_html(LinkTarget::ENTRY_PAGES);
+        let truncated = write_linked_dump(
+            &mut file,
+            export,
+            annotate_index,
+            options.collapse_depth,
+            Some(&package_root),
+            Some(options.max_dump_size),
+        )?;
+        file.write_all(b"</span>")?;
+        if truncated {
+            file.write_all(truncated_dump_html(options.max_dump_size).as_bytes())?;
+            truncated_dumps.push(i as i32 + 1);
+        }
+        file.flush()?;
+    }
+    phase_times.export_pages += phase_start.elapsed();
+    phase_start = Instant::now();
+    let classes = import_classes(asset);
+    let unreferenced: HashSet<i32> = unreferenced_imports.iter().copied().collect();
+    if asset.imports.len() > options.listing_page_size {
+        let entries = imports_listing_order(names);
+        let pages = paginate_imports(&entries, options.listing_page_size);
+        for page in 0..pages.len() {
+            let path = imports_dir.join(listing_page_file(page));
+            let mut imports_index = BufWriter::new(outputs.create(&path)?);
+            imports_index.write_all(listing_heading("imports").as_bytes())?;
+            write_imports_listing_page(
+                &mut imports_index,
+                names,
+                &classes,
+                &unreferenced,
+                &entries,
+                &pages,
+                page,
+            )?;
+            imports_index.flush()?;
+        }
+    } else {
+        let mut imports_index = BufWriter::new(outputs.create(&imports_dir.join("index.html"))?);
+        imports_index.write_all(listing_heading("imports").as_bytes())?;
+        write_imports_listing(&mut imports_index, names, &classes, &unreferenced)?;
+        imports_index.flush()?;
+    }
+    phase_times.listing_pages += phase_start.elapsed();
+    phase_start = Instant::now();
+    // The first import each page body was written for, by a hash of the
+    // body, with `--dedupe-pages`.
+    let mut canonical_imports: HashMap<u64, usize> = HashMap::new();
+    for (i, import) in asset.imports.iter().enumerate() {
+        let dir = imports_dir.join((i + 1).to_string());
+        try_create_dir(&dir)?;
+        let mut body = Vec::new();
+        body.write_all(
+            import_header_html(names, &classes, -(i as i32 + 1), LinkTarget::ENTRY_PAGES)
+                .as_bytes(),
+        )?;
+        body.write_all(
+            referenced_by_html(referrers.get(&-(i as i32 + 1)), annotate_index).as_bytes(),
+        )?;
+        body.write_all(b"<span style=\"white-space-collapse:preserve;font-family:monospace\">")?;
+        write_linked_dump(
+            &mut body,
+            import,
+            annotate_index,
+            options.collapse_depth,
+            None,
+            None,
+        )?;
+        body.write_all(b"</span>")?;
+        let mut file = BufWriter::new(outputs.create(&dir.join("index.html"))?);
+        file.write_all(nested_style_link.as_bytes())?;
+        file.write_all(
+            format!(
+                "<h1>
+                    <a href=\"../../..\">.</a>/
+                    <a href=\"../..\">{base}</a>/
+                    <a href=\"..\">imports</a>/
+                    {i}
+                    </h1>",
+                base = escape_html(uasset_name),
+                i = i + 1
+            )
+            .as_bytes(),
+        )?;
+        let body_hash = {
+            let mut hasher = DefaultHasher::new();
+            body.hash(&mut hasher);
+            hasher.finish()
+        };
+        match canonical_imports.get(&body_hash) {
+            Some(&first) if options.dedupe_pages => {
+                let redirect = redirect_html(first + 1);
+                file.write_all(redirect.as_bytes())?;
+                deduped.pages += 1;
+                deduped.bytes_saved += body.len().saturating_sub(redirect.len()) as u64;
+            }
+            _ => {
+                file.write_all(&body)?;
+                if options.dedupe_pages {
+                    canonical_imports.entry(body_hash).or_insert(i);
+                }
+            }
+        }
+        file.flush()?;
+    }
+    phase_times.import_pages += phase_start.elapsed();
+    Ok(out_of_range_warnings(file_name, out_of_range))
+}
+
+/// What `--dedupe-pages` writes below the heading of an import page whose
+/// body is the same as that of import `position`, counting from 1: a
+/// redirect to that import's page, and a link to it for browsers that don't
+/// follow the redirect.
+fn redirect_html(position: usize) -> String {
+    format!(
+        "<meta http-equiv=\"refresh\" content=\"0; url=../{position}/\">\
+        <p>Same as <a href=\"../{position}/\">import -{position}</a>.</p>",
+        position = position
+    )
+}
+
+#[test]
+fn test_redirect_html() {
+    assert_eq!(
+        "<meta http-equiv=\"refresh\" content=\"0; url=../3/\">\
+        <p>Same as <a href=\"../3/\">import -3</a>.</p>",
+        redirect_html(3)
+    );
+}
+
+/// How the pages of an asset show a package index: a link through `links`,
+/// plain text for exports without a page, and a red note for indices that
+/// point nowhere, which are also collected into `out_of_range`. They're
+/// collected rather than printed, since every page mentioning the index
+/// would warn again.
+fn index_annotator<'a>(
+    names: &'a IndexNames,
+    export_pages: &'a [bool],
+    links: LinkTarget,
+    out_of_range: &'a RefCell<BTreeSet<i32>>,
+) -> impl Fn(i32) -> String + Copy + 'a {
+    move |index: i32| {
+        if index != 0 && names.get(index).is_none() {
+            out_of_range.borrow_mut().insert(index);
+        }
+        if index > 0
+            && !export_pages
+                .get(index as usize - 1)
+                .copied()
+                .unwrap_or(true)
+        {
+            return match names.label(index) {
+                Some(label) => escape_html(&format!("{} ({})", index, label)),
+                None => escape_html(&text::annotate_index(names, index)),
+            };
+        }
+        link_and_annotate_index(names, index, links)
+    }
+}
+
+/// Where a link to `index` goes, for imports and for exports that have a
+/// page or section.
+fn index_href(
+    export_pages: &[bool],
+    links: LinkTarget,
+) -> impl Fn(i32) -> Option<String> + Copy + '_ {
+    move |index: i32| {
+        let has_page = index < 0
+            || export_pages
+                .get((index as usize).wrapping_sub(1))
+                .copied()
+                .unwrap_or(false);
+        has_page.then(|| links.href(index))
+    }
+}
+
+fn out_of_range_warnings(file_name: &str, out_of_range: RefCell<BTreeSet<i32>>) -> Vec<String> {
+    out_of_range
+        .into_inner()
+        .into_iter()
+        .map(|index| format!("{}: package index {} is out of range", file_name, index))
+        .collect()
+}
@@ -0,0 +1,342 @@
+use serde::Serialize;
+use std::io::{BufReader, Read, Result, Seek, SeekFrom, Write};
+use std::path::Path;
+use unreal_asset::engine_version::EngineVersion;
+
+use crate::{
+    csv,
+    import_table::{self, check_count, ImportEntry},
+    layout::{self, VER_UE4_64BIT_EXPORTMAP_SERIALSIZES, VER_UE4_TEMPLATE_INDEX_IN_COOKED_EXPORTS},
+    summary::{self, read_i32, PackageVersions},
+};
+
+/// `uasset-index list --format`: how each line is written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ListFormat {
+    /// Fields separated by tabs, without a header, for grep, sort and awk.
+    Tsv,
+    /// A header row, then a row per entry.
+    Csv,
+    /// A JSON object per line.
+    Json,
+}
+
+/// Which table `uasset-index list` prints.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ListTable {
+    Exports,
+    Imports,
+    Names,
+}
+
+/// An export as `read_tables` reads it from the export table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExportEntry {
+    pub class_index: i32,
+    pub object_name: String,
+    pub serial_size: i64,
+}
+
+/// The name map, import table and export table of a package.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Tables {
+    pub names: Vec<String>,
+    pub imports: Vec<ImportEntry>,
+    pub exports: Vec<ExportEntry>,
+}
+
+fn read_i64<R: Read>(reader: &mut R) -> Result<i64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(i64::from_le_bytes(bytes))
+}
+
+/// Reads one entry of the export table, as far as its serial size.
+fn read_export<R: Read>(
+    reader: &mut R,
+    names: &[String],
+    versions: &PackageVersions,
+) -> Result<ExportEntry> {
+    let class_index = read_i32(reader)?;
+    // The super and template indexes and the outer index.
+    let indexes = if versions.file_version_ue4 >= VER_UE4_TEMPLATE_INDEX_IN_COOKED_EXPORTS {
+        3
+    } else {
+        2
+    };
+    for _ in 0..indexes {
+        read_i32(reader)?;
+    }
+    let object_name = import_table::read_name(reader, names)?;
+    let _object_flags = read_i32(reader)?;
+    let serial_size = if versions.file_version_ue4 >= VER_UE4_64BIT_EXPORTMAP_SERIALSIZES {
+        read_i64(reader)?
+    } else {
+        read_i32(reader)?.into()
+    };
+    Ok(ExportEntry {
+        class_index,
+        object_name,
+        serial_size,
+    })
+}
+
+/// The tables of a package, from the summary alone, like
+/// `import_table::read_import_table`: no export is deserialized, so even
+/// large assets are listed at once. Unversioned packages are read as
+/// `engine_version` saves them.
+pub fn read_tables<R: Read + Seek>(
+    reader: &mut R,
+    engine_version: EngineVersion,
+) -> Result<Tables> {
+    let header = summary::read_package_header(reader)?;
+    let versions = summary::table_versions(&header.versions, engine_version)?;
+    let offsets = summary::read_table_offsets(reader, &header, &versions)?;
+    let names =
+        import_table::read_names(reader, header.name_count, offsets.name_offset, &versions)?;
+    let imports = import_table::read_imports(reader, &names, &header, &versions, &offsets)?;
+    check_count("export", offsets.export_count)?;
+    let entry_size = layout::export_entry_size(&versions);
+    let mut exports = Vec::with_capacity(offsets.export_count as usize);
+    for i in 0..offsets.export_count {
+        reader.seek(SeekFrom::Start(
+            offsets.export_offset as u64 + i as u64 * entry_size,
+        ))?;
+        exports.push(read_export(reader, &names, &versions)?);
+    }
+    Ok(Tables {
+        names,
+        imports,
+        exports,
+    })
+}
+
+/// `read_tables` of the asset at `path`.
+pub fn read_tables_of(path: &Path, engine_version: EngineVersion) -> Result<Tables> {
+    let mut reader = BufReader::new(std::fs::File::open(path)?);
+    read_tables(&mut reader, engine_version)
+}
+
+#[test]
+fn test_read_tables() {
+    use crate::test_support::{self, TestPackage};
+
+    // The class index, the super, template and outer indexes, the object
+    // name, the flags and the serial size.
+    let mut export = (-2_i32).to_le_bytes().to_vec();
+    export.extend_from_slice(&[0; 3 * 4]);
+    export.extend(test_support::fname_bytes(3, 0));
+    export.extend_from_slice(&0u32.to_le_bytes());
+    export.extend_from_slice(&1234_i64.to_le_bytes());
+    let package = TestPackage {
+        names: &["/Script/CoreUObject", "Package", "/Game/Hero", "Hero_C"],
+        imports: &[(0, 2, 0), (-1, 1, 3)],
+        exports: &[export],
+        ..Default::default()
+    }
+    .bytes();
+
+    let tables = read_tables(
+        &mut std::io::Cursor::new(&package),
+        EngineVersion::VER_UE4_27,
+    )
+    .unwrap();
+    assert_eq!(
+        vec!["/Script/CoreUObject", "Package", "/Game/Hero", "Hero_C"],
+        tables.names
+    );
+    assert_eq!(
+        vec!["/Game/Hero", "Package_2"],
+        tables
+            .imports
+            .iter()
+            .map(|import| import.object_name.as_str())
+            .collect::<Vec<&str>>()
+    );
+    assert_eq!(
+        vec![ExportEntry {
+            class_index: -2,
+            object_name: "Hero_C".to_string(),
+            serial_size: 1234,
+        }],
+        tables.exports
+    );
+}
+
+/// A line of the listing, as `--format json` writes it.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+enum Row<'a> {
+    Export {
+        index: i32,
+        class: &'a str,
+        name: &'a str,
+        serial_size: i64,
+    },
+    Import {
+        index: i32,
+        class: &'a str,
+        object_path: String,
+    },
+    Name {
+        index: usize,
+        name: &'a str,
+    },
+}
+
+impl Row<'_> {
+    fn fields(&self) -> Vec<String> {
+        match self {
+            Row::Export {
+                index,
+                class,
+                name,
+                serial_size,
+            } => vec![
+                index.to_string(),
+                class.to_string(),
+                name.to_string(),
+                serial_size.to_string(),
+            ],
+            Row::Import {
+                index,
+                class,
+                object_path,
+            } => vec![index.to_string(), class.to_string(), object_path.clone()],
+            Row::Name { index, name } => vec![index.to_string(), name.to_string()],
+        }
+    }
+}
+
+/// The CSV header of `table`, naming the fields of its rows.
+fn columns(table: ListTable) -> &'static str {
+    match table {
+        ListTable::Exports => "index,class,name,serial_size",
+        ListTable::Imports => "index,class,object_path",
+        ListTable::Names => "index,name",
+    }
+}
+
+/// The class of an export, named by the import or export `class_index`
+/// points at.
+fn class_name(tables: &Tables, class_index: i32) -> &str {
+    let name = match class_index {
+        index if index < 0 => usize::try_from(-(index + 1))
+            .ok()
+            .and_then(|i| tables.imports.get(i))
+            .map(|import| import.object_name.as_str()),
+        index if index > 0 => usize::try_from(index - 1)
+            .ok()
+            .and_then(|i| tables.exports.get(i))
+            .map(|export| export.object_name.as_str()),
+        _ => None,
+    };
+    name.unwrap_or("UnknownClass")
+}
+
+fn rows(tables: &Tables, table: ListTable) -> Vec<Row<'_>> {
+    match table {
+        ListTable::Exports => (1..)
+            .zip(&tables.exports)
+            .map(|(index, export)| Row::Export {
+                index,
+                class: class_name(tables, export.class_index),
+                name: &export.object_name,
+                serial_size: export.serial_size,
+            })
+            .collect(),
+        ListTable::Imports => (1..)
+            .zip(&tables.imports)
+            .map(|(i, import)| Row::Import {
+                index: -i,
+                class: &import.class_name,
+                object_path: import_table::import_object_path(&tables.imports, -i)
+                    .unwrap_or_else(|| import.object_name.clone()),
+            })
+            .collect(),
+        ListTable::Names => tables
+            .names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| Row::Name { index, name })
+            .collect(),
+    }
+}
+
+/// Writes a line per entry of `table` to `out`, in `format`. Exports are
+/// `index<TAB>class<TAB>name<TAB>serial_size`, imports
+/// `index<TAB>class<TAB>object_path` and names `index<TAB>name`.
+pub fn write_list<W: Write>(
+    out: &mut W,
+    tables: &Tables,
+    table: ListTable,
+    format: ListFormat,
+) -> Result<()> {
+    if format == ListFormat::Csv {
+        writeln!(out, "{}", columns(table))?;
+    }
+    for row in rows(tables, table) {
+        match format {
+            ListFormat::Tsv => writeln!(out, "{}", row.fields().join("\t"))?,
+            ListFormat::Csv => csv::write_row(out, &row.fields())?,
+            ListFormat::Json => {
+                serde_json::to_writer(&mut *out, &row)?;
+                out.write_all(b"\n")?;
+            }
+        }
+    }
+    out.flush()
+}
+
+#[cfg(test)]
+fn listed(table: ListTable, format: ListFormat) -> String {
+    let import = |class_name: &str, outer_index, object_name: &str| ImportEntry {
+        class_package: "/Script/CoreUObject".to_string(),
+        class_name: class_name.to_string(),
+        outer_index,
+        object_name: object_name.to_string(),
+    };
+    let tables = Tables {
+        names: vec!["None".to_string(), "Hero_C".to_string()],
+        imports: vec![
+            import("Package", 0, "/Script/Engine"),
+            import("Class", -1, "Actor"),
+        ],
+        exports: vec![
+            ExportEntry {
+                class_index: -2,
+                object_name: "Hero_C".to_string(),
+                serial_size: 1234,
+            },
+            ExportEntry {
+                class_index: 1,
+                object_name: "Default__Hero_C".to_string(),
+                serial_size: 56,
+            },
+        ],
+    };
+    let mut out = Vec::new();
+    write_list(&mut out, &tables, table, format).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn test_write_list() {
+    assert_eq!(
+        "1\tActor\tHero_C\t1234\n2\tHero_C\tDefault__Hero_C\t56\n",
+        listed(ListTable::Exports, ListFormat::Tsv)
+    );
+    assert_eq!(
+        "index,class,object_path\n\
+         -1,Package,/Script/Engine\n\
+         -2,Class,/Script/Engine.Actor\n",
+        listed(ListTable::Imports, ListFormat::Csv)
+    );
+    assert_eq!(
+        "{\"index\":0,\"name\":\"None\"}\n{\"index\":1,\"name\":\"Hero_C\"}\n",
+        listed(ListTable::Names, ListFormat::Json)
+    );
+    assert!(listed(ListTable::Exports, ListFormat::Json).starts_with(
+        "{\"index\":1,\"class\":\"Actor\",\"name\":\"Hero_C\",\"serial_size\":1234}\n"
+    ));
+}
@@ -1,330 +1,491 @@
-#![allow(unused_imports)]
-use lazy_static::lazy_static;
-use regex::Regex;
-use std::collections::HashSet;
-use std::ffi::OsStr;
-use std::fs::{create_dir, File};
-use std::io::prelude::Write;
-use std::io::BufReader;
-use std::io::Error as IOError;
-use std::path::Path;
-use unreal_asset::{
-    base::types::PackageIndex,
-    cast,
-    engine_version::EngineVersion,
-    exports::{Export, ExportBaseTrait, ExportNormalTrait, NormalExport},
-    properties::{object_property::ObjectProperty, Property},
-    Asset,
+use clap::{CommandFactory, FromArgMatches};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use uindex::{
+    check, clean, config, diff, filter::PathFilter, grep, hexdump, list, manifest,
+    ndjson::NdjsonOutput, pak, progress::Progress, references, serve, stats::RunStats, strings,
+    texts, tui, version_map::VersionMap, IndexOptions, IndexReport, Mappings, OutputFormat,
 };
 
-lazy_static! {
-    static ref RE_INDEX: Regex = Regex::new(r"([^_]index: )(-?[1-9][0-9]*)").unwrap();
-    static ref SUPPORTED_EXTENSIONS: HashSet<String> = vec!["uasset", "umap"]
-        .iter()
-        .map(|s| s.to_string())
-        .collect();
-}
-
-const GLOBAL_STYLE: &str = "<style>a{text-decoration:none}a:visited{color:darkmagenta}</style>";
+mod cli;
 
-fn link_and_transform_indices(haystack: &str, transform: impl Fn(i32) -> String) -> String {
-    let mut result = String::with_capacity(haystack.len());
-    let mut last_match = 0;
-    for caps in RE_INDEX.captures_iter(haystack) {
-        let m = caps.get(0).unwrap();
-        result.push_str(&haystack[last_match..m.start()]);
-        result.push_str(caps.get(1).unwrap().as_str());
-        let index = i32::from_str_radix(caps.get(2).unwrap().as_str(), 10).unwrap();
-        result += &transform(index);
-        last_match = m.end();
+/// Runs `uasset-index clean <dir>...`.
+fn clean(dirs: &[PathBuf]) {
+    let mut report = clean::CleanReport::default();
+    for dir in dirs {
+        if let Err(err) = clean::clean_dir(dir, &mut report) {
+            eprintln!("failed to clean {}: {}", dir.display(), err);
+            std::process::exit(1);
+        }
+    }
+    for path in &report.removed {
+        println!("Removed {}", path.display());
     }
-    result.push_str(&haystack[last_match..]);
-    result
+    for path in &report.refused {
+        eprintln!(
+            "Refusing to remove {}: it contains .uasset or .umap files",
+            path.display()
+        );
+    }
+    println!(
+        "Removed {} generated directories, refused {}.",
+        report.removed.len(),
+        report.refused.len()
+    );
 }
 
-#[test]
-fn test_link_and_transform_indices() {
-    assert_eq!(
-        " index: 42  _index: 1  index: -42  etc".to_string(),
-        link_and_transform_indices(" index: 21  _index: 1  index: -21  etc", |i| (i * 2)
-            .to_string())
+/// Runs `uasset-index diff`, exiting with 0 when the assets match, 1 when
+/// they differ and 2 when either can't be read.
+fn diff(args: cli::DiffArgs) -> ! {
+    let mappings = args.usmap.map(|path| match Mappings::load(&path) {
+        Ok(mappings) => mappings,
+        Err(err) => {
+            eprintln!("failed to load mappings {}: {}", path.display(), err);
+            std::process::exit(2);
+        }
+    });
+    let diff = match diff::diff_files(&args.old, &args.new, args.engine_version, mappings.as_ref())
+    {
+        Ok(diff) => diff,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        }
+    };
+    if let Err(err) = diff::write_report(&args.output, &args.old, &args.new, &diff) {
+        eprintln!("failed to write {}: {}", args.output.display(), err);
+        std::process::exit(2);
+    }
+    if diff.is_empty() {
+        println!("No differences. Wrote {}", args.output.display());
+        std::process::exit(0);
+    }
+    println!(
+        "Exports: {} added, {} removed, {} changed. Imports: {} added, {} removed, {} changed. \
+         Names: {} added, {} removed. Wrote {}",
+        diff.exports.added.len(),
+        diff.exports.removed.len(),
+        diff.exports.changed.len(),
+        diff.imports.added.len(),
+        diff.imports.removed.len(),
+        diff.imports.changed.len(),
+        diff.names_added.len(),
+        diff.names_removed.len(),
+        args.output.display()
     );
+    std::process::exit(1);
 }
 
-fn try_create_dir<P: AsRef<Path>>(path: P) -> std::io::Result<()> {
-    let path = path.as_ref();
-    if path.exists() && path.is_dir() {
-        Ok(())
+/// Runs `uasset-index grep`, exiting with 0 when something matched, 1 when
+/// nothing did and 2 when an asset couldn't be read.
+fn grep(args: cli::GrepArgs) -> ! {
+    let grep_options = grep::GrepOptions::new(
+        &args.pattern,
+        args.regex,
+        args.ignore_case,
+        args.class,
+        args.object_paths,
+    )
+    .unwrap_or_else(|err| {
+        eprintln!("invalid pattern: {}", err);
+        std::process::exit(2);
+    });
+    let mappings = args.usmap.map(|path| match Mappings::load(&path) {
+        Ok(mappings) => mappings,
+        Err(err) => {
+            eprintln!("failed to load mappings {}: {}", path.display(), err);
+            std::process::exit(2);
+        }
+    });
+    let options = IndexOptions {
+        engine_version: args.engine_version,
+        mappings,
+        ..Default::default()
+    };
+    let mut report = grep::GrepReport::default();
+    for path in &args.paths {
+        report.add(grep::grep(path, &options, &grep_options));
+    }
+    std::process::exit(if report.failed > 0 {
+        2
+    } else if report.matches > 0 {
+        0
     } else {
-        create_dir(path)
+        1
+    });
+}
+
+/// Runs `uasset-index find-references`, exiting with 0 when something
+/// references the path, 1 when nothing does and 2 when an asset couldn't be
+/// read.
+fn find_references(args: cli::FindReferencesArgs) -> ! {
+    let query = references::ReferenceQuery::new(&args.object_path, args.package);
+    let options = IndexOptions {
+        engine_version: args.engine_version,
+        ..Default::default()
+    };
+    let mut report = references::ReferenceReport::default();
+    for path in &args.paths {
+        report.add(references::find_references(path, &options, &query));
     }
+    if args.json {
+        match serde_json::to_string_pretty(&report.references) {
+            Ok(json) => println!("{}", json),
+            Err(err) => {
+                eprintln!("failed to write JSON: {}", err);
+                std::process::exit(2);
+            }
+        }
+    } else {
+        for found in &report.references {
+            println!(
+                "{}:{}: {} ({})",
+                found.asset.display(),
+                found.index,
+                found.object_path,
+                found.class_name
+            );
+        }
+    }
+    std::process::exit(if report.failed > 0 {
+        2
+    } else if !report.references.is_empty() {
+        0
+    } else {
+        1
+    });
 }
 
-fn print_usage() {
-    eprintln!("Please pass in at least one uasset. Example:");
-    eprintln!("> ./uasset-index path/to/my_uasset.uasset");
+/// Runs `uasset-index list`, exiting with 1 when the asset can't be read.
+fn list(args: cli::ListArgs) -> ! {
+    if pak::is_pak(&args.path) {
+        eprintln!("{}: list doesn't read .pak files", args.path.display());
+        std::process::exit(1);
+    }
+    let tables = list::read_tables_of(&args.path, args.engine_version).unwrap_or_else(|err| {
+        eprintln!("failed to read {}: {}", args.path.display(), err);
+        std::process::exit(1);
+    });
+    let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+    match list::write_list(&mut out, &tables, args.table(), args.format) {
+        // Piped into head, which stopped reading.
+        Err(err) if err.kind() == std::io::ErrorKind::BrokenPipe => {}
+        Err(err) => {
+            eprintln!("failed to write the list: {}", err);
+            std::process::exit(1);
+        }
+        Ok(()) => {}
+    }
+    std::process::exit(0);
 }
 
-fn is_valid_extension(ext: Option<&OsStr>) -> bool {
-    match ext {
-        Some(ext) => ext == "uasset" || ext == "umap",
-        None => false,
+/// Runs `uasset-index tui`, exiting with 1 when the asset can't be read.
+fn tui(args: cli::TuiArgs) -> ! {
+    let mappings = args.usmap.map(|path| match Mappings::load(&path) {
+        Ok(mappings) => mappings,
+        Err(err) => {
+            eprintln!("failed to load mappings {}: {}", path.display(), err);
+            std::process::exit(1);
+        }
+    });
+    let options = IndexOptions {
+        engine_version: args.engine_version,
+        mappings,
+        ..Default::default()
+    };
+    let browser = tui::Browser::open(&args.path, &options).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+    if let Err(err) = tui::run(browser) {
+        eprintln!("terminal error: {}", err);
+        std::process::exit(1);
     }
+    std::process::exit(0);
 }
 
 fn main() {
-    let mut args = std::env::args();
-    _ = args.next();
-    let paths: Vec<String> = args.collect();
-    if paths.len() == 0 {
-        print_usage();
+    let matches = cli::Cli::command().get_matches();
+    let cli = cli::Cli::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+    let index_matches = match matches.subcommand() {
+        Some(("index", index_matches)) => index_matches,
+        _ => &matches,
+    };
+    let mut args = match cli.command {
+        Some(cli::Command::Clean { dirs }) => {
+            clean(&dirs);
+            return;
+        }
+        Some(cli::Command::Diff(args)) => diff(args),
+        Some(cli::Command::FindReferences(args)) => find_references(args),
+        Some(cli::Command::Grep(args)) => grep(args),
+        Some(cli::Command::List(args)) => list(args),
+        Some(cli::Command::Tui(args)) => tui(args),
+        Some(cli::Command::Index(args)) => args,
+        None => cli.index,
+    };
+    if let Some(list) = &args.files_from {
+        let paths = if list.as_os_str() == "-" {
+            uindex::read_path_list(std::io::stdin().lock(), args.null)
+        } else {
+            std::fs::File::open(list)
+                .and_then(|file| uindex::read_path_list(std::io::BufReader::new(file), args.null))
+        };
+        match paths {
+            Ok(paths) => args.paths.extend(paths),
+            Err(err) => {
+                eprintln!("failed to read {}: {}", list.display(), err);
+                std::process::exit(1);
+            }
+        }
+    } else if args.paths.is_empty() {
+        cli::Cli::command()
+            .print_help()
+            .expect("Failed to print help.");
         return;
     }
-    for path in paths {
-        let path = Path::new(&path);
-        index(path);
+    let configs = if args.no_config {
+        Vec::new()
+    } else {
+        config::discover(&args.paths).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        })
+    };
+    let mut given = cli::given_on_command_line(index_matches);
+    let directory_configs = config::DirectoryConfigs::new(
+        &configs,
+        !given.contains("engine_version"),
+        !given.contains("usmap"),
+    )
+    .unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+    for config in &configs {
+        args.apply_config(config, &mut given);
     }
-}
-
-fn index(path: &Path) {
-    if path.is_dir() {
-        let _ = index_dir(path);
-    } else if path.is_file() {
-        index_file(path);
+    if args.csv_combined && !args.formats.contains(&OutputFormat::Csv) {
+        eprintln!("--csv-combined needs --format csv");
+        std::process::exit(1);
     }
-}
-
-fn index_dir(path: &Path) -> Result<(), IOError> {
-    println!("Indexing directory: {}", path.to_str().unwrap());
-    let mut known_index_dirs = HashSet::new();
-    for entry in std::fs::read_dir(path).unwrap() {
-        let entry = entry?;
-        let path = entry.path();
-        if !entry.file_type()?.is_file() {
-            continue;
+    match (&args.output, args.formats.contains(&OutputFormat::Ndjson)) {
+        (None, true) => {
+            eprintln!("--format ndjson needs --output <FILE>, or --output - for stdout");
+            std::process::exit(1);
         }
-        if !SUPPORTED_EXTENSIONS.contains(path.extension().unwrap().to_str().unwrap()) {
-            continue;
+        (Some(_), false) => {
+            eprintln!("--output needs --format ndjson");
+            std::process::exit(1);
         }
-        index_file(&path);
-        known_index_dirs.insert(path.with_extension("").to_string_lossy().to_string());
+        _ => {}
     }
-    for entry in std::fs::read_dir(path).unwrap() {
-        let entry = entry?;
-        let path = entry.path();
-        if !entry.file_type()?.is_dir() {
-            continue;
+    let ndjson = args.output.as_deref().map(|path| {
+        if path == Path::new("-") {
+            return NdjsonOutput::stdout();
         }
-        if known_index_dirs.contains(path.to_str().unwrap()) {
-            continue;
+        NdjsonOutput::create(path).unwrap_or_else(|err| {
+            eprintln!("failed to create {}: {}", path.display(), err);
+            std::process::exit(1);
+        })
+    });
+    let mappings = args.usmap.map(|path| match Mappings::load(&path) {
+        Ok(mappings) => mappings,
+        Err(err) => {
+            eprintln!("failed to load mappings {}: {}", path.display(), err);
+            std::process::exit(1);
         }
-        index_dir(&path)?;
+    });
+    let stylesheet = match &args.style {
+        Some(path) => std::fs::read(path).unwrap_or_else(|err| {
+            eprintln!("failed to read stylesheet {}: {}", path.display(), err);
+            std::process::exit(1);
+        }),
+        None => args.theme.stylesheet().as_bytes().to_vec(),
+    };
+    let version_map = match &args.version_map {
+        Some(path) => VersionMap::load(path).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }),
+        None => VersionMap::default(),
+    };
+    let path_filter = PathFilter::new(&args.include, &args.exclude).unwrap_or_else(|err| {
+        eprintln!("invalid --include or --exclude pattern: {}", err);
+        std::process::exit(1);
+    });
+    let mut options = IndexOptions {
+        output_dir: args.output_dir,
+        engine_version: args.engine_version,
+        mappings,
+        formats: args.formats,
+        graph_max_nodes: args.graph.then_some(args.graph_max_nodes),
+        max_array_elements: args.max_array_elements,
+        max_string_bytes: args.max_string_bytes,
+        max_dump_size: args.max_dump_size,
+        listing_page_size: args.listing_page_size,
+        incremental: args.incremental && !args.force,
+        class_filter: args.filter_class,
+        raw_dumps: args.raw_dumps.then_some(hexdump::RawDumps {
+            max_bytes: args.raw_max_bytes,
+            full: args.raw_full,
+        }),
+        stylesheet,
+        aes_key: args.aes_key,
+        pak_filter: args.pak_filter,
+        follow_symlinks: args.follow_symlinks,
+        max_depth: args.max_depth,
+        path_filter,
+        single_file: args.single_file,
+        version_map,
+        directory_configs,
+        collapse_depth: (!args.no_collapse).then_some(args.collapse_depth),
+        progress: None,
+        csv_combined: args.csv_combined,
+        dedupe_pages: args.dedupe_pages,
+        compression: args.compress,
+        extensions: args.extensions,
+        prefer: args.prefer,
+        ndjson,
+        layout: args.layout,
+        extract_strings: args.extract_strings.then_some(strings::ExtractStrings {
+            dedupe: args.strings_dedupe,
+            unfiltered: args.strings_unfiltered,
+        }),
+    };
+    // 0 lets rayon pick one thread per core.
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(args.jobs.unwrap_or(0))
+        .build()
+        .expect("Failed to create thread pool.");
+    if args.check {
+        let mut report = check::CheckReport::default();
+        pool.install(|| {
+            for path in &args.paths {
+                report.add(check::check(path, &options));
+            }
+        });
+        println!(
+            "Checked {} files: {} passed, {} failed.",
+            report.passed + report.failed,
+            report.passed,
+            report.failed
+        );
+        std::process::exit(if report.failed > 0 { 1 } else { 0 });
     }
-    Ok(())
-}
-
-fn index_file(path: &Path) {
-    println!(
-        "Indexing uasset file: {}",
-        path.file_name().unwrap().to_str().unwrap()
-    );
-    if !is_valid_extension(path.extension()) {
-        eprintln!("Invalid extension. Valid extensions are: 'umap', 'uasset'");
-        return;
+    let start = Instant::now();
+    // Counted up front so the progress display knows how far along it is.
+    let total = args
+        .paths
+        .iter()
+        .map(|path| uindex::count_assets(path, &options))
+        .sum();
+    options.progress = Some(Progress::new(total, std::io::stderr().is_terminal()));
+    let mut report = IndexReport::default();
+    pool.install(|| {
+        for path in &args.paths {
+            report.add(uindex::index(path, &options));
+        }
+    });
+    if let Some(progress) = &options.progress {
+        progress.done();
     }
-    if !path.exists() {
-        eprintln!("File does not exist: {}", path.display());
-        return;
+    if let Some(manifest_path) = &args.manifest {
+        if let Err(err) = manifest::write_manifest(
+            manifest_path,
+            options.output_dir.as_deref(),
+            &report.generated,
+            &report.errors,
+        ) {
+            eprintln!(
+                "failed to write manifest {}: {}",
+                manifest_path.display(),
+                err
+            );
+            std::process::exit(1);
+        }
     }
-    let uexp_path = path.with_extension("uexp");
-
-    let uasset_file = File::open(path).unwrap();
-    let maybe_uexp_file = File::open(uexp_path).ok();
-
-    let asset = Asset::new(uasset_file, maybe_uexp_file, EngineVersion::VER_UE5_1, None).unwrap();
-
-    let uasset_name = path.file_stem().unwrap();
-    let main_dir = path.parent().unwrap().join(uasset_name);
-    let exports_dir = main_dir.join("exports");
-    let imports_dir = main_dir.join("imports");
-    try_create_dir(&main_dir).expect("Failed to create main directory.");
-    try_create_dir(&exports_dir).expect("Failed to create exports directory.");
-    try_create_dir(&imports_dir).expect("Failed to create imports directory.");
-
-    let mut main_index =
-        File::create(main_dir.join("index.html")).expect("Failed to create main index file.");
-    main_index
-        .write_all(GLOBAL_STYLE.as_bytes())
-        .expect("Failed to write to main index file.");
-    main_index
-        .write_all(
-            format!(
-                "<h1>
-        <a href=\"..\">.</a>/
-        {}/
-        </h1>
-        <ul>
-        <li><a href=\"imports\">imports</a></li>
-        <li><a href=\"exports\">exports</a></li>
-        </ul>",
-                uasset_name.to_string_lossy()
-            )
-            .as_bytes(),
-        )
-        .expect("Failed to write to main index file.");
-
-    let link_and_annotate_index = |index: i32| {
-        if index == 0 {
-            panic!("Tried to annotate 0 index.");
+    options.log(format_args!(
+        "Indexed {} files successfully, {} failed.",
+        report.succeeded, report.failed
+    ));
+    if !report.unreadable_dirs.is_empty() {
+        options.log(format_args!(
+            "Skipped {} directories that couldn't be read:",
+            report.unreadable_dirs.len()
+        ));
+        for dir in &report.unreadable_dirs {
+            options.log(format_args!("  {}", dir.display()));
         }
-        if index < 0 {
-            let name = asset.imports[(-index - 1) as usize]
-                .object_name
-                .get_owned_content();
-            format!(
-                "<a href=\"../../imports/{pos_index}\">{neg_index} ({name})</a>",
-                name = name,
-                pos_index = -index,
-                neg_index = index
-            )
-        } else {
-            let name = asset.asset_data.exports[(index - 1) as usize]
-                .get_base_export()
-                .object_name
-                .get_owned_content();
-            format!(
-                "<a href=\"../../exports/{index}\">{index} ({name})</a>",
-                name = name,
-                index = index
-            )
+    }
+    if !report.collisions.is_empty() {
+        options.log(format_args!(
+            "{} package paths come from more than one file:",
+            report.collisions.len()
+        ));
+        for collision in &report.collisions {
+            options.log(format_args!(
+                "  {}: links resolve to {}",
+                collision.package_path,
+                collision.resolved.display()
+            ));
+            for file in collision
+                .files
+                .iter()
+                .filter(|file| **file != collision.resolved)
+            {
+                options.log(format_args!("    also {}", file.display()));
+            }
         }
-    };
+    }
+    if let Some(texts_path) = &args.texts_csv {
+        if let Err(err) = texts::write_texts_csv(texts_path, &report.texts) {
+            eprintln!("failed to write texts {}: {}", texts_path.display(), err);
+            std::process::exit(1);
+        }
+    }
+    let mut stats = RunStats::new(&report, start.elapsed());
+    if args.timings {
+        stats = stats.with_timings(&report);
+    }
+    eprint!("{}", stats.summary_table());
+    if let Some(stats_path) = &args.stats_json {
+        if let Err(err) = stats.write_json(stats_path) {
+            eprintln!(
+                "failed to write statistics {}: {}",
+                stats_path.display(),
+                err
+            );
+            std::process::exit(1);
+        }
+    }
+    if let Some(addr) = &args.serve {
+        let root = serve_root(options.output_dir.as_deref(), &args.paths);
+        if let Err(err) = serve::serve(&root, addr, &options) {
+            eprintln!("failed to serve on {}: {}", addr, err);
+            std::process::exit(1);
+        }
+    }
+    if report.failed > 0 {
+        std::process::exit(1);
+    }
+}
 
-    let mut exports_index =
-        File::create(exports_dir.join("index.html")).expect("Failed to create exports index file.");
-    let mut exports_index_contents = asset
-        .asset_data
-        .exports
-        .iter()
-        .enumerate()
-        .map(|(i, export)| {
-            format!(
-                "<li><a href=\"{i}\">{i} ({name})</a></li>",
-                i = i + 1,
-                name = export.get_base_export().object_name.get_owned_content()
-            )
-        })
-        .fold("<ul>".to_string(), |a, b| a + &b);
-    exports_index_contents += "</ul>";
-    exports_index
-        .write_all(GLOBAL_STYLE.as_bytes())
-        .expect("Failed to write to exports index file.");
-    exports_index
-        .write_all(
-            format!(
-                "<h1>
-                <a href=\"../..\">.</a>/
-                <a href=\"..\">{}</a>/
-                exports
-                </h1>",
-                uasset_name.to_string_lossy()
-            )
-            .as_bytes(),
-        )
-        .expect("Failed to write to exports index file.");
-    exports_index
-        .write_all(exports_index_contents.as_bytes())
-        .expect("Failed to write to exports index file.");
-    for (i, export) in asset.asset_data.exports.iter().enumerate() {
-        let dir = exports_dir.join((i + 1).to_string());
-        try_create_dir(&dir).expect("Failed to create export directory.");
-        let mut file =
-            File::create(dir.join("index.html")).expect("Failed to create export HTML file.");
-        let dump = format!(
-            "<span style=\"white-space-collapse:preserve;font-family:monospace\">{:#?}</span>",
-            export
-        );
-        let dump = link_and_transform_indices(&dump, link_and_annotate_index);
-        file.write_all(GLOBAL_STYLE.as_bytes())
-            .expect("Failed to write to export HTML file.");
-        file.write_all(
-            format!(
-                "<h1>
-                    <a href=\"../../..\">.</a>/
-                    <a href=\"../..\">{base}</a>/
-                    <a href=\"..\">exports</a>/
-                    {i}
-                    </h1>",
-                base = uasset_name.to_string_lossy(),
-                i = i + 1
-            )
-            .as_bytes(),
-        )
-        .expect("Failed to write to export HTML file.");
-        file.write_all(dump.as_bytes())
-            .expect("Failed to write to export HTML file.");
+/// The directory `--serve` serves: the output directory if there is one,
+/// otherwise the single input directory (or the directory of the single
+/// input file), falling back to the current directory.
+fn serve_root(output_dir: Option<&Path>, paths: &[PathBuf]) -> PathBuf {
+    if let Some(output_dir) = output_dir {
+        return output_dir.to_path_buf();
     }
-    let mut imports_index =
-        File::create(imports_dir.join("index.html")).expect("Failed to create imports index file.");
-    let mut imports_index_contents = asset
-        .imports
-        .iter()
-        .enumerate()
-        .map(|(i, import)| {
-            format!(
-                "<li><a href=\"{i}\">{i} ({name})</a></li>",
-                i = i + 1,
-                name = import.object_name.get_owned_content()
-            )
-        })
-        .fold("<ul>".to_string(), |a, b| a + &b);
-    imports_index_contents += "</ul>";
-    imports_index
-        .write_all(GLOBAL_STYLE.as_bytes())
-        .expect("Failed to write to imports index file.");
-    imports_index
-        .write_all(
-            format!(
-                "<h1>
-                <a href=\"../..\">.</a>/
-                <a href=\"..\">{}</a>/
-                imports
-                </h1>",
-                uasset_name.to_string_lossy()
-            )
-            .as_bytes(),
-        )
-        .expect("Failed to write to imports index file.");
-    imports_index
-        .write_all(imports_index_contents.as_bytes())
-        .expect("Failed to write to imports index file.");
-    for (i, import) in asset.imports.iter().enumerate() {
-        let dir = imports_dir.join((i + 1).to_string());
-        try_create_dir(&dir).expect("Failed to create import directory.");
-        let mut file =
-            File::create(dir.join("index.html")).expect("Failed to create import HTML file.");
-        let dump = format!(
-            "<span style=\"white-space-collapse:preserve;font-family:monospace\">{:#?}</span>",
-            import
-        );
-        let dump = link_and_transform_indices(&dump, link_and_annotate_index);
-        file.write_all(GLOBAL_STYLE.as_bytes())
-            .expect("Failed to write to import HTML file.");
-        file.write_all(
-            format!(
-                "<h1>
-                    <a href=\"../../..\">.</a>/
-                    <a href=\"../..\">{base}</a>/
-                    <a href=\"..\">imports</a>/
-                    {i}
-                    </h1>",
-                base = uasset_name.to_string_lossy(),
-                i = i + 1
-            )
-            .as_bytes(),
-        )
-        .expect("Failed to write to import HTML file.");
-        file.write_all(dump.as_bytes())
-            .expect("Failed to write to import HTML file.");
+    match paths {
+        [path] if path.is_dir() => path.clone(),
+        [path] if pak::is_pak(path) => path.with_extension(""),
+        [path] => path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .map_or_else(|| PathBuf::from("."), Path::to_path_buf),
+        _ => PathBuf::from("."),
     }
 }
@@ -0,0 +1,406 @@
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{BufWriter, Result};
+use std::path::{Path, PathBuf};
+
+use crate::compress::{self, Compression};
+
+/// Added to the name of each file while it is written. Files are renamed
+/// into place once everything for their asset is written, so an interrupted
+/// run leaves these behind rather than truncated pages.
+pub const TEMP_SUFFIX: &str = ".uindex-tmp";
+
+/// Where the file for `path` is written before it's renamed into place.
+pub fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(TEMP_SUFFIX);
+    path.with_file_name(name)
+}
+
+/// Replaces the file at `path` with `contents` by way of a temporary file,
+/// so it holds either the old contents or the new ones, never a part.
+pub fn write_atomically(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    let temp = temp_path(path);
+    fs::write(&temp, contents)?;
+    fs::rename(&temp, path).map_err(|err| {
+        let _ = fs::remove_file(&temp);
+        err
+    })
+}
+
+/// Deletes the temporary files that runs which were killed left under
+/// `dir`. Anything that can't be read or removed is left for the walk to
+/// report.
+pub fn remove_stale_temp_files(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            remove_stale_temp_files(&entry.path());
+        } else if file_type.is_file() && entry.file_name().to_string_lossy().ends_with(TEMP_SUFFIX)
+        {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+#[test]
+fn test_remove_stale_temp_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let exports = dir.path().join("Hero").join("exports");
+    fs::create_dir_all(&exports).unwrap();
+    let stale = temp_path(&exports.join("index.html"));
+    fs::write(&stale, "<h1>Her").unwrap();
+    fs::write(exports.join("index.html"), "<h1>Hero</h1>").unwrap();
+    remove_stale_temp_files(dir.path());
+    assert!(!stale.exists());
+    assert!(exports.join("index.html").exists());
+    // A missing output directory has nothing to clean.
+    remove_stale_temp_files(&dir.path().join("Missing"));
+}
+
+/// A file written during the run.
+#[derive(Debug, PartialEq)]
+pub struct GeneratedFile {
+    pub path: PathBuf,
+    /// The asset the file was generated from, or `None` for directory pages.
+    pub source: Option<PathBuf>,
+    /// Whether a file already existed at `path` before the run wrote it.
+    pub overwritten: bool,
+}
+
+/// An asset that failed to index, or an entry of a directory that was
+/// skipped because it couldn't be read.
+#[derive(Debug)]
+pub struct IndexFailure {
+    pub path: PathBuf,
+    /// What kind of problem it was, from `Error::category`, or `unreadable`
+    /// for skipped entries.
+    pub category: &'static str,
+    pub message: String,
+    /// The asset's output directory, when an earlier run left a main page
+    /// there.
+    pub output: Option<PathBuf>,
+    /// The files written for the asset before it failed, which stay in
+    /// place.
+    pub generated: Vec<GeneratedFile>,
+}
+
+impl IndexFailure {
+    pub fn new(path: &Path, err: crate::Error) -> Self {
+        let category = err.category();
+        let message = err.to_string();
+        IndexFailure {
+            path: path.to_path_buf(),
+            category,
+            message,
+            output: None,
+            generated: match err {
+                crate::Error::Write(_, _, written) => written,
+                _ => Vec::new(),
+            },
+        }
+    }
+
+    pub fn skipped(path: &Path, err: &std::io::Error) -> Self {
+        IndexFailure {
+            path: path.to_path_buf(),
+            category: "unreadable",
+            message: err.to_string(),
+            output: None,
+            generated: Vec::new(),
+        }
+    }
+}
+
+/// Records the files generated for one asset (or for no asset in
+/// particular) as they are created. Files it creates are written under
+/// temporary names until `finish`; dropping it before then deletes them and
+/// leaves whatever was at their paths untouched. With `compression`, they
+/// are put in place compressed instead, as `compress::gz_path`.
+#[derive(Debug, Default)]
+pub struct OutputFiles {
+    source: Option<PathBuf>,
+    pub files: Vec<GeneratedFile>,
+    /// The paths of files still under their temporary names.
+    staged: Vec<PathBuf>,
+    pub compression: Option<Compression>,
+}
+
+impl OutputFiles {
+    pub fn for_asset(source: &Path) -> Self {
+        OutputFiles {
+            source: Some(source.to_path_buf()),
+            files: Vec::new(),
+            staged: Vec::new(),
+            compression: None,
+        }
+    }
+
+    /// Where `path` ends up once finished.
+    fn final_path(&self, path: &Path) -> PathBuf {
+        if compress::is_compressed(self.compression, path) {
+            compress::gz_path(path)
+        } else {
+            path.to_path_buf()
+        }
+    }
+
+    /// Notes that `path` is about to be written. Call before writing so
+    /// overwrites can be told apart from new files.
+    pub fn record(&mut self, path: &Path) {
+        self.files.push(GeneratedFile {
+            path: path.to_path_buf(),
+            source: self.source.clone(),
+            overwritten: path.exists(),
+        });
+    }
+
+    /// Records `path` and returns the temporary path to write it at, which
+    /// `finish` renames to `path`, or compresses to its `.gz`.
+    pub fn stage(&mut self, path: &Path) -> PathBuf {
+        self.record(&self.final_path(path));
+        self.staged.push(path.to_path_buf());
+        temp_path(path)
+    }
+
+    /// Records `path` and creates it under its temporary name.
+    pub fn create(&mut self, path: &Path) -> Result<File> {
+        File::create(self.stage(path))
+    }
+
+    /// Takes the records of the files that are in place, leaving out those
+    /// still under their temporary names, which dropping deletes, and any
+    /// that were never written.
+    pub fn take_written(&mut self) -> Vec<GeneratedFile> {
+        let staged: Vec<PathBuf> = self
+            .staged
+            .iter()
+            .map(|path| self.final_path(path))
+            .collect();
+        std::mem::take(&mut self.files)
+            .into_iter()
+            .filter(|file| file.path.exists() && !staged.contains(&file.path))
+            .collect()
+    }
+
+    /// Renames the files written so far into place. Call once all of them
+    /// are complete and closed.
+    pub fn finish(&mut self) -> Result<()> {
+        // The first page written, usually the main one, ends up last.
+        while let Some(path) = self.staged.last() {
+            let temp = temp_path(path);
+            let gz = compress::gz_path(path);
+            if compress::is_compressed(self.compression, path) {
+                compress::gzip_file(&temp, &temp_path(&gz))?;
+                fs::rename(temp_path(&gz), &gz)?;
+                fs::remove_file(&temp)?;
+                // Servers would prefer a page left from an uncompressed run.
+                let _ = fs::remove_file(path);
+            } else {
+                fs::rename(&temp, path)?;
+                let _ = fs::remove_file(&gz);
+            }
+            self.staged.pop();
+        }
+        Ok(())
+    }
+}
+
+impl Drop for OutputFiles {
+    fn drop(&mut self) {
+        for path in &self.staged {
+            let _ = fs::remove_file(temp_path(path));
+            let _ = fs::remove_file(temp_path(&compress::gz_path(path)));
+        }
+    }
+}
+
+#[test]
+fn test_output_files_finish() {
+    let dir = tempfile::tempdir().unwrap();
+    let page = dir.path().join("index.html");
+    let mut outputs = OutputFiles::default();
+    std::io::Write::write_all(&mut outputs.create(&page).unwrap(), b"<h1>Hero</h1>").unwrap();
+    assert!(!page.exists());
+    outputs.finish().unwrap();
+    assert_eq!("<h1>Hero</h1>", fs::read_to_string(&page).unwrap());
+    assert!(!temp_path(&page).exists());
+}
+
+#[test]
+fn test_output_files_finish_compressed() {
+    let dir = tempfile::tempdir().unwrap();
+    let page = dir.path().join("index.html");
+    fs::write(&page, "<h1>Old</h1>").unwrap();
+    let mut outputs = OutputFiles {
+        compression: Some(Compression::Gzip),
+        ..Default::default()
+    };
+    std::io::Write::write_all(&mut outputs.create(&page).unwrap(), b"<h1>Hero</h1>").unwrap();
+    outputs.finish().unwrap();
+    assert!(!page.exists());
+    assert!(!temp_path(&page).exists());
+    assert_eq!(compress::gz_path(&page), outputs.files[0].path);
+    assert_eq!("<h1>Hero</h1>", compress::read_page(&page).unwrap());
+}
+
+#[test]
+fn test_output_files_dropped_unfinished() {
+    let dir = tempfile::tempdir().unwrap();
+    let page = dir.path().join("index.html");
+    fs::write(&page, "<h1>Hero</h1><ul><li>1</li></ul>").unwrap();
+    {
+        let mut outputs = OutputFiles::default();
+        // As if the run stopped halfway through the page.
+        std::io::Write::write_all(&mut outputs.create(&page).unwrap(), b"<h1>Hero</h1><ul>")
+            .unwrap();
+    }
+    assert_eq!(
+        "<h1>Hero</h1><ul><li>1</li></ul>",
+        fs::read_to_string(&page).unwrap()
+    );
+    assert!(!temp_path(&page).exists());
+}
+
+#[test]
+fn test_output_files_take_written() {
+    let dir = tempfile::tempdir().unwrap();
+    let marker = dir.path().join(".uasset-index");
+    let page = dir.path().join("index.html");
+    let mut outputs = OutputFiles::for_asset(Path::new("Content/Hero.uasset"));
+    outputs.record(&marker);
+    fs::write(&marker, "Hero.uasset").unwrap();
+    std::io::Write::write_all(&mut outputs.create(&page).unwrap(), b"<h1>Hero</h1>").unwrap();
+    // Recorded, but failed before it was written.
+    outputs.record(&dir.path().join("graph.dot"));
+    assert_eq!(
+        vec![GeneratedFile {
+            path: marker,
+            source: Some(PathBuf::from("Content/Hero.uasset")),
+            overwritten: false,
+        }],
+        outputs.take_written()
+    );
+    drop(outputs);
+    assert!(!temp_path(&page).exists());
+}
+
+#[derive(Serialize)]
+struct FileJson {
+    path: String,
+    size: u64,
+    source: Option<String>,
+    status: &'static str,
+}
+
+#[derive(Serialize)]
+struct ErrorJson {
+    path: String,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct ManifestJson {
+    files: Vec<FileJson>,
+    errors: Vec<ErrorJson>,
+}
+
+/// Writes the manifest of `files` and `errors` to `path`. File paths are
+/// made relative to `root` when they are inside it.
+pub fn write_manifest(
+    path: &Path,
+    root: Option<&Path>,
+    files: &[GeneratedFile],
+    errors: &[IndexFailure],
+) -> Result<()> {
+    let display = |path: &Path| {
+        root.and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string()
+    };
+    let manifest = ManifestJson {
+        files: files
+            .iter()
+            .map(|file| FileJson {
+                path: display(&file.path),
+                // Sizes are taken at the end, after the cross-reference passes
+                // have rewritten pages.
+                size: fs::metadata(&file.path).map_or(0, |metadata| metadata.len()),
+                source: file
+                    .source
+                    .as_ref()
+                    .map(|source| source.to_string_lossy().to_string()),
+                status: if file.overwritten {
+                    "overwritten"
+                } else {
+                    "created"
+                },
+            })
+            .collect(),
+        errors: errors
+            .iter()
+            .map(|error| ErrorJson {
+                path: error.path.to_string_lossy().to_string(),
+                message: error.message.clone(),
+            })
+            .collect(),
+    };
+    let file = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(file, &manifest)?;
+    Ok(())
+}
+
+#[test]
+fn test_write_manifest() {
+    let dir = tempfile::tempdir().unwrap();
+    let page = dir.path().join("Hero").join("index.html");
+    fs::create_dir_all(page.parent().unwrap()).unwrap();
+    fs::write(&page, "<h1>Hero</h1>").unwrap();
+
+    let mut outputs = OutputFiles::for_asset(Path::new("Content/Hero.uasset"));
+    outputs.record(&page);
+    outputs.record(&dir.path().join("Hero").join("asset.json"));
+    let errors = vec![IndexFailure {
+        path: PathBuf::from("Content/Broken.uasset"),
+        category: "parse",
+        message: "failed to parse Broken.uasset".to_string(),
+        output: None,
+        generated: Vec::new(),
+    }];
+    let manifest_path = dir.path().join("manifest.json");
+    write_manifest(&manifest_path, Some(dir.path()), &outputs.files, &errors).unwrap();
+
+    let manifest: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&manifest_path).unwrap()).unwrap();
+    assert_eq!(
+        serde_json::json!({
+            "files": [
+                {
+                    "path": Path::new("Hero").join("index.html").to_string_lossy(),
+                    "size": 13,
+                    "source": "Content/Hero.uasset",
+                    "status": "overwritten",
+                },
+                {
+                    "path": Path::new("Hero").join("asset.json").to_string_lossy(),
+                    "size": 0,
+                    "source": "Content/Hero.uasset",
+                    "status": "created",
+                },
+            ],
+            "errors": [
+                {
+                    "path": "Content/Broken.uasset",
+                    "message": "failed to parse Broken.uasset",
+                },
+            ],
+        }),
+        manifest
+    );
+}
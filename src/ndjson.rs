@@ -0,0 +1,143 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::File;
+use std::io::{BufWriter, Result, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::{json::AssetJson, manifest::IndexFailure};
+
+/// Where `--format ndjson` writes: one JSON object per line, each asset's
+/// lines as soon as it's indexed, for piping into other tools. Shared by the
+/// threads indexing the assets, which write an asset's lines under one lock
+/// so they stay together.
+pub struct NdjsonOutput {
+    writer: Mutex<Box<dyn Write + Send>>,
+    stdout: bool,
+}
+
+impl NdjsonOutput {
+    /// Writes to stdout, moving the run's log lines to stderr.
+    pub fn stdout() -> Self {
+        NdjsonOutput {
+            writer: Mutex::new(Box::new(BufWriter::new(std::io::stdout()))),
+            stdout: true,
+        }
+    }
+
+    /// Writes to a new file at `path`.
+    pub fn create(path: &Path) -> Result<Self> {
+        Ok(NdjsonOutput {
+            writer: Mutex::new(Box::new(BufWriter::new(File::create(path)?))),
+            stdout: false,
+        })
+    }
+
+    /// Whether the lines go to stdout.
+    pub fn is_stdout(&self) -> bool {
+        self.stdout
+    }
+
+    /// Writes `lines` one after another and flushes them, so a reader sees
+    /// each asset as soon as it's done.
+    fn write_lines(&self, lines: &[Line]) -> Result<()> {
+        let mut writer = self.writer.lock().unwrap();
+        for line in lines {
+            serde_json::to_writer(&mut *writer, line)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()
+    }
+
+    /// Writes the lines of the indexed asset at `path`.
+    pub(crate) fn write_asset(
+        &self,
+        path: &Path,
+        engine_version: &str,
+        asset: &AssetJson,
+    ) -> Result<()> {
+        self.write_lines(&asset_lines(path, engine_version, asset))
+    }
+
+    /// Writes the line of an asset that failed to index.
+    pub(crate) fn write_failure(&self, failure: &IndexFailure) -> Result<()> {
+        self.write_lines(&[Line::Error {
+            asset: failure.path.display().to_string(),
+            category: failure.category,
+            message: &failure.message,
+        }])
+    }
+}
+
+/// A line of the output, told apart by its `type` key.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Line<'a> {
+    /// Comes before the exports of the asset.
+    Asset {
+        asset: String,
+        engine_version: &'a str,
+        imports: usize,
+        exports: usize,
+    },
+    Export {
+        asset: String,
+        index: i32,
+        name: &'a str,
+        class: Option<&'a str>,
+        object_path: Option<&'a str>,
+        properties: &'a [Value],
+    },
+    Error {
+        asset: String,
+        category: &'a str,
+        message: &'a str,
+    },
+}
+
+/// The asset line of the asset at `path`, then a line per export, from the
+/// same data as `asset.json`.
+fn asset_lines<'a>(path: &Path, engine_version: &'a str, asset: &'a AssetJson) -> Vec<Line<'a>> {
+    let name = path.display().to_string();
+    let mut lines = vec![Line::Asset {
+        asset: name.clone(),
+        engine_version,
+        imports: asset.imports.len(),
+        exports: asset.exports.len(),
+    }];
+    lines.extend(asset.exports.iter().map(|export| Line::Export {
+        asset: name.clone(),
+        index: export.index,
+        name: &export.object_name,
+        class: export.class_name.as_deref(),
+        object_path: export.object_path.as_deref(),
+        properties: &export.properties,
+    }));
+    lines
+}
+
+#[test]
+fn test_line_json() {
+    let properties = [serde_json::json!({"name": "Health", "value": 100})];
+    let export = Line::Export {
+        asset: "Content/Hero.uasset".to_string(),
+        index: 1,
+        name: "Default__Hero_C",
+        class: Some("Hero_C"),
+        object_path: None,
+        properties: &properties,
+    };
+    assert_eq!(
+        r#"{"type":"export","asset":"Content/Hero.uasset","index":1,"name":"Default__Hero_C","class":"Hero_C","object_path":null,"properties":[{"name":"Health","value":100}]}"#,
+        serde_json::to_string(&export).unwrap()
+    );
+    let error = Line::Error {
+        asset: "Content/Broken.uasset".to_string(),
+        category: "parse",
+        message: "failed to parse",
+    };
+    assert_eq!(
+        r#"{"type":"error","asset":"Content/Broken.uasset","category":"parse","message":"failed to parse"}"#,
+        serde_json::to_string(&error).unwrap()
+    );
+}
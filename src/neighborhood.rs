@@ -0,0 +1,269 @@
+use crate::{escape_html, graph::Edge, IndexNames};
+
+/// The most imports and exports an export's neighborhood picture holds, the
+/// export itself included. Bigger neighborhoods are listed instead.
+pub const MAX_NODES: usize = 25;
+
+const NODE_WIDTH: usize = 220;
+const NODE_HEIGHT: usize = 22;
+const ROW_HEIGHT: usize = 30;
+const COLUMN_GAP: usize = 60;
+const MARGIN: usize = 4;
+/// Longer node labels are cut short, with the full name in the tooltip.
+const MAX_LABEL_CHARS: usize = 30;
+
+/// An import or export connected to the export, with the labels of the
+/// edges between them, e.g. `outer` and `Owner`.
+#[derive(Debug, PartialEq)]
+struct Neighbor {
+    index: i32,
+    labels: Vec<String>,
+}
+
+/// The other ends of `edges`, each once, in the order they first appear.
+/// The export itself and indices that point nowhere are left out.
+fn neighbors<'a>(
+    names: &IndexNames,
+    index: i32,
+    edges: impl Iterator<Item = &'a &'a Edge>,
+    other_end: impl Fn(&Edge) -> i32,
+) -> Vec<Neighbor> {
+    let mut neighbors: Vec<Neighbor> = Vec::new();
+    for edge in edges {
+        let other = other_end(edge);
+        if other == index || names.get(other).is_none() {
+            continue;
+        }
+        match neighbors
+            .iter_mut()
+            .find(|neighbor| neighbor.index == other)
+        {
+            Some(neighbor) if neighbor.labels.contains(&edge.label) => {}
+            Some(neighbor) => neighbor.labels.push(edge.label.clone()),
+            None => neighbors.push(Neighbor {
+                index: other,
+                labels: vec![edge.label.clone()],
+            }),
+        }
+    }
+    neighbors
+}
+
+fn shorten(label: &str) -> String {
+    if label.chars().count() <= MAX_LABEL_CHARS {
+        return label.to_string();
+    }
+    let mut short: String = label.chars().take(MAX_LABEL_CHARS - 1).collect();
+    short.push('…');
+    short
+}
+
+/// One box of the picture at `x`, `y`, linked through `href` when it has a
+/// page.
+fn node_svg(
+    names: &IndexNames,
+    index: i32,
+    labels: &[String],
+    (x, y): (usize, usize),
+    href: &impl Fn(i32) -> Option<String>,
+) -> String {
+    let name = names.get(index).unwrap_or_default();
+    let mut title = format!("{} ({})", index, name);
+    if !labels.is_empty() {
+        title += ": ";
+        title += &labels.join(", ");
+    }
+    // The export itself is drawn bold and imports dashed.
+    let style = if labels.is_empty() {
+        " stroke-width=\"2\""
+    } else if index < 0 {
+        " stroke-dasharray=\"4 2\""
+    } else {
+        ""
+    };
+    let node = format!(
+        "<g><title>{title}</title>\
+         <rect x=\"{x}\" y=\"{y}\" width=\"{NODE_WIDTH}\" height=\"{NODE_HEIGHT}\" rx=\"3\" \
+         fill=\"none\" stroke=\"currentColor\"{style}/>\
+         <text x=\"{text_x}\" y=\"{text_y}\" fill=\"currentColor\">{label}</text></g>",
+        title = escape_html(&title),
+        text_x = x + 6,
+        text_y = y + 15,
+        label = escape_html(&shorten(&format!("{} {}", index, name))),
+    );
+    match href(index) {
+        Some(href) => format!("<a href=\"{}\">{}</a>", escape_html(&href), node),
+        None => node,
+    }
+}
+
+/// The top `y` of each of `count` boxes in a column, centered in `rows` rows.
+fn column_ys(count: usize, rows: usize) -> Vec<usize> {
+    let top = MARGIN + (rows - count) * ROW_HEIGHT / 2;
+    (0..count).map(|i| top + i * ROW_HEIGHT).collect()
+}
+
+/// A picture of the export `index` and its immediate neighbors: what
+/// references it on the left and what it references, its outer, class and
+/// template included, on the right. Boxes link through `href`. When there
+/// are more than `MAX_NODES` nodes, what it references is listed with
+/// `annotate` instead, since the referrers have their own list already.
+/// Empty when the export is connected to nothing.
+pub fn neighborhood_html(
+    names: &IndexNames,
+    index: i32,
+    referrers: Option<&Vec<&Edge>>,
+    references: Option<&Vec<&Edge>>,
+    href: impl Fn(i32) -> Option<String>,
+    annotate: impl Fn(i32) -> String,
+) -> String {
+    let empty = Vec::new();
+    let left = neighbors(names, index, referrers.unwrap_or(&empty).iter(), |e| e.from);
+    let right = neighbors(names, index, references.unwrap_or(&empty).iter(), |e| e.to);
+    if left.is_empty() && right.is_empty() {
+        return String::new();
+    }
+    let count = left.len() + right.len() + 1;
+    if count > MAX_NODES {
+        let items: String = right
+            .iter()
+            .map(|neighbor| {
+                format!(
+                    "<li>{} ({})</li>",
+                    annotate(neighbor.index),
+                    escape_html(&neighbor.labels.join(", "))
+                )
+            })
+            .collect();
+        return format!(
+            "<h2>Neighborhood</h2><p>{} imports and exports are connected to this one, too \
+             many to draw. It references:</p><ul>{}</ul>",
+            count - 1,
+            items
+        );
+    }
+
+    let rows = left.len().max(right.len()).max(1);
+    let width = 3 * NODE_WIDTH + 2 * COLUMN_GAP + 2 * MARGIN;
+    let height = (rows - 1) * ROW_HEIGHT + NODE_HEIGHT + 2 * MARGIN;
+    let middle_x = MARGIN + NODE_WIDTH + COLUMN_GAP;
+    let right_x = middle_x + NODE_WIDTH + COLUMN_GAP;
+    let middle_y = column_ys(1, rows)[0];
+    let mut svg = format!(
+        "<h2>Neighborhood</h2><svg width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\" font-family=\"monospace\" font-size=\"12\">"
+    );
+    let columns = [(&left, MARGIN), (&right, right_x)];
+    for (column, x) in columns {
+        for (neighbor, y) in column.iter().zip(column_ys(column.len(), rows)) {
+            // Lines run from the right edge of a box to the left edge of the next.
+            let (x1, y1, x2, y2) = if x < middle_x {
+                (x + NODE_WIDTH, y, middle_x, middle_y)
+            } else {
+                (middle_x + NODE_WIDTH, middle_y, x, y)
+            };
+            svg += &format!(
+                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"gray\"/>",
+                x1,
+                y1 + NODE_HEIGHT / 2,
+                x2,
+                y2 + NODE_HEIGHT / 2
+            );
+            svg += &node_svg(names, neighbor.index, &neighbor.labels, (x, y), &href);
+        }
+    }
+    svg += &node_svg(names, index, &[], (middle_x, middle_y), &href);
+    svg += "</svg>";
+    svg
+}
+
+#[cfg(test)]
+fn edge(from: i32, to: i32, label: &str) -> Edge {
+    Edge {
+        from,
+        to,
+        label: label.to_string(),
+    }
+}
+
+#[test]
+fn test_neighbors() {
+    let names = crate::test_index_names();
+    let edges = [
+        edge(1, -2, "class"),
+        edge(1, -2, "class"),
+        edge(1, -1, "outer"),
+        edge(1, -2, "Texture"),
+        edge(1, 7, "Broken"),
+        edge(1, 1, "Self"),
+    ];
+    let edges: Vec<&Edge> = edges.iter().collect();
+    assert_eq!(
+        vec![
+            Neighbor {
+                index: -2,
+                labels: vec!["class".to_string(), "Texture".to_string()]
+            },
+            Neighbor {
+                index: -1,
+                labels: vec!["outer".to_string()]
+            }
+        ],
+        neighbors(&names, 1, edges.iter(), |e| e.to)
+    );
+}
+
+#[test]
+fn test_neighborhood_html() {
+    let names = crate::test_index_names();
+    let class = edge(1, -2, "class");
+    let references = vec![&class];
+    let html = neighborhood_html(
+        &names,
+        1,
+        None,
+        Some(&references),
+        |index| (index < 0).then(|| format!("../../imports/{}", -index)),
+        |index| index.to_string(),
+    );
+    assert!(html.starts_with("<h2>Neighborhood</h2><svg width=\"788\" height=\"30\""));
+    assert!(html.contains(
+        "<a href=\"../../imports/2\"><g><title>-2 (Texture2D): class</title>\
+         <rect x=\"564\" y=\"4\""
+    ));
+    assert!(html.contains("<line x1=\"504\" y1=\"15\" x2=\"564\" y2=\"15\" stroke=\"gray\"/>"));
+    // Exports have no pages here, so the export itself isn't linked.
+    assert!(html.contains("<g><title>1 (Default__Foo)</title><rect x=\"284\" y=\"4\""));
+    assert!(html.ends_with("1 Default__Foo</text></g></svg>"));
+
+    assert_eq!(
+        "",
+        neighborhood_html(&names, 1, None, None, |_| None, |i| i.to_string())
+    );
+}
+
+#[test]
+fn test_neighborhood_html_lists_big_neighborhoods() {
+    let names = IndexNames {
+        imports: Vec::new(),
+        exports: (1..=30).map(|i| format!("Actor_{}", i)).collect(),
+        import_outers: Vec::new(),
+        export_outers: vec![0; 30],
+        export_classes: Vec::new(),
+    };
+    let edges: Vec<Edge> = (2..=30).map(|i| edge(1, i, "Actors")).collect();
+    let references: Vec<&Edge> = edges.iter().collect();
+    let html = neighborhood_html(
+        &names,
+        1,
+        None,
+        Some(&references),
+        |_| None,
+        |i| i.to_string(),
+    );
+    assert!(html.starts_with(
+        "<h2>Neighborhood</h2><p>29 imports and exports are connected to this one, too many to draw."
+    ));
+    assert!(html.contains("<li>2 (Actors)</li>"));
+    assert!(!html.contains("<svg"));
+}
@@ -0,0 +1,245 @@
+use aes::cipher::KeyInit;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, Cursor};
+use std::path::{Component, Path, PathBuf};
+
+use crate::{
+    index_source, is_valid_extension, manifest, matches_wildcard, report_index_errors,
+    sanitize_dir_name, unique_dir_name, with_progress, write_tree_pages, AssetSource, AssetSummary,
+    CollectedDir, Error, IndexOptions, IndexReport,
+};
+
+/// A 256-bit AES key for encrypted `.pak` files.
+#[derive(Clone)]
+pub struct AesKey([u8; 32]);
+
+impl std::fmt::Debug for AesKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Keep the key out of logs.
+        f.write_str("AesKey(..)")
+    }
+}
+
+/// Parses `--aes-key`: 64 hex digits, optionally prefixed with `0x`.
+pub fn parse_aes_key(s: &str) -> Result<AesKey, String> {
+    let hex = s
+        .strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .unwrap_or(s);
+    if hex.len() != 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("expected 64 hex digits, optionally prefixed with 0x".to_string());
+    }
+    let mut key = [0; 32];
+    for (i, byte) in key.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+    }
+    Ok(AesKey(key))
+}
+
+#[test]
+fn test_parse_aes_key() {
+    let hex = "00112233445566778899AABBCCDDEEFF00112233445566778899aabbccddeeff";
+    let key = parse_aes_key(hex).unwrap();
+    assert_eq!([0x00, 0x11, 0x22], key.0[..3]);
+    assert_eq!(0xff, key.0[31]);
+    assert_eq!(key.0, parse_aes_key(&format!("0x{}", hex)).unwrap().0);
+    assert!(parse_aes_key(&hex[1..]).is_err());
+    assert!(parse_aes_key(&format!("+{}", &hex[1..])).is_err());
+}
+
+/// Whether `path` names a `.pak` archive rather than an asset or directory.
+pub fn is_pak(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("pak"))
+}
+
+/// The path of a pak entry below the output directory: the mount point
+/// followed by the entry, keeping only plain names so nothing can escape.
+fn entry_path(mount_point: &str, entry: &str) -> PathBuf {
+    Path::new(mount_point)
+        .join(entry)
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_entry_path() {
+    assert_eq!(
+        PathBuf::from("FactoryGame/Content/Maps/Hub.umap"),
+        entry_path("../../../FactoryGame/", "Content/Maps/Hub.umap")
+    );
+    assert_eq!(
+        PathBuf::from("Content/Hero.uasset"),
+        entry_path("../../../", "../Content/Hero.uasset")
+    );
+}
+
+/// A pak opened for indexing.
+struct OpenPak {
+    path: PathBuf,
+    reader: repak::PakReader,
+    entries: HashSet<String>,
+}
+
+impl OpenPak {
+    /// Reads the whole of `entry` into memory. Each call opens the archive
+    /// again so entries can be read from several threads.
+    fn read(&self, entry: &str) -> Result<Vec<u8>, Error> {
+        let file = File::open(&self.path).map_err(|err| Error::Open(self.path.clone(), err))?;
+        self.reader
+            .get(entry, &mut BufReader::new(file))
+            .map_err(|err| Error::Pak(PathBuf::from(entry), err))
+    }
+}
+
+/// Reads the asset `entry` and its `.uexp`, if the pak has one, and indexes
+/// them as if they were the files at `asset_path`.
+fn index_entry(
+    pak: &OpenPak,
+    entry: &str,
+    asset_path: &Path,
+    main_dir: &Path,
+    style_root: &Path,
+    options: &IndexOptions,
+) -> Result<AssetSummary, Error> {
+    let uasset = pak.read(entry)?;
+    let uexp_entry = Path::new(entry)
+        .with_extension("uexp")
+        .to_string_lossy()
+        .to_string();
+    let uexp = if pak.entries.contains(&uexp_entry) {
+        let uexp = pak.read(&uexp_entry)?;
+        let len = uexp.len() as u64;
+        Some((Cursor::new(uexp), len))
+    } else {
+        None
+    };
+    let source = AssetSource {
+        uasset_len: uasset.len() as u64,
+        uasset: Cursor::new(uasset),
+        uexp,
+    };
+    // Paths inside a pak are relative to its mount point.
+    index_source(
+        asset_path,
+        Path::new(""),
+        source,
+        None,
+        main_dir,
+        style_root,
+        options,
+    )
+}
+
+/// Indexes the assets inside the `.pak` at `path` into the options' output
+/// directory, or into a directory next to the pak named after it, laid out
+/// by their paths inside the archive. `--pak-filter` limits which assets are
+/// indexed; `--incremental` doesn't apply.
+pub fn index_pak(path: &Path, options: &IndexOptions) -> Result<IndexReport, Error> {
+    options.log(format_args!("Indexing pak: {}", path.display()));
+    let out_dir = options
+        .output_dir
+        .clone()
+        .unwrap_or_else(|| path.with_extension(""));
+    let out_dir = out_dir.as_path();
+    manifest::remove_stale_temp_files(out_dir);
+    let mut builder = repak::PakBuilder::new();
+    if let Some(key) = &options.aes_key {
+        builder = builder.key(aes::Aes256::new(&key.0.into()));
+    }
+    let file = File::open(path).map_err(|err| Error::Open(path.to_path_buf(), err))?;
+    let reader = builder
+        .reader(&mut BufReader::new(file))
+        .map_err(|err| Error::OpenPak(path.to_path_buf(), err))?;
+    let mount_point = reader.mount_point().to_string();
+    let pak = OpenPak {
+        path: path.to_path_buf(),
+        entries: reader.files().into_iter().collect(),
+        reader,
+    };
+
+    let mut assets: Vec<(&String, PathBuf)> = pak
+        .entries
+        .iter()
+        .map(|entry| (entry, entry_path(&mount_point, entry)))
+        .filter(|(_, asset_path)| is_valid_extension(asset_path.extension(), &options.extensions))
+        .filter(|(_, asset_path)| {
+            let asset_path = asset_path.to_string_lossy().replace('\\', "/");
+            options.pak_filter.is_empty()
+                || options
+                    .pak_filter
+                    .iter()
+                    .any(|pattern| matches_wildcard(pattern, &asset_path))
+        })
+        .collect();
+    // Sorted like `collect_dir` does, so the `unique_dir_name` suffixes are
+    // stable across runs.
+    assets.sort_by(|a, b| a.1.cmp(&b.1));
+
+    // Every directory on the way to an asset, with the subdirectories it
+    // leads to. The root sorts first, as `write_tree_pages` expects.
+    let mut tree: BTreeMap<PathBuf, BTreeSet<String>> = BTreeMap::new();
+    tree.insert(PathBuf::new(), BTreeSet::new());
+    let mut used_dir_names: HashMap<PathBuf, HashSet<String>> = HashMap::new();
+    let mut files = Vec::with_capacity(assets.len());
+    let mut entries = Vec::with_capacity(assets.len());
+    for (entry, asset_path) in assets {
+        let parent: PathBuf = asset_path
+            .parent()
+            .unwrap_or(Path::new(""))
+            .components()
+            .map(|component| sanitize_dir_name(&component.as_os_str().to_string_lossy()))
+            .collect();
+        let mut dir = PathBuf::new();
+        for component in parent.components() {
+            let name = component.as_os_str().to_string_lossy().to_string();
+            tree.entry(dir.clone()).or_default().insert(name);
+            dir.push(component);
+            tree.entry(dir.clone()).or_default();
+        }
+        let dir_name = unique_dir_name(
+            sanitize_dir_name(&asset_path.file_stem().unwrap().to_string_lossy()),
+            used_dir_names.entry(parent.clone()).or_default(),
+        );
+        files.push((asset_path, out_dir.join(&parent).join(dir_name)));
+        entries.push(entry.as_str());
+    }
+    let dirs: Vec<CollectedDir> = tree
+        .into_iter()
+        .map(|(dir, subdirs)| CollectedDir {
+            out_dir: out_dir.join(dir),
+            subdirs: subdirs.into_iter().collect(),
+        })
+        .collect();
+
+    if let Some(progress) = &options.progress {
+        progress.add_total(files.len());
+    }
+    let summaries: Vec<Result<AssetSummary, manifest::IndexFailure>> = files
+        .par_iter()
+        .zip(&entries)
+        .map(|((asset_path, main_dir), entry)| {
+            with_progress(asset_path, options, || {
+                report_index_errors(asset_path, options, || {
+                    index_entry(&pak, entry, asset_path, main_dir, out_dir, options)
+                })
+            })
+        })
+        .collect();
+    write_tree_pages(
+        Path::new(""),
+        out_dir,
+        &files,
+        &dirs,
+        summaries,
+        Vec::new(),
+        options,
+    )
+    .map_err(|err| Error::Dir(path.to_path_buf(), err))
+}
@@ -0,0 +1,158 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// How far a run over many assets has got, shown as a numbered line per
+/// asset or, when stderr is a terminal, as a bar redrawn on stderr. Shared by
+/// the threads indexing the assets.
+pub struct Progress {
+    total: AtomicUsize,
+    started: AtomicUsize,
+    finished: AtomicUsize,
+    failed: AtomicUsize,
+    start: Instant,
+    bar: bool,
+}
+
+impl Progress {
+    /// Starts counting towards `total` assets, found by a walk like
+    /// `count_assets` before indexing begins.
+    pub fn new(total: usize, bar: bool) -> Self {
+        Progress {
+            total: AtomicUsize::new(total),
+            started: AtomicUsize::new(0),
+            finished: AtomicUsize::new(0),
+            failed: AtomicUsize::new(0),
+            start: Instant::now(),
+            bar,
+        }
+    }
+
+    /// Adds assets the walk couldn't count, such as the entries of a `.pak`.
+    pub fn add_total(&self, count: usize) {
+        self.total.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// How many of the assets finished so far failed.
+    pub fn failed(&self) -> usize {
+        self.failed.load(Ordering::Relaxed)
+    }
+
+    /// Shows that the asset at `path` is being indexed, returning the line
+    /// to log when there's no bar.
+    pub(crate) fn start(&self, path: &Path) -> Option<String> {
+        let n = self.started.fetch_add(1, Ordering::Relaxed) + 1;
+        let total = self.total.load(Ordering::Relaxed).max(n);
+        let finished = self.finished.load(Ordering::Relaxed);
+        let eta = (finished > 0).then(|| {
+            self.start
+                .elapsed()
+                .mul_f64((total - finished) as f64 / finished as f64)
+        });
+        let line = progress_line(
+            n,
+            total,
+            self.failed(),
+            &path.file_name().unwrap_or_default().to_string_lossy(),
+            eta,
+        );
+        if self.bar {
+            // One call, so threads can't interleave within the line.
+            eprint!("\r\x1b[K{} {}", bar(finished, total), line);
+            None
+        } else {
+            Some(line)
+        }
+    }
+
+    /// Counts an asset `start` showed as done.
+    pub(crate) fn finish(&self, succeeded: bool) {
+        if !succeeded {
+            self.failed.fetch_add(1, Ordering::Relaxed);
+        }
+        self.finished.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Ends the bar's line, so whatever is printed next starts on its own.
+    pub fn done(&self) {
+        if self.bar && self.started.load(Ordering::Relaxed) > 0 {
+            eprintln!();
+        }
+    }
+}
+
+/// `3m 05s`, `1h 02m` or `45s`.
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 3600 {
+        format!("{}h {:02}m", secs / 3600, secs % 3600 / 60)
+    } else if secs >= 60 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+#[test]
+fn test_format_duration() {
+    assert_eq!("45s", format_duration(Duration::from_secs(45)));
+    assert_eq!("3m 05s", format_duration(Duration::from_secs(185)));
+    assert_eq!("1h 02m", format_duration(Duration::from_secs(3725)));
+}
+
+/// `[1234/8765] Indexing Foo.uasset (3 failed, ETA 4m 05s)`.
+fn progress_line(
+    n: usize,
+    total: usize,
+    failed: usize,
+    file_name: &str,
+    eta: Option<Duration>,
+) -> String {
+    let mut notes = Vec::new();
+    if failed > 0 {
+        notes.push(format!("{} failed", failed));
+    }
+    if let Some(eta) = eta {
+        notes.push(format!("ETA {}", format_duration(eta)));
+    }
+    let notes = if notes.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", notes.join(", "))
+    };
+    format!("[{}/{}] Indexing {}{}", n, total, file_name, notes)
+}
+
+#[test]
+fn test_progress_line() {
+    assert_eq!(
+        "[1/8765] Indexing Foo.uasset",
+        progress_line(1, 8765, 0, "Foo.uasset", None)
+    );
+    assert_eq!(
+        "[1234/8765] Indexing Hub.umap (3 failed, ETA 4m 05s)",
+        progress_line(1234, 8765, 3, "Hub.umap", Some(Duration::from_secs(245)))
+    );
+}
+
+const BAR_WIDTH: usize = 30;
+
+/// `[#########.....................]`, filled in proportion to `done` out of
+/// `total`.
+fn bar(done: usize, total: usize) -> String {
+    let filled = match total {
+        0 => BAR_WIDTH,
+        total => done.min(total) * BAR_WIDTH / total,
+    };
+    format!("[{}{}]", "#".repeat(filled), ".".repeat(BAR_WIDTH - filled))
+}
+
+#[test]
+fn test_bar() {
+    assert_eq!(format!("[{}]", ".".repeat(30)), bar(0, 10));
+    assert_eq!(
+        format!("[{}{}]", "#".repeat(15), ".".repeat(15)),
+        bar(5, 10)
+    );
+    assert_eq!(format!("[{}]", "#".repeat(30)), bar(10, 10));
+}
@@ -0,0 +1,225 @@
+use unreal_asset::{
+    properties::{
+        int_property::BytePropertyValue, soft_path_property::SoftObjectPathPropertyValue,
+        str_property::TextHistoryType, Property, PropertyDataTrait,
+    },
+    types::{fname::FName, SoftObjectPath},
+};
+
+/// A short, human-readable rendering of a scalar property's value, or `None`
+/// for properties that don't have one (objects, structs, containers and types
+/// without a dedicated rendering).
+pub fn scalar_value(property: &Property) -> Option<String> {
+    let value = match property {
+        Property::BoolProperty(p) => p.value.to_string(),
+        Property::Int8Property(p) => p.value.to_string(),
+        Property::Int16Property(p) => p.value.to_string(),
+        Property::IntProperty(p) => p.value.to_string(),
+        Property::Int64Property(p) => p.value.to_string(),
+        Property::UInt16Property(p) => p.value.to_string(),
+        Property::UInt32Property(p) => p.value.to_string(),
+        Property::UInt64Property(p) => p.value.to_string(),
+        Property::FloatProperty(p) => p.value.0.to_string(),
+        Property::DoubleProperty(p) => p.value.0.to_string(),
+        Property::StrProperty(p) => p.value.clone().unwrap_or_default(),
+        Property::NameProperty(p) => p.value.get_owned_content(),
+        Property::EnumProperty(p) => p.value.as_ref()?.get_owned_content(),
+        Property::ByteProperty(p) => match &p.value {
+            BytePropertyValue::Byte(byte) => byte.to_string(),
+            BytePropertyValue::FName(name) => name.get_owned_content(),
+        },
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// The tag name of a `GameplayTag` struct, which holds it as its only
+/// member.
+fn gameplay_tag(members: &[Property]) -> Option<String> {
+    match members {
+        [Property::NameProperty(p)] if p.get_name().get_owned_content() == "TagName" => {
+            Some(p.value.get_owned_content())
+        }
+        _ => None,
+    }
+}
+
+fn has_struct_type(struct_type: &Option<FName>, name: &str) -> bool {
+    struct_type
+        .as_ref()
+        .is_some_and(|struct_type| struct_type.get_owned_content() == name)
+}
+
+/// The dotted tags of a `GameplayTagContainer` or `GameplayTag` property,
+/// e.g. `Ability.Melee.Heavy`, leaving out unset ones. `None` for any other
+/// property, and for tag structs that don't have the expected members.
+pub fn gameplay_tags(property: &Property) -> Option<Vec<String>> {
+    let tags = match property {
+        Property::GameplayTagContainerProperty(p) => {
+            p.value.iter().map(|tag| tag.get_owned_content()).collect()
+        }
+        Property::StructProperty(p) if has_struct_type(&p.struct_type, "GameplayTag") => {
+            vec![gameplay_tag(&p.value)?]
+        }
+        // Containers read without being special-cased hold an array of tags.
+        Property::StructProperty(p) if has_struct_type(&p.struct_type, "GameplayTagContainer") => {
+            let [Property::ArrayProperty(array)] = p.value.as_slice() else {
+                return None;
+            };
+            array
+                .value
+                .iter()
+                .map(|element| match element {
+                    Property::NameProperty(name) => Some(name.value.get_owned_content()),
+                    Property::StructProperty(tag) => gameplay_tag(&tag.value),
+                    _ => None,
+                })
+                .collect::<Option<Vec<String>>>()?
+        }
+        _ => return None,
+    };
+    Some(tags.into_iter().filter(|tag| tag != "None").collect())
+}
+
+/// What a text property holds, for the kinds of text history worth showing
+/// as more than a dump.
+#[derive(Debug, PartialEq)]
+pub enum LocalizedText {
+    /// Text localized under a namespace and key, with the source string
+    /// translations start from.
+    Localized {
+        namespace: String,
+        key: String,
+        source: String,
+    },
+    /// Text that is never translated.
+    CultureInvariant(String),
+    /// An entry of a string table, e.g. `/Game/Text/ST_Dialogue.ST_Dialogue`.
+    StringTableEntry { table: String, key: String },
+}
+
+/// The text a `TextProperty` holds, or `None` for any other property and for
+/// the history types that format other texts.
+pub fn localized_text(property: &Property) -> Option<LocalizedText> {
+    let Property::TextProperty(p) = property else {
+        return None;
+    };
+    let text = match p.history_type {
+        // The key is read into `value` and the source string into
+        // `culture_invariant_string`.
+        TextHistoryType::Base => LocalizedText::Localized {
+            namespace: p.namespace.clone().unwrap_or_default(),
+            key: p.value.clone().unwrap_or_default(),
+            source: p.culture_invariant_string.clone().unwrap_or_default(),
+        },
+        TextHistoryType::None => {
+            LocalizedText::CultureInvariant(p.culture_invariant_string.clone().unwrap_or_default())
+        }
+        TextHistoryType::StringTableEntry => LocalizedText::StringTableEntry {
+            table: p.table_id.as_ref()?.get_owned_content(),
+            key: p.value.clone().unwrap_or_default(),
+        },
+        _ => return None,
+    };
+    Some(text)
+}
+
+/// Formats a soft object path the way the engine prints it, e.g.
+/// `/Game/Maps/Hub.Hub:PersistentLevel.Actor_3`.
+pub fn format_soft_path(package: &str, asset: &str, sub_path: Option<&str>) -> String {
+    let mut path = package.to_string();
+    if !asset.is_empty() {
+        path += ".";
+        path += asset;
+    }
+    if let Some(sub_path) = sub_path.filter(|sub_path| !sub_path.is_empty()) {
+        path += ":";
+        path += sub_path;
+    }
+    path
+}
+
+#[test]
+fn test_format_soft_path() {
+    assert_eq!(
+        "/Game/Maps/Hub.Hub:PersistentLevel.Actor_3",
+        format_soft_path("/Game/Maps/Hub", "Hub", Some("PersistentLevel.Actor_3"))
+    );
+    assert_eq!(
+        "/Game/Weapons/Rifle.Rifle",
+        format_soft_path("/Game/Weapons/Rifle", "Rifle", Some(""))
+    );
+    assert_eq!("/Game/Loose", format_soft_path("/Game/Loose", "", None));
+}
+
+fn soft_object_path_string(path: &SoftObjectPath) -> String {
+    format_soft_path(
+        &path.asset_path.package_name.get_owned_content(),
+        &path.asset_path.asset_name.get_owned_content(),
+        path.sub_path_string.as_deref(),
+    )
+}
+
+fn soft_path_value_string(value: &SoftObjectPathPropertyValue) -> Option<String> {
+    match value {
+        SoftObjectPathPropertyValue::Old(path) => path.clone(),
+        SoftObjectPathPropertyValue::New(path) => Some(soft_object_path_string(path)),
+    }
+}
+
+/// The path a soft object or class property points at, if it's set, or the
+/// string table a text property takes its text from.
+pub fn soft_reference(property: &Property) -> Option<String> {
+    if let Some(LocalizedText::StringTableEntry { table, .. }) = localized_text(property) {
+        return Some(table).filter(|table| !table.is_empty() && table != "None");
+    }
+    let path = match property {
+        Property::SoftObjectProperty(p) => Some(soft_object_path_string(&p.value)),
+        Property::SoftObjectPathProperty(p) => soft_path_value_string(&p.value),
+        Property::SoftClassPathProperty(p) => soft_path_value_string(&p.value),
+        Property::SoftAssetPathProperty(p) => soft_path_value_string(&p.value),
+        _ => None,
+    };
+    path.filter(|path| !path.is_empty() && path != "None")
+}
+
+/// Calls `visit` with `property` and every property inside it, descending
+/// into structs and containers, along with where it sits below `path`:
+/// struct members are `path.Member`, elements `path[2]`, and map entries
+/// `path[2].key` and `path[2].value`.
+pub fn walk_property(property: &Property, path: &str, visit: &mut impl FnMut(&str, &Property)) {
+    visit(path, property);
+    match property {
+        Property::StructProperty(p) => {
+            for member in &p.value {
+                let member_path = format!("{}.{}", path, member.get_name().get_owned_content());
+                walk_property(member, &member_path, visit);
+            }
+        }
+        Property::ArrayProperty(p) => {
+            for (i, element) in p.value.iter().enumerate() {
+                walk_property(element, &format!("{}[{}]", path, i), visit);
+            }
+        }
+        Property::SetProperty(p) => {
+            for (i, element) in p.value.value.iter().enumerate() {
+                walk_property(element, &format!("{}[{}]", path, i), visit);
+            }
+        }
+        Property::MapProperty(p) => {
+            for (i, (key, value)) in p.value.iter().enumerate() {
+                walk_property(key, &format!("{}[{}].key", path, i), visit);
+                walk_property(value, &format!("{}[{}].value", path, i), visit);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Appends every non-empty soft object or class path inside `property`,
+/// descending into structs and containers.
+pub fn collect_soft_references(property: &Property, paths: &mut Vec<String>) {
+    walk_property(property, "", &mut |_, property| {
+        paths.extend(soft_reference(property));
+    });
+}
@@ -0,0 +1,163 @@
+use rayon::prelude::*;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    check,
+    import_table::{self, ImportEntry},
+    is_valid_extension, pak, DirWalk, Error, IndexOptions,
+};
+
+/// What `uasset-index find-references` looks for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReferenceQuery {
+    /// Every import from the package, e.g. `/Game/Characters/Hero/Hero_BP`.
+    Package(String),
+    /// The import of one object, e.g.
+    /// `/Game/Characters/Hero/Hero_BP.Hero_BP_C`.
+    Object(String),
+}
+
+impl ReferenceQuery {
+    /// Matches the object `path` names, or with `package_only` or when it
+    /// names no object, anything in its package.
+    pub fn new(path: &str, package_only: bool) -> Self {
+        let package = path.split(['.', ':']).next().unwrap_or_default();
+        if package_only || package.len() == path.len() {
+            ReferenceQuery::Package(package.to_string())
+        } else {
+            ReferenceQuery::Object(path.to_string())
+        }
+    }
+
+    /// Whether the import at `object_path` is one the query looks for.
+    /// Package and object paths are case-insensitive, as in the engine.
+    fn matches(&self, object_path: &str) -> bool {
+        match self {
+            ReferenceQuery::Package(package) => object_path
+                .split(['.', ':'])
+                .next()
+                .is_some_and(|own| own.eq_ignore_ascii_case(package)),
+            ReferenceQuery::Object(path) => object_path.eq_ignore_ascii_case(path),
+        }
+    }
+}
+
+#[test]
+fn test_reference_query() {
+    let package = ReferenceQuery::new("/Game/Hero/Hero_BP", false);
+    assert_eq!(
+        ReferenceQuery::Package("/Game/Hero/Hero_BP".to_string()),
+        package
+    );
+    assert!(package.matches("/Game/Hero/Hero_BP"));
+    assert!(package.matches("/game/hero/hero_bp.Hero_BP_C"));
+    assert!(!package.matches("/Game/Hero/Hero_BP_Child.Hero_BP_Child_C"));
+
+    let object = ReferenceQuery::new("/Game/Hero/Hero_BP.Hero_BP_C", false);
+    assert!(object.matches("/Game/Hero/Hero_BP.Hero_BP_C"));
+    assert!(!object.matches("/Game/Hero/Hero_BP"));
+    assert!(!object.matches("/Game/Hero/Hero_BP.Default__Hero_BP_C"));
+    assert_eq!(
+        ReferenceQuery::Package("/Game/Hero/Hero_BP".to_string()),
+        ReferenceQuery::new("/Game/Hero/Hero_BP.Hero_BP_C", true)
+    );
+}
+
+/// An import that matched, as `--json` prints it.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Reference {
+    pub asset: PathBuf,
+    pub index: i32,
+    pub object_path: String,
+    pub class_package: String,
+    pub class_name: String,
+}
+
+/// The imports of an asset that `query` matches.
+fn asset_references(
+    asset: &Path,
+    imports: &[ImportEntry],
+    query: &ReferenceQuery,
+) -> Vec<Reference> {
+    let mut references = Vec::new();
+    for (i, import) in imports.iter().enumerate() {
+        let index = -(i as i32 + 1);
+        let Some(object_path) = import_table::import_object_path(imports, index) else {
+            continue;
+        };
+        if query.matches(&object_path) {
+            references.push(Reference {
+                asset: asset.to_path_buf(),
+                index,
+                object_path,
+                class_package: import.class_package.clone(),
+                class_name: import.class_name.clone(),
+            });
+        }
+    }
+    references
+}
+
+/// What `find_references` found.
+#[derive(Debug, Default)]
+pub struct ReferenceReport {
+    pub references: Vec<Reference>,
+    pub failed: usize,
+}
+
+impl ReferenceReport {
+    pub fn add(&mut self, other: ReferenceReport) {
+        self.references.extend(other.references);
+        self.failed += other.failed;
+    }
+}
+
+/// Finds the imports `query` matches in the asset at `path`, or in every
+/// asset under the directory `path`, reading nothing but their import
+/// tables. Assets that can't be read are reported on stderr.
+pub fn find_references(
+    path: &Path,
+    options: &IndexOptions,
+    query: &ReferenceQuery,
+) -> ReferenceReport {
+    let mut assets = Vec::new();
+    if pak::is_pak(path) {
+        eprintln!(
+            "{}: find-references doesn't read .pak files",
+            path.display()
+        );
+        return ReferenceReport {
+            references: Vec::new(),
+            failed: 1,
+        };
+    } else if path.is_dir() {
+        let mut walk = DirWalk::new(path, options);
+        check::find_assets(path, &mut walk, &mut assets);
+    } else if !is_valid_extension(path.extension(), &options.extensions) {
+        eprintln!("{}", Error::InvalidExtension(path.to_path_buf()));
+        return ReferenceReport {
+            references: Vec::new(),
+            failed: 1,
+        };
+    } else {
+        assets.push(path.to_path_buf());
+    }
+    let results: Vec<std::io::Result<Vec<ImportEntry>>> = assets
+        .par_iter()
+        .map(|asset| import_table::read_import_table_of(asset, options.engine_version_for(asset)))
+        .collect();
+    let mut report = ReferenceReport::default();
+    for (asset, result) in assets.iter().zip(results) {
+        match result {
+            Ok(imports) => report
+                .references
+                .extend(asset_references(asset, &imports, query)),
+            Err(err) => {
+                report.failed += 1;
+                eprintln!("failed to read imports of {}: {}", asset.display(), err);
+            }
+        }
+    }
+    report
+}
@@ -0,0 +1,189 @@
+use serde::Serialize;
+use std::io::{BufWriter, Read, Result, Seek};
+use std::path::Path;
+use unreal_asset::{
+    exports::{Export, ExportBaseTrait, ExportNormalTrait},
+    Asset,
+};
+
+use crate::{display_name, export_class_name, manifest::OutputFiles, properties, IndexNames};
+
+/// One export or import as listed in `search.json`.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct SearchEntry {
+    /// `export` or `import`.
+    pub kind: &'static str,
+    /// The package index, negative for imports.
+    pub index: i32,
+    pub name: String,
+    pub class: String,
+    /// An export's object path, e.g. `/Game/Maps/Hub.Hub:PersistentLevel`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub object_path: Option<String>,
+    /// The entry's page relative to the asset's main directory, or `None`
+    /// for exports `--filter-class` left without one.
+    pub page: Option<String>,
+    /// The gameplay tags in an export's properties, in the order they're
+    /// found and without repeats.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+/// The gameplay tags anywhere in `export`'s properties.
+fn export_tags(export: &Export) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    let Some(normal) = export.get_normal_export() else {
+        return tags;
+    };
+    for property in &normal.properties {
+        properties::walk_property(property, "", &mut |_, property| {
+            for tag in properties::gameplay_tags(property).unwrap_or_default() {
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
+        });
+    }
+    tags
+}
+
+/// Lists the exports and then the imports of the asset in package order.
+pub fn search_entries<C: Read + Seek>(
+    asset: &Asset<C>,
+    names: &IndexNames,
+    export_pages: &[bool],
+    object_paths: &[Option<String>],
+) -> Vec<SearchEntry> {
+    let exports = asset
+        .asset_data
+        .exports
+        .iter()
+        .enumerate()
+        .map(|(i, export)| {
+            let base = export.get_base_export();
+            SearchEntry {
+                kind: "export",
+                index: i as i32 + 1,
+                name: display_name(&base.object_name),
+                class: export_class_name(names, base.class_index.index).to_string(),
+                object_path: object_paths[i].clone(),
+                page: export_pages[i].then(|| format!("exports/{}", i + 1)),
+                tags: export_tags(export),
+            }
+        });
+    let imports = asset
+        .imports
+        .iter()
+        .enumerate()
+        .map(|(i, import)| SearchEntry {
+            kind: "import",
+            index: -(i as i32 + 1),
+            name: display_name(&import.object_name),
+            class: import.class_name.get_owned_content(),
+            object_path: None,
+            page: Some(format!("imports/{}", i + 1)),
+            tags: Vec::new(),
+        });
+    exports.chain(imports).collect()
+}
+
+/// Writes `entries` to `search.json` in `main_dir`.
+pub fn write_search_json(
+    entries: &[SearchEntry],
+    main_dir: &Path,
+    outputs: &mut OutputFiles,
+) -> Result<()> {
+    let file = BufWriter::new(outputs.create(&main_dir.join("search.json"))?);
+    serde_json::to_writer(file, entries)?;
+    Ok(())
+}
+
+/// The search box of an asset's main page. The entries are inlined rather
+/// than fetched from `search.json`, since browsers refuse to fetch from
+/// `file://` pages.
+pub fn search_box_html(entries: &[SearchEntry]) -> String {
+    // `</` can't appear inside a script element, and JSON allows `<\/`.
+    let data = serde_json::to_string(entries)
+        .unwrap()
+        .replace("</", "<\\/");
+    format!(
+        "<p><input id=\"search\" type=\"search\" placeholder=\"Search exports and imports\" \
+         autocomplete=\"off\" size=\"40\"></p>\
+         <ul id=\"search-results\"></ul>\
+         <script>const searchEntries = {};\n{}</script>",
+        data, SEARCH_SCRIPT
+    )
+}
+
+const SEARCH_SCRIPT: &str = r#"(function () {
+  const input = document.getElementById("search");
+  const results = document.getElementById("search-results");
+  const limit = 100;
+  input.addEventListener("input", function () {
+    results.replaceChildren();
+    const query = input.value.trim().toLowerCase();
+    if (query === "") {
+      return;
+    }
+    const matches = searchEntries.filter(function (entry) {
+      return entry.name.toLowerCase().includes(query) ||
+        entry.class.toLowerCase().includes(query) ||
+        (entry.object_path || "").toLowerCase().includes(query) ||
+        (entry.tags || []).some(function (tag) {
+          return tag.toLowerCase().includes(query);
+        });
+    });
+    for (const entry of matches.slice(0, limit)) {
+      const item = document.createElement("li");
+      const label = entry.index + " (" + entry.name + ")";
+      if (entry.page === null) {
+        item.textContent = label;
+      } else {
+        const link = document.createElement("a");
+        link.href = entry.page;
+        link.textContent = label;
+        item.appendChild(link);
+      }
+      item.appendChild(document.createTextNode(" " + entry.kind + ", " + entry.class));
+      results.appendChild(item);
+    }
+    if (matches.length > limit) {
+      const more = document.createElement("li");
+      more.textContent = (matches.length - limit) + " more matches";
+      results.appendChild(more);
+    }
+  });
+})();"#;
+
+#[test]
+fn test_search_box_html() {
+    let entries = vec![
+        SearchEntry {
+            kind: "export",
+            index: 1,
+            name: "Door</script>".to_string(),
+            class: "StaticMesh".to_string(),
+            object_path: Some("/Game/Hub.Hub:Door</script>".to_string()),
+            page: Some("exports/1".to_string()),
+            tags: vec!["Door.Locked".to_string()],
+        },
+        SearchEntry {
+            kind: "import",
+            index: -1,
+            name: "CoreUObject".to_string(),
+            class: "Package".to_string(),
+            object_path: None,
+            page: Some("imports/1".to_string()),
+            tags: Vec::new(),
+        },
+    ];
+    let html = search_box_html(&entries);
+    assert!(html.contains(
+        "const searchEntries = [{\"kind\":\"export\",\"index\":1,\"name\":\"Door<\\/script>\""
+    ));
+    assert!(html.contains(
+        "\"object_path\":\"/Game/Hub.Hub:Door<\\/script>\",\"page\":\"exports/1\",\"tags\":[\"Door.Locked\"]}"
+    ));
+    assert!(html.contains("\"class\":\"Package\",\"page\":\"imports/1\"}];"));
+    assert_eq!(1, html.matches("</script>").count());
+}
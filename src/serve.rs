@@ -0,0 +1,244 @@
+use std::io::{BufRead, BufReader, Result, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::{compress, IndexOptions};
+
+/// Address `--serve` listens on when none is given.
+pub const DEFAULT_ADDR: &str = "127.0.0.1:8000";
+
+/// The `Content-Type` for a generated file.
+fn content_type(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase());
+    match ext.as_deref() {
+        Some("html") => "text/html; charset=utf-8",
+        Some("json") => "application/json",
+        Some("txt") | Some("dot") | Some("yaml") => "text/plain; charset=utf-8",
+        Some("css") => "text/css",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        _ => "application/octet-stream",
+    }
+}
+
+#[test]
+fn test_content_type() {
+    assert_eq!(
+        "text/html; charset=utf-8",
+        content_type(Path::new("a/index.html"))
+    );
+    assert_eq!("application/json", content_type(Path::new("asset.json")));
+    assert_eq!(
+        "application/octet-stream",
+        content_type(Path::new(".uasset-index"))
+    );
+}
+
+fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// Maps the path of a request URL onto a file or directory under `root`.
+/// Anything that would escape `root` is refused.
+fn resolve_request_path(root: &Path, url_path: &str) -> Option<PathBuf> {
+    let url_path = url_path.split(['?', '#']).next().unwrap();
+    let decoded = percent_decode(url_path)?;
+    let mut path = root.to_path_buf();
+    for component in Path::new(decoded.trim_start_matches('/')).components() {
+        match component {
+            Component::Normal(name) => path.push(name),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    Some(path)
+}
+
+#[test]
+fn test_resolve_request_path() {
+    let root = Path::new("out");
+    assert_eq!(
+        Some(PathBuf::from("out/Hero/exports/1")),
+        resolve_request_path(root, "/Hero/exports/1/")
+    );
+    assert_eq!(
+        Some(PathBuf::from("out/My Asset/index.html")),
+        resolve_request_path(root, "/My%20Asset/index.html?x=1")
+    );
+    assert_eq!(Some(PathBuf::from("out")), resolve_request_path(root, "/"));
+    assert_eq!(None, resolve_request_path(root, "/../secret"));
+    assert_eq!(
+        None,
+        resolve_request_path(root, "/Hero/%2e%2e/%2e%2e/secret")
+    );
+}
+
+/// Whether a request header line says the client takes gzipped bodies.
+fn accepts_gzip(header: &str) -> bool {
+    let Some((name, value)) = header.split_once(':') else {
+        return false;
+    };
+    name.trim().eq_ignore_ascii_case("accept-encoding")
+        && value
+            .split(',')
+            .any(|coding| coding.split(';').next().unwrap().trim() == "gzip")
+}
+
+#[test]
+fn test_accepts_gzip() {
+    assert!(accepts_gzip("Accept-Encoding: gzip, deflate, br\r\n"));
+    assert!(accepts_gzip("accept-encoding: br;q=1.0, gzip;q=0.8\r\n"));
+    assert!(!accepts_gzip("Accept-Encoding: identity\r\n"));
+    assert!(!accepts_gzip("Accept: text/html, gzip\r\n"));
+}
+
+fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    headers: &[(&str, &str)],
+    body: &[u8],
+) -> Result<()> {
+    write!(stream, "HTTP/1.1 {}\r\n", status)?;
+    for (name, value) in headers {
+        write!(stream, "{}: {}\r\n", name, value)?;
+    }
+    write!(
+        stream,
+        "Content-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(body)?;
+    stream.flush()
+}
+
+fn handle_connection(mut stream: TcpStream, root: &Path) -> Result<()> {
+    let mut request_line = String::new();
+    let mut gzip = false;
+    {
+        let mut reader = BufReader::new(&stream);
+        reader.read_line(&mut request_line)?;
+        // Drain the headers; only Accept-Encoding changes the response.
+        let mut header = String::new();
+        while reader.read_line(&mut header)? > 2 {
+            gzip |= accepts_gzip(&header);
+            header.clear();
+        }
+    }
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return respond(&mut stream, "400 Bad Request", &[], b"Bad request");
+    };
+    if method != "GET" && method != "HEAD" {
+        return respond(
+            &mut stream,
+            "405 Method Not Allowed",
+            &[("Allow", "GET, HEAD")],
+            b"Method not allowed",
+        );
+    }
+    let Some(mut path) = resolve_request_path(root, target) else {
+        return respond(&mut stream, "403 Forbidden", &[], b"Forbidden");
+    };
+    if path.is_dir() {
+        // The generated pages link relative to their directory, so directory
+        // URLs need the trailing slash.
+        let url_path = target.split(['?', '#']).next().unwrap();
+        if !url_path.ends_with('/') {
+            let location = format!("{}/", url_path);
+            return respond(
+                &mut stream,
+                "301 Moved Permanently",
+                &[("Location", &location)],
+                b"",
+            );
+        }
+        path.push("index.html");
+    }
+    let gz = compress::gz_path(&path);
+    // Pages written with --compress are only there as their `.gz`, sent as
+    // it is to clients that take gzip.
+    let (contents, encoding) = if !path.is_file() && gz.is_file() && gzip {
+        (std::fs::read(&gz), Some("gzip"))
+    } else if !path.is_file() && gz.is_file() {
+        (compress::read_page(&path).map(String::into_bytes), None)
+    } else {
+        (std::fs::read(&path), None)
+    };
+    match contents {
+        Ok(contents) => {
+            let body: &[u8] = if method == "HEAD" { b"" } else { &contents };
+            let mut headers = vec![("Content-Type", content_type(&path))];
+            if let Some(encoding) = encoding {
+                headers.push(("Content-Encoding", encoding));
+            }
+            respond(&mut stream, "200 OK", &headers, body)
+        }
+        Err(_) => respond(&mut stream, "404 Not Found", &[], b"Not found"),
+    }
+}
+
+/// Serves the files under `root` on `addr` until Ctrl-C is pressed. Where
+/// it listens goes to the log of `options`.
+pub fn serve(root: &Path, addr: &str, options: &IndexOptions) -> Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    let local_addr = listener.local_addr()?;
+    options.log(format_args!(
+        "Serving {} at http://{}/ (press Ctrl-C to stop)",
+        root.display(),
+        local_addr
+    ));
+
+    let stopping = Arc::new(AtomicBool::new(false));
+    {
+        let stopping = stopping.clone();
+        // Connecting to ourselves wakes up the blocking accept below.
+        let wake_addr = match local_addr.ip() {
+            IpAddr::V4(ip) if ip.is_unspecified() => {
+                SocketAddr::new(Ipv4Addr::LOCALHOST.into(), local_addr.port())
+            }
+            IpAddr::V6(ip) if ip.is_unspecified() => {
+                SocketAddr::new(Ipv6Addr::LOCALHOST.into(), local_addr.port())
+            }
+            _ => local_addr,
+        };
+        ctrlc::set_handler(move || {
+            stopping.store(true, Ordering::SeqCst);
+            _ = TcpStream::connect(wake_addr);
+        })
+        .map_err(std::io::Error::other)?;
+    }
+
+    for stream in listener.incoming() {
+        if stopping.load(Ordering::SeqCst) {
+            break;
+        }
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let root = root.to_path_buf();
+        std::thread::spawn(move || {
+            if let Err(err) = handle_connection(stream, &root) {
+                eprintln!("failed to serve request: {}", err);
+            }
+        });
+    }
+    options.log(format_args!("Stopped serving."));
+    Ok(())
+}
@@ -0,0 +1,289 @@
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashSet};
+use std::io::{BufWriter, Read, Result, Seek, Write};
+use unreal_asset::{
+    exports::{Export, ExportBaseTrait, ExportNormalTrait},
+    Asset,
+};
+
+use crate::{
+    class_default_object_badge, crossref, datatable, display_name, escape_html, exports_by_size,
+    flags, formatters, group_exports_by_class, import_classes, import_header_html, index_annotator,
+    index_href, kismet, manifest, neighborhood, object_path_html, out_of_range_warnings,
+    package_root_html, package_summary_html, referenced_by_html, serial_html, template_chain_html,
+    top_level_exports, truncated_dump_html, unreferenced_badge, user_defined_enums,
+    write_linked_dump, HtmlContext, LinkTarget, PageStats, LARGEST_EXPORTS,
+};
+
+/// Writes everything `write_html` spreads over a folder of pages into one
+/// `<asset>.html` in `main_dir`: the summary, the import and export listings
+/// and a `<section>` per import and export, linked by `#export-<n>` and
+/// `#import-<n>` fragments. The stylesheet is inlined so the file can be
+/// passed around on its own. Returns the same warnings as `write_html`.
+pub(crate) fn write_single_file<C: Read + Seek>(
+    asset: &Asset<C>,
+    context: &HtmlContext,
+    outputs: &mut manifest::OutputFiles,
+    stats: &mut PageStats,
+) -> Result<Vec<String>> {
+    let HtmlContext {
+        names,
+        export_pages,
+        object_paths,
+        referrers,
+        references,
+        unreferenced_imports,
+        soft_references,
+        uasset_name,
+        main_dir,
+        file_name,
+        parse_info,
+        options,
+        ..
+    } = *context;
+    let page_name = format!(
+        "{}.html",
+        main_dir.file_name().unwrap_or_default().to_string_lossy()
+    );
+    let mut file = BufWriter::new(outputs.create(&main_dir.join(page_name))?);
+    file.write_all(b"<style>")?;
+    file.write_all(&options.stylesheet)?;
+    write!(
+        file,
+        "</style><h1>{}</h1>{}",
+        escape_html(uasset_name),
+        package_summary_html(
+            parse_info,
+            asset.imports.len(),
+            unreferenced_imports.len(),
+            asset.asset_data.exports.len(),
+            &asset.package_guid.0
+        )
+    )?;
+
+    let out_of_range = RefCell::new(BTreeSet::new());
+    let annotate_index = index_annotator(names, export_pages, LinkTarget::Sections, &out_of_range);
+    let index_href = index_href(export_pages, LinkTarget::Sections);
+
+    let classes: Vec<i32> = asset
+        .asset_data
+        .exports
+        .iter()
+        .map(|export| export.get_base_export().class_index.index)
+        .collect();
+    let user_enums = user_defined_enums(names, &classes);
+    let templates: Vec<i32> = asset
+        .asset_data
+        .exports
+        .iter()
+        .map(|export| export.get_base_export().template_index.index)
+        .collect();
+    let sizes: Vec<i64> = asset
+        .asset_data
+        .exports
+        .iter()
+        .map(|export| export.get_base_export().serial_size)
+        .collect();
+    if !sizes.is_empty() {
+        file.write_all(b"<h2>Largest exports</h2><ol>")?;
+        for i in exports_by_size(&sizes).into_iter().take(LARGEST_EXPORTS) {
+            write!(
+                file,
+                "<li>{} {}</li>",
+                annotate_index(i as i32 + 1),
+                formatters::format_size(sizes[i])
+            )?;
+        }
+        file.write_all(b"</ol>")?;
+    }
+
+    file.write_all(b"<h2>Exports</h2>")?;
+    let top_level = top_level_exports(&names.export_outers);
+    if !top_level.is_empty() {
+        file.write_all(b"<h3>Top-level exports</h3><ul>")?;
+        for i in top_level {
+            write!(file, "<li>{}</li>", annotate_index(i as i32 + 1))?;
+        }
+        file.write_all(b"</ul>")?;
+    }
+    for (class, exports) in group_exports_by_class(names, &classes) {
+        write!(
+            file,
+            "<h3>{} ({})</h3><ul>",
+            escape_html(&class),
+            exports.len()
+        )?;
+        for i in exports {
+            let base = asset.asset_data.exports[i].get_base_export();
+            write!(
+                file,
+                "<li>{}{} <code>{}</code> {}</li>",
+                annotate_index(i as i32 + 1),
+                class_default_object_badge(base.object_flags.bits()),
+                flags::decode_object_flags(base.object_flags.bits()),
+                serial_html(base)
+            )?;
+        }
+        file.write_all(b"</ul>")?;
+    }
+    file.write_all(b"<h2>Imports</h2><ul>")?;
+    let unreferenced: HashSet<i32> = unreferenced_imports.iter().copied().collect();
+    for i in 0..asset.imports.len() {
+        let index = -(i as i32 + 1);
+        write!(
+            file,
+            "<li>{}{}</li>",
+            annotate_index(index),
+            unreferenced_badge(&unreferenced, index)
+        )?;
+    }
+    file.write_all(b"</ul>")?;
+
+    for (i, export) in asset.asset_data.exports.iter().enumerate() {
+        if !export_pages[i] {
+            continue;
+        }
+        let index = i as i32 + 1;
+        file.write_all(section_start(index, &names.label(index).unwrap_or_default()).as_bytes())?;
+        file.write_all(object_path_html(object_paths[i].as_deref()).as_bytes())?;
+        file.write_all(template_chain_html(&templates, index, annotate_index).as_bytes())?;
+        file.write_all(
+            neighborhood::neighborhood_html(
+                names,
+                index,
+                referrers.get(&index),
+                references.get(&index),
+                index_href,
+                annotate_index,
+            )
+            .as_bytes(),
+        )?;
+        file.write_all(referenced_by_html(referrers.get(&index), annotate_index).as_bytes())?;
+        if !soft_references[i].is_empty() {
+            let items: String = soft_references[i]
+                .iter()
+                .map(|path| crossref::soft_reference_item(path))
+                .collect();
+            write!(file, "<h2>Soft references</h2><ul>{}</ul>", items)?;
+        }
+        if let Some(normal) = export.get_normal_export() {
+            if !normal.properties.is_empty() {
+                // The page stays self-contained, so long strings are only
+                // cut, their full values left to asset.json.
+                let limits = formatters::PropertyLimits {
+                    max_elements: options.max_array_elements,
+                    max_string_bytes: options.max_string_bytes,
+                    export: index,
+                    strings_href: None,
+                };
+                file.write_all(
+                    formatters::properties_html(
+                        &normal.properties,
+                        annotate_index,
+                        &user_enums,
+                        &limits,
+                    )
+                    .as_bytes(),
+                )?;
+            }
+        }
+        if let Some(script) = kismet::function_script(export) {
+            file.write_all(b"<h2>Script</h2>")?;
+            let anchor_prefix = format!("export-{}-", index);
+            let offsets = kismet::statement_offsets(asset, &script);
+            kismet::write_script(
+                &mut file,
+                &script,
+                offsets.as_deref(),
+                &anchor_prefix,
+                annotate_index,
+            )?;
+        }
+        if let Export::DataTableExport(data_table) = export {
+            file.write_all(b"<h2>Table</h2>")?;
+            datatable::write_table(&mut file, data_table, annotate_index, &user_enums)?;
+        }
+        file.write_all(b"<span style=\"white-space-collapse:preserve;font-family:monospace\">")?;
+        let package_root = package_root_html(LinkTarget::Sections);
+        let truncated = write_linked_dump(
+            &mut file,
+            export,
+            annotate_index,
+            options.collapse_depth,
+            Some(&package_root),
+            Some(options.max_dump_size),
+        )?;
+        file.write_all(b"</span>")?;
+        if truncated {
+            file.write_all(truncated_dump_html(options.max_dump_size).as_bytes())?;
+            stats.truncated_dumps.push(index);
+        }
+        file.write_all(b"</section>")?;
+    }
+    let classes = import_classes(asset);
+    for (i, import) in asset.imports.iter().enumerate() {
+        let index = -(i as i32 + 1);
+        file.write_all(section_start(index, &display_name(&import.object_name)).as_bytes())?;
+        file.write_all(
+            import_header_html(names, &classes, index, LinkTarget::Sections).as_bytes(),
+        )?;
+        file.write_all(referenced_by_html(referrers.get(&index), annotate_index).as_bytes())?;
+        file.write_all(b"<span style=\"white-space-collapse:preserve;font-family:monospace\">")?;
+        write_linked_dump(
+            &mut file,
+            import,
+            annotate_index,
+            options.collapse_depth,
+            None,
+            None,
+        )?;
+        file.write_all(b"</span></section>")?;
+    }
+    file.flush()?;
+    Ok(out_of_range_warnings(file_name, out_of_range))
+}
+
+/// The opening of the `<section>` of the export or import at `index`, with
+/// the id the page's links go to and a heading naming it `name`.
+fn section_start(index: i32, name: &str) -> String {
+    let kind = if index < 0 { "Import" } else { "Export" };
+    format!(
+        "<section id=\"{id}\"><h2>{kind} {index} ({name})</h2>",
+        id = &LinkTarget::Sections.href(index)[1..],
+        kind = kind,
+        index = index,
+        name = escape_html(name)
+    )
+}
+
+#[test]
+fn test_section_start() {
+    assert_eq!(
+        "<section id=\"export-3\"><h2>Export 3 (Door &amp; Frame)</h2>",
+        section_start(3, "Door & Frame")
+    );
+    assert_eq!(
+        "<section id=\"import-2\"><h2>Import -2 (Texture2D)</h2>",
+        section_start(-2, "Texture2D")
+    );
+}
+
+#[test]
+fn test_section_links() {
+    let names = crate::test_index_names();
+    let out_of_range = RefCell::new(BTreeSet::new());
+    let annotate_index = index_annotator(&names, &[true], LinkTarget::Sections, &out_of_range);
+    // Every link lands on a section the page has.
+    for index in [1, -2] {
+        let html = annotate_index(index);
+        let id = html
+            .strip_prefix("<a href=\"#")
+            .and_then(|rest| rest.split('"').next())
+            .unwrap();
+        assert!(section_start(index, "").starts_with(&format!("<section id=\"{}\">", id)));
+    }
+    // Exports left without a section by `--filter-class` aren't linked.
+    let annotate_index = index_annotator(&names, &[false], LinkTarget::Sections, &out_of_range);
+    assert_eq!("1 (Default__Foo : Texture2D)", annotate_index(1));
+    assert!(out_of_range.borrow().is_empty());
+}
@@ -0,0 +1,400 @@
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufWriter, Result, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::IndexReport;
+
+/// Where the time writing an asset's pages went. Recording it costs a few
+/// clock reads per asset, so it's always done; `--timings` shows it.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PhaseTimes {
+    pub export_pages: Duration,
+    pub import_pages: Duration,
+    /// The asset's main page, the export and import listings, `search.json`
+    /// and `level.html`.
+    pub listing_pages: Duration,
+}
+
+/// The pages `--dedupe-pages` wrote as redirects to an identical one, and
+/// how many bytes smaller the redirects are than the pages would have been.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct DedupedPages {
+    pub pages: usize,
+    pub bytes_saved: u64,
+}
+
+impl DedupedPages {
+    pub fn add(&mut self, other: DedupedPages) {
+        self.pages += other.pages;
+        self.bytes_saved += other.bytes_saved;
+    }
+}
+
+/// An export whose dump stopped at `--max-dump-size`, by its index.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TruncatedDump {
+    pub path: PathBuf,
+    pub export: i32,
+}
+
+/// How long one asset took, split into parsing and writing its output.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AssetTiming {
+    pub path: PathBuf,
+    pub parse: Duration,
+    pub write: Duration,
+    /// The part of `write` spent on HTML pages.
+    pub phases: PhaseTimes,
+}
+
+impl AssetTiming {
+    pub fn total(&self) -> Duration {
+        self.parse + self.write
+    }
+}
+
+/// The phases of `--timings`, in seconds. `other_secs` is the rest of the
+/// time writing, such as `asset.json`, the text dumps and the CSV tables.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct PhaseSecs {
+    pub parse_secs: f64,
+    pub export_pages_secs: f64,
+    pub import_pages_secs: f64,
+    pub listing_pages_secs: f64,
+    pub other_secs: f64,
+}
+
+impl PhaseSecs {
+    fn of(timing: &AssetTiming) -> Self {
+        let phases = &timing.phases;
+        let pages = phases.export_pages + phases.import_pages + phases.listing_pages;
+        PhaseSecs {
+            parse_secs: timing.parse.as_secs_f64(),
+            export_pages_secs: phases.export_pages.as_secs_f64(),
+            import_pages_secs: phases.import_pages.as_secs_f64(),
+            listing_pages_secs: phases.listing_pages.as_secs_f64(),
+            other_secs: timing.write.saturating_sub(pages).as_secs_f64(),
+        }
+    }
+
+    fn add(&mut self, other: &PhaseSecs) {
+        self.parse_secs += other.parse_secs;
+        self.export_pages_secs += other.export_pages_secs;
+        self.import_pages_secs += other.import_pages_secs;
+        self.listing_pages_secs += other.listing_pages_secs;
+        self.other_secs += other.other_secs;
+    }
+
+    /// The phases with their names, in the order the breakdown lists them.
+    fn named(&self) -> [(&'static str, f64); 5] {
+        [
+            ("parsing", self.parse_secs),
+            ("export pages", self.export_pages_secs),
+            ("import pages", self.import_pages_secs),
+            ("listing pages", self.listing_pages_secs),
+            ("other output", self.other_secs),
+        ]
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct AssetPhases {
+    pub path: String,
+    #[serde(flatten)]
+    pub phases: PhaseSecs,
+}
+
+/// The `--timings` breakdown: every asset's phases, by path, and their sum.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Timings {
+    pub total: PhaseSecs,
+    pub assets: Vec<AssetPhases>,
+}
+
+impl Timings {
+    fn new(timings: &[AssetTiming]) -> Self {
+        let mut assets: Vec<AssetPhases> = timings
+            .iter()
+            .map(|timing| AssetPhases {
+                path: timing.path.display().to_string(),
+                phases: PhaseSecs::of(timing),
+            })
+            .collect();
+        assets.sort_by(|a, b| a.path.cmp(&b.path));
+        let mut total = PhaseSecs::default();
+        for asset in &assets {
+            total.add(&asset.phases);
+        }
+        Timings { total, assets }
+    }
+
+    fn table(&self) -> String {
+        let sum: f64 = self.total.named().iter().map(|(_, secs)| secs).sum();
+        let mut table = "Time per phase\n".to_string();
+        for (name, secs) in self.total.named() {
+            let percent = if sum > 0.0 { secs / sum * 100.0 } else { 0.0 };
+            table += &format!("  {:<14} {:>7.2}s {:>5.1}%\n", name, secs, percent);
+        }
+        table += "Time per asset (parsing, export pages, import pages, listing pages, other)\n";
+        for asset in &self.assets {
+            let secs: Vec<String> = asset
+                .phases
+                .named()
+                .iter()
+                .map(|(_, secs)| format!("{:.2}s", secs))
+                .collect();
+            table += &format!("  {}  {}\n", asset.path, secs.join(" "));
+        }
+        table
+    }
+}
+
+/// How many of the slowest assets the summary lists.
+const SLOWEST: usize = 5;
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct SlowAsset {
+    pub path: String,
+    pub parse_secs: f64,
+    pub write_secs: f64,
+}
+
+/// What `--stats-json` writes and the end of a run prints.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct RunStats {
+    pub files_indexed: usize,
+    pub files_failed: usize,
+    pub exports: usize,
+    pub imports: usize,
+    pub html_bytes: u64,
+    pub elapsed_secs: f64,
+    /// Summed over every asset, so across threads they can add up to more
+    /// than `elapsed_secs`.
+    pub parse_secs: f64,
+    pub write_secs: f64,
+    pub slowest: Vec<SlowAsset>,
+    pub deduped: DedupedPages,
+    pub truncated_dumps: Vec<TruncatedDump>,
+    /// Only with `--timings`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timings: Option<Timings>,
+}
+
+impl RunStats {
+    /// Tallies `report`, which took `elapsed`. Assets `--incremental` found
+    /// up to date count as indexed but took no time.
+    pub fn new(report: &IndexReport, elapsed: Duration) -> Self {
+        let html_bytes = report
+            .generated
+            .iter()
+            .filter(|file| file.path.extension().is_some_and(|ext| ext == "html"))
+            .filter_map(|file| std::fs::metadata(&file.path).ok())
+            .map(|metadata| metadata.len())
+            .sum();
+        let mut timings: Vec<&AssetTiming> = report.timings.iter().collect();
+        timings.sort_by(|a, b| b.total().cmp(&a.total()).then(a.path.cmp(&b.path)));
+        RunStats {
+            files_indexed: report.succeeded,
+            files_failed: report.failed,
+            exports: report.exports,
+            imports: report.imports,
+            html_bytes,
+            elapsed_secs: elapsed.as_secs_f64(),
+            parse_secs: timings.iter().map(|t| t.parse.as_secs_f64()).sum(),
+            write_secs: timings.iter().map(|t| t.write.as_secs_f64()).sum(),
+            slowest: timings
+                .into_iter()
+                .take(SLOWEST)
+                .map(|timing| SlowAsset {
+                    path: timing.path.display().to_string(),
+                    parse_secs: timing.parse.as_secs_f64(),
+                    write_secs: timing.write.as_secs_f64(),
+                })
+                .collect(),
+            deduped: report.deduped,
+            truncated_dumps: {
+                let mut truncated = report.truncated_dumps.clone();
+                truncated.sort_by(|a, b| a.path.cmp(&b.path).then(a.export.cmp(&b.export)));
+                truncated
+            },
+            timings: None,
+        }
+    }
+
+    /// Adds the per-phase breakdown of `report` for `--timings`.
+    pub fn with_timings(self, report: &IndexReport) -> Self {
+        RunStats {
+            timings: Some(Timings::new(&report.timings)),
+            ..self
+        }
+    }
+
+    /// The table printed at the end of a run.
+    pub fn summary_table(&self) -> String {
+        let mut table = format!(
+            "Files indexed  {}\n\
+             Files failed   {}\n\
+             Exports        {}\n\
+             Imports        {}\n\
+             HTML written   {}\n\
+             Elapsed        {:.2}s (parsing {:.2}s, writing {:.2}s)\n",
+            self.files_indexed,
+            self.files_failed,
+            self.exports,
+            self.imports,
+            crate::formatters::format_size(self.html_bytes as i64),
+            self.elapsed_secs,
+            self.parse_secs,
+            self.write_secs
+        );
+        if self.deduped.pages > 0 {
+            table += &format!(
+                "Deduplicated   {} pages, {} saved\n",
+                self.deduped.pages,
+                crate::formatters::format_size(self.deduped.bytes_saved as i64)
+            );
+        }
+        if !self.truncated_dumps.is_empty() {
+            table += "Truncated dumps (raise --max-dump-size to see them whole)\n";
+            for truncated in &self.truncated_dumps {
+                table += &format!(
+                    "  {} export {}\n",
+                    truncated.path.display(),
+                    truncated.export
+                );
+            }
+        }
+        if !self.slowest.is_empty() {
+            table += "Slowest assets\n";
+            for asset in &self.slowest {
+                table += &format!(
+                    "  {:>7.2}s  {} (parsing {:.2}s)\n",
+                    asset.parse_secs + asset.write_secs,
+                    asset.path,
+                    asset.parse_secs
+                );
+            }
+        }
+        if let Some(timings) = &self.timings {
+            table += &timings.table();
+        }
+        table
+    }
+
+    pub fn write_json(&self, path: &Path) -> Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut file, self)?;
+        file.flush()
+    }
+}
+
+#[test]
+fn test_run_stats() {
+    let timing = |path: &str, parse: u64, write: u64| AssetTiming {
+        path: PathBuf::from(path),
+        parse: Duration::from_millis(parse),
+        write: Duration::from_millis(write),
+        ..Default::default()
+    };
+    let report = IndexReport {
+        succeeded: 7,
+        failed: 1,
+        exports: 120,
+        imports: 45,
+        timings: vec![
+            timing("a.uasset", 100, 100),
+            timing("b.uasset", 500, 250),
+            timing("c.uasset", 10, 0),
+            timing("d.uasset", 300, 0),
+            timing("e.uasset", 0, 50),
+            timing("f.uasset", 1000, 0),
+            timing("g.uasset", 20, 0),
+        ],
+        ..Default::default()
+    };
+    let stats = RunStats::new(&report, Duration::from_secs(3));
+    let slowest: Vec<&str> = stats.slowest.iter().map(|a| a.path.as_str()).collect();
+    assert_eq!(
+        vec!["f.uasset", "b.uasset", "d.uasset", "a.uasset", "e.uasset"],
+        slowest
+    );
+    assert!((stats.parse_secs - 1.93).abs() < 1e-9);
+    let table = stats.summary_table();
+    assert!(table.starts_with("Files indexed  7\nFiles failed   1\nExports        120\n"));
+    assert!(table.contains("HTML written   0 B\n"));
+    assert!(table.contains("Elapsed        3.00s (parsing 1.93s, writing 0.40s)\n"));
+    assert!(table.contains("     1.00s  f.uasset (parsing 1.00s)\n"));
+    assert!(!table.contains("Deduplicated"));
+
+    let report = IndexReport {
+        deduped: DedupedPages {
+            pages: 12,
+            bytes_saved: 3072,
+        },
+        ..Default::default()
+    };
+    let table = RunStats::new(&report, Duration::from_secs(1)).summary_table();
+    assert!(table.contains("Deduplicated   12 pages, 3.0 KiB saved\n"));
+    assert!(!table.contains("Truncated dumps"));
+
+    let truncated = |path: &str, export: i32| TruncatedDump {
+        path: PathBuf::from(path),
+        export,
+    };
+    let report = IndexReport {
+        truncated_dumps: vec![truncated("b.uasset", 2), truncated("a.uasset", 7)],
+        ..Default::default()
+    };
+    let table = RunStats::new(&report, Duration::from_secs(1)).summary_table();
+    assert!(table.contains(
+        "Truncated dumps (raise --max-dump-size to see them whole)\n  \
+         a.uasset export 7\n  b.uasset export 2\n"
+    ));
+}
+
+#[test]
+fn test_run_stats_timings() {
+    let report = IndexReport {
+        succeeded: 2,
+        timings: vec![
+            AssetTiming {
+                path: PathBuf::from("Hub.umap"),
+                parse: Duration::from_millis(600),
+                write: Duration::from_millis(1000),
+                phases: PhaseTimes {
+                    export_pages: Duration::from_millis(700),
+                    import_pages: Duration::from_millis(100),
+                    listing_pages: Duration::from_millis(100),
+                },
+            },
+            AssetTiming {
+                path: PathBuf::from("Hero.uasset"),
+                parse: Duration::from_millis(400),
+                write: Duration::from_millis(100),
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+    let stats = RunStats::new(&report, Duration::from_secs(2));
+    assert_eq!(None, stats.timings);
+    let stats = stats.with_timings(&report);
+    let timings = stats.timings.as_ref().unwrap();
+    assert_eq!(
+        vec!["Hero.uasset", "Hub.umap"],
+        timings
+            .assets
+            .iter()
+            .map(|asset| asset.path.as_str())
+            .collect::<Vec<_>>()
+    );
+    assert!((timings.total.other_secs - 0.2).abs() < 1e-9);
+    let table = stats.summary_table();
+    assert!(table.contains("  parsing           1.00s  47.6%\n"));
+    assert!(table.contains("  export pages      0.70s  33.3%\n"));
+    assert!(table.contains("  Hub.umap  0.60s 0.70s 0.10s 0.10s 0.10s\n"));
+    let json = serde_json::to_value(&stats).unwrap();
+    assert_eq!(0.7, json["timings"]["assets"][1]["export_pages_secs"]);
+}
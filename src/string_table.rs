@@ -0,0 +1,68 @@
+use unreal_asset::{base::types::PackageIndex, exports::string_table_export::StringTableExport};
+
+use crate::escape_html;
+
+/// The namespace of a StringTable export and its entries, key and source
+/// string, in the order they were saved.
+pub fn table_entries(export: &StringTableExport<PackageIndex>) -> (&str, Vec<(&str, &str)>) {
+    let namespace = export.table.namespace.as_deref().unwrap_or_default();
+    let entries = export
+        .table
+        .value
+        .iter()
+        .map(|(key, source)| (key.as_str(), source.as_str()))
+        .collect();
+    (namespace, entries)
+}
+
+/// The `id` of the row for `key` in `string_table_html`, usable as a URL
+/// fragment as it is: everything but letters, digits and `-_.~` is
+/// percent-encoded.
+pub fn key_anchor(key: &str) -> String {
+    let mut anchor = "key-".to_string();
+    for byte in key.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.~".contains(&byte) {
+            anchor.push(byte as char);
+        } else {
+            anchor += &format!("%{:02X}", byte);
+        }
+    }
+    anchor
+}
+
+#[test]
+fn test_key_anchor() {
+    assert_eq!("key-NPC_Greeting_03", key_anchor("NPC_Greeting_03"));
+    assert_eq!("key-Say%20%22hi%22%C3%A9", key_anchor("Say \"hi\"é"));
+}
+
+/// A string table as a table of keys and source strings, each row with a
+/// `key_anchor` other assets' texts link to.
+pub fn string_table_html(namespace: &str, entries: &[(&str, &str)]) -> String {
+    let mut html = format!(
+        "<h2>String table</h2><p>Namespace: <code>{}</code></p>\
+         <table><tr><th>Key</th><th>Source string</th></tr>",
+        escape_html(namespace)
+    );
+    for (key, source) in entries {
+        html += &format!(
+            "<tr id=\"{}\"><td><code>{}</code></td><td>{}</td></tr>",
+            escape_html(&key_anchor(key)),
+            escape_html(key),
+            escape_html(source)
+        );
+    }
+    html += "</table>";
+    html
+}
+
+#[test]
+fn test_string_table_html() {
+    assert_eq!(
+        "<h2>String table</h2><p>Namespace: <code>Dialogue</code></p>\
+         <table><tr><th>Key</th><th>Source string</th></tr>\
+         <tr id=\"key-Greeting\"><td><code>Greeting</code></td><td>Hello, &lt;traveler&gt;.</td></tr>\
+         </table>",
+        string_table_html("Dialogue", &[("Greeting", "Hello, <traveler>.")])
+    );
+}
@@ -0,0 +1,236 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufWriter, Result, Write};
+use std::path::Path;
+use unreal_asset::{
+    exports::{Export, ExportNormalTrait},
+    properties::{Property, PropertyDataTrait},
+};
+
+use crate::{
+    csv,
+    manifest::OutputFiles,
+    properties::{self, LocalizedText},
+};
+
+/// `--extract-strings`: which strings `strings.txt` lists, and how.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExtractStrings {
+    /// Lists each distinct string of an asset once, with how often it
+    /// occurs.
+    pub dedupe: bool,
+    /// Keeps the short and binary-looking strings `is_readable` leaves out.
+    pub unfiltered: bool,
+}
+
+/// Strings with fewer characters than this, besides surrounding whitespace,
+/// are left out unless `--strings-unfiltered` is given.
+pub const MIN_STRING_LENGTH: usize = 3;
+
+/// A string found in an export, as `strings.txt` and `strings.csv` list it.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct StringEntry {
+    pub export: i32,
+    /// Where the property sits in the export, e.g. `Lines[2].Text`.
+    pub property: String,
+    pub value: String,
+    /// How often the value occurs in the asset with `dedupe`, otherwise 1.
+    pub count: usize,
+}
+
+/// The human-readable string a property holds: the contents of string and
+/// name properties and the source string of texts.
+fn string_value(property: &Property) -> Option<String> {
+    match property {
+        Property::StrProperty(p) => p.value.clone(),
+        Property::NameProperty(p) => Some(p.value.get_owned_content()),
+        property => match properties::localized_text(property)? {
+            LocalizedText::Localized { source, .. } | LocalizedText::CultureInvariant(source) => {
+                Some(source)
+            }
+            LocalizedText::StringTableEntry { .. } => None,
+        },
+    }
+}
+
+/// Whether `value` looks like something people read: at least
+/// `MIN_STRING_LENGTH` characters long, without control characters other
+/// than line breaks and tabs.
+pub fn is_readable(value: &str) -> bool {
+    value.trim().chars().count() >= MIN_STRING_LENGTH
+        && !value
+            .chars()
+            .any(|c| c.is_control() && !matches!(c, '\n' | '\r' | '\t'))
+}
+
+#[test]
+fn test_is_readable() {
+    assert!(is_readable("Hello, traveler."));
+    assert!(is_readable("Line one\nLine two"));
+    assert!(!is_readable("HP"));
+    assert!(!is_readable("  \t "));
+    assert!(!is_readable("\u{1}\u{7f}K\u{3}"));
+}
+
+/// Every string in the properties of `export`, the export at `index`. Empty
+/// strings are always left out.
+pub fn export_strings(export: &Export, index: i32, options: ExtractStrings) -> Vec<StringEntry> {
+    let mut strings = Vec::new();
+    let Some(normal) = export.get_normal_export() else {
+        return strings;
+    };
+    for property in &normal.properties {
+        let name = property.get_name().get_owned_content();
+        properties::walk_property(property, &name, &mut |path, property| {
+            let Some(value) = string_value(property) else {
+                return;
+            };
+            if value.is_empty() || !(options.unfiltered || is_readable(&value)) {
+                return;
+            }
+            strings.push(StringEntry {
+                export: index,
+                property: path.to_string(),
+                value,
+                count: 1,
+            });
+        });
+    }
+    strings
+}
+
+/// `strings` with each value listed once, where it first occurs, counting
+/// how often it does.
+pub fn dedupe_strings(strings: Vec<StringEntry>) -> Vec<StringEntry> {
+    let mut deduped: Vec<StringEntry> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    for entry in strings {
+        match positions.get(&entry.value) {
+            Some(&i) => deduped[i].count += entry.count,
+            None => {
+                positions.insert(entry.value.clone(), deduped.len());
+                deduped.push(entry);
+            }
+        }
+    }
+    deduped
+}
+
+#[cfg(test)]
+fn string_entry(export: i32, property: &str, value: &str) -> StringEntry {
+    StringEntry {
+        export,
+        property: property.to_string(),
+        value: value.to_string(),
+        count: 1,
+    }
+}
+
+#[test]
+fn test_dedupe_strings() {
+    let deduped = dedupe_strings(vec![
+        string_entry(2, "Lines[0]", "Hello"),
+        string_entry(2, "Title", "Quest"),
+        string_entry(5, "Lines[3]", "Hello"),
+    ]);
+    assert_eq!(
+        vec![
+            StringEntry {
+                count: 2,
+                ..string_entry(2, "Lines[0]", "Hello")
+            },
+            string_entry(2, "Title", "Quest"),
+        ],
+        deduped
+    );
+}
+
+/// `2.Lines[0]: Hello, traveler.`, with line breaks escaped so every string
+/// takes one line, and ` (×3)` after a value that occurs more than once.
+fn string_line(entry: &StringEntry) -> String {
+    let value = entry
+        .value
+        .replace('\\', "\\\\")
+        .replace('\r', "\\r")
+        .replace('\n', "\\n");
+    let mut line = format!("{}.{}: {}", entry.export, entry.property, value);
+    if entry.count > 1 {
+        line += &format!(" (×{})", entry.count);
+    }
+    line
+}
+
+#[test]
+fn test_string_line() {
+    assert_eq!(
+        "2.Lines[0]: Hello,\\ntraveler.",
+        string_line(&string_entry(2, "Lines[0]", "Hello,\ntraveler."))
+    );
+    assert_eq!(
+        "5.Path: C:\\\\Game (×3)",
+        string_line(&StringEntry {
+            count: 3,
+            ..string_entry(5, "Path", "C:\\Game")
+        })
+    );
+}
+
+/// Writes an asset's strings to `strings.txt` in `main_dir`, one per line.
+pub fn write_strings(
+    strings: &[StringEntry],
+    main_dir: &Path,
+    outputs: &mut OutputFiles,
+) -> Result<()> {
+    let mut file = BufWriter::new(outputs.create(&main_dir.join("strings.txt"))?);
+    for entry in strings {
+        writeln!(file, "{}", string_line(entry))?;
+    }
+    file.flush()
+}
+
+/// Writes `strings.csv` into `out_dir`: the strings of every asset a
+/// directory run indexed, each row starting with the asset it's in.
+pub fn write_combined_strings(
+    out_dir: &Path,
+    assets: &[(&Path, &[StringEntry])],
+    outputs: &mut OutputFiles,
+) -> Result<()> {
+    let mut file = BufWriter::new(outputs.create(&out_dir.join("strings.csv"))?);
+    file.write_all(b"asset,export,property,value,count\n")?;
+    for (asset, strings) in assets {
+        for entry in strings.iter() {
+            let fields = [
+                asset.display().to_string(),
+                entry.export.to_string(),
+                entry.property.clone(),
+                entry.value.clone(),
+                entry.count.to_string(),
+            ];
+            csv::write_row(&mut file, &fields)?;
+        }
+    }
+    file.flush()
+}
+
+#[test]
+fn test_write_combined_strings() {
+    let dir = tempfile::tempdir().unwrap();
+    let npc = [
+        string_entry(2, "Lines[0]", "Hello, traveler."),
+        string_entry(3, "Name", "Guard"),
+    ];
+    let mut outputs = OutputFiles::default();
+    write_combined_strings(
+        dir.path(),
+        &[(Path::new("Content/NPC.uasset"), &npc[..])],
+        &mut outputs,
+    )
+    .unwrap();
+    outputs.finish().unwrap();
+    assert_eq!(
+        "asset,export,property,value,count\n\
+         Content/NPC.uasset,2,Lines[0],\"Hello, traveler.\",1\n\
+         Content/NPC.uasset,3,Name,Guard,1\n",
+        std::fs::read_to_string(dir.path().join("strings.csv")).unwrap()
+    );
+}
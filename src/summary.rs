@@ -0,0 +1,857 @@
+use std::io::{Error, ErrorKind, Read, Result};
+use unreal_asset::engine_version::EngineVersion;
+
+/// The tag every package file starts with.
+pub const PACKAGE_FILE_TAG: u32 = 0x9E2A83C1;
+
+/// The version fields at the start of a package file summary.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PackageVersions {
+    pub legacy_file_version: i32,
+    pub file_version_ue4: i32,
+    pub file_version_ue5: i32,
+    pub file_version_licensee_ue4: i32,
+}
+
+impl PackageVersions {
+    /// Cooked assets are usually saved without version numbers, in which case
+    /// the engine version can't be told from the header.
+    pub fn is_unversioned(&self) -> bool {
+        self.file_version_ue4 == 0
+    }
+}
+
+pub(crate) fn read_i32<R: Read>(reader: &mut R) -> Result<i32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(i32::from_le_bytes(bytes))
+}
+
+/// Reads just the version fields of the package summary, without parsing the
+/// rest of the asset.
+pub fn read_package_versions<R: Read>(reader: &mut R) -> Result<PackageVersions> {
+    let tag = read_i32(reader)? as u32;
+    if tag != PACKAGE_FILE_TAG {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("unexpected package tag {:#010x}", tag),
+        ));
+    }
+    let legacy_file_version = read_i32(reader)?;
+    if legacy_file_version != -4 {
+        let _legacy_ue3_version = read_i32(reader)?;
+    }
+    let file_version_ue4 = read_i32(reader)?;
+    let file_version_ue5 = if legacy_file_version <= -8 {
+        read_i32(reader)?
+    } else {
+        0
+    };
+    let file_version_licensee_ue4 = read_i32(reader)?;
+    Ok(PackageVersions {
+        legacy_file_version,
+        file_version_ue4,
+        file_version_ue5,
+        file_version_licensee_ue4,
+    })
+}
+
+/// Set on packages whose properties were serialized without tags, which can
+/// only be read with mappings.
+pub const PKG_UNVERSIONED_PROPERTIES: u32 = 0x00002000;
+
+/// Set on packages compressed as a whole, which unreal_asset can't read.
+pub const PKG_STORE_COMPRESSED: u32 = 0x02000000;
+
+/// Set on packages saved without editor-only data, which cooked ones are.
+pub const PKG_FILTER_EDITOR_ONLY: u32 = 0x80000000;
+
+/// What a file that doesn't start with `PACKAGE_FILE_TAG` looks like instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BadTag {
+    /// The tag, byte-swapped: saved for a big-endian platform.
+    BigEndian,
+    /// Bytes as close to random as encrypted or compressed data is.
+    Encrypted,
+    /// Anything else, starting with this.
+    Other(u32),
+}
+
+impl std::fmt::Display for BadTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BadTag::BigEndian => f.write_str(
+                "file starts with the package tag byte-swapped, so it was saved for a \
+                 big-endian platform, which isn't supported",
+            ),
+            BadTag::Encrypted => f.write_str(
+                "file does not start with the package tag and looks like random bytes. Is it \
+                 still encrypted inside the pak? Index the .pak with --aes-key, or extract it \
+                 with the game's key first",
+            ),
+            BadTag::Other(tag) => write!(
+                f,
+                "file does not start with the package tag (found {:#010x}), so it isn't an \
+                 Unreal package",
+                tag
+            ),
+        }
+    }
+}
+
+/// Bits of information per byte of `bytes`, from 0 for one repeated byte to 8
+/// for uniformly random ones.
+fn byte_entropy(bytes: &[u8]) -> f64 {
+    let mut counts = [0usize; 256];
+    for &byte in bytes {
+        counts[byte as usize] += 1;
+    }
+    let len = bytes.len() as f64;
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Fewer bytes than this say too little about their entropy.
+const MIN_ENTROPY_SAMPLE: usize = 256;
+
+/// Tells apart why the file starting with `start`, the first few KiB of it,
+/// doesn't begin with the package tag, or `None` when it does or is too short
+/// to have a tag at all.
+pub fn diagnose_bad_tag(start: &[u8]) -> Option<BadTag> {
+    let tag = u32::from_le_bytes(start.get(..4)?.try_into().unwrap());
+    if tag == PACKAGE_FILE_TAG {
+        None
+    } else if tag == PACKAGE_FILE_TAG.swap_bytes() {
+        Some(BadTag::BigEndian)
+    } else if start.len() >= MIN_ENTROPY_SAMPLE && byte_entropy(start) > 7.0 {
+        Some(BadTag::Encrypted)
+    } else {
+        Some(BadTag::Other(tag))
+    }
+}
+
+#[test]
+fn test_diagnose_bad_tag() {
+    let mut package = PACKAGE_FILE_TAG.to_le_bytes().to_vec();
+    package.extend_from_slice(&[0; 1020]);
+    assert_eq!(None, diagnose_bad_tag(&package));
+    assert_eq!(
+        Some(BadTag::BigEndian),
+        diagnose_bad_tag(&PACKAGE_FILE_TAG.to_be_bytes())
+    );
+    // A linear congruential generator stands in for encrypted bytes.
+    let mut state: u32 = 12345;
+    let random: Vec<u8> = (0..4096)
+        .map(|_| {
+            state = state.wrapping_mul(1103515245).wrapping_add(12345);
+            (state >> 16) as u8
+        })
+        .collect();
+    assert_eq!(Some(BadTag::Encrypted), diagnose_bad_tag(&random));
+    // Too few bytes to tell.
+    assert!(matches!(
+        diagnose_bad_tag(&random[..64]),
+        Some(BadTag::Other(_))
+    ));
+    let text = "[/Script/Engine.Engine]\n".repeat(40);
+    assert_eq!(
+        Some(BadTag::Other(0x63532f5b)),
+        diagnose_bad_tag(text.as_bytes())
+    );
+    assert_eq!(None, diagnose_bad_tag(b"abc"));
+}
+
+/// An entry of the custom version container: the GUID identifying the engine
+/// subsystem or plugin, and the version it saved with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CustomVersionEntry {
+    pub guid: [u8; 16],
+    pub version: i32,
+}
+
+/// The custom versions of the engine's own subsystems, by `format_guid`.
+const KNOWN_CUSTOM_VERSIONS: &[(&str, &str)] = &[
+    (
+        "B0D832E41F894F0DACCF7EB736FD4AA2",
+        "FBlueprintsObjectVersion",
+    ),
+    ("E1C64328A22C4D53A36C8E866417BD8C", "FBuildObjectVersion"),
+    ("375EC13C06E448FBB50084F0262A717E", "FCoreObjectVersion"),
+    ("E4B068EDF49442E9A231DA0B2E46BB41", "FEditorObjectVersion"),
+    (
+        "CFFC743F43B04480939114DF171D2073",
+        "FFrameworkObjectVersion",
+    ),
+    ("B02B49B5BB2044E9A30432B752E40360", "FMobileObjectVersion"),
+    (
+        "A4E4105C59A149B5A7C540C4547EDFEE",
+        "FNetworkingObjectVersion",
+    ),
+    ("39C831C95AE647DC9A449C173E1C8E7C", "FOnlineObjectVersion"),
+    ("78F01B33EBEA4F98B9B484EACCB95AA2", "FPhysicsObjectVersion"),
+    ("6631380F2D4D43E08009CF276956A95A", "FPlatformObjectVersion"),
+    (
+        "12F88B9F88754AFCA67CD90C383ABD29",
+        "FRenderingObjectVersion",
+    ),
+    (
+        "7B5AE74CD2704431ACA02B3A0B5A4530",
+        "FSequencerObjectVersion",
+    ),
+    ("D72969181DD64BDD9DE264A83CC13884", "FVRObjectVersion"),
+    (
+        "C2A15278BFE74AFE6C1790FF531DF755",
+        "FLoadTimesObjectVersion",
+    ),
+    ("6EACA3D440EC4CC1B7868BED09428FC5", "FGeometryObjectVersion"),
+    ("29E575DDE0A346279D10D276232CDCEA", "FAnimPhysObjectVersion"),
+    ("AF43A65D7FD3494798733E8ED9C1BB05", "FAnimObjectVersion"),
+    (
+        "6B266CEC1EC74B8FA30BE4D90942FC07",
+        "FReflectionCaptureObjectVersion",
+    ),
+    (
+        "0DF73D61A23F47EAB72789E90C41499A",
+        "FAutomationObjectVersion",
+    ),
+    (
+        "9DFFBCD6494F0158E22112823C92A888",
+        "FEnterpriseObjectVersion",
+    ),
+    ("F2AED0AC9AFE416F8664AA7FFA26D6FC", "FNiagaraObjectVersion"),
+    (
+        "174F1F0BB4C645A5B13F2EE8D0FB917D",
+        "FDestructionObjectVersion",
+    ),
+    ("9C54D522A8264FBE9421074661B482D0", "FReleaseObjectVersion"),
+    (
+        "601D1886AC644F84AA16D3DE0DEAC7D6",
+        "FFortniteMainBranchObjectVersion",
+    ),
+    (
+        "E70863686B234C5884391B7016265E91",
+        "FFortniteReleaseBranchCustomObjectVersion",
+    ),
+    (
+        "697DD581E64F41ABAA4A51ECBEB7B628",
+        "FUE5MainStreamObjectVersion",
+    ),
+    (
+        "D89B5E4224BD4D468412ACA8DF641779",
+        "FUE5ReleaseStreamObjectVersion",
+    ),
+];
+
+impl CustomVersionEntry {
+    /// The name of the engine's version this is, e.g. `FCoreObjectVersion`,
+    /// or `None` for a plugin's or a game's own.
+    pub fn friendly_name(&self) -> Option<&'static str> {
+        let guid = format_guid(&self.guid);
+        KNOWN_CUSTOM_VERSIONS
+            .iter()
+            .find(|(known, _)| *known == guid)
+            .map(|(_, name)| *name)
+    }
+}
+
+#[test]
+fn test_custom_version_friendly_name() {
+    let mut guid = [0; 16];
+    for (i, word) in [0x375EC13Cu32, 0x06E448FB, 0xB50084F0, 0x262A717E]
+        .iter()
+        .enumerate()
+    {
+        guid[i * 4..i * 4 + 4].copy_from_slice(&word.to_le_bytes());
+    }
+    let entry = CustomVersionEntry { guid, version: 3 };
+    assert_eq!(Some("FCoreObjectVersion"), entry.friendly_name());
+    guid[15] ^= 1;
+    assert_eq!(
+        None,
+        CustomVersionEntry { guid, version: 3 }.friendly_name()
+    );
+}
+
+/// The leading part of the package file summary, up to the name count.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PackageHeader {
+    pub versions: PackageVersions,
+    pub custom_versions: Vec<CustomVersionEntry>,
+    pub total_header_size: i32,
+    pub folder_name: String,
+    pub package_flags: u32,
+    pub name_count: i32,
+}
+
+impl PackageHeader {
+    pub fn has_unversioned_properties(&self) -> bool {
+        self.package_flags & PKG_UNVERSIONED_PROPERTIES != 0
+    }
+
+    pub fn is_store_compressed(&self) -> bool {
+        self.package_flags & PKG_STORE_COMPRESSED != 0
+    }
+
+    pub fn is_filter_editor_only(&self) -> bool {
+        self.package_flags & PKG_FILTER_EDITOR_ONLY != 0
+    }
+
+    /// Whether the export data lives in a separate `.uexp`. Cooked, split
+    /// packages end right where the header does.
+    pub fn has_separate_export_data(&self, uasset_len: u64) -> bool {
+        self.total_header_size > 0 && uasset_len <= self.total_header_size as u64
+    }
+}
+
+fn read_guid<R: Read>(reader: &mut R) -> Result<[u8; 16]> {
+    let mut guid = [0; 16];
+    reader.read_exact(&mut guid)?;
+    Ok(guid)
+}
+
+/// Reads an FString: a length prefix counting the null terminator, negative
+/// for UTF-16 strings.
+pub(crate) fn read_fstring<R: Read>(reader: &mut R) -> Result<String> {
+    let len = read_i32(reader)?;
+    if len == 0 {
+        return Ok(String::new());
+    }
+    if len > 0 {
+        let mut bytes = vec![0; len as usize];
+        reader.read_exact(&mut bytes)?;
+        bytes.pop();
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    } else {
+        let mut bytes = vec![0; len.unsigned_abs() as usize * 2];
+        reader.read_exact(&mut bytes)?;
+        let mut units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+            .collect();
+        units.pop();
+        Ok(String::from_utf16_lossy(&units))
+    }
+}
+
+pub(crate) fn read_custom_versions<R: Read>(
+    reader: &mut R,
+    legacy_file_version: i32,
+) -> Result<Vec<CustomVersionEntry>> {
+    if legacy_file_version > -2 {
+        return Ok(Vec::new());
+    }
+    let count = read_i32(reader)?;
+    let mut custom_versions = Vec::with_capacity(count.clamp(0, 1024) as usize);
+    for _ in 0..count {
+        let entry = if legacy_file_version == -2 {
+            // The oldest format keys versions by an enum tag instead of a GUID.
+            let mut guid = [0; 16];
+            guid[..4].copy_from_slice(&read_i32(reader)?.to_le_bytes());
+            CustomVersionEntry {
+                guid,
+                version: read_i32(reader)?,
+            }
+        } else {
+            let guid = read_guid(reader)?;
+            let version = read_i32(reader)?;
+            if legacy_file_version >= -5 {
+                let _friendly_name = read_fstring(reader)?;
+            }
+            CustomVersionEntry { guid, version }
+        };
+        custom_versions.push(entry);
+    }
+    Ok(custom_versions)
+}
+
+/// Reads the package summary up to and including the package flags, which is
+/// enough to make decisions about an asset before handing it to unreal_asset.
+pub fn read_package_header<R: Read>(reader: &mut R) -> Result<PackageHeader> {
+    let versions = read_package_versions(reader)?;
+    let custom_versions = read_custom_versions(reader, versions.legacy_file_version)?;
+    let total_header_size = read_i32(reader)?;
+    let folder_name = read_fstring(reader)?;
+    let package_flags = read_i32(reader)? as u32;
+    let name_count = read_i32(reader)?;
+    Ok(PackageHeader {
+        versions,
+        custom_versions,
+        total_header_size,
+        folder_name,
+        package_flags,
+        name_count,
+    })
+}
+
+/// The object versions that added the summary fields between the name
+/// count and the thumbnail table offset.
+pub(crate) const VER_UE4_ADD_STRING_ASSET_REFERENCES_MAP: i32 = 384;
+pub(crate) const VER_UE4_SERIALIZE_TEXT_IN_PACKAGES: i32 = 459;
+pub(crate) const VER_UE4_ADDED_SEARCHABLE_NAMES: i32 = 510;
+pub(crate) const VER_UE4_ADDED_PACKAGE_SUMMARY_LOCALIZATION_ID: i32 = 516;
+pub(crate) const VER_UE5_ADD_SOFTOBJECTPATH_LIST: i32 = 1008;
+
+/// The newest UE5 object version the summary layout is known for, that of
+/// the newest release in `OBJECT_VERSIONS`. Later ones can't be vouched for:
+/// those of 5.4 and 5.5 add fields before the thumbnail table offset
+/// (METADATA_SERIALIZATION_OFFSET and VERSE_CELLS).
+pub(crate) const VER_UE5_NEWEST_KNOWN: i32 = 1009;
+
+/// Where the tables of a package are, from the summary past the name count.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TableOffsets {
+    pub name_offset: i32,
+    pub export_count: i32,
+    pub export_offset: i32,
+    pub import_count: i32,
+    pub import_offset: i32,
+    pub thumbnail_table_offset: i32,
+}
+
+/// Reads on from where `read_package_header` stopped, given what it read,
+/// to the offset of the thumbnail table. The layout depends on the object
+/// versions, so `header` must be of a versioned package.
+pub fn read_thumbnail_table_offset<R: Read>(reader: &mut R, header: &PackageHeader) -> Result<i32> {
+    let offsets = read_table_offsets(reader, header, &header.versions)?;
+    Ok(offsets.thumbnail_table_offset)
+}
+
+/// Reads on from where `read_package_header` stopped to the offset of the
+/// thumbnail table, laid out as `versions` says: the header's own, or for
+/// an unversioned package those of the engine it was saved by. Fails on a
+/// UE5 object version newer than `VER_UE5_NEWEST_KNOWN` rather than
+/// reading a wrong offset.
+pub fn read_table_offsets<R: Read>(
+    reader: &mut R,
+    header: &PackageHeader,
+    versions: &PackageVersions,
+) -> Result<TableOffsets> {
+    if versions.file_version_ue5 > VER_UE5_NEWEST_KNOWN {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "unknown UE5 object version {}, newer than {}",
+                versions.file_version_ue5, VER_UE5_NEWEST_KNOWN
+            ),
+        ));
+    }
+    let skip = |reader: &mut R, count: usize| -> Result<()> {
+        for _ in 0..count {
+            read_i32(reader)?;
+        }
+        Ok(())
+    };
+    let name_offset = read_i32(reader)?;
+    if versions.file_version_ue5 >= VER_UE5_ADD_SOFTOBJECTPATH_LIST {
+        skip(reader, 2)?;
+    }
+    if !header.is_filter_editor_only()
+        && versions.file_version_ue4 >= VER_UE4_ADDED_PACKAGE_SUMMARY_LOCALIZATION_ID
+    {
+        let _localization_id = read_fstring(reader)?;
+    }
+    if versions.file_version_ue4 >= VER_UE4_SERIALIZE_TEXT_IN_PACKAGES {
+        skip(reader, 2)?;
+    }
+    let export_count = read_i32(reader)?;
+    let export_offset = read_i32(reader)?;
+    let import_count = read_i32(reader)?;
+    let import_offset = read_i32(reader)?;
+    // The depends offset.
+    skip(reader, 1)?;
+    if versions.file_version_ue4 >= VER_UE4_ADD_STRING_ASSET_REFERENCES_MAP {
+        skip(reader, 2)?;
+    }
+    if versions.file_version_ue4 >= VER_UE4_ADDED_SEARCHABLE_NAMES {
+        skip(reader, 1)?;
+    }
+    Ok(TableOffsets {
+        name_offset,
+        export_count,
+        export_offset,
+        import_count,
+        import_offset,
+        thumbnail_table_offset: read_i32(reader)?,
+    })
+}
+
+#[test]
+fn test_read_thumbnail_table_offset() {
+    let mut summary = Vec::new();
+    for value in [PACKAGE_FILE_TAG as i32, -7, 864, 522, 0, 0, 4096] {
+        summary.extend_from_slice(&value.to_le_bytes());
+    }
+    summary.extend_from_slice(&5i32.to_le_bytes());
+    summary.extend_from_slice(b"None\0");
+    summary.extend_from_slice(&0i32.to_le_bytes());
+    // The name count and offset, then the localization ID.
+    summary.extend_from_slice(&[0; 8]);
+    summary.extend_from_slice(&3i32.to_le_bytes());
+    summary.extend_from_slice(b"ab\0");
+    // Gatherable text, exports, imports, depends, soft package references
+    // and searchable names, then the thumbnail table.
+    summary.extend_from_slice(&[0; 4 * 10]);
+    summary.extend_from_slice(&2048i32.to_le_bytes());
+
+    let mut reader = summary.as_slice();
+    let header = read_package_header(&mut reader).unwrap();
+    assert_eq!(
+        2048,
+        read_thumbnail_table_offset(&mut reader, &header).unwrap()
+    );
+    assert!(reader.is_empty());
+}
+
+#[test]
+fn test_read_table_offsets_newer_ue5() {
+    assert_eq!(
+        Some(VER_UE5_NEWEST_KNOWN),
+        OBJECT_VERSIONS.iter().map(|(_, _, ue5)| *ue5).max()
+    );
+    let header = PackageHeader {
+        versions: versions(522, VER_UE5_NEWEST_KNOWN + 1),
+        custom_versions: Vec::new(),
+        total_header_size: 0,
+        folder_name: "None".to_string(),
+        package_flags: 0,
+        name_count: 0,
+    };
+    let summary = [0; 128];
+    let err = read_table_offsets(&mut summary.as_slice(), &header, &header.versions).unwrap_err();
+    assert_eq!(ErrorKind::InvalidData, err.kind());
+}
+
+/// The object versions each engine release saves with, oldest first.
+const OBJECT_VERSIONS: &[(EngineVersion, i32, i32)] = &[
+    (EngineVersion::VER_UE4_0, 342, 0),
+    (EngineVersion::VER_UE4_1, 352, 0),
+    (EngineVersion::VER_UE4_2, 363, 0),
+    (EngineVersion::VER_UE4_3, 382, 0),
+    (EngineVersion::VER_UE4_4, 385, 0),
+    (EngineVersion::VER_UE4_5, 401, 0),
+    (EngineVersion::VER_UE4_6, 413, 0),
+    (EngineVersion::VER_UE4_7, 434, 0),
+    (EngineVersion::VER_UE4_8, 451, 0),
+    (EngineVersion::VER_UE4_9, 482, 0),
+    (EngineVersion::VER_UE4_10, 482, 0),
+    (EngineVersion::VER_UE4_11, 498, 0),
+    (EngineVersion::VER_UE4_12, 504, 0),
+    (EngineVersion::VER_UE4_13, 505, 0),
+    (EngineVersion::VER_UE4_14, 508, 0),
+    (EngineVersion::VER_UE4_15, 510, 0),
+    (EngineVersion::VER_UE4_16, 513, 0),
+    (EngineVersion::VER_UE4_17, 513, 0),
+    (EngineVersion::VER_UE4_18, 514, 0),
+    (EngineVersion::VER_UE4_19, 516, 0),
+    (EngineVersion::VER_UE4_20, 516, 0),
+    (EngineVersion::VER_UE4_21, 517, 0),
+    (EngineVersion::VER_UE4_22, 517, 0),
+    (EngineVersion::VER_UE4_23, 517, 0),
+    (EngineVersion::VER_UE4_24, 518, 0),
+    (EngineVersion::VER_UE4_25, 518, 0),
+    (EngineVersion::VER_UE4_26, 519, 0),
+    (EngineVersion::VER_UE4_27, 522, 0),
+    (EngineVersion::VER_UE5_0, 522, 1004),
+    (EngineVersion::VER_UE5_1, 522, 1008),
+    (EngineVersion::VER_UE5_2, 522, 1009),
+];
+
+/// The object versions `engine_version` saves with, for reading the tables
+/// of unversioned packages. `None` for a release that isn't listed, older
+/// or newer, whose versions can't be vouched for.
+pub fn object_versions(engine_version: EngineVersion) -> Option<PackageVersions> {
+    OBJECT_VERSIONS
+        .iter()
+        .find(|(version, _, _)| *version == engine_version)
+        .map(|(_, ue4, ue5)| PackageVersions {
+            legacy_file_version: if *ue5 > 0 { -8 } else { -7 },
+            file_version_ue4: *ue4,
+            file_version_ue5: *ue5,
+            file_version_licensee_ue4: 0,
+        })
+}
+
+/// The object versions the tables of a package are laid out with: those of
+/// its header, or for an unversioned package those `engine_version` saves
+/// with, failing when they aren't known.
+pub fn table_versions(
+    header_versions: &PackageVersions,
+    engine_version: EngineVersion,
+) -> Result<PackageVersions> {
+    if !header_versions.is_unversioned() {
+        return Ok(*header_versions);
+    }
+    object_versions(engine_version).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("no object versions known for {:?}", engine_version),
+        )
+    })
+}
+
+/// Maps serialized object versions to the newest engine release that could
+/// have written them. Returns `None` for unversioned packages or versions older
+/// than any supported release.
+pub fn detect_engine_version(versions: &PackageVersions) -> Option<EngineVersion> {
+    if versions.is_unversioned() {
+        return None;
+    }
+    OBJECT_VERSIONS
+        .iter()
+        .rev()
+        .find(|(_, ue4, ue5)| {
+            if versions.file_version_ue5 > 0 {
+                *ue5 > 0 && *ue5 <= versions.file_version_ue5
+            } else {
+                *ue5 == 0 && *ue4 <= versions.file_version_ue4
+            }
+        })
+        .map(|(engine_version, _, _)| *engine_version)
+}
+
+/// The custom versions only UE5 saves with.
+const UE5_CUSTOM_VERSIONS: &[&str] = &[
+    "FUE5MainStreamObjectVersion",
+    "FUE5ReleaseStreamObjectVersion",
+];
+
+/// What the header says saved the asset, e.g. `4.26`, when that disagrees
+/// with `parsed_as`, the engine version it was parsed with. Releases saving
+/// the same object versions read the same way, so they aren't told apart;
+/// without object versions only UE5's own custom versions give a UE5 asset
+/// away, and as they don't tell the UE5 releases apart the newest is named.
+pub fn version_mismatch(header: &PackageHeader, parsed_as: EngineVersion) -> Option<String> {
+    let expected = object_versions(parsed_as);
+    let versions = header.versions;
+    if !versions.is_unversioned() {
+        return match detect_engine_version(&versions) {
+            Some(detected) if object_versions(detected) == expected => None,
+            Some(detected) => Some(crate::engine_version_name(detected)),
+            None if versions.file_version_ue5 > 0 => Some("an early UE5 release".to_string()),
+            None => Some("older than 4.0".to_string()),
+        };
+    }
+    let ue5 = header.custom_versions.iter().any(|entry| {
+        entry
+            .friendly_name()
+            .is_some_and(|name| UE5_CUSTOM_VERSIONS.contains(&name))
+    });
+    let parsed_as_ue5 = expected.is_some_and(|versions| versions.file_version_ue5 > 0);
+    if !ue5 || parsed_as_ue5 {
+        return None;
+    }
+    OBJECT_VERSIONS
+        .iter()
+        .rev()
+        .find(|(_, _, ue5)| *ue5 > 0)
+        .map(|(engine_version, _, _)| crate::engine_version_name(*engine_version))
+}
+
+#[cfg(test)]
+fn versions(file_version_ue4: i32, file_version_ue5: i32) -> PackageVersions {
+    PackageVersions {
+        legacy_file_version: -8,
+        file_version_ue4,
+        file_version_ue5,
+        file_version_licensee_ue4: 0,
+    }
+}
+
+#[test]
+fn test_detect_engine_version() {
+    assert_eq!(
+        Some(EngineVersion::VER_UE4_27),
+        detect_engine_version(&versions(522, 0))
+    );
+    assert_eq!(
+        Some(EngineVersion::VER_UE4_23),
+        detect_engine_version(&versions(517, 0))
+    );
+    assert_eq!(
+        Some(EngineVersion::VER_UE5_1),
+        detect_engine_version(&versions(522, 1008))
+    );
+    assert_eq!(None, detect_engine_version(&versions(0, 0)));
+    assert_eq!(None, detect_engine_version(&versions(200, 0)));
+}
+
+#[test]
+fn test_version_mismatch() {
+    let header = |versions, custom_versions| PackageHeader {
+        versions,
+        custom_versions,
+        total_header_size: 0,
+        folder_name: "None".to_string(),
+        package_flags: 0,
+        name_count: 0,
+    };
+    let saved_by = |v: EngineVersion| header(object_versions(v).unwrap(), Vec::new());
+    assert_eq!(
+        None,
+        version_mismatch(
+            &saved_by(EngineVersion::VER_UE4_26),
+            EngineVersion::VER_UE4_26
+        )
+    );
+    // 4.21 to 4.23 save the same object versions.
+    assert_eq!(
+        None,
+        version_mismatch(
+            &saved_by(EngineVersion::VER_UE4_23),
+            EngineVersion::VER_UE4_21
+        )
+    );
+    assert_eq!(
+        Some("4.26".to_string()),
+        version_mismatch(
+            &saved_by(EngineVersion::VER_UE4_26),
+            EngineVersion::VER_UE5_1
+        )
+    );
+    assert_eq!(
+        Some("an early UE5 release".to_string()),
+        version_mismatch(
+            &header(versions(522, 1002), Vec::new()),
+            EngineVersion::VER_UE4_27
+        )
+    );
+    assert_eq!(
+        Some("older than 4.0".to_string()),
+        version_mismatch(
+            &header(versions(300, 0), Vec::new()),
+            EngineVersion::VER_UE4_27
+        )
+    );
+
+    let mut ue5_guid = [0; 16];
+    for (i, word) in [0x697DD581u32, 0xE64F41AB, 0xAA4A51EC, 0xBEB7B628]
+        .iter()
+        .enumerate()
+    {
+        ue5_guid[i * 4..i * 4 + 4].copy_from_slice(&word.to_le_bytes());
+    }
+    let ue5_custom = vec![CustomVersionEntry {
+        guid: ue5_guid,
+        version: 12,
+    }];
+    let unversioned = header(versions(0, 0), ue5_custom);
+    assert_eq!(
+        Some("5.2".to_string()),
+        version_mismatch(&unversioned, EngineVersion::VER_UE4_27)
+    );
+    assert_eq!(
+        None,
+        version_mismatch(&unversioned, EngineVersion::VER_UE5_0)
+    );
+    assert_eq!(
+        None,
+        version_mismatch(
+            &header(versions(0, 0), Vec::new()),
+            EngineVersion::VER_UE4_27
+        )
+    );
+}
+
+#[test]
+fn test_read_package_header() {
+    let mut header = Vec::new();
+    for value in [PACKAGE_FILE_TAG as i32, -8, 864, 0, 0, 0, 1] {
+        header.extend_from_slice(&value.to_le_bytes());
+    }
+    header.extend_from_slice(&[7; 16]);
+    header.extend_from_slice(&42i32.to_le_bytes());
+    header.extend_from_slice(&1234i32.to_le_bytes());
+    header.extend_from_slice(&5i32.to_le_bytes());
+    header.extend_from_slice(b"None\0");
+    header.extend_from_slice(&(PKG_UNVERSIONED_PROPERTIES | 0x8000_0000).to_le_bytes());
+    header.extend_from_slice(&17i32.to_le_bytes());
+
+    let header = read_package_header(&mut header.as_slice()).unwrap();
+    assert!(header.versions.is_unversioned());
+    assert_eq!(
+        vec![CustomVersionEntry {
+            guid: [7; 16],
+            version: 42
+        }],
+        header.custom_versions
+    );
+    assert_eq!(1234, header.total_header_size);
+    assert_eq!("None", header.folder_name);
+    assert!(header.has_unversioned_properties());
+    assert_eq!(17, header.name_count);
+    assert!(header.has_separate_export_data(1234));
+    assert!(!header.has_separate_export_data(5000));
+}
+
+#[test]
+fn test_read_package_versions() {
+    let mut header = Vec::new();
+    for value in [PACKAGE_FILE_TAG as i32, -8, 864, 522, 1008, 0] {
+        header.extend_from_slice(&value.to_le_bytes());
+    }
+    assert_eq!(
+        versions(522, 1008),
+        read_package_versions(&mut header.as_slice()).unwrap()
+    );
+    assert!(read_package_versions(&mut [0u8; 24].as_slice()).is_err());
+}
+
+/// Formats a GUID the way the engine does: four little-endian 32-bit words
+/// in upper-case hex.
+pub fn format_guid(guid: &[u8; 16]) -> String {
+    guid.chunks(4)
+        .map(|word| format!("{:08X}", u32::from_le_bytes(word.try_into().unwrap())))
+        .collect()
+}
+
+#[test]
+fn test_format_guid() {
+    let mut guid = [0; 16];
+    guid[..4].copy_from_slice(&0x29E575DDu32.to_le_bytes());
+    guid[12] = 0xAB;
+    assert_eq!("29E575DD0000000000000000000000AB", format_guid(&guid));
+}
+
+/// How an asset was parsed: its header, the engine version used and where
+/// that came from, and the mappings, if any.
+pub struct ParseInfo {
+    pub header: PackageHeader,
+    pub engine_version: EngineVersion,
+    /// Whether `engine_version` was detected from the header rather than
+    /// falling back to `--engine-version`.
+    pub detected: bool,
+    /// File name of the `.usmap` passed with `--usmap`.
+    pub mappings: Option<String>,
+    /// File name of the `.uexp` the export data was read from, if any.
+    pub uexp: Option<String>,
+    pub uasset_size: u64,
+    pub uexp_size: Option<u64>,
+}
+
+impl ParseInfo {
+    /// E.g. `asset appears to be 4.26 but was parsed as 5.1`, when
+    /// `version_mismatch` finds the header disagreeing with the engine
+    /// version used.
+    pub fn version_warning(&self) -> Option<String> {
+        let appears = version_mismatch(&self.header, self.engine_version)?;
+        Some(format!(
+            "asset appears to be {} but was parsed as {}",
+            appears,
+            crate::engine_version_name(self.engine_version)
+        ))
+    }
+
+    pub fn source(&self) -> &'static str {
+        if self.detected {
+            "detected"
+        } else {
+            "unversioned, from --engine-version"
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use crate::summary::{PACKAGE_FILE_TAG, PKG_FILTER_EDITOR_ONLY};
+
+/// An FName as packages save it: an index into the name map, then the
+/// instance number.
+pub fn fname_bytes(index: i32, number: i32) -> Vec<u8> {
+    [index.to_le_bytes(), number.to_le_bytes()].concat()
+}
+
+/// An FString as packages save it: its length counting the terminator, then
+/// the bytes and the terminator.
+pub fn fstring_bytes(s: &str) -> Vec<u8> {
+    let mut bytes = (s.len() as i32 + 1).to_le_bytes().to_vec();
+    bytes.extend_from_slice(s.as_bytes());
+    bytes.push(0);
+    bytes
+}
+
+/// A package for the tests, laid out the way 4.21 up to 4.27 save it: a
+/// complete summary, then, when there are any tables, `names` from offset
+/// 200, from offset 300 an import per `(outer_index, name_index, number)`
+/// of `imports`, each of class `names[1]` from package `names[0]`, and the
+/// `exports` entries from offset 400, 104 bytes apart, to 600 bytes.
+pub struct TestPackage<'a> {
+    /// 0 for an unversioned package, which reads as whichever of those
+    /// releases it's parsed with.
+    pub file_version_ue4: i32,
+    /// Each a GUID as the engine's four words, and a version.
+    pub custom_versions: &'a [([u32; 4], i32)],
+    pub total_header_size: i32,
+    pub package_flags: u32,
+    pub names: &'a [&'a str],
+    pub imports: &'a [(i32, i32, i32)],
+    pub exports: &'a [Vec<u8>],
+}
+
+impl Default for TestPackage<'_> {
+    fn default() -> Self {
+        Self {
+            file_version_ue4: 522,
+            custom_versions: &[],
+            total_header_size: 0,
+            package_flags: 0,
+            names: &[],
+            imports: &[],
+            exports: &[],
+        }
+    }
+}
+
+impl TestPackage<'_> {
+    pub fn bytes(&self) -> Vec<u8> {
+        let has_tables =
+            !self.names.is_empty() || !self.imports.is_empty() || !self.exports.is_empty();
+        let offset = |offset: i32| if has_tables { offset } else { 0 };
+        let mut package = Vec::new();
+        let push = |package: &mut Vec<u8>, values: &[i32]| {
+            for value in values {
+                package.extend_from_slice(&value.to_le_bytes());
+            }
+        };
+        push(
+            &mut package,
+            &[
+                PACKAGE_FILE_TAG as i32,
+                -7,
+                864,
+                self.file_version_ue4,
+                0,
+                self.custom_versions.len() as i32,
+            ],
+        );
+        for (guid, version) in self.custom_versions {
+            for word in guid {
+                package.extend_from_slice(&word.to_le_bytes());
+            }
+            package.extend_from_slice(&version.to_le_bytes());
+        }
+        package.extend_from_slice(&self.total_header_size.to_le_bytes());
+        package.extend(fstring_bytes("None"));
+        package.extend_from_slice(&self.package_flags.to_le_bytes());
+        push(&mut package, &[self.names.len() as i32, offset(200)]);
+        if self.package_flags & PKG_FILTER_EDITOR_ONLY == 0 {
+            // An empty localization ID.
+            push(&mut package, &[0]);
+        }
+        // No gatherable text.
+        push(&mut package, &[0, 0]);
+        push(
+            &mut package,
+            &[
+                self.exports.len() as i32,
+                offset(400),
+                self.imports.len() as i32,
+                offset(300),
+            ],
+        );
+        // Depends, soft package references, searchable names and
+        // thumbnails.
+        package.extend_from_slice(&[0; 4 * 5]);
+        // The package GUID and no generations.
+        package.extend_from_slice(&[0; 16 + 4]);
+        // Saved-by and compatible-with engine versions, without branch names.
+        package.extend_from_slice(&[0; 2 * (2 * 3 + 4 + 4)]);
+        // Compression flags, compressed chunks, package source and additional
+        // packages to cook.
+        package.extend_from_slice(&[0; 4 * 4]);
+        // Asset registry offset, bulk data start, world tile info, chunk IDs
+        // and preload dependencies.
+        package.extend_from_slice(&[0; 4 + 8 + 4 + 4 + 4 * 2]);
+        if !has_tables {
+            return package;
+        }
+        assert!(package.len() <= 200, "the summary runs into the names");
+        package.resize(200, 0);
+        for name in self.names {
+            package.extend(fstring_bytes(name));
+            // The hashes.
+            package.extend_from_slice(&0u32.to_le_bytes());
+        }
+        package.resize(300, 0);
+        for &(outer_index, name_index, number) in self.imports {
+            package.extend(fname_bytes(0, 0));
+            package.extend(fname_bytes(1, 0));
+            package.extend_from_slice(&outer_index.to_le_bytes());
+            package.extend(fname_bytes(name_index, number));
+            // The package name.
+            package.extend(fname_bytes(0, 0));
+        }
+        for (i, export) in self.exports.iter().enumerate() {
+            package.resize(400 + i * 104, 0);
+            package.extend_from_slice(export);
+        }
+        package.resize(600, 0);
+        package
+    }
+}
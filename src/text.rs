@@ -0,0 +1,137 @@
+use std::io::{BufWriter, Read, Result, Seek, Write};
+use std::path::Path;
+use unreal_asset::{exports::ExportBaseTrait, Asset};
+
+use crate::{
+    display_name, manifest::OutputFiles, try_create_dir, write_annotated_dump, IndexNames,
+};
+
+/// How a package index reads in the text dumps, e.g. `-3 (Texture2D)`.
+pub fn annotate_index(names: &IndexNames, index: i32) -> String {
+    if index == 0 {
+        return "0 (null)".to_string();
+    }
+    match names.get(index) {
+        Some(name) => format!("{} ({})", index, name),
+        None => format!("{} (out of range)", index),
+    }
+}
+
+#[test]
+fn test_annotate_index() {
+    let names = crate::test_index_names();
+    assert_eq!("0 (null)", annotate_index(&names, 0));
+    assert_eq!("-2 (Texture2D)", annotate_index(&names, -2));
+    assert_eq!("1 (Default__Foo)", annotate_index(&names, 1));
+    assert_eq!("5 (out of range)", annotate_index(&names, 5));
+}
+
+/// Writes the export and import tables in package order.
+fn write_summary<W: Write>(
+    sink: &mut W,
+    names: &IndexNames,
+    exports: &[(String, i32)],
+    imports: &[(String, String)],
+) -> Result<()> {
+    writeln!(sink, "Exports:")?;
+    for (i, (name, class_index)) in exports.iter().enumerate() {
+        writeln!(
+            sink,
+            "  {} {} (class {})",
+            i + 1,
+            name,
+            annotate_index(names, *class_index)
+        )?;
+    }
+    writeln!(sink, "Imports:")?;
+    for (i, (name, class)) in imports.iter().enumerate() {
+        writeln!(sink, "  {} {} ({})", -(i as i32 + 1), name, class)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_write_summary() {
+    let names = crate::test_index_names();
+    let mut out = Vec::new();
+    write_summary(
+        &mut out,
+        &names,
+        &[("Default__Foo".to_string(), -2)],
+        &[
+            (
+                "CoreUObject".to_string(),
+                "/Script/CoreUObject.Package".to_string(),
+            ),
+            (
+                "Texture2D".to_string(),
+                "/Script/CoreUObject.Class".to_string(),
+            ),
+        ],
+    )
+    .unwrap();
+    assert_eq!(
+        "Exports:\n  1 Default__Foo (class -2 (Texture2D))\n\
+         Imports:\n  -1 CoreUObject (/Script/CoreUObject.Package)\n  -2 Texture2D (/Script/CoreUObject.Class)\n",
+        String::from_utf8(out).unwrap()
+    );
+}
+
+/// Writes the `--format text` output: `summary.txt` and one `<n>.txt` per
+/// export and import holding the annotated Debug dump. Nothing that varies
+/// between runs on the same input (paths, times) goes into the files, so they
+/// can be committed and diffed.
+pub fn write_text<C: Read + Seek>(
+    asset: &Asset<C>,
+    names: &IndexNames,
+    main_dir: &Path,
+    outputs: &mut OutputFiles,
+) -> Result<()> {
+    let exports_dir = main_dir.join("exports");
+    let imports_dir = main_dir.join("imports");
+    try_create_dir(&exports_dir)?;
+    try_create_dir(&imports_dir)?;
+
+    let annotate = |index: i32| annotate_index(names, index);
+    for (i, export) in asset.asset_data.exports.iter().enumerate() {
+        let path = exports_dir.join(format!("{}.txt", i + 1));
+        let mut file = BufWriter::new(outputs.create(&path)?);
+        write_annotated_dump(&mut file, export, annotate)?;
+        writeln!(file)?;
+        file.flush()?;
+    }
+    for (i, import) in asset.imports.iter().enumerate() {
+        let path = imports_dir.join(format!("{}.txt", i + 1));
+        let mut file = BufWriter::new(outputs.create(&path)?);
+        write_annotated_dump(&mut file, import, annotate)?;
+        writeln!(file)?;
+        file.flush()?;
+    }
+
+    let exports: Vec<(String, i32)> = asset
+        .asset_data
+        .exports
+        .iter()
+        .map(|export| {
+            let base = export.get_base_export();
+            (display_name(&base.object_name), base.class_index.index)
+        })
+        .collect();
+    let imports: Vec<(String, String)> = asset
+        .imports
+        .iter()
+        .map(|import| {
+            (
+                display_name(&import.object_name),
+                format!(
+                    "{}.{}",
+                    import.class_package.get_owned_content(),
+                    import.class_name.get_owned_content()
+                ),
+            )
+        })
+        .collect();
+    let mut file = BufWriter::new(outputs.create(&main_dir.join("summary.txt"))?);
+    write_summary(&mut file, names, &exports, &imports)?;
+    file.flush()
+}
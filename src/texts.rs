@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufWriter, Result, Write};
+use std::path::{Path, PathBuf};
+use unreal_asset::{
+    exports::{Export, ExportNormalTrait},
+    properties::PropertyDataTrait,
+};
+
+use crate::{
+    csv,
+    properties::{self, LocalizedText},
+};
+
+/// A text property found in an export, as `--texts-csv` lists it.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TextEntry {
+    pub export: i32,
+    /// Where the property sits in the export, e.g. `Lines[2].Text`.
+    pub property: String,
+    /// `localized`, `culture_invariant` or `string_table`.
+    pub kind: String,
+    pub namespace: String,
+    pub key: String,
+    pub source: String,
+    pub string_table: String,
+}
+
+/// Every text in the properties of `export`, the export at `index`.
+pub fn export_texts(export: &Export, index: i32) -> Vec<TextEntry> {
+    let mut texts = Vec::new();
+    let Some(normal) = export.get_normal_export() else {
+        return texts;
+    };
+    for property in &normal.properties {
+        let name = property.get_name().get_owned_content();
+        properties::walk_property(property, &name, &mut |path, property| {
+            let Some(text) = properties::localized_text(property) else {
+                return;
+            };
+            let mut entry = TextEntry {
+                export: index,
+                property: path.to_string(),
+                ..Default::default()
+            };
+            match text {
+                LocalizedText::Localized {
+                    namespace,
+                    key,
+                    source,
+                } => {
+                    entry.kind = "localized".to_string();
+                    entry.namespace = namespace;
+                    entry.key = key;
+                    entry.source = source;
+                }
+                LocalizedText::CultureInvariant(source) => {
+                    entry.kind = "culture_invariant".to_string();
+                    entry.source = source;
+                }
+                LocalizedText::StringTableEntry { table, key } => {
+                    entry.kind = "string_table".to_string();
+                    entry.string_table = table;
+                    entry.key = key;
+                }
+            }
+            texts.push(entry);
+        });
+    }
+    texts
+}
+
+/// Writes the texts of every asset a run indexed to the CSV file at `path`,
+/// one row per text, with the path of the asset it's in.
+pub fn write_texts_csv(path: &Path, texts: &[(PathBuf, TextEntry)]) -> Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    file.write_all(b"asset,export,property,kind,namespace,key,source,string_table\n")?;
+    for (asset, text) in texts {
+        let fields = [
+            asset.display().to_string(),
+            text.export.to_string(),
+            text.property.clone(),
+            text.kind.clone(),
+            text.namespace.clone(),
+            text.key.clone(),
+            text.source.clone(),
+            text.string_table.clone(),
+        ];
+        csv::write_row(&mut file, &fields)?;
+    }
+    file.flush()
+}
+
+#[test]
+fn test_write_texts_csv() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("texts.csv");
+    let texts = [
+        (
+            PathBuf::from("Content/NPC.uasset"),
+            TextEntry {
+                export: 2,
+                property: "Lines[0]".to_string(),
+                kind: "localized".to_string(),
+                namespace: "Dialogue".to_string(),
+                key: "NPC_Greeting_03".to_string(),
+                source: "Hello, traveler.".to_string(),
+                string_table: String::new(),
+            },
+        ),
+        (
+            PathBuf::from("Content/NPC.uasset"),
+            TextEntry {
+                export: 2,
+                property: "Farewell".to_string(),
+                kind: "string_table".to_string(),
+                key: "Bye".to_string(),
+                string_table: "/Game/Text/ST_Dialogue.ST_Dialogue".to_string(),
+                ..Default::default()
+            },
+        ),
+    ];
+    write_texts_csv(&path, &texts).unwrap();
+    assert_eq!(
+        "asset,export,property,kind,namespace,key,source,string_table\n\
+         Content/NPC.uasset,2,Lines[0],localized,Dialogue,NPC_Greeting_03,\"Hello, traveler.\",\n\
+         Content/NPC.uasset,2,Farewell,string_table,,Bye,,/Game/Text/ST_Dialogue.ST_Dialogue\n",
+        std::fs::read_to_string(&path).unwrap()
+    );
+}
@@ -0,0 +1,137 @@
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom};
+
+use crate::summary::{read_fstring, read_i32};
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const JPEG_SIGNATURE: &[u8] = b"\xff\xd8\xff";
+
+/// More entries than any package has, so a corrupt count fails early.
+const MAX_ENTRIES: i32 = 1 << 16;
+/// Thumbnails are at most a few hundred KiB.
+const MAX_IMAGE_BYTES: i32 = 64 << 20;
+
+/// A thumbnail from a package's thumbnail table, as the editor saved it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Thumbnail {
+    pub width: u32,
+    pub height: u32,
+    /// The compressed image, as PNG or JPEG data.
+    pub data: Vec<u8>,
+    /// Whether `data` is JPEG rather than PNG, which newer engines save.
+    pub jpeg: bool,
+}
+
+impl Thumbnail {
+    /// What the thumbnail is saved as next to the asset's pages.
+    pub fn file_name(&self) -> &'static str {
+        if self.jpeg {
+            "thumbnail.jpg"
+        } else {
+            "thumbnail.png"
+        }
+    }
+}
+
+/// Reads the thumbnail of the object `object_name` from the thumbnail table
+/// at `table_offset` in `reader`, or of the first object listed when none is
+/// called that. `None` when the table is empty or the thumbnail is, as
+/// thumbnails the editor couldn't render are.
+pub fn read_thumbnail<R: Read + Seek>(
+    reader: &mut R,
+    table_offset: i32,
+    object_name: &str,
+) -> Result<Option<Thumbnail>> {
+    if table_offset <= 0 {
+        return Ok(None);
+    }
+    reader.seek(SeekFrom::Start(table_offset as u64))?;
+    let count = read_i32(reader)?;
+    if !(0..=MAX_ENTRIES).contains(&count) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("thumbnail table has {} entries", count),
+        ));
+    }
+    let mut offsets = Vec::new();
+    for _ in 0..count {
+        let _class_name = read_fstring(reader)?;
+        let object_path = read_fstring(reader)?;
+        let offset = read_i32(reader)?;
+        offsets.push((object_path, offset));
+    }
+    let Some((_, offset)) = offsets
+        .iter()
+        .find(|(object_path, _)| object_path == object_name)
+        .or(offsets.first())
+    else {
+        return Ok(None);
+    };
+    reader.seek(SeekFrom::Start(*offset as u64))?;
+    let width = read_i32(reader)?;
+    // Negative for JPEG data in engines that save it.
+    let height = read_i32(reader)?;
+    let len = read_i32(reader)?;
+    if width <= 0 || height == 0 || len == 0 {
+        return Ok(None);
+    }
+    if !(0..=MAX_IMAGE_BYTES).contains(&len) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("thumbnail image is {} bytes", len),
+        ));
+    }
+    let mut data = vec![0; len as usize];
+    reader.read_exact(&mut data)?;
+    let jpeg = if data.starts_with(PNG_SIGNATURE) {
+        false
+    } else if data.starts_with(JPEG_SIGNATURE) {
+        true
+    } else {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "thumbnail image is neither PNG nor JPEG data",
+        ));
+    };
+    Ok(Some(Thumbnail {
+        width: width as u32,
+        height: height.unsigned_abs(),
+        data,
+        jpeg,
+    }))
+}
+
+#[test]
+fn test_read_thumbnail() {
+    let image = [PNG_SIGNATURE, b"IHDR"].concat();
+    let mut package = vec![0; 16];
+    // Two entries, the asset's own second, then an empty thumbnail and the
+    // asset's.
+    package.extend_from_slice(&2i32.to_le_bytes());
+    for (name, offset) in [("Cube_Material", 100), ("Cube", 112)] {
+        package.extend(crate::test_support::fstring_bytes("StaticMesh"));
+        package.extend(crate::test_support::fstring_bytes(name));
+        package.extend_from_slice(&(offset as i32).to_le_bytes());
+    }
+    package.resize(100, 0);
+    package.extend_from_slice(&[0; 12]);
+    for value in [256, 256, image.len() as i32] {
+        package.extend_from_slice(&value.to_le_bytes());
+    }
+    package.extend_from_slice(&image);
+
+    let mut reader = std::io::Cursor::new(&package);
+    let thumbnail = read_thumbnail(&mut reader, 16, "Cube").unwrap().unwrap();
+    assert_eq!(
+        (256, 256, false),
+        (thumbnail.width, thumbnail.height, thumbnail.jpeg)
+    );
+    assert_eq!(image, thumbnail.data);
+    assert_eq!("thumbnail.png", thumbnail.file_name());
+    assert_eq!(None, read_thumbnail(&mut reader, 16, "Other").unwrap());
+    assert_eq!(None, read_thumbnail(&mut reader, 0, "Cube").unwrap());
+
+    // Compressed some other way.
+    package[124..128].copy_from_slice(b"DDS ");
+    let mut reader = std::io::Cursor::new(&package);
+    assert!(read_thumbnail(&mut reader, 16, "Cube").is_err());
+}
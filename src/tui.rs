@@ -0,0 +1,448 @@
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, List, ListState, Paragraph},
+    Frame, Terminal,
+};
+use std::io::{self, Read, Seek};
+use std::path::Path;
+use unreal_asset::{exports::ExportBaseTrait, Asset};
+
+use crate::{
+    catch_panics, display_name, dump_indices, export_class_name, parse_file, text,
+    write_annotated_dump, Error, IndexNames, IndexOptions,
+};
+
+/// An export or import in the left pane.
+struct Entry {
+    index: i32,
+    /// e.g. `3 Door_2 (StaticMeshActor)`.
+    label: String,
+    /// The annotated dump, the same as `--format text` writes, one element
+    /// per line.
+    dump: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Focus {
+    List,
+    Dump,
+}
+
+/// The state of `uasset-index tui`: what the panes show and where the
+/// cursors are. Nothing here touches the terminal, so the keys can be tested.
+pub struct Browser {
+    title: String,
+    entries: Vec<Entry>,
+    /// Typed after `/`, matched against the labels ignoring case.
+    filter: String,
+    filtering: bool,
+    /// The positions in `entries` of those matching `filter`.
+    visible: Vec<usize>,
+    /// The position in `visible` of the selected entry.
+    selected: usize,
+    focus: Focus,
+    /// The line of the dump Enter follows an index from.
+    dump_line: usize,
+    /// The entries left by following indices, for Backspace.
+    history: Vec<i32>,
+}
+
+impl Browser {
+    /// Parses the asset at `path` for browsing.
+    pub fn open(path: &Path, options: &IndexOptions) -> Result<Self, Error> {
+        let (asset, _) = catch_panics(path, || parse_file(path, options))?;
+        Ok(Browser::new(
+            &path.file_name().unwrap_or_default().to_string_lossy(),
+            &asset,
+        ))
+    }
+
+    fn new<C: Read + Seek>(title: &str, asset: &Asset<C>) -> Self {
+        let names = IndexNames::from_asset(asset);
+        let annotate = |index: i32| text::annotate_index(&names, index);
+        let dump_lines = |value: &dyn std::fmt::Debug| {
+            let mut dump = Vec::new();
+            // Writing into memory can't fail.
+            let _ = write_annotated_dump(&mut dump, &value, annotate);
+            String::from_utf8_lossy(&dump)
+                .lines()
+                .map(str::to_string)
+                .collect()
+        };
+        let mut entries = Vec::new();
+        for (i, export) in asset.asset_data.exports.iter().enumerate() {
+            let base = export.get_base_export();
+            entries.push(Entry {
+                index: i as i32 + 1,
+                label: format!(
+                    "{} {} ({})",
+                    i + 1,
+                    display_name(&base.object_name),
+                    export_class_name(&names, base.class_index.index)
+                ),
+                dump: dump_lines(export),
+            });
+        }
+        for (i, import) in asset.imports.iter().enumerate() {
+            entries.push(Entry {
+                index: -(i as i32 + 1),
+                label: format!(
+                    "{} {} ({})",
+                    -(i as i32 + 1),
+                    display_name(&import.object_name),
+                    import.class_name.get_owned_content()
+                ),
+                dump: dump_lines(import),
+            });
+        }
+        Browser::from_entries(title, entries)
+    }
+
+    fn from_entries(title: &str, entries: Vec<Entry>) -> Self {
+        let mut browser = Browser {
+            title: title.to_string(),
+            entries,
+            filter: String::new(),
+            filtering: false,
+            visible: Vec::new(),
+            selected: 0,
+            focus: Focus::List,
+            dump_line: 0,
+            history: Vec::new(),
+        };
+        browser.apply_filter();
+        browser
+    }
+
+    fn selected_entry(&self) -> Option<&Entry> {
+        self.visible
+            .get(self.selected)
+            .map(|&position| &self.entries[position])
+    }
+
+    fn apply_filter(&mut self) {
+        let current = self.selected_entry().map(|entry| entry.index);
+        let filter = self.filter.to_lowercase();
+        self.visible = (0..self.entries.len())
+            .filter(|&position| {
+                self.entries[position]
+                    .label
+                    .to_lowercase()
+                    .contains(&filter)
+            })
+            .collect();
+        // Stay on the same entry while it still matches.
+        self.selected = current
+            .and_then(|index| self.position_of(index))
+            .unwrap_or(0);
+        self.dump_line = 0;
+    }
+
+    fn position_of(&self, index: i32) -> Option<usize> {
+        self.visible
+            .iter()
+            .position(|&position| self.entries[position].index == index)
+    }
+
+    /// Selects the entry for `index`, clearing the filter if it hides it.
+    fn select(&mut self, index: i32) -> bool {
+        if self.position_of(index).is_none() {
+            self.filter.clear();
+            self.apply_filter();
+        }
+        let Some(position) = self.position_of(index) else {
+            return false;
+        };
+        self.selected = position;
+        self.dump_line = 0;
+        true
+    }
+
+    /// Follows the first index on the dump line under the cursor that
+    /// points at another entry.
+    fn follow(&mut self) {
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        let from = entry.index;
+        let Some(line) = entry.dump.get(self.dump_line) else {
+            return;
+        };
+        let targets: Vec<i32> = dump_indices(line)
+            .into_iter()
+            .filter(|&index| index != from)
+            .collect();
+        for target in targets {
+            if self.select(target) {
+                self.history.push(from);
+                return;
+            }
+        }
+    }
+
+    fn back(&mut self) {
+        if let Some(index) = self.history.pop() {
+            self.select(index);
+            self.focus = Focus::Dump;
+        }
+    }
+
+    fn move_cursor(&mut self, by: isize) {
+        let (cursor, len) = match self.focus {
+            Focus::List => (&mut self.selected, self.visible.len()),
+            Focus::Dump => (
+                &mut self.dump_line,
+                self.visible
+                    .get(self.selected)
+                    .map_or(0, |&position| self.entries[position].dump.len()),
+            ),
+        };
+        let moved = (*cursor as isize + by).clamp(0, len.saturating_sub(1) as isize);
+        *cursor = moved as usize;
+        if self.focus == Focus::List {
+            self.dump_line = 0;
+        }
+    }
+
+    /// Handles a key press, returning whether to quit.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return true;
+        }
+        if self.filtering {
+            match key.code {
+                KeyCode::Char(c) => {
+                    self.filter.push(c);
+                    self.apply_filter();
+                }
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                    self.apply_filter();
+                }
+                KeyCode::Enter | KeyCode::Esc => self.filtering = false,
+                _ => {}
+            }
+            return false;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return true,
+            KeyCode::Char('/') => {
+                self.filtering = true;
+                self.focus = Focus::List;
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(1),
+            KeyCode::PageUp => self.move_cursor(-20),
+            KeyCode::PageDown => self.move_cursor(20),
+            KeyCode::Home => self.move_cursor(isize::MIN / 2),
+            KeyCode::End => self.move_cursor(isize::MAX / 2),
+            KeyCode::Tab => {
+                self.focus = match self.focus {
+                    Focus::List => Focus::Dump,
+                    Focus::Dump => Focus::List,
+                }
+            }
+            KeyCode::Right => self.focus = Focus::Dump,
+            KeyCode::Left => self.focus = Focus::List,
+            KeyCode::Enter => match self.focus {
+                Focus::List => self.focus = Focus::Dump,
+                Focus::Dump => self.follow(),
+            },
+            KeyCode::Backspace => self.back(),
+            _ => {}
+        }
+        false
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .split(frame.size());
+        let panes = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+            .split(rows[0]);
+        let focused = |focus: Focus| {
+            if self.focus == focus {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            }
+        };
+
+        let items: Vec<&str> = self
+            .visible
+            .iter()
+            .map(|&position| self.entries[position].label.as_str())
+            .collect();
+        let title = if self.filter.is_empty() && !self.filtering {
+            self.title.clone()
+        } else {
+            format!("{} /{}", self.title, self.filter)
+        };
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(title)
+                    .border_style(focused(Focus::List)),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        frame.render_stateful_widget(list, panes[0], &mut state);
+
+        let entry = self.selected_entry();
+        let lines: Vec<Line> = entry
+            .map(|entry| entry.dump.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .enumerate()
+            .map(|(i, line)| {
+                if self.focus == Focus::Dump && i == self.dump_line {
+                    Line::styled(
+                        line.as_str(),
+                        Style::default().add_modifier(Modifier::REVERSED),
+                    )
+                } else {
+                    Line::raw(line.as_str())
+                }
+            })
+            .collect();
+        // Keep the cursor line in view, a third of the way down.
+        let height = panes[1].height.saturating_sub(2) as usize;
+        let scroll = self.dump_line.saturating_sub(height / 3);
+        let dump = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(entry.map(|entry| entry.label.clone()).unwrap_or_default())
+                    .border_style(focused(Focus::Dump)),
+            )
+            .scroll((scroll.min(u16::MAX as usize) as u16, 0));
+        frame.render_widget(dump, panes[1]);
+
+        let help = if self.filtering {
+            "type to filter  Enter/Esc: done"
+        } else {
+            "/: filter  Tab: switch pane  Enter: follow index  Backspace: back  q: quit"
+        };
+        frame.render_widget(Paragraph::new(help), rows[1]);
+    }
+}
+
+/// Runs the terminal browser until the user quits, restoring the terminal
+/// afterwards even when drawing fails.
+pub fn run(mut browser: Browser) -> io::Result<()> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    if let Err(err) = execute!(stdout, EnterAlternateScreen) {
+        let _ = disable_raw_mode();
+        return Err(err);
+    }
+    let result = (|| {
+        let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+        loop {
+            terminal.draw(|frame| browser.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                // Windows reports releases too.
+                if key.kind == KeyEventKind::Press && browser.handle_key(key) {
+                    return Ok(());
+                }
+            }
+        }
+    })();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen);
+    let _ = disable_raw_mode();
+    result
+}
+
+#[cfg(test)]
+fn test_browser() -> Browser {
+    let entry = |index: i32, label: &str, dump: &[&str]| Entry {
+        index,
+        label: label.to_string(),
+        dump: dump.iter().map(|line| line.to_string()).collect(),
+    };
+    Browser::from_entries(
+        "Hub.umap",
+        vec![
+            entry(
+                1,
+                "1 Door_2 (StaticMeshActor)",
+                &[
+                    "NormalExport {",
+                    "    class_index: PackageIndex { index: -2 (StaticMeshActor) },",
+                    "    outer_index: PackageIndex { index: 3 (PersistentLevel) },",
+                    "}",
+                ],
+            ),
+            entry(2, "2 Door_3 (StaticMeshActor)", &["NormalExport {", "}"]),
+            entry(3, "3 PersistentLevel (Level)", &["LevelExport {", "}"]),
+            entry(-1, "-1 /Script/Engine (Package)", &["Import {", "}"]),
+            entry(-2, "-2 StaticMeshActor (Class)", &["Import {", "}"]),
+        ],
+    )
+}
+
+#[cfg(test)]
+fn press(browser: &mut Browser, keys: &[KeyCode]) {
+    for &code in keys {
+        assert!(!browser.handle_key(KeyEvent::new(code, KeyModifiers::NONE)));
+    }
+}
+
+#[test]
+fn test_browser_filter() {
+    let mut browser = test_browser();
+    press(&mut browser, &[KeyCode::Down]);
+    press(
+        &mut browser,
+        &[KeyCode::Char('/'), KeyCode::Char('D'), KeyCode::Char('o')],
+    );
+    assert_eq!(vec![0, 1], browser.visible);
+    // Door_3 was selected and still matches.
+    assert_eq!(2, browser.selected_entry().unwrap().index);
+    press(&mut browser, &[KeyCode::Char('z')]);
+    assert!(browser.selected_entry().is_none());
+    press(&mut browser, &[KeyCode::Backspace, KeyCode::Enter]);
+    assert!(!browser.filtering);
+    assert_eq!(0, browser.selected);
+    // Outside the filter, q quits.
+    assert!(browser.handle_key(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE)));
+}
+
+#[test]
+fn test_browser_follows_indices() {
+    let mut browser = test_browser();
+    press(
+        &mut browser,
+        &[KeyCode::Char('/'), KeyCode::Char('1'), KeyCode::Enter],
+    );
+    assert_eq!(1, browser.selected_entry().unwrap().index);
+    // Into the dump, down to the outer, and through it.
+    press(
+        &mut browser,
+        &[KeyCode::Enter, KeyCode::Down, KeyCode::Down, KeyCode::Enter],
+    );
+    assert_eq!(3, browser.selected_entry().unwrap().index);
+    assert!(browser.filter.is_empty());
+    assert_eq!(vec![1], browser.history);
+    // Lines without an index go nowhere.
+    press(&mut browser, &[KeyCode::Enter]);
+    assert_eq!(3, browser.selected_entry().unwrap().index);
+
+    press(&mut browser, &[KeyCode::Backspace]);
+    assert_eq!(1, browser.selected_entry().unwrap().index);
+    assert!(browser.history.is_empty());
+    press(&mut browser, &[KeyCode::Down, KeyCode::Enter]);
+    assert_eq!(-2, browser.selected_entry().unwrap().index);
+}
@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use unreal_asset::engine_version::EngineVersion;
+
+use crate::{canonical_path, parse_engine_version};
+
+#[derive(Debug, thiserror::Error)]
+pub enum VersionMapError {
+    #[error("failed to read {}: {}", .0.display(), .1)]
+    Read(PathBuf, #[source] std::io::Error),
+    #[error("{}: expected an object of path prefixes and engine versions: {}", .0.display(), .1)]
+    Json(PathBuf, #[source] serde_json::Error),
+    #[error("{}: unknown engine version {:?} for {:?}, see --help for the supported versions", .0.display(), .2, .1)]
+    UnknownVersion(PathBuf, String, String),
+}
+
+/// `--version-map`: engine versions for the assets under particular
+/// directories, for trees holding content of more than one game. Like
+/// `--engine-version`, which applies everywhere else, they're only used for
+/// assets that don't record their own version.
+#[derive(Debug, Default)]
+pub struct VersionMap {
+    /// Canonical directory paths, longest first so the most specific one
+    /// matches.
+    prefixes: Vec<(PathBuf, EngineVersion)>,
+}
+
+impl VersionMap {
+    /// Loads a JSON object such as `{"Game422": "4.22", "Game427/Content":
+    /// "VER_UE4_27"}`, whose keys are directories relative to the file.
+    pub fn load(path: &Path) -> Result<Self, VersionMapError> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| VersionMapError::Read(path.to_path_buf(), err))?;
+        let entries: BTreeMap<String, String> = serde_json::from_str(&text)
+            .map_err(|err| VersionMapError::Json(path.to_path_buf(), err))?;
+        let base = path.parent().unwrap_or(Path::new(""));
+        let mut prefixes = Vec::with_capacity(entries.len());
+        for (prefix, version) in entries {
+            let Some(engine_version) = parse_engine_version(&version) else {
+                return Err(VersionMapError::UnknownVersion(
+                    path.to_path_buf(),
+                    prefix,
+                    version,
+                ));
+            };
+            prefixes.push((canonical_path(&base.join(prefix)), engine_version));
+        }
+        prefixes.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.components().count()));
+        Ok(VersionMap { prefixes })
+    }
+
+    /// The version for the asset at `path`, if it's under one of the
+    /// directories.
+    pub fn engine_version(&self, path: &Path) -> Option<EngineVersion> {
+        if self.prefixes.is_empty() {
+            return None;
+        }
+        let dir = canonical_parent(path);
+        self.prefixes
+            .iter()
+            .find(|(prefix, _)| dir.starts_with(prefix))
+            .map(|(_, engine_version)| *engine_version)
+    }
+}
+
+/// The canonical path of the directory the file at `path` is in.
+pub(crate) fn canonical_parent(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => canonical_path(dir),
+        _ => canonical_path(Path::new(".")),
+    }
+}
+
+#[test]
+fn test_version_map() {
+    let dir = tempfile::tempdir().unwrap();
+    for sub in ["Game422/Content", "Game427/Content/Old"] {
+        std::fs::create_dir_all(dir.path().join(sub)).unwrap();
+    }
+    let map_path = dir.path().join("versions.json");
+    std::fs::write(
+        &map_path,
+        r#"{"Game422": "4.22", "Game427": "VER_UE4_27", "Game427/Content/Old": "4.25"}"#,
+    )
+    .unwrap();
+    let map = VersionMap::load(&map_path).unwrap();
+    let version = |path: &str| map.engine_version(&dir.path().join(path));
+    assert_eq!(
+        Some(EngineVersion::VER_UE4_22),
+        version("Game422/Content/Hero.uasset")
+    );
+    assert_eq!(
+        Some(EngineVersion::VER_UE4_27),
+        version("Game427/Content/Hero.uasset")
+    );
+    assert_eq!(
+        Some(EngineVersion::VER_UE4_25),
+        version("Game427/Content/Old/Hero.uasset")
+    );
+    // Prefixes match whole directory names.
+    assert_eq!(None, version("Game4220/Hero.uasset"));
+    assert_eq!(None, VersionMap::default().engine_version(&map_path));
+
+    std::fs::write(&map_path, r#"{"Game422": "4.99"}"#).unwrap();
+    assert!(matches!(
+        VersionMap::load(&map_path),
+        Err(VersionMapError::UnknownVersion(..))
+    ));
+    std::fs::write(&map_path, r#"["Game422"]"#).unwrap();
+    assert!(matches!(
+        VersionMap::load(&map_path),
+        Err(VersionMapError::Json(..))
+    ));
+}
@@ -0,0 +1,757 @@
+use std::collections::BTreeMap;
+use std::fs::{create_dir_all, read, read_dir, write};
+use std::path::{Path, PathBuf};
+use uindex::{
+    check,
+    config::{self, DirectoryConfigs},
+    index, index_dir, index_file,
+    manifest::{temp_path, TEMP_SUFFIX},
+    ndjson::NdjsonOutput,
+    test_support::TestPackage,
+    version_map::VersionMap,
+    BadTag, EngineVersion, Error, IndexOptions, OutputFormat,
+};
+
+const PKG_UNVERSIONED_PROPERTIES: u32 = 0x2000;
+
+/// A cooked asset with unversioned properties, unreadable without mappings.
+fn write_unversioned_asset(path: &Path) {
+    let package = TestPackage {
+        package_flags: PKG_UNVERSIONED_PROPERTIES,
+        ..Default::default()
+    };
+    write(path, package.bytes()).unwrap();
+}
+
+const PKG_FILTER_EDITOR_ONLY: u32 = 0x8000_0000;
+
+/// A complete cooked UE4 package summary with no version numbers and empty
+/// name, import and export tables. The fields are laid out the same from 4.21
+/// up to 4.27, so it parses with whichever of those it's indexed as.
+fn write_empty_unversioned_asset(path: &Path) {
+    write_empty_asset_with_custom_versions(path, &[]);
+}
+
+/// `write_empty_unversioned_asset`, with `custom_versions` in its custom
+/// version container, each a GUID as the engine's four words and a version.
+fn write_empty_asset_with_custom_versions(path: &Path, custom_versions: &[([u32; 4], i32)]) {
+    let package = TestPackage {
+        file_version_ue4: 0,
+        custom_versions,
+        package_flags: PKG_FILTER_EDITOR_ONLY,
+        ..Default::default()
+    };
+    write(path, package.bytes()).unwrap();
+}
+
+/// A split asset whose `.uexp` is missing.
+fn write_split_asset(path: &Path) {
+    let package = TestPackage {
+        total_header_size: 4096,
+        ..Default::default()
+    };
+    write(path, package.bytes()).unwrap();
+}
+
+#[test]
+fn test_index_file_lists_custom_versions() {
+    let dir = tempfile::tempdir().unwrap();
+    let asset = dir.path().join("Hero.uasset");
+    write_empty_asset_with_custom_versions(
+        &asset,
+        &[
+            ([0x375EC13C, 0x06E448FB, 0xB50084F0, 0x262A717E], 40),
+            ([0x9C54D522, 0xA8264FBE, 0x94210746, 0x61B482D0], 43),
+            ([0x12345678, 0, 0, 0x9ABCDEF0], 2),
+        ],
+    );
+    let options = IndexOptions {
+        engine_version: EngineVersion::VER_UE4_27,
+        formats: vec![OutputFormat::Html, OutputFormat::Json, OutputFormat::Yaml],
+        ..Default::default()
+    };
+    index_file(&asset, &options).unwrap();
+    let main_dir = dir.path().join("Hero");
+    let page = String::from_utf8(read(main_dir.join("index.html")).unwrap()).unwrap();
+    assert!(page.contains(
+        "<tr><td>FCoreObjectVersion</td><td><code>375EC13C06E448FBB50084F0262A717E</code></td>\
+        <td>40</td></tr>\
+        <tr><td>FReleaseObjectVersion</td><td><code>9C54D522A8264FBE9421074661B482D0</code></td>\
+        <td>43</td></tr>\
+        <tr><td></td><td><code>1234567800000000000000009ABCDEF0</code></td><td>2</td></tr>"
+    ));
+    let json: serde_json::Value =
+        serde_json::from_slice(&read(main_dir.join("asset.json")).unwrap()).unwrap();
+    assert_eq!(
+        serde_json::json!([
+            {"guid": "375EC13C06E448FBB50084F0262A717E", "version": 40, "name": "FCoreObjectVersion"},
+            {"guid": "9C54D522A8264FBE9421074661B482D0", "version": 43, "name": "FReleaseObjectVersion"},
+            {"guid": "1234567800000000000000009ABCDEF0", "version": 2, "name": null},
+        ]),
+        json["custom_versions"]
+    );
+    let yaml: serde_json::Value =
+        serde_yaml::from_slice(&read(main_dir.join("asset.yaml")).unwrap()).unwrap();
+    assert_eq!(json, yaml);
+    assert!(page.contains("<li><a href=\"asset.yaml\">asset.yaml</a></li>"));
+}
+
+#[test]
+fn test_index_file_writes_layout() {
+    let dir = tempfile::tempdir().unwrap();
+    let asset = dir.path().join("Hero.uasset");
+    write_empty_unversioned_asset(&asset);
+    let options = IndexOptions {
+        engine_version: EngineVersion::VER_UE4_27,
+        layout: true,
+        ..Default::default()
+    };
+    index_file(&asset, &options).unwrap();
+    let main_dir = dir.path().join("Hero");
+    let layout: serde_json::Value =
+        serde_json::from_slice(&read(main_dir.join("layout.json")).unwrap()).unwrap();
+    let fields: Vec<&str> = layout["summary"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|field| field["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        vec![
+            "TotalHeaderSize",
+            "PackageFlags",
+            "NameCount",
+            "NameOffset",
+            "ExportCount",
+            "ExportOffset",
+            "ImportCount",
+            "ImportOffset",
+        ],
+        fields
+    );
+    // After the tag, four version fields and the empty custom versions.
+    assert_eq!(24, layout["summary"][0]["offset"]);
+    assert_eq!(Some(0), layout["names"].as_array().map(Vec::len));
+    let page = std::fs::read_to_string(main_dir.join("index.html")).unwrap();
+    assert!(page.contains("<a href=\"layout.html\">layout</a>"));
+    assert!(std::fs::read_to_string(main_dir.join("layout.html"))
+        .unwrap()
+        .contains("<td>NameOffset</td>"));
+}
+
+#[test]
+fn test_index_file_warns_of_engine_version_mismatch() {
+    let dir = tempfile::tempdir().unwrap();
+    let asset = dir.path().join("Hero.uasset");
+    // FUE5MainStreamObjectVersion, which only UE5 saves.
+    write_empty_asset_with_custom_versions(
+        &asset,
+        &[([0x697DD581, 0xE64F41AB, 0xAA4A51EC, 0xBEB7B628], 12)],
+    );
+    let options = IndexOptions {
+        engine_version: EngineVersion::VER_UE4_27,
+        ..Default::default()
+    };
+    let report = index_file(&asset, &options).unwrap();
+    assert_eq!(
+        vec!["Hero.uasset: asset appears to be 5.2 but was parsed as 4.27".to_string()],
+        report.warnings
+    );
+    let page = std::fs::read_to_string(dir.path().join("Hero").join("index.html")).unwrap();
+    assert!(
+        page.contains("<strong>Warning: asset appears to be 5.2 but was parsed as 4.27.</strong>")
+    );
+
+    let single_file = IndexOptions {
+        single_file: true,
+        ..options
+    };
+    let report = index_file(&asset, &single_file).unwrap();
+    assert_eq!(
+        vec!["Hero.uasset: asset appears to be 5.2 but was parsed as 4.27".to_string()],
+        report.warnings
+    );
+    let page = std::fs::read_to_string(dir.path().join("Hero").join("Hero.html")).unwrap();
+    assert!(
+        page.contains("<strong>Warning: asset appears to be 5.2 but was parsed as 4.27.</strong>")
+    );
+}
+
+#[test]
+fn test_index_file_missing_mappings() {
+    let dir = tempfile::tempdir().unwrap();
+    let asset = dir.path().join("Hero.uasset");
+    write_unversioned_asset(&asset);
+    match index_file(&asset, &IndexOptions::default()) {
+        Err(Error::MissingMappings(path)) => assert_eq!(asset, path),
+        other => panic!("expected MissingMappings, got {:?}", other),
+    }
+    assert!(!dir.path().join("Hero").exists());
+}
+
+#[test]
+fn test_index_file_missing_uexp() {
+    let dir = tempfile::tempdir().unwrap();
+    let asset = dir.path().join("Hub.umap");
+    write_split_asset(&asset);
+    match index_file(&asset, &IndexOptions::default()) {
+        Err(Error::MissingUexp(path, uexp)) => {
+            assert_eq!(asset, path);
+            assert_eq!(dir.path().join("Hub.uexp"), uexp);
+        }
+        other => panic!("expected MissingUexp, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_index_file_rejects_other_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let readme = dir.path().join("README.txt");
+    write(&readme, "not an asset").unwrap();
+    let options = IndexOptions::default();
+    assert!(matches!(
+        index_file(&readme, &options),
+        Err(Error::InvalidExtension(_))
+    ));
+    assert!(matches!(
+        index_file(&dir.path().join("Missing.uasset"), &options),
+        Err(Error::NotFound(_))
+    ));
+}
+
+#[test]
+fn test_index_file_reports_encrypted_assets() {
+    let dir = tempfile::tempdir().unwrap();
+    let asset = dir.path().join("Hero.uasset");
+    let mut state: u32 = 7;
+    let bytes: Vec<u8> = (0..8192)
+        .map(|_| {
+            state = state.wrapping_mul(1103515245).wrapping_add(12345);
+            (state >> 16) as u8
+        })
+        .collect();
+    write(&asset, bytes).unwrap();
+    let report = index(&asset, &IndexOptions::default());
+    assert_eq!(1, report.failed);
+    assert_eq!("encrypted", report.errors[0].category);
+    assert!(report.errors[0]
+        .message
+        .contains("still encrypted inside the pak?"));
+    assert!(matches!(
+        index_file(&asset, &IndexOptions::default()),
+        Err(Error::BadTag(_, BadTag::Encrypted))
+    ));
+}
+
+#[test]
+fn test_index_counts_failures() {
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("Missing.uasset");
+    let report = index(&missing, &IndexOptions::default());
+    assert_eq!(0, report.succeeded);
+    assert_eq!(1, report.failed);
+    assert_eq!(missing, report.errors[0].path);
+    assert_eq!(
+        format!("file does not exist: {}", missing.display()),
+        report.errors[0].message
+    );
+}
+
+#[test]
+fn test_index_dir_reports_failed_assets() {
+    let dir = tempfile::tempdir().unwrap();
+    let content = dir.path().join("Content");
+    create_dir_all(content.join("Maps")).unwrap();
+    write_unversioned_asset(&content.join("Hero.uasset"));
+    write_split_asset(&content.join("Maps").join("Hub.umap"));
+    write(content.join("notes.txt"), "not an asset").unwrap();
+    let out = dir.path().join("out");
+    let options = IndexOptions {
+        output_dir: Some(out.clone()),
+        ..Default::default()
+    };
+
+    let report = index_dir(&content, &options).unwrap();
+    assert_eq!(0, report.succeeded);
+    assert_eq!(2, report.failed);
+    let mut failed: Vec<&PathBuf> = report.errors.iter().map(|error| &error.path).collect();
+    failed.sort();
+    assert_eq!(
+        vec![
+            &content.join("Hero.uasset"),
+            &content.join("Maps").join("Hub.umap")
+        ],
+        failed
+    );
+    assert!(report
+        .errors
+        .iter()
+        .any(|error| error.message.contains("--usmap")));
+    assert!(report.warnings.is_empty());
+
+    // The directory pages are written even when none of the assets are.
+    let generated: Vec<&PathBuf> = report.generated.iter().map(|file| &file.path).collect();
+    for page in [
+        out.join("index.html"),
+        out.join("Maps").join("index.html"),
+        out.join("style.css"),
+    ] {
+        assert!(
+            generated.contains(&&page),
+            "{} not generated",
+            page.display()
+        );
+        assert!(page.exists());
+    }
+}
+
+/// Every file under `root` by its path relative to it, with its contents.
+fn read_tree(root: &Path) -> BTreeMap<PathBuf, Vec<u8>> {
+    fn walk(root: &Path, dir: &Path, files: &mut BTreeMap<PathBuf, Vec<u8>>) {
+        for entry in read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                walk(root, &path, files);
+            } else {
+                let relative = path.strip_prefix(root).unwrap().to_path_buf();
+                files.insert(relative, read(&path).unwrap());
+            }
+        }
+    }
+    let mut files = BTreeMap::new();
+    walk(root, root, &mut files);
+    files
+}
+
+#[test]
+fn test_index_dir_output_is_deterministic() {
+    let fixture = tempfile::tempdir().unwrap();
+    // Created out of order, so a walk in creation order would show.
+    for sub in ["Zeta", "Alpha", "Mid"] {
+        let sub = fixture.path().join(sub);
+        create_dir_all(&sub).unwrap();
+        write_split_asset(&sub.join("Level.umap"));
+        write_empty_unversioned_asset(&sub.join("Hero.uasset"));
+    }
+    write_unversioned_asset(&fixture.path().join("Hero.uasset"));
+
+    let runs: Vec<BTreeMap<PathBuf, Vec<u8>>> = (0..2)
+        .map(|_| {
+            let out = tempfile::tempdir().unwrap();
+            let out_dir = out.path().join("out");
+            let options = IndexOptions {
+                output_dir: Some(out_dir.clone()),
+                engine_version: EngineVersion::VER_UE4_27,
+                formats: vec![OutputFormat::Html, OutputFormat::Json],
+                ..Default::default()
+            };
+            let report = index_dir(fixture.path(), &options).unwrap();
+            assert_eq!((3, 4), (report.succeeded, report.failed));
+            read_tree(&out_dir)
+        })
+        .collect();
+    // The pages of the assets that parsed are compared too, not only those
+    // of the directories and the errors.
+    for sub in ["Alpha", "Mid", "Zeta"] {
+        let hero = Path::new(sub).join("Hero");
+        assert!(runs[0].contains_key(&hero.join("index.html")));
+        assert!(runs[0].contains_key(&hero.join("asset.json")));
+    }
+    assert_eq!(runs[0], runs[1]);
+
+    let root_page = String::from_utf8(runs[0][Path::new("index.html")].clone()).unwrap();
+    let positions: Vec<usize> = ["Alpha/", "Mid/", "Zeta/"]
+        .iter()
+        .map(|name| root_page.find(name).unwrap())
+        .collect();
+    assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+    let fixture_path = fixture.path().to_string_lossy();
+    assert!(runs[0]
+        .values()
+        .all(|contents| !String::from_utf8_lossy(contents).contains(fixture_path.as_ref())));
+}
+
+#[test]
+fn test_version_map_picks_engine_version_per_directory() {
+    let fixture = tempfile::tempdir().unwrap();
+    for sub in ["Game422", "Game427"] {
+        let sub = fixture.path().join(sub);
+        create_dir_all(&sub).unwrap();
+        write_empty_unversioned_asset(&sub.join("Hero.uasset"));
+    }
+    let map_path = fixture.path().join("versions.json");
+    write(&map_path, r#"{"Game422": "4.22"}"#).unwrap();
+    let out = tempfile::tempdir().unwrap();
+    let options = IndexOptions {
+        output_dir: Some(out.path().to_path_buf()),
+        engine_version: EngineVersion::VER_UE4_27,
+        version_map: VersionMap::load(&map_path).unwrap(),
+        ..Default::default()
+    };
+    let report = index_dir(fixture.path(), &options).unwrap();
+    assert_eq!((2, 0), (report.succeeded, report.failed));
+    for (sub, version) in [("Game422", "VER_UE4_22"), ("Game427", "VER_UE4_27")] {
+        let page = read(out.path().join(sub).join("Hero").join("index.html")).unwrap();
+        let page = String::from_utf8(page).unwrap();
+        assert!(
+            page.contains(&format!("<dd>{} (", version)),
+            "{} wasn't parsed as {}",
+            sub,
+            version
+        );
+    }
+}
+
+#[test]
+fn test_config_files_pick_engine_version_per_directory() {
+    let fixture = tempfile::tempdir().unwrap();
+    for sub in ["Game422", "Game422/Patch", "Game427"] {
+        let sub = fixture.path().join(sub);
+        create_dir_all(&sub).unwrap();
+        write_empty_unversioned_asset(&sub.join("Hero.uasset"));
+    }
+    write(
+        fixture.path().join(config::CONFIG_FILE),
+        "engine_version = \"4.27\"\n",
+    )
+    .unwrap();
+    write(
+        fixture.path().join("Game422").join(config::CONFIG_FILE),
+        "engine_version = \"4.22\"\n",
+    )
+    .unwrap();
+    let configs = config::discover(&[fixture.path().to_path_buf()]).unwrap();
+    let out = tempfile::tempdir().unwrap();
+    let options = IndexOptions {
+        output_dir: Some(out.path().to_path_buf()),
+        directory_configs: DirectoryConfigs::new(&configs, true, true).unwrap(),
+        ..Default::default()
+    };
+    let report = index_dir(fixture.path(), &options).unwrap();
+    assert_eq!((3, 0), (report.succeeded, report.failed));
+    for (sub, version) in [
+        ("Game422", "VER_UE4_22"),
+        ("Game422/Patch", "VER_UE4_22"),
+        ("Game427", "VER_UE4_27"),
+    ] {
+        let page = read(out.path().join(sub).join("Hero").join("index.html")).unwrap();
+        let page = String::from_utf8(page).unwrap();
+        assert!(
+            page.contains(&format!("<dd>{} (", version)),
+            "{} wasn't parsed as {}",
+            sub,
+            version
+        );
+    }
+}
+
+#[test]
+fn test_check_fails_unreadable_assets_without_writing() {
+    let dir = tempfile::tempdir().unwrap();
+    write_unversioned_asset(&dir.path().join("Hero.uasset"));
+    write_split_asset(&dir.path().join("Hub.umap"));
+    let report = check::check(dir.path(), &IndexOptions::default());
+    assert_eq!(0, report.passed);
+    assert_eq!(2, report.failed);
+    assert_eq!(2, read_dir(dir.path()).unwrap().count());
+}
+
+#[test]
+fn test_index_file_never_leaves_truncated_pages() {
+    let dir = tempfile::tempdir().unwrap();
+    let asset = dir.path().join("Hero.uasset");
+    write_empty_unversioned_asset(&asset);
+    let out = dir.path().join("out");
+    let main_dir = out.join("Hero");
+    let options = IndexOptions {
+        output_dir: Some(out.clone()),
+        engine_version: EngineVersion::VER_UE4_27,
+        ..Default::default()
+    };
+    // A page from an earlier run, and a directory in the way of the imports
+    // listing so this run fails partway through the asset.
+    create_dir_all(main_dir.join("imports")).unwrap();
+    write(
+        main_dir.join("index.html"),
+        "<h1>Hero</h1> from an earlier run",
+    )
+    .unwrap();
+    let blocker = temp_path(&main_dir.join("imports").join("index.html"));
+    create_dir_all(&blocker).unwrap();
+
+    assert!(matches!(
+        index_file(&asset, &options),
+        Err(Error::Write(..))
+    ));
+    // The marker is put in place before the failure, so the manifest lists
+    // it.
+    let report = index(&asset, &options);
+    let generated: Vec<&PathBuf> = report.generated.iter().map(|file| &file.path).collect();
+    assert_eq!(
+        (1, vec![&main_dir.join(".uasset-index")]),
+        (report.failed, generated)
+    );
+    assert_eq!(
+        b"<h1>Hero</h1> from an earlier run".to_vec(),
+        read(main_dir.join("index.html")).unwrap()
+    );
+    assert!(!main_dir.join("exports").join("index.html").exists());
+    let is_temp = |path: &PathBuf| path.to_string_lossy().ends_with(TEMP_SUFFIX);
+    assert!(!read_tree(&out).keys().any(is_temp));
+
+    // The next run removes what a killed run left behind.
+    std::fs::remove_dir(&blocker).unwrap();
+    let stale = temp_path(&main_dir.join("exports").join("index.html"));
+    write(&stale, "<h1>Her").unwrap();
+    index_file(&asset, &options).unwrap();
+    assert!(!read_tree(&out).keys().any(is_temp));
+    let page = String::from_utf8(read(main_dir.join("index.html")).unwrap()).unwrap();
+    assert!(page.contains("<dd>VER_UE4_27 ("));
+}
+
+#[test]
+fn test_index_dir_writes_combined_csv() {
+    let fixture = tempfile::tempdir().unwrap();
+    create_dir_all(fixture.path().join("Content").join("Maps")).unwrap();
+    write_empty_unversioned_asset(&fixture.path().join("Content").join("Hero.uasset"));
+    write_empty_unversioned_asset(&fixture.path().join("Content").join("Maps").join("Hub.umap"));
+    let out = tempfile::tempdir().unwrap();
+    let options = IndexOptions {
+        output_dir: Some(out.path().to_path_buf()),
+        engine_version: EngineVersion::VER_UE4_27,
+        formats: vec![OutputFormat::Csv],
+        csv_combined: true,
+        ..Default::default()
+    };
+    let report = index_dir(fixture.path(), &options).unwrap();
+    assert_eq!((2, 0), (report.succeeded, report.failed));
+    let hero = std::fs::read_to_string(out.path().join("Content").join("Hero").join("exports.csv"))
+        .unwrap();
+    assert_eq!(
+        "index,object_name,class,outer_index,serial_size,serial_offset,object_flags\n",
+        hero
+    );
+    assert!(out
+        .path()
+        .join("Content")
+        .join("Maps")
+        .join("Hub")
+        .join("imports.csv")
+        .exists());
+    let combined = std::fs::read_to_string(out.path().join("imports.csv")).unwrap();
+    assert_eq!(
+        "package,index,class_package,class_name,object_name,outer_index\n",
+        combined
+    );
+}
+
+#[test]
+fn test_index_dir_reads_mixed_case_and_registered_extensions() {
+    let fixture = tempfile::tempdir().unwrap();
+    let content = fixture.path().join("Content");
+    create_dir_all(&content).unwrap();
+    write_empty_unversioned_asset(&content.join("Hero.UAsset"));
+    write_empty_unversioned_asset(&content.join("Hub.UMAP"));
+    write_empty_unversioned_asset(&content.join("Door.gasset"));
+    let mut options = IndexOptions {
+        engine_version: EngineVersion::VER_UE4_27,
+        ..Default::default()
+    };
+    let report = index_dir(&content, &options).unwrap();
+    assert_eq!((2, 0), (report.succeeded, report.failed));
+    assert!(!content.join("Door").exists());
+    assert!(matches!(
+        index_file(&content.join("Door.gasset"), &options),
+        Err(Error::InvalidExtension(_))
+    ));
+
+    options.extensions = vec!["gasset".to_string()];
+    let report = index_dir(&content, &options).unwrap();
+    assert_eq!((3, 0), (report.succeeded, report.failed));
+    assert!(content.join("Door").join("index.html").exists());
+    assert!(content.join("Hub").join("index.html").exists());
+}
+
+#[test]
+fn test_index_dir_writes_ndjson_lines() {
+    let dir = tempfile::tempdir().unwrap();
+    let content = dir.path().join("Content");
+    create_dir_all(&content).unwrap();
+    write_empty_unversioned_asset(&content.join("Hero.uasset"));
+    write_unversioned_asset(&content.join("Cooked.uasset"));
+    let lines_path = dir.path().join("assets.ndjson");
+    let options = IndexOptions {
+        output_dir: Some(dir.path().join("out")),
+        engine_version: EngineVersion::VER_UE4_27,
+        formats: vec![OutputFormat::Ndjson],
+        ndjson: Some(NdjsonOutput::create(&lines_path).unwrap()),
+        ..Default::default()
+    };
+    let report = index(&content, &options);
+    assert_eq!((1, 1), (report.succeeded, report.failed));
+    let mut lines: Vec<serde_json::Value> = std::fs::read_to_string(&lines_path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    lines.sort_by_key(|line| line["type"].as_str().unwrap().to_string());
+    assert_eq!(2, lines.len());
+    assert_eq!(
+        serde_json::json!({
+            "type": "asset",
+            "asset": content.join("Hero.uasset").display().to_string(),
+            "engine_version": "4.27",
+            "imports": 0,
+            "exports": 0,
+        }),
+        lines[0]
+    );
+    assert_eq!("error", lines[1]["type"]);
+    assert_eq!("mappings", lines[1]["category"]);
+    assert_eq!(
+        content.join("Cooked.uasset").display().to_string(),
+        lines[1]["asset"]
+    );
+}
+
+#[test]
+fn test_index_dir_reports_package_collisions() {
+    let fixture = tempfile::tempdir().unwrap();
+    let mods = fixture.path().join("Mods");
+    for root in ["Base", "Override"] {
+        create_dir_all(mods.join(root).join("Content")).unwrap();
+        write_empty_unversioned_asset(&mods.join(root).join("Content").join("Hero.uasset"));
+    }
+    write_empty_unversioned_asset(&mods.join("Base").join("Content").join("Door.uasset"));
+    let mut options = IndexOptions {
+        engine_version: EngineVersion::VER_UE4_27,
+        ..Default::default()
+    };
+    let report = index_dir(&mods, &options).unwrap();
+    assert_eq!(1, report.collisions.len());
+    let collision = &report.collisions[0];
+    assert_eq!("/Game/Hero", collision.package_path);
+    assert_eq!(2, collision.files.len());
+    assert_eq!(
+        mods.join("Base").join("Content").join("Hero.uasset"),
+        collision.resolved
+    );
+    let errors = std::fs::read_to_string(mods.join("errors.html")).unwrap();
+    assert!(errors.contains("<h2>Package collisions</h2>"));
+
+    options.prefer = vec![PathBuf::from("Override")];
+    let report = index_dir(&mods, &options).unwrap();
+    assert_eq!(
+        mods.join("Override").join("Content").join("Hero.uasset"),
+        report.collisions[0].resolved
+    );
+}
+
+#[test]
+fn test_index_dir_links_each_asset_to_its_neighbors() {
+    let fixture = tempfile::tempdir().unwrap();
+    let content = fixture.path().join("Content");
+    create_dir_all(content.join("Maps")).unwrap();
+    for name in ["Key", "Door", "Hub"] {
+        write_empty_unversioned_asset(&content.join(format!("{}.uasset", name)));
+    }
+    write_empty_unversioned_asset(&content.join("Maps").join("Arena.umap"));
+    let options = IndexOptions {
+        engine_version: EngineVersion::VER_UE4_27,
+        ..Default::default()
+    };
+    index_dir(&content, &options).unwrap();
+    let page = |path: &Path| std::fs::read_to_string(path.join("index.html")).unwrap();
+    let door = page(&content.join("Door"));
+    assert!(!door.contains("rel=\"prev\""));
+    assert!(door.contains("<a href=\"../Hub\" rel=\"next\">Hub &rarr;</a>"));
+    let hub = page(&content.join("Hub"));
+    assert!(hub.contains(
+        "<a href=\"../Door\" rel=\"prev\">&larr; Door</a> | <a href=\"..\">up</a> | \
+         <a href=\"../Key\" rel=\"next\">Key &rarr;</a>"
+    ));
+    let key = page(&content.join("Key"));
+    assert!(key.contains("rel=\"prev\"") && !key.contains("rel=\"next\""));
+    let arena = page(&content.join("Maps").join("Arena"));
+    assert!(arena.contains("<nav class=\"asset-nav\"><a href=\"..\">up</a></nav>"));
+
+    // Another run replaces the links rather than adding a second bar.
+    index_dir(&content, &options).unwrap();
+    assert_eq!(1, page(&content.join("Hub")).matches("asset-nav").count());
+}
+
+#[test]
+fn test_index_dir_lists_external_actors_of_world_partition_maps() {
+    let fixture = tempfile::tempdir().unwrap();
+    let content = fixture.path().join("Content");
+    let actors = content
+        .join("__ExternalActors__")
+        .join("Maps")
+        .join("Hub")
+        .join("0")
+        .join("AB");
+    create_dir_all(&actors).unwrap();
+    create_dir_all(content.join("Maps")).unwrap();
+    write_empty_unversioned_asset(&content.join("Maps").join("Hub.umap"));
+    write_empty_unversioned_asset(&content.join("Maps").join("Arena.umap"));
+    write_empty_unversioned_asset(&actors.join("3CDEF0123.uasset"));
+    let options = IndexOptions {
+        engine_version: EngineVersion::VER_UE4_27,
+        ..Default::default()
+    };
+    let report = index_dir(&content, &options).unwrap();
+    assert_eq!((3, 0), (report.succeeded, report.failed));
+    assert!(actors.join("3CDEF0123").join("index.html").exists());
+    let hub = content.join("Maps").join("Hub");
+    let page = std::fs::read_to_string(hub.join("actors.html")).unwrap();
+    assert!(page.contains("<h2>External actors (0)</h2>"));
+    let map_page = std::fs::read_to_string(hub.join("index.html")).unwrap();
+    assert_eq!(1, map_page.matches("<a href=\"actors.html\">").count());
+    assert!(!content
+        .join("Maps")
+        .join("Arena")
+        .join("actors.html")
+        .exists());
+
+    index_dir(&content, &options).unwrap();
+    let map_page = std::fs::read_to_string(hub.join("index.html")).unwrap();
+    assert_eq!(1, map_page.matches("<a href=\"actors.html\">").count());
+}
+
+#[test]
+fn test_index_dir_keeps_asset_output_out_of_same_named_directory() {
+    let fixture = tempfile::tempdir().unwrap();
+    let content = fixture.path().join("Content");
+    create_dir_all(content.join("Weapons")).unwrap();
+    write_empty_unversioned_asset(&content.join("Weapons.uasset"));
+    write_empty_unversioned_asset(&content.join("Weapons").join("Rifle.uasset"));
+    // What a run that wrote Weapons.uasset's pages into Weapons/ left.
+    write(
+        content.join("Weapons").join(".uasset-index"),
+        "Weapons.uasset",
+    )
+    .unwrap();
+    let options = IndexOptions {
+        engine_version: EngineVersion::VER_UE4_27,
+        ..Default::default()
+    };
+    let report = index_dir(&content, &options).unwrap();
+    assert_eq!((2, 0), (report.succeeded, report.failed));
+    assert!(content
+        .join("Weapons.uasset-index")
+        .join("exports")
+        .is_dir());
+    assert!(content
+        .join("Weapons")
+        .join("Rifle")
+        .join("index.html")
+        .exists());
+    assert!(!content.join("Weapons").join("exports").exists());
+    let root_page = std::fs::read_to_string(content.join("index.html")).unwrap();
+    assert!(root_page.contains("<a href=\"Weapons\">Weapons/</a>"));
+    assert!(root_page.contains("<a href=\"Weapons.uasset-index\">Weapons</a>"));
+
+    // Indexing the asset on its own goes to the same place.
+    index_file(&content.join("Weapons.uasset"), &options).unwrap();
+    assert!(!content.join("Weapons").join("imports").exists());
+}